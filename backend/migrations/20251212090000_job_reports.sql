@@ -0,0 +1,4 @@
+-- migrations/20251212090000_job_reports.sql
+
+-- Rapport de quantification (tailles, perplexité, latence)
+ALTER TABLE jobs ADD COLUMN report JSONB;
@@ -0,0 +1,11 @@
+-- migrations/20251212100000_notification_preferences.sql
+
+-- Préférences de notification par utilisateur (une ligne par utilisateur)
+CREATE TABLE notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    job_complete_email BOOLEAN NOT NULL DEFAULT TRUE,
+    job_failed_email BOOLEAN NOT NULL DEFAULT TRUE,
+    job_complete_sms BOOLEAN NOT NULL DEFAULT FALSE,
+    marketing BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- migrations/20251212110000_job_hub_source.sql
+
+-- Un job peut partir d'un fichier uploadé ou d'un dépôt Hugging Face
+ALTER TABLE jobs ALTER COLUMN input_file_id DROP NOT NULL;
+ALTER TABLE jobs ADD COLUMN source_repo_id VARCHAR(255);
+ALTER TABLE jobs ADD COLUMN source_revision VARCHAR(255);
+ALTER TABLE jobs ADD CONSTRAINT jobs_input_source_check
+    CHECK (input_file_id IS NOT NULL OR source_repo_id IS NOT NULL);
@@ -0,0 +1,6 @@
+-- migrations/20251212120000_file_retention.sql
+
+-- Date de purge effective du contenu (expires_at reste la date prévue)
+ALTER TABLE model_files ADD COLUMN purged_at TIMESTAMPTZ;
+
+CREATE INDEX idx_model_files_pending_purge ON model_files (expires_at) WHERE purged_at IS NULL;
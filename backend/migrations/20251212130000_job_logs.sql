@@ -0,0 +1,14 @@
+-- migrations/20251212130000_job_logs.sql
+
+-- Journal d'exécution des jobs (diagnostic des échecs de quantification)
+CREATE TABLE job_logs (
+    id UUID PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    level VARCHAR(16) NOT NULL,
+    message TEXT NOT NULL,
+    -- Sortie d'erreur brute (visible uniquement par les admins)
+    stderr TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_job_logs_job_id ON job_logs (job_id, created_at);
@@ -0,0 +1,10 @@
+-- migrations/20251212140000_file_expiry_notice.sql
+
+-- Avertissement d'expiration déjà envoyé pour ce fichier
+ALTER TABLE model_files ADD COLUMN expiry_notified BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_model_files_expiry_notice ON model_files (expires_at)
+    WHERE purged_at IS NULL AND expiry_notified = FALSE;
+
+-- Préférence: email avant suppression d'un fichier
+ALTER TABLE notification_preferences ADD COLUMN file_expiry_email BOOLEAN NOT NULL DEFAULT TRUE;
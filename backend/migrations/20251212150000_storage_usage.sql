@@ -0,0 +1,40 @@
+-- migrations/20251212150000_storage_usage.sql
+
+-- Compteur d'espace utilisé par utilisateur (octets des fichiers non purgés)
+ALTER TABLE users ADD COLUMN storage_used_bytes BIGINT NOT NULL DEFAULT 0;
+
+UPDATE users u
+SET storage_used_bytes = COALESCE((
+    SELECT SUM(f.file_size) FROM model_files f
+    WHERE f.user_id = u.id AND f.purged_at IS NULL
+), 0);
+
+-- Maintenir le compteur à l'insertion, la purge et la suppression des fichiers
+CREATE OR REPLACE FUNCTION update_user_storage_used()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        IF NEW.purged_at IS NULL THEN
+            UPDATE users SET storage_used_bytes = storage_used_bytes + NEW.file_size WHERE id = NEW.user_id;
+        END IF;
+        RETURN NEW;
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.purged_at IS NULL AND NEW.purged_at IS NOT NULL THEN
+            UPDATE users SET storage_used_bytes = GREATEST(storage_used_bytes - OLD.file_size, 0) WHERE id = OLD.user_id;
+        END IF;
+        RETURN NEW;
+    ELSIF TG_OP = 'DELETE' THEN
+        IF OLD.purged_at IS NULL THEN
+            UPDATE users SET storage_used_bytes = GREATEST(storage_used_bytes - OLD.file_size, 0) WHERE id = OLD.user_id;
+        END IF;
+        RETURN OLD;
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER update_model_files_storage_used
+    AFTER INSERT OR UPDATE OF purged_at OR DELETE ON model_files
+    FOR EACH ROW EXECUTE FUNCTION update_user_storage_used();
+
+CREATE INDEX idx_model_files_user_live ON model_files (user_id) WHERE purged_at IS NULL;
@@ -0,0 +1,20 @@
+-- migrations/20251212160000_checkout_sessions.sql
+
+-- Sessions de checkout Stripe (suivi des upgrades non finalisés)
+CREATE TYPE checkout_session_status AS ENUM ('pending', 'completed', 'expired');
+
+CREATE TABLE checkout_sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    stripe_session_id VARCHAR(255) NOT NULL UNIQUE,
+    plan subscription_plan NOT NULL,
+    status checkout_session_status NOT NULL DEFAULT 'pending',
+    -- Expiration côté Stripe (24h par défaut)
+    expires_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_checkout_sessions_pending ON checkout_sessions (expires_at) WHERE status = 'pending';
+CREATE INDEX idx_checkout_sessions_user_id ON checkout_sessions (user_id);
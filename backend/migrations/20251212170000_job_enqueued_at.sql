@@ -0,0 +1,9 @@
+-- migrations/20251212170000_job_enqueued_at.sql
+
+-- Date d'ajout dans la queue Redis (NULL: job à récupérer si Redis était indisponible)
+ALTER TABLE jobs ADD COLUMN enqueued_at TIMESTAMPTZ;
+
+-- Les jobs existants sont considérés comme déjà en queue
+UPDATE jobs SET enqueued_at = created_at;
+
+CREATE INDEX idx_jobs_not_enqueued ON jobs (created_at) WHERE status = 'pending' AND enqueued_at IS NULL;
@@ -0,0 +1,16 @@
+-- migrations/20251212180000_api_key_hash.sql
+
+-- Les clés API ne sont plus stockées en clair: seul leur SHA-256 est conservé
+ALTER TABLE api_keys ADD COLUMN key_hash VARCHAR(64);
+ALTER TABLE api_keys ADD COLUMN key_prefix VARCHAR(12);
+ALTER TABLE api_keys ADD COLUMN revoked_at TIMESTAMPTZ;
+
+UPDATE api_keys
+SET key_hash = encode(sha256(key::bytea), 'hex'),
+    key_prefix = left(key, 8);
+
+ALTER TABLE api_keys ALTER COLUMN key_hash SET NOT NULL;
+ALTER TABLE api_keys ALTER COLUMN key_prefix SET NOT NULL;
+ALTER TABLE api_keys DROP COLUMN key;
+
+CREATE UNIQUE INDEX idx_api_keys_key_hash ON api_keys (key_hash);
@@ -0,0 +1,4 @@
+-- migrations/20251212190000_job_quantization_config.sql
+
+-- Options de quantification propres au job (précision par couche, ...)
+ALTER TABLE jobs ADD COLUMN quantization_config JSONB;
@@ -0,0 +1,4 @@
+-- migrations/20251212200000_subscription_trial.sql
+
+-- Fin de la période d'essai d'un plan payant (NULL hors essai)
+ALTER TABLE subscriptions ADD COLUMN trial_ends_at TIMESTAMPTZ;
@@ -0,0 +1,10 @@
+-- migrations/20251212210000_file_analysis_status.sql
+
+-- Analyse détaillée d'un fichier (asynchrone au-delà d'une taille configurable)
+CREATE TYPE analysis_status AS ENUM ('pending', 'running', 'completed', 'failed');
+
+ALTER TABLE model_files
+    ADD COLUMN analysis_status analysis_status,
+    ADD COLUMN analysis_result JSONB,
+    ADD COLUMN analysis_error TEXT,
+    ADD COLUMN analyzed_at TIMESTAMPTZ;
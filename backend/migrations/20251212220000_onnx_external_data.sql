@@ -0,0 +1,7 @@
+-- migrations/20251212220000_onnx_external_data.sql
+
+-- Données externes ONNX: fichiers de poids rattachés à leur modèle .onnx
+ALTER TABLE model_files
+    ADD COLUMN parent_file_id UUID REFERENCES model_files(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_model_files_parent ON model_files(parent_file_id);
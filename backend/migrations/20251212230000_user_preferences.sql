@@ -0,0 +1,11 @@
+-- migrations/20251212230000_user_preferences.sql
+
+-- Réglages de quantification par défaut (une ligne par utilisateur)
+CREATE TABLE user_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    default_method quantization_method,
+    default_bits SMALLINT CHECK (default_bits IN (2, 3, 4, 8)),
+    default_group_size INTEGER CHECK (default_group_size > 0),
+    default_output_format model_format,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
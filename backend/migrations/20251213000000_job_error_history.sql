@@ -0,0 +1,6 @@
+-- migrations/20251213000000_job_error_history.sql
+
+-- Historique des échecs d'un job (les relances ne perdent plus les erreurs précédentes)
+ALTER TABLE jobs
+    ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN error_history JSONB NOT NULL DEFAULT '[]'::jsonb;
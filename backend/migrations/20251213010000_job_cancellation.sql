@@ -0,0 +1,8 @@
+-- migrations/20251213010000_job_cancellation.sql
+
+-- Auteur et motif de l'annulation d'un job (utilisateur, admin ou système)
+CREATE TYPE cancelled_by AS ENUM ('user', 'admin', 'system');
+
+ALTER TABLE jobs
+    ADD COLUMN cancelled_by cancelled_by,
+    ADD COLUMN cancellation_reason TEXT;
@@ -0,0 +1,4 @@
+-- migrations/20251213020000_subscription_version.sql
+
+-- Verrou optimiste: chaque mouvement de crédits vérifie puis incrémente la version
+ALTER TABLE subscriptions ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- migrations/20251213030000_unique_job_names.sql
+
+-- Refuser deux jobs actifs de même nom (option par utilisateur, désactivée par défaut)
+ALTER TABLE user_preferences ADD COLUMN unique_job_names BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_jobs_user_active_name ON jobs (user_id, name)
+    WHERE status IN ('pending', 'processing');
@@ -0,0 +1,20 @@
+-- migrations/20251213040000_job_events.sql
+
+-- Chronologie structurée du cycle de vie des jobs (distincte du journal libre)
+CREATE TYPE job_event_type AS ENUM (
+    'created', 'queued', 'picked_up', 'stage_started', 'completed', 'failed', 'cancelled'
+);
+
+CREATE TABLE job_events (
+    id UUID PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    -- Ordre d'écriture (les horodatages peuvent être égaux)
+    sequence BIGSERIAL NOT NULL,
+    event job_event_type NOT NULL,
+    stage VARCHAR(32),
+    worker_id VARCHAR(255),
+    message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_job_events_job_id ON job_events (job_id, sequence);
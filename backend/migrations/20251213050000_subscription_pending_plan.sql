@@ -0,0 +1,7 @@
+-- migrations/20251213050000_subscription_pending_plan.sql
+
+-- Rétrogradation programmée: appliquée à la fin de la période en cours
+ALTER TABLE subscriptions ADD COLUMN pending_plan subscription_plan;
+
+CREATE INDEX idx_subscriptions_pending_plan ON subscriptions(current_period_end)
+    WHERE pending_plan IS NOT NULL;
@@ -0,0 +1,17 @@
+-- migrations/20251213060000_job_share_links.sql
+
+-- Liens de partage publics du résultat d'un job: le token est la seule
+-- authentification, seule son empreinte est conservée
+CREATE TABLE job_share_links (
+    id UUID PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    max_downloads INTEGER,
+    download_count INTEGER NOT NULL DEFAULT 0,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_job_share_links_job_id ON job_share_links(job_id);
@@ -0,0 +1,14 @@
+-- migrations/20251213070000_user_webhooks.sql
+
+-- Webhooks enregistrés par les utilisateurs: le secret sert à signer
+-- (HMAC-SHA256) chaque envoi, il est donc conservé en clair
+CREATE TABLE user_webhooks (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    event_types JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_webhooks_user_id ON user_webhooks(user_id);
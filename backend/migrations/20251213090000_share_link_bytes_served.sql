@@ -0,0 +1,5 @@
+-- migrations/20251213090000_share_link_bytes_served.sql
+
+-- Octets servis par lien de partage: le plafond de téléchargements est un
+-- budget de `max_downloads` fois la taille du fichier, reprises comprises
+ALTER TABLE job_share_links ADD COLUMN bytes_served BIGINT NOT NULL DEFAULT 0;
@@ -1,11 +1,38 @@
 // api/admin.rs
-use crate::models::{SystemMetrics, HealthStatus, PaginatedResponse};
-use crate::api::AuthenticatedUser;
+use crate::models::{SystemMetrics, HealthStatus, Pagination, BroadcastRequest};
+use crate::api::{audit_entry, AuthenticatedUser};
+use crate::api::maintenance::MaintenanceMode;
 use crate::core::system_service::SystemService;
-use actix_web::{web, HttpResponse, Responder};
+use crate::core::user_service::UserService;
+use crate::core::job_service::JobService;
+use crate::core::billing_service::BillingService;
+use crate::core::notification_service::NotificationService;
+use crate::utils::config::Config;
+use crate::services::audit::{AuditRepository, AuditFilter};
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use validator::Validate;
+
+/// Taille de page par défaut des listes admin
+const ADMIN_DEFAULT_PER_PAGE: i64 = 50;
+
+/// Taille de page maximale du journal d'audit
+const AUDIT_MAX_PER_PAGE: i64 = 500;
+
+/// Nombre maximal d'annonces par admin et par heure
+const BROADCASTS_PER_HOUR: i64 = 5;
 
 /// Middleware pour vérifier les permissions admin
-fn require_admin(user: &AuthenticatedUser) -> Result<(), actix_web::Error> {
+pub(crate) fn require_admin(user: &AuthenticatedUser) -> Result<(), actix_web::Error> {
+    // Un token d'impersonation ne donne jamais les droits admin de sa cible
+    if user.is_impersonated() {
+        return Err(actix_web::error::ErrorForbidden("Accès admin interdit en mode impersonation"));
+    }
+    
+    // Les clés API n'ouvrent jamais les routes admin, quels que soient leurs scopes
+    if user.is_api_key() {
+        return Err(actix_web::error::ErrorForbidden("Accès admin interdit par clé API"));
+    }
+    
     // Dans le MVP, on peut avoir une liste d'admins en dur
     // En production, on utiliserait un système de rôles
     let admin_emails = vec![
@@ -31,15 +58,34 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .route("/metrics", web::get().to(get_metrics))
             // Statistiques
             .route("/stats", web::get().to(get_stats))
+            // Signal d'autoscaling des workers
+            .route("/scaling", web::get().to(get_scaling))
+            // Workers (pause de maintenance)
+            .route("/workers", web::get().to(get_workers))
+            // Contenu des queues Redis (lecture seule)
+            .route("/queue", web::get().to(inspect_queue))
+            .route("/worker/pause", web::post().to(pause_worker))
+            .route("/worker/resume", web::post().to(resume_worker))
+            // Mode maintenance (503 pour le trafic non admin)
+            .route("/maintenance", web::get().to(get_maintenance))
+            .route("/maintenance", web::post().to(set_maintenance))
+            // Annonces
+            .route("/broadcast", web::post().to(broadcast))
             // Utilisateurs (admin)
             .route("/users", web::get().to(list_users))
             .route("/users/{user_id}", web::get().to(get_user))
             .route("/users/{user_id}", web::delete().to(delete_user))
+            .route("/users/{user_id}/restore", web::post().to(restore_user))
+            .route("/users/{user_id}/credits", web::post().to(adjust_user_credits))
+            .route("/users/{user_id}/impersonate", web::post().to(impersonate_user))
             // Jobs (admin)
             .route("/jobs", web::get().to(list_all_jobs))
             .route("/jobs/{job_id}", web::get().to(get_job_details))
             .route("/jobs/{job_id}/retry", web::post().to(retry_job))
+            .route("/jobs/{job_id}/cancel", web::post().to(cancel_job))
+            .route("/jobs/{job_id}/logs", web::get().to(get_job_logs))
             // Logs d'audit
+            .route("/audit", web::get().to(get_audit_logs))
             .route("/audit-logs", web::get().to(get_audit_logs)),
     );
 }
@@ -92,33 +138,268 @@ async fn get_stats(
     }
 }
 
-/// Lister tous les utilisateurs (admin)
-async fn list_users(
+/// Obtenir le signal d'autoscaling (lecture seule)
+async fn get_scaling(
     user: AuthenticatedUser,
-    system_service: web::Data<SystemService>,
-    query: web::Query<AdminListQuery>,
+    job_service: web::Data<JobService>,
+    config: web::Data<Config>,
 ) -> impl Responder {
     // Vérifier les permissions admin
     if let Err(e) = require_admin(&user) {
         return e.into();
     }
     
-    match system_service.list_users(
-        query.page.unwrap_or(1),
-        query.per_page.unwrap_or(50),
-        query.search.as_deref(),
+    match job_service.get_scaling_signal(
+        config.scaling_target_drain_seconds,
+        config.scaling_min_workers,
+        config.scaling_max_workers,
     ).await {
-        Ok(users) => {
-            let total = users.len() as i64;
-            let response = PaginatedResponse {
-                items: users,
-                total,
-                page: query.page.unwrap_or(1),
-                per_page: query.per_page.unwrap_or(50),
-                total_pages: (total as f64 / query.per_page.unwrap_or(50) as f64).ceil() as i64,
-            };
-            HttpResponse::Ok().json(response)
+        Ok(signal) => HttpResponse::Ok().json(signal),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// État des workers (admin)
+async fn get_workers(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    match job_service.get_worker_status().await {
+        Ok(status) => HttpResponse::Ok().json(status),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Prochains jobs de chaque queue avec leur charge utile brute (admin)
+///
+/// Lecture seule: rien n'est retiré des queues. Couvre les niveaux de
+/// priorité et les marqueurs de traitement (avec TTL). Permet de repérer un job illisible bloqué en tête.
+async fn inspect_queue(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    config: web::Data<Config>,
+    query: web::Query<QueueInspectQuery>,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    // Même validation que les listes paginées: zéro ou négatif refusé
+    let limit = match Pagination::from_params(None, query.limit, config.pagination_max_per_page) {
+        Ok(pagination) => pagination.per_page,
+        Err(e) => return e.error_response(),
+    };
+    
+    match job_service.inspect_queue(limit as usize).await {
+        Ok(snapshot) => HttpResponse::Ok().json(serde_json::json!({
+            "limit": limit,
+            "tiers": snapshot.tiers,
+            "processing": snapshot.processing,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Suspendre la prise de nouveaux jobs (admin)
+async fn pause_worker(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    audit: web::Data<AuditRepository>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    if job_service.pause_worker() {
+        log::warn!("Workers mis en pause par {}", user.email);
+        audit.log(audit_entry(&req, Some(user.id), "admin.worker_pause", None, None)).await;
+    }
+    
+    HttpResponse::Ok().json(serde_json::json!({ "paused": true }))
+}
+
+/// Reprendre la prise de jobs (admin)
+async fn resume_worker(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    audit: web::Data<AuditRepository>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    if job_service.resume_worker() {
+        log::info!("Workers relancés par {}", user.email);
+        audit.log(audit_entry(&req, Some(user.id), "admin.worker_resume", None, None)).await;
+    }
+    
+    HttpResponse::Ok().json(serde_json::json!({ "paused": false }))
+}
+
+/// État du mode maintenance (admin)
+async fn get_maintenance(
+    user: AuthenticatedUser,
+    maintenance: web::Data<MaintenanceMode>,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    HttpResponse::Ok().json(maintenance.status())
+}
+
+/// Activer ou désactiver le mode maintenance (admin)
+///
+/// Les jobs en cours continuent; seules les requêtes non admin sont refusées.
+async fn set_maintenance(
+    user: AuthenticatedUser,
+    maintenance: web::Data<MaintenanceMode>,
+    audit: web::Data<AuditRepository>,
+    request: web::Json<MaintenanceRequest>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    let was_enabled = maintenance.set(request.enabled, request.retry_after_seconds);
+    if was_enabled != request.enabled {
+        if request.enabled {
+            log::warn!("Mode maintenance activé par {}", user.email);
+            audit.log(audit_entry(&req, Some(user.id), "admin.maintenance_on", None, None)).await;
+        } else {
+            log::info!("Mode maintenance désactivé par {}", user.email);
+            audit.log(audit_entry(&req, Some(user.id), "admin.maintenance_off", None, None)).await;
+        }
+    }
+    
+    HttpResponse::Ok().json(maintenance.status())
+}
+
+/// Envoyer une annonce aux utilisateurs actifs (admin)
+///
+/// L'envoi se fait en arrière-plan, par lots; la réponse (202) n'attend pas
+/// la fin. Le bilan est journalisé une fois tous les lots envoyés.
+async fn broadcast(
+    user: AuthenticatedUser,
+    notification_service: web::Data<NotificationService>,
+    cache: web::Data<crate::services::Cache>,
+    audit: web::Data<AuditRepository>,
+    config: web::Data<Config>,
+    request: web::Json<BroadcastRequest>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    if let Err(errors) = request.validate() {
+        return crate::utils::error::AppError::from(errors).error_response();
+    }
+    if !request.email && !request.in_app {
+        return HttpResponse::BadRequest().json("Au moins un canal (email ou in_app) est requis");
+    }
+    
+    let rate_key = format!("admin_broadcast:{}", user.id);
+    match cache.check_rate_limit(&rate_key, BROADCASTS_PER_HOUR, 3600).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::TooManyRequests().json("Trop d'annonces, réessayez plus tard"),
+        Err(e) => log::warn!("Limitation des annonces indisponible: {}", e),
+    }
+    
+    let request = request.into_inner();
+    let entry = audit_entry(&req, Some(user.id), "admin.broadcast", None, None)
+        .with_changes(
+            serde_json::Value::Null,
+            serde_json::json!({
+                "subject": request.subject,
+                "plans": request.plans,
+                "email": request.email,
+                "in_app": request.in_app,
+                "critical": request.critical,
+            }),
+        );
+    audit.log(entry).await;
+    log::info!("Annonce '{}' lancée par {}", request.subject, user.email);
+    
+    let notification_service = notification_service.into_inner();
+    let batch_size = config.broadcast_batch_size;
+    let batch_delay = std::time::Duration::from_millis(config.broadcast_batch_delay_ms);
+    tokio::spawn(async move {
+        match notification_service.send_broadcast(&request, batch_size, batch_delay).await {
+            Ok(summary) => log::info!(
+                "📣 Annonce '{}' envoyée: {} destinataire(s), {} email(s), {} in-app, {} ignoré(s), {} échec(s)",
+                request.subject, summary.recipients, summary.emails_sent,
+                summary.in_app_sent, summary.skipped, summary.failed
+            ),
+            Err(e) => log::error!("Échec de l'annonce '{}': {}", request.subject, e),
         }
+    });
+    
+    HttpResponse::Accepted().json(serde_json::json!({ "status": "accepted" }))
+}
+
+/// Rechercher les utilisateurs (admin)
+///
+/// `q` filtre sur un fragment d'email (sans distinction de casse), `plan` et
+/// `active` sur l'abonnement courant et l'état du compte. Chaque résumé inclut
+/// le plan et la consommation de crédits, jamais le hash du mot de passe.
+async fn list_users(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+    cache: web::Data<crate::services::Cache>,
+    config: web::Data<Config>,
+    query: web::Query<AdminListQuery>,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    let rate_key = format!("admin_search:{}", user.id);
+    match cache.check_rate_limit(&rate_key, config.admin_search_requests_per_minute, 60).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::TooManyRequests().json("Trop de recherches, réessayez dans une minute"),
+        Err(e) => log::warn!("Limitation des recherches admin indisponible: {}", e),
+    }
+    
+    let plan = match query.plan.as_deref().map(str::to_lowercase).as_deref() {
+        None => None,
+        Some("free") => Some(crate::models::SubscriptionPlan::Free),
+        Some("starter") => Some(crate::models::SubscriptionPlan::Starter),
+        Some("pro") => Some(crate::models::SubscriptionPlan::Pro),
+        Some(_) => return HttpResponse::BadRequest().json("Plan invalide"),
+    };
+    
+    let filter = crate::models::UserSearchFilter {
+        query: query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()).map(str::to_string),
+        plan,
+        active: query.active,
+    };
+    let pagination = match Pagination::from_params(
+        query.page,
+        query.per_page.or(Some(ADMIN_DEFAULT_PER_PAGE)),
+        config.pagination_max_per_page,
+    ) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    match user_service.search_users(&filter, pagination).await {
+        Ok((users, total)) => HttpResponse::Ok().json(pagination.response(users, total)),
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
 }
@@ -151,7 +432,9 @@ async fn get_user(
 async fn delete_user(
     user: AuthenticatedUser,
     system_service: web::Data<SystemService>,
+    audit: web::Data<AuditRepository>,
     user_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
     // Vérifier les permissions admin
     if let Err(e) = require_admin(&user) {
@@ -164,12 +447,139 @@ async fn delete_user(
     }
     
     match system_service.delete_user(*user_id).await {
-        Ok(_) => HttpResponse::NoContent().finish(),
+        Ok(_) => {
+            audit.log(audit_entry(&req, Some(user.id), "admin.user_delete", Some("user"), Some(*user_id))).await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::UserNotFound => {
+                    HttpResponse::NotFound().json("Utilisateur non trouvé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Restaurer un utilisateur supprimé (admin)
+async fn restore_user(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
+    user_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    match user_service.restore_user_account(*user_id).await {
+        Ok(_) => {
+            audit.log(audit_entry(&req, Some(user.id), "admin.user_restore", Some("user"), Some(*user_id))).await;
+            HttpResponse::Ok().json("Utilisateur restauré")
+        }
         Err(e) => {
             match e {
                 crate::utils::error::AppError::UserNotFound => {
+                    HttpResponse::NotFound().json("Utilisateur supprimé non trouvé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Ajuster les crédits d'un utilisateur (admin)
+async fn adjust_user_credits(
+    user: AuthenticatedUser,
+    billing_service: web::Data<BillingService>,
+    audit: web::Data<AuditRepository>,
+    user_id: web::Path<uuid::Uuid>,
+    adjustment: web::Json<CreditAdjustmentRequest>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    if let Err(errors) = adjustment.validate() {
+        return crate::utils::error::AppError::from(errors).error_response();
+    }
+    
+    match billing_service.adjust_credits(*user_id, adjustment.amount, &adjustment.reason).await {
+        Ok(credits) => {
+            let entry = audit_entry(&req, Some(user.id), "admin.credits_adjust", Some("user"), Some(*user_id))
+                .with_changes(
+                    serde_json::json!({ "remaining_credits": credits.remaining_credits.map(|remaining| remaining - adjustment.amount) }),
+                    serde_json::json!({
+                        "remaining_credits": credits.remaining_credits,
+                        "amount": adjustment.amount,
+                        "reason": adjustment.reason,
+                    }),
+                );
+            audit.log(entry).await;
+            HttpResponse::Ok().json(credits)
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::UserNotFound
+                | crate::utils::error::AppError::NotFound(_) => {
                     HttpResponse::NotFound().json("Utilisateur non trouvé")
                 }
+                crate::utils::error::AppError::InsufficientCredits => {
+                    HttpResponse::BadRequest().json("Le solde de crédits ne peut pas devenir négatif")
+                }
+                crate::utils::error::AppError::Validation(msg) => {
+                    HttpResponse::BadRequest().json(msg)
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Émettre un token d'impersonation pour le support (admin)
+async fn impersonate_user(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
+    config: web::Data<Config>,
+    user_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    // Pas d'impersonation en chaîne
+    if user.is_impersonated() {
+        return HttpResponse::Forbidden().json("Action interdite en mode impersonation");
+    }
+    
+    match user_service.generate_impersonation_token(
+        user.id,
+        *user_id,
+        config.impersonation_token_expiry_minutes,
+    ).await {
+        Ok(token) => {
+            let mut entry = audit_entry(&req, Some(user.id), "admin.impersonate", Some("user"), Some(*user_id));
+            entry.message = Some(format!("Token valable {} minutes", config.impersonation_token_expiry_minutes));
+            audit.log(entry).await;
+            HttpResponse::Ok().json(token)
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::UserNotFound
+                | crate::utils::error::AppError::NotFound(_) => {
+                    HttpResponse::NotFound().json("Utilisateur non trouvé")
+                }
+                crate::utils::error::AppError::Validation(msg) => {
+                    HttpResponse::BadRequest().json(msg)
+                }
                 _ => HttpResponse::InternalServerError().json("Erreur serveur"),
             }
         }
@@ -180,29 +590,36 @@ async fn delete_user(
 async fn list_all_jobs(
     user: AuthenticatedUser,
     system_service: web::Data<SystemService>,
+    audit: web::Data<AuditRepository>,
+    config: web::Data<Config>,
     query: web::Query<AdminJobQuery>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
     // Vérifier les permissions admin
     if let Err(e) = require_admin(&user) {
         return e.into();
     }
     
+    let pagination = match Pagination::from_params(
+        query.page,
+        query.per_page.or(Some(ADMIN_DEFAULT_PER_PAGE)),
+        config.pagination_max_per_page,
+    ) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    audit.log(audit_entry(&req, Some(user.id), "admin.jobs_list", Some("job"), None)).await;
+    
     match system_service.list_all_jobs(
         query.status.as_deref(),
         query.user_id,
-        query.page.unwrap_or(1),
-        query.per_page.unwrap_or(50),
+        pagination.page,
+        pagination.per_page,
     ).await {
         Ok(jobs) => {
             let total = jobs.len() as i64;
-            let response = PaginatedResponse {
-                items: jobs,
-                total,
-                page: query.page.unwrap_or(1),
-                per_page: query.per_page.unwrap_or(50),
-                total_pages: (total as f64 / query.per_page.unwrap_or(50) as f64).ceil() as i64,
-            };
-            HttpResponse::Ok().json(response)
+            HttpResponse::Ok().json(pagination.response(jobs, total))
         }
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
@@ -232,11 +649,30 @@ async fn get_job_details(
     }
 }
 
+/// Obtenir le journal complet d'un job, sortie d'erreur incluse (admin)
+async fn get_job_logs(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    job_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    match job_service.get_job_logs(*job_id, true).await {
+        Ok(logs) => HttpResponse::Ok().json(logs),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
 /// Réessayer un job échoué (admin)
 async fn retry_job(
     user: AuthenticatedUser,
     system_service: web::Data<SystemService>,
+    audit: web::Data<AuditRepository>,
     job_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
     // Vérifier les permissions admin
     if let Err(e) = require_admin(&user) {
@@ -244,7 +680,10 @@ async fn retry_job(
     }
     
     match system_service.retry_job(*job_id).await {
-        Ok(job) => HttpResponse::Ok().json(job),
+        Ok(job) => {
+            audit.log(audit_entry(&req, Some(user.id), "admin.job_requeue", Some("job"), Some(*job_id))).await;
+            HttpResponse::Ok().json(job)
+        }
         Err(e) => {
             match e {
                 crate::utils::error::AppError::JobNotFound => {
@@ -259,10 +698,56 @@ async fn retry_job(
     }
 }
 
+/// Annuler le job d'un utilisateur (admin)
+async fn cancel_job(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    audit: web::Data<AuditRepository>,
+    job_id: web::Path<uuid::Uuid>,
+    body: Option<web::Json<crate::api::job::CancelJobRequest>>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Vérifier les permissions admin
+    if let Err(e) = require_admin(&user) {
+        return e.into();
+    }
+    
+    let reason = match body {
+        Some(body) => {
+            if let Err(errors) = body.validate() {
+                return crate::utils::error::AppError::from(errors).error_response();
+            }
+            body.into_inner().reason
+        }
+        None => None,
+    };
+    
+    match job_service.cancel_job(*job_id, crate::models::CancelledBy::Admin, reason).await {
+        Ok(job) => {
+            let mut entry = audit_entry(&req, Some(user.id), "admin.job_cancel", Some("job"), Some(*job_id));
+            entry.message = job.cancellation_reason.clone();
+            audit.log(entry).await;
+            HttpResponse::Ok().json(job)
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::JobNotFound
+                | crate::utils::error::AppError::NotFound(_) => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                crate::utils::error::AppError::JobCannotBeCancelled => {
+                    HttpResponse::BadRequest().json("Ce job ne peut pas être annulé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
 /// Obtenir les logs d'audit (admin)
 async fn get_audit_logs(
     user: AuthenticatedUser,
-    system_service: web::Data<SystemService>,
+    audit: web::Data<AuditRepository>,
     query: web::Query<AuditLogQuery>,
 ) -> impl Responder {
     // Vérifier les permissions admin
@@ -270,26 +755,21 @@ async fn get_audit_logs(
         return e.into();
     }
     
-    match system_service.get_audit_logs(
-        query.action.as_deref(),
-        query.user_id,
-        query.resource_type.as_deref(),
-        query.start_date,
-        query.end_date,
-        query.page.unwrap_or(1),
-        query.per_page.unwrap_or(100),
-    ).await {
-        Ok(logs) => {
-            let total = logs.len() as i64;
-            let response = PaginatedResponse {
-                items: logs,
-                total,
-                page: query.page.unwrap_or(1),
-                per_page: query.per_page.unwrap_or(100),
-                total_pages: (total as f64 / query.per_page.unwrap_or(100) as f64).ceil() as i64,
-            };
-            HttpResponse::Ok().json(response)
-        }
+    let filter = AuditFilter {
+        actor: query.actor.or(query.user_id),
+        action: query.action.clone(),
+        resource_type: query.resource_type.clone(),
+        start_date: query.start_date,
+        end_date: query.end_date,
+    };
+    // Journal d'audit: pages plus longues que les autres listes
+    let pagination = match Pagination::from_params(query.page, query.per_page.or(Some(100)), AUDIT_MAX_PER_PAGE) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    match audit.list(&filter, pagination).await {
+        Ok((logs, total)) => HttpResponse::Ok().json(pagination.response(logs, total)),
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
 }
@@ -299,7 +779,31 @@ async fn get_audit_logs(
 struct AdminListQuery {
     page: Option<i64>,
     per_page: Option<i64>,
-    search: Option<String>,
+    /// Fragment d'email (`search` accepté pour compatibilité)
+    #[serde(alias = "search")]
+    q: Option<String>,
+    plan: Option<String>,
+    active: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MaintenanceRequest {
+    enabled: bool,
+    /// Valeur de l'en-tête `Retry-After` (inchangée si absente)
+    retry_after_seconds: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QueueInspectQuery {
+    limit: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, Validate)]
+struct CreditAdjustmentRequest {
+    /// Positif pour créditer, négatif pour débiter
+    amount: i32,
+    #[validate(length(min = 1, max = 500))]
+    reason: String,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -312,6 +816,7 @@ struct AdminJobQuery {
 
 #[derive(Debug, serde::Deserialize)]
 struct AuditLogQuery {
+    actor: Option<uuid::Uuid>,
     action: Option<String>,
     user_id: Option<uuid::Uuid>,
     resource_type: Option<String>,
@@ -319,4 +824,93 @@ struct AuditLogQuery {
     end_date: Option<chrono::DateTime<chrono::Utc>>,
     page: Option<i64>,
     per_page: Option<i64>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SubscriptionPlan, User};
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn user_search_matches_a_partial_email_without_the_password_hash() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let target = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        testing::grant_credits(&db, target.id, 10).await;
+        let admin = User::new("admin@quantization.com".to_string(), "MotDePasse123!");
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(testing::user_service(db.clone(), &config).await))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+
+        // Fragment de l'email, en majuscules: la recherche ignore la casse
+        let fragment = target.email.trim_start_matches("test-")[..8].to_uppercase();
+        let request = test::TestRequest::get()
+            .uri(&format!("/admin/users?q={}", fragment))
+            .insert_header(testing::bearer(&config, &admin))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = test::read_body(response).await;
+        assert!(!String::from_utf8_lossy(&body).contains("password_hash"));
+        let page: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(page["total"], 1);
+        let found = &page["items"][0];
+        assert_eq!(found["id"], target.id.to_string());
+        assert_eq!(found["email"], target.email);
+        assert_eq!(found["plan"], "Starter");
+        assert_eq!(found["remaining_credits"], 10);
+
+        // Réservé aux admins
+        let request = test::TestRequest::get()
+            .uri("/admin/users")
+            .insert_header(testing::bearer(&config, &target))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn queue_inspection_limit_uses_pagination_validation() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let admin = User::new("admin@quantization.com".to_string(), "MotDePasse123!");
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(testing::user_service(db.clone(), &config).await))
+                .app_data(web::Data::new(testing::job_service(db.clone(), &config).await))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let inspect = |limit: &str| {
+            test::TestRequest::get()
+                .uri(&format!("/admin/queue?limit={}", limit))
+                .insert_header(testing::bearer(&config, &admin))
+                .to_request()
+        };
+
+        for refused in ["0", "-5"] {
+            let response = test::call_service(&app, inspect(refused)).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "limit={}", refused);
+        }
+
+        // Au-delà du maximum: ramené à `pagination_max_per_page`
+        let too_large = (config.pagination_max_per_page + 1).to_string();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, inspect(&too_large)).await;
+        assert_eq!(body["limit"], config.pagination_max_per_page);
+        assert!(body.get("delayed").is_none());
+    }
+}
@@ -1,8 +1,10 @@
 // api/auth.rs
 use crate::models::{User, NewUser, UserLogin, GoogleAuth, AuthToken};
 use crate::core::user_service::UserService;
+use crate::api::{audit_entry, AuthenticatedUser};
+use crate::services::audit::AuditRepository;
 use crate::services::external::google_auth_client::GoogleAuthClient;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder, ResponseError};
 use validator::Validate;
 
 /// Configure les routes d'authentification
@@ -33,7 +35,7 @@ async fn register(
 ) -> impl Responder {
     // Validation
     if let Err(errors) = new_user.validate() {
-        return HttpResponse::BadRequest().json(errors);
+        return crate::utils::error::AppError::from(errors).error_response();
     }
     
     match user_service.register_user(&new_user.email, &new_user.password).await {
@@ -56,11 +58,13 @@ async fn register(
 /// Connexion avec email/mot de passe
 async fn login(
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     credentials: web::Json<UserLogin>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
     // Validation
     if let Err(errors) = credentials.validate() {
-        return HttpResponse::BadRequest().json(errors);
+        return crate::utils::error::AppError::from(errors).error_response();
     }
     
     match user_service.authenticate_user(&credentials.email, &credentials.password).await {
@@ -68,11 +72,17 @@ async fn login(
             // Mettre à jour la dernière connexion
             user_service.update_last_login(user.id).await.ok();
             
+            audit.log(audit_entry(&req, Some(user.id), "auth.login", Some("user"), Some(user.id))).await;
+            
             // Générer le token JWT
             let token = user_service.generate_auth_token(&user).await;
             HttpResponse::Ok().json(token)
         }
         Err(e) => {
+            let mut entry = audit_entry(&req, None, "auth.login_failed", Some("user"), None);
+            entry.message = Some(credentials.email.clone());
+            audit.log(entry).await;
+            
             match e {
                 crate::utils::error::AppError::Unauthorized => {
                     HttpResponse::Unauthorized().json("Email ou mot de passe incorrect")
@@ -135,7 +145,14 @@ async fn refresh_token(
 }
 
 /// Déconnexion
-async fn logout() -> impl Responder {
+async fn logout(
+    user: Option<AuthenticatedUser>,
+    audit: web::Data<AuditRepository>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    let actor = user.map(|u| u.id);
+    audit.log(audit_entry(&req, actor, "auth.logout", Some("user"), actor)).await;
+    
     // Pour une déconnexion côté client, on peut simplement retourner un succès
     // La véritable invalidation se fait côté client en supprimant les tokens
     HttpResponse::Ok().json("Déconnexion réussie")
@@ -144,8 +161,14 @@ async fn logout() -> impl Responder {
 /// Mot de passe oublié
 async fn forgot_password(
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<ForgotPasswordRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    let mut entry = audit_entry(&req, None, "auth.password_reset_request", Some("user"), None);
+    entry.message = Some(request.email.clone());
+    audit.log(entry).await;
+    
     match user_service.initiate_password_reset(&request.email).await {
         Ok(_) => HttpResponse::Ok().json("Email de réinitialisation envoyé"),
         Err(e) => {
@@ -158,10 +181,15 @@ async fn forgot_password(
 /// Réinitialiser le mot de passe
 async fn reset_password(
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<ResetPasswordRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
     match user_service.reset_password(&request.token, &request.new_password).await {
-        Ok(_) => HttpResponse::Ok().json("Mot de passe réinitialisé avec succès"),
+        Ok(user_id) => {
+            audit.log(audit_entry(&req, Some(user_id), "auth.password_reset", Some("user"), Some(user_id))).await;
+            HttpResponse::Ok().json("Mot de passe réinitialisé avec succès")
+        }
         Err(e) => {
             match e {
                 crate::utils::error::AppError::InvalidToken => {
@@ -175,7 +203,7 @@ async fn reset_password(
 
 // Structures de requête spécifiques
 #[derive(Debug, serde::Deserialize)]
-struct RefreshTokenRequest {
+pub(crate) struct RefreshTokenRequest {
     refresh_token: String,
 }
 
@@ -0,0 +1,151 @@
+// api/auth_middleware.rs
+use crate::api::{impersonation_audit_entry, AuthenticatedUser};
+use crate::core::user_service::UserService;
+use crate::services::audit::AuditRepository;
+use crate::utils::config::Config;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::middleware::Next;
+use actix_web::{http::header, web, HttpMessage, HttpResponse};
+
+/// En-tête portant une clé API (alternative au token Bearer)
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Préfixe des clés API (`generate_api_key`), accepté aussi en `Bearer`
+const API_KEY_PREFIX: &str = "qnt_";
+
+/// Middleware des routes protégées: 401 sans token d'accès ni clé API valide
+///
+/// L'utilisateur authentifié est rangé dans les extensions de la requête,
+/// d'où l'extracteur `AuthenticatedUser` le lit.
+pub fn require_auth<S, B>() -> impl Transform<
+    S,
+    ServiceRequest,
+    Response = ServiceResponse<BoxBody>,
+    Error = actix_web::Error,
+    InitError = (),
+>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    actix_web::middleware::from_fn(authenticate::<B>)
+}
+
+/// Authentifier la requête; une impersonation est auditée à chaque requête
+async fn authenticate<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let Some(user) = resolve_user(&req).await else {
+        let response = HttpResponse::Unauthorized().json("Authentification requise");
+        return Ok(req.into_response(response));
+    };
+
+    // Clé API en lecture seule: seules les méthodes sans effet passent
+    if !req.method().is_safe() && !user.can_write() {
+        let response = HttpResponse::Forbidden().json("Clé API en lecture seule");
+        return Ok(req.into_response(response));
+    }
+
+    if let Some(entry) = impersonation_audit_entry(req.request(), &user) {
+        match req.app_data::<web::Data<AuditRepository>>() {
+            Some(audit) => audit.log(entry).await,
+            None => log::warn!("Requête sous impersonation non auditée: journal d'audit indisponible"),
+        }
+    }
+
+    req.extensions_mut().insert(user);
+    next.call(req).await.map(ServiceResponse::map_into_boxed_body)
+}
+
+/// Utilisateur du token Bearer ou de la clé API (None si aucun n'est valide)
+async fn resolve_user(req: &ServiceRequest) -> Option<AuthenticatedUser> {
+    let bearer = req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    let api_key = req.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .or_else(|| bearer.filter(|token| token.starts_with(API_KEY_PREFIX)));
+
+    if let Some(api_key) = api_key {
+        let user_service = req.app_data::<web::Data<UserService>>()?;
+        let (user_id, permissions) = user_service.verify_api_key(api_key).await.ok()?;
+        let profile = user_service.get_user_profile(user_id).await.ok()?;
+        return Some(AuthenticatedUser {
+            id: profile.id,
+            email: profile.email,
+            impersonator_id: None,
+            api_key_permissions: Some(permissions),
+        });
+    }
+
+    let config = req.app_data::<web::Data<Config>>()?;
+    let data = crate::utils::security::verify_access_token(bearer?, &config.jwt_secret).ok()?;
+    Some(AuthenticatedUser::from_claims(&data.claims))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{SubscriptionPlan, User};
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, web, App};
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn read_only_keys_cannot_write_and_keys_never_open_admin_routes() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let user_service = testing::user_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let admin = match db.get_user_by_email("admin@quantization.com").await {
+            Ok(admin) => admin,
+            Err(_) => db.create_user(&User::new("admin@quantization.com".to_string(), "MotDePasse123!")).await.unwrap(),
+        };
+
+        let read_only = user_service.create_api_key(user.id, "lecture", &["jobs:read".to_string()]).await.unwrap();
+        let admin_key = user_service.create_api_key(admin.id, "admin", &["write".to_string()]).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(user_service))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(crate::api::user::configure_routes)
+                .configure(crate::api::admin::configure_routes),
+        )
+        .await;
+
+        // Lecture permise, écriture refusée
+        let request = test::TestRequest::get()
+            .uri("/keys")
+            .insert_header(("X-API-Key", read_only.secret.clone()))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+        let request = test::TestRequest::post()
+            .uri("/keys")
+            .insert_header(("X-API-Key", read_only.secret.clone()))
+            .set_json(serde_json::json!({ "name": "escalade", "permissions": ["write"] }))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+
+        // Une clé d'admin n'ouvre pas les routes admin; son token de session, si
+        let request = test::TestRequest::get()
+            .uri("/admin/users")
+            .insert_header(("X-API-Key", admin_key.secret.clone()))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+
+        let request = test::TestRequest::get()
+            .uri("/admin/users")
+            .insert_header(testing::bearer(&config, &admin))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+    }
+}
@@ -1,11 +1,17 @@
 // api/billing.rs
-use crate::models::{Subscription, PlanInfo, CreditInfo, CreditTransaction, PaginatedResponse};
-use crate::api::AuthenticatedUser;
+use crate::models::{Subscription, PlanInfo, CreditInfo, CreditTransaction, Pagination};
+use crate::api::{audit_entry, forbid_impersonation, AuthenticatedUser};
 use crate::core::billing_service::BillingService;
-use actix_web::{web, HttpResponse, Responder};
+use crate::services::audit::AuditRepository;
+use actix_web::{web, HttpResponse, Responder, ResponseError};
 
 /// Configure les routes de facturation
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    // Webhook Stripe: hors du scope authentifié et enregistré avant lui
+    // (le corps brut est requis tel quel pour vérifier la signature)
+    cfg.route("/billing/webhook", web::post().to(stripe_webhook))
+        .route("/billing/webhook/stripe", web::post().to(stripe_webhook));
+    
     cfg.service(
         web::scope("/billing")
             .wrap(crate::api::auth_middleware::require_auth())
@@ -15,14 +21,15 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .route("/subscription", web::get().to(get_subscription))
             .route("/subscription", web::post().to(update_subscription))
             .route("/subscription/cancel", web::post().to(cancel_subscription))
+            // Projection de la consommation jusqu'à la fin de la période
+            .route("/subscription/projection", web::get().to(get_usage_projection))
             // Crédits
             .route("/credits", web::get().to(get_credit_info))
             .route("/credits/history", web::get().to(get_credit_history))
             // Paiement
             .route("/checkout", web::post().to(create_checkout_session))
-            .route("/portal", web::post().to(create_customer_portal))
-            // Webhook Stripe (pas d'authentification requise)
-            .route("/webhook/stripe", web::post().to(stripe_webhook)),
+            .route("/checkout/{session_id}", web::get().to(get_checkout_session))
+            .route("/portal", web::post().to(create_customer_portal)),
     );
 }
 
@@ -65,14 +72,26 @@ async fn get_subscription(
 async fn update_subscription(
     user: AuthenticatedUser,
     billing_service: web::Data<BillingService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<UpdateSubscriptionRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "billing.subscription_update").await {
+        return denied;
+    }
+    
     match billing_service.update_subscription(
         user.id,
         &request.plan,
         &request.payment_method_id,
     ).await {
-        Ok(subscription) => HttpResponse::Ok().json(subscription),
+        Ok(subscription) => {
+            let mut entry = audit_entry(&req, Some(user.id), "billing.subscription_update", Some("subscription"), Some(subscription.id));
+            entry.message = Some(format!("Plan: {}", request.plan));
+            audit.log(entry).await;
+            
+            HttpResponse::Ok().json(subscription)
+        }
         Err(e) => {
             match e {
                 crate::utils::error::AppError::InvalidPlan => {
@@ -91,9 +110,18 @@ async fn update_subscription(
 async fn cancel_subscription(
     user: AuthenticatedUser,
     billing_service: web::Data<BillingService>,
+    audit: web::Data<AuditRepository>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "billing.subscription_cancel").await {
+        return denied;
+    }
+    
     match billing_service.cancel_subscription(user.id).await {
-        Ok(_) => HttpResponse::Ok().json("Abonnement annulé avec succès"),
+        Ok(_) => {
+            audit.log(audit_entry(&req, Some(user.id), "billing.subscription_cancel", Some("subscription"), None)).await;
+            HttpResponse::Ok().json("Abonnement annulé avec succès")
+        }
         Err(e) => {
             match e {
                 crate::utils::error::AppError::NoSubscription => {
@@ -116,27 +144,40 @@ async fn get_credit_info(
     }
 }
 
+/// Projeter la consommation de crédits (moyenne des 30 derniers jours)
+async fn get_usage_projection(
+    user: AuthenticatedUser,
+    billing_service: web::Data<BillingService>,
+) -> impl Responder {
+    match billing_service.get_usage_projection(user.id).await {
+        Ok(projection) => HttpResponse::Ok().json(projection),
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::NoSubscription => {
+                    HttpResponse::NotFound().json("Aucun abonnement")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
 /// Obtenir l'historique des crédits
 async fn get_credit_history(
     user: AuthenticatedUser,
     billing_service: web::Data<BillingService>,
+    config: web::Data<crate::utils::config::Config>,
     query: web::Query<CreditHistoryQuery>,
 ) -> impl Responder {
-    match billing_service.get_credit_history(
-        user.id,
-        query.page.unwrap_or(1),
-        query.per_page.unwrap_or(20),
-    ).await {
+    let pagination = match Pagination::from_params(query.page, query.per_page, config.pagination_max_per_page) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    match billing_service.get_credit_history(user.id, pagination).await {
         Ok(transactions) => {
             let total = transactions.len() as i64;
-            let response = PaginatedResponse {
-                items: transactions,
-                total,
-                page: query.page.unwrap_or(1),
-                per_page: query.per_page.unwrap_or(20),
-                total_pages: (total as f64 / query.per_page.unwrap_or(20) as f64).ceil() as i64,
-            };
-            HttpResponse::Ok().json(response)
+            HttpResponse::Ok().json(pagination.response(transactions, total))
         }
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
@@ -146,8 +187,14 @@ async fn get_credit_history(
 async fn create_checkout_session(
     user: AuthenticatedUser,
     billing_service: web::Data<BillingService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<CreateCheckoutRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "billing.checkout").await {
+        return denied;
+    }
+    
     match billing_service.create_checkout_session(
         user.id,
         &request.plan,
@@ -169,11 +216,32 @@ async fn create_checkout_session(
     }
 }
 
+/// Obtenir l'état d'une session de checkout (finalisée, en attente ou expirée)
+async fn get_checkout_session(
+    user: AuthenticatedUser,
+    billing_service: web::Data<BillingService>,
+    session_id: web::Path<String>,
+) -> impl Responder {
+    match billing_service.get_checkout_session(user.id, &session_id).await {
+        Ok(session) => HttpResponse::Ok().json(session),
+        Err(crate::utils::error::AppError::NotFound(_)) => {
+            HttpResponse::NotFound().json("Session de checkout non trouvée")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
 /// Créer un portail client Stripe
 async fn create_customer_portal(
     user: AuthenticatedUser,
     billing_service: web::Data<BillingService>,
+    audit: web::Data<AuditRepository>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "billing.portal").await {
+        return denied;
+    }
+    
     match billing_service.create_customer_portal(user.id).await {
         Ok(portal_url) => HttpResponse::Ok().json(portal_url),
         Err(e) => {
@@ -188,6 +256,9 @@ async fn create_customer_portal(
 }
 
 /// Webhook Stripe pour les événements de paiement
+///
+/// Le corps est lu en `web::Bytes` et jamais désérialisé avant vérification:
+/// la signature porte sur les octets exacts envoyés par Stripe.
 async fn stripe_webhook(
     billing_service: web::Data<BillingService>,
     req: actix_web::HttpRequest,
@@ -218,7 +289,7 @@ async fn stripe_webhook(
 
 // Structures de requête
 #[derive(Debug, serde::Deserialize)]
-struct UpdateSubscriptionRequest {
+pub(crate) struct UpdateSubscriptionRequest {
     plan: String,
     payment_method_id: Option<String>,
 }
@@ -230,8 +301,143 @@ struct CreditHistoryQuery {
 }
 
 #[derive(Debug, serde::Deserialize)]
-struct CreateCheckoutRequest {
+pub(crate) struct CreateCheckoutRequest {
     plan: String,
     success_url: String,
     cancel_url: String,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SubscriptionPlan;
+    use crate::services::AuditFilter;
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn cancelling_a_subscription_writes_an_audit_row() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let audit = Arc::new(AuditRepository::new(&db));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(testing::billing_service(db.clone(), &config)))
+                .app_data(web::Data::from(audit.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/billing/subscription/cancel")
+            .insert_header(testing::bearer(&config, &user))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let filter = AuditFilter {
+            actor: Some(user.id),
+            action: Some("billing.subscription_cancel".to_string()),
+            ..Default::default()
+        };
+        let (entries, total) = audit
+            .list(&filter, Pagination::from_params(None, None, 100).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(entries[0].resource_type.as_deref(), Some("subscription"));
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn impersonation_token_cannot_cancel_and_the_refusal_is_audited() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let admin = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let audit = Arc::new(AuditRepository::new(&db));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(testing::billing_service(db.clone(), &config)))
+                .app_data(web::Data::from(audit.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let token = crate::utils::security::generate_impersonation_token(user.id, &user.email, admin.id, &config.jwt_secret, 15);
+        let request = test::TestRequest::post()
+            .uri("/billing/subscription/cancel")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // L'abonnement de la cible est intact
+        let subscription = db.get_user_subscription(user.id).await.unwrap();
+        assert_eq!(subscription.plan, SubscriptionPlan::Starter);
+
+        let filter = AuditFilter {
+            actor: Some(admin.id),
+            action: Some("impersonation.denied".to_string()),
+            ..Default::default()
+        };
+        let (entries, total) = audit
+            .list(&filter, Pagination::from_params(None, None, 100).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(entries[0].resource_id, Some(user.id));
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn webhook_accepts_signed_payloads_and_rejects_tampered_ones() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let secret = "whsec_test_secret";
+        let billing = BillingService::new(
+            db,
+            String::new(),
+            secret.to_string(),
+            config.stripe_currency.clone(),
+            config.stripe_trial_period_days,
+            config.retention_policy(),
+            config.storage_quota_policy(),
+        );
+        let app = test::init_service(App::new().app_data(web::Data::new(billing)).configure(configure_routes)).await;
+
+        // Événement ignoré par le service: seule la signature est en jeu
+        let timestamp = chrono::Utc::now().timestamp();
+        let payload = format!(
+            r#"{{"id":"evt_test","object":"event","type":"customer.created","created":{0},"livemode":false,"pending_webhooks":0,"data":{{"object":{{"id":"cus_test","object":"customer","created":{0},"livemode":false}}}}}}"#,
+            timestamp
+        );
+        let signature = format!(
+            "t={},v1={}",
+            timestamp,
+            crate::utils::security::sign_webhook_payload(secret, timestamp, &payload)
+        );
+        let post = |body: String| {
+            test::TestRequest::post()
+                .uri("/billing/webhook")
+                .insert_header(("Stripe-Signature", signature.clone()))
+                .insert_header(("Content-Type", "application/json"))
+                .set_payload(body)
+                .to_request()
+        };
+
+        let response = test::call_service(&app, post(payload.clone())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let tampered = payload.replace("cus_test", "cus_evil");
+        let response = test::call_service(&app, post(tampered)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
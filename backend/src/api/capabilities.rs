@@ -0,0 +1,16 @@
+// api/capabilities.rs
+use crate::core::JobService;
+use actix_web::{web, HttpResponse, Responder};
+
+/// Configure les routes des capacités du serveur
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/capabilities", web::get().to(get_capabilities));
+}
+
+/// Méthodes de quantification disponibles (backends Python sondés au démarrage)
+async fn get_capabilities(job_service: web::Data<JobService>) -> impl Responder {
+    match job_service.get_capabilities() {
+        Some(capabilities) => HttpResponse::Ok().json(capabilities),
+        None => HttpResponse::ServiceUnavailable().json("Capacités pas encore sondées"),
+    }
+}
@@ -1,9 +1,13 @@
 // api/file.rs
-use crate::models::{ModelFile, FileUpload, FileMetadata, PaginatedResponse};
+use crate::models::{ModelFile, FileUpload, FileMetadata, Pagination};
 use crate::api::AuthenticatedUser;
 use crate::services::storage::FileStorage;
+use crate::core::billing_service::BillingService;
+use crate::core::job_service::JobService;
+use crate::services::cache::Cache;
+use crate::utils::config::Config;
 use actix_multipart::Multipart;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
 use futures_util::StreamExt as _;
 use validator::Validate;
 
@@ -21,19 +25,58 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             // Supprimer un fichier
             .route("/{file_id}", web::delete().to(delete_file))
             // Télécharger un fichier
-            .route("/{file_id}/download", web::get().to(download_file)),
+            .route("/{file_id}/download", web::get().to(download_file))
+            // Analyse détaillée (statistiques des poids, méthode conseillée)
+            .route("/{file_id}/analysis", web::get().to(analyze_file)),
     );
 }
 
 /// Uploader un fichier modèle
+///
+/// Champ `file` pour le modèle; un modèle ONNX peut être accompagné de ses
+/// fichiers de poids externes (champs `external_data`, répétables).
+///
+/// Avec l'en-tête `X-Content-SHA256`, le modèle est refusé (422) si son
+/// empreinte ne correspond pas, avant toute écriture en stockage ou en base.
 async fn upload_file(
+    req: HttpRequest,
     user: AuthenticatedUser,
     storage: web::Data<FileStorage>,
+    job_service: web::Data<JobService>,
+    billing_service: web::Data<BillingService>,
+    cache: web::Data<Cache>,
+    config: web::Data<Config>,
     mut payload: Multipart,
 ) -> impl Responder {
+    // Limiter les uploads simultanés (créneau libéré à la fin du handler)
+    let _upload_slot = match cache.acquire_upload_slot(user.id, config.max_concurrent_uploads_per_user).await {
+        Ok(slot) => slot,
+        Err(crate::utils::error::AppError::ResourceBusy) => {
+            return HttpResponse::TooManyRequests().json("Trop d'uploads simultanés");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur serveur"),
+    };
+    
+    // Empreinte attendue par le client (vérifiée avant tout stockage)
+    let expected_checksum = match expected_sha256(&req) {
+        Ok(expected) => expected,
+        Err(message) => return HttpResponse::BadRequest().json(message),
+    };
+    
+    // Hash SHA256 calculé au fil de la réception
+    use sha2::{Sha256, Digest};
+    let mut hasher = Sha256::new();
+    
+    // Limite propre à cette route (les autres corps sont plafonnés à MAX_JSON_PAYLOAD_KB)
+    let max_upload_bytes = config.max_upload_size_mb as usize * 1024 * 1024;
+    let too_large = || HttpResponse::PayloadTooLarge()
+        .json(format!("Fichier trop volumineux (max {} Mo)", config.max_upload_size_mb));
+    let mut received = 0usize;
+    
     let mut file_data = Vec::new();
     let mut filename = None;
     let mut content_type = None;
+    let mut external_data: Vec<(String, Vec<u8>)> = Vec::new();
     
     // Lire le multipart form
     while let Some(item) = payload.next().await {
@@ -41,7 +84,30 @@ async fn upload_file(
             Ok(mut field) => {
                 let field_name = field.name().to_string();
                 
-                if field_name == "file" {
+                if field_name == "external_data" {
+                    let data_name = match field.content_disposition().get_filename() {
+                        Some(name) => name.to_string(),
+                        None => return HttpResponse::BadRequest().json("Fichier de données externes sans nom"),
+                    };
+                    
+                    let mut data = Vec::new();
+                    while let Some(chunk) = field.next().await {
+                        match chunk {
+                            Ok(bytes) => {
+                                received += bytes.len();
+                                if received > max_upload_bytes {
+                                    return too_large();
+                                }
+                                data.extend_from_slice(&bytes);
+                            }
+                            Err(e) => {
+                                return HttpResponse::InternalServerError()
+                                    .json(format!("Erreur de lecture du fichier: {}", e));
+                            }
+                        }
+                    }
+                    external_data.push((data_name, data));
+                } else if field_name == "file" {
                     filename = field.content_disposition().get_filename().map(|s| s.to_string());
                     content_type = field.content_type().map(|ct| ct.to_string());
                     
@@ -49,6 +115,11 @@ async fn upload_file(
                     while let Some(chunk) = field.next().await {
                         match chunk {
                             Ok(data) => {
+                                received += data.len();
+                                if received > max_upload_bytes {
+                                    return too_large();
+                                }
+                                hasher.update(&data);
                                 file_data.extend_from_slice(&data);
                             }
                             Err(e) => {
@@ -71,46 +142,122 @@ async fn upload_file(
         None => return HttpResponse::BadRequest().json("Aucun fichier fourni"),
     };
     
-    // Vérifier la taille du fichier (max 10GB)
-    if file_data.len() > 10 * 1024 * 1024 * 1024 {
-        return HttpResponse::PayloadTooLarge().json("Fichier trop volumineux (max 10GB)");
+    // Extension autorisée par la configuration
+    let extension = file_extension(&filename);
+    if !config.allowed_upload_extensions.iter().any(|allowed| *allowed == extension) {
+        return HttpResponse::UnsupportedMediaType().json(format!(
+            "Extension non autorisée (acceptées: {})",
+            config.allowed_upload_extensions.join(", ")
+        ));
+    }
+    
+    // Le quota couvre le modèle et ses données externes
+    let total_size = file_data.len() + external_data.iter().map(|(_, data)| data.len()).sum::<usize>();
+    
+    // Vérifier le quota de stockage du plan
+    match billing_service.check_storage_quota(user.id, total_size as i64).await {
+        Ok(_) => {}
+        Err(crate::utils::error::AppError::StorageQuotaExceeded) => {
+            return HttpResponse::PayloadTooLarge().json("Quota de stockage dépassé");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification du quota"),
     }
     
-    // Calculer le hash SHA256
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    hasher.update(&file_data);
     let checksum = format!("{:x}", hasher.finalize());
     
-    // Détecter le format du fichier
-    let format = detect_file_format(&filename, content_type.as_deref());
+    // Contenu altéré pendant le transfert: rien n'est enregistré
+    if let Some(expected) = expected_checksum {
+        if expected != checksum {
+            return HttpResponse::UnprocessableEntity().json(format!(
+                "Empreinte SHA-256 différente de celle annoncée (reçu {})",
+                checksum
+            ));
+        }
+    }
+    
+    // Détecter le format du fichier (l'extension doit correspondre au contenu)
+    let format = match detect_file_format(&filename, content_type.as_deref(), &file_data) {
+        Ok(format) => format,
+        Err(message) => return HttpResponse::UnprocessableEntity().json(message),
+    };
+    
+    // Données externes: uniquement ONNX, noms uniques et distincts du modèle
+    if !external_data.is_empty() {
+        if !matches!(format, crate::models::ModelFormat::Onnx) {
+            return HttpResponse::BadRequest().json("Les données externes ne concernent que les modèles ONNX");
+        }
+        let mut names = std::collections::HashSet::new();
+        names.insert(filename.as_str());
+        if !external_data.iter().all(|(name, _)| names.insert(name.as_str())) {
+            return HttpResponse::BadRequest().json("Noms de fichiers de données externes en double");
+        }
+        for (data_name, data) in &external_data {
+            if let Err(e) = storage.check_external_data(data_name, data.len()) {
+                return upload_error_response(e);
+            }
+        }
+    }
+    
+    // Expiration selon la rétention du plan
+    let expires_at = match billing_service.file_expiry_for(user.id).await {
+        Ok(expires_at) => expires_at,
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification de l'abonnement"),
+    };
     
     // Uploader le fichier vers le stockage
-    match storage.upload_file(
+    let model = match storage.upload_file(
         user.id,
         &filename,
         &file_data,
         &checksum,
         format,
+        expires_at,
     ).await {
-        Ok(file_metadata) => {
+        Ok(model) => model,
+        Err(e) => return upload_error_response(e),
+    };
+    
+    // Stocker les données externes sous le même modèle logique; au moindre
+    // échec, tout ce qui a été stocké est supprimé (pas de modèle sans poids)
+    let mut stored = vec![model.clone()];
+    for (data_name, data) in &external_data {
+        match storage.upload_external_data(&model, user.id, data_name, data, expires_at).await {
+            Ok(data_file) => stored.push(data_file),
+            Err(e) => {
+                storage.discard_objects(&stored).await;
+                return upload_error_response(e);
+            }
+        }
+    }
+    
+    match job_service.register_model_group(model, stored[1..].to_vec()).await {
+        Ok(uploaded) => {
             // Analyser le modèle pour extraire les métadonnées
             let metadata = analyze_model_metadata(&file_data, &filename).await;
-            storage.update_file_metadata(file_metadata.id, metadata).await.ok();
+            storage.update_file_metadata(uploaded.file.id, metadata).await.ok();
             
-            HttpResponse::Created().json(file_metadata)
+            HttpResponse::Created().json(uploaded)
         }
         Err(e) => {
-            match e {
-                crate::utils::error::AppError::InvalidFileFormat => {
-                    HttpResponse::BadRequest().json("Format de fichier non supporté")
-                }
-                crate::utils::error::AppError::FileTooLarge => {
-                    HttpResponse::PayloadTooLarge().json("Fichier trop volumineux")
-                }
-                _ => HttpResponse::InternalServerError().json("Erreur lors de l'upload"),
-            }
+            storage.discard_objects(&stored).await;
+            upload_error_response(e)
+        }
+    }
+}
+
+/// Réponse d'erreur d'un upload de modèle
+fn upload_error_response(e: crate::utils::error::AppError) -> HttpResponse {
+    match e {
+        crate::utils::error::AppError::InvalidFileFormat => {
+            HttpResponse::BadRequest().json("Format de fichier non supporté")
+        }
+        crate::utils::error::AppError::Validation(message) => {
+            HttpResponse::BadRequest().json(message)
         }
+        crate::utils::error::AppError::FileTooLarge => {
+            HttpResponse::PayloadTooLarge().json("Fichier trop volumineux")
+        }
+        _ => HttpResponse::InternalServerError().json("Erreur lors de l'upload"),
     }
 }
 
@@ -118,24 +265,23 @@ async fn upload_file(
 async fn list_files(
     user: AuthenticatedUser,
     storage: web::Data<FileStorage>,
+    config: web::Data<Config>,
     query: web::Query<ListFilesQuery>,
 ) -> impl Responder {
+    let pagination = match Pagination::from_params(query.page, query.per_page, config.pagination_max_per_page) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
     match storage.list_user_files(
         user.id,
         query.format.as_deref(),
-        query.page.unwrap_or(1),
-        query.per_page.unwrap_or(20),
+        pagination.page,
+        pagination.per_page,
     ).await {
         Ok(files) => {
             let total = files.len() as i64;
-            let response = PaginatedResponse {
-                items: files,
-                total,
-                page: query.page.unwrap_or(1),
-                per_page: query.per_page.unwrap_or(20),
-                total_pages: (total as f64 / query.per_page.unwrap_or(20) as f64).ceil() as i64,
-            };
-            HttpResponse::Ok().json(response)
+            HttpResponse::Ok().json(pagination.response(files, total))
         }
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
@@ -201,58 +347,134 @@ async fn delete_file(
 async fn download_file(
     user: AuthenticatedUser,
     storage: web::Data<FileStorage>,
+    job_service: web::Data<JobService>,
     file_id: web::Path<uuid::Uuid>,
 ) -> impl Responder {
-    match storage.get_file_metadata(*file_id).await {
-        Ok(file_metadata) => {
-            // Vérifier que l'utilisateur est propriétaire du fichier
-            if file_metadata.user_id != user.id {
-                return HttpResponse::Forbidden().json("Accès non autorisé");
-            }
-            
-            // Générer une URL de téléchargement signée
-            match storage.generate_download_url(*file_id).await {
-                Ok(download_url) => {
-                    let response = crate::models::file::FileDownload {
-                        id: *file_id,
-                        filename: file_metadata.filename,
-                        file_size: file_metadata.file_size,
-                        download_url,
-                        expires_at: chrono::Utc::now() + chrono::Duration::hours(24),
-                    };
-                    HttpResponse::Ok().json(response)
+    let file = match job_service.get_user_file(user.id, *file_id).await {
+        Ok(file) => file,
+        Err(e) => {
+            return match e {
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Fichier non trouvé")
                 }
-                Err(e) => HttpResponse::InternalServerError().json("Erreur de génération du lien"),
-            }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::FileExpired => {
+                    HttpResponse::Gone().json("Fichier expiré")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            };
+        }
+    };
+    
+    // Objet chiffré ou compressé: téléchargement restauré par le proxy de l'API,
+    // sinon URL signée (durée configurée, cohérente avec `expires_at`)
+    let download_url = match storage.requires_proxy_download(&file).await {
+        Ok(true) => Ok(format!("/api/models/{}/download", file.id)),
+        Ok(false) => storage.generate_download_url(&file, storage.download_url_expiry_hours()).await,
+        Err(e) => Err(e),
+    };
+    match download_url {
+        Ok(download_url) => {
+            let response = crate::models::file::FileDownload {
+                id: file.id,
+                filename: file.original_filename.clone(),
+                file_size: file.file_size,
+                download_url,
+                expires_at: storage.download_url_expires_at(),
+            };
+            HttpResponse::Ok().json(response)
         }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur de génération du lien"),
+    }
+}
+
+/// Analyser un fichier modèle (lecture seule, sans quantification)
+async fn analyze_file(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    file_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match job_service.analyze_file(user.id, *file_id).await {
+        Ok((analysis, recommendation)) => HttpResponse::Ok().json(serde_json::json!({
+            "analysis": analysis,
+            "recommendation": recommendation,
+        })),
         Err(e) => {
             match e {
                 crate::utils::error::AppError::FileNotFound => {
                     HttpResponse::NotFound().json("Fichier non trouvé")
                 }
-                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+                crate::utils::error::AppError::InsufficientDiskSpace(_) => {
+                    HttpResponse::InsufficientStorage().json("Espace disque insuffisant pour l'analyse")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur lors de l'analyse"),
             }
         }
     }
 }
 
+/// Empreinte SHA-256 annoncée par le client (`X-Content-SHA256`, hexadécimal)
+fn expected_sha256(req: &HttpRequest) -> Result<Option<String>, &'static str> {
+    let Some(value) = req.headers().get("X-Content-SHA256") else {
+        return Ok(None);
+    };
+    
+    let value = value.to_str().map_err(|_| "En-tête X-Content-SHA256 invalide")?.trim();
+    if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("En-tête X-Content-SHA256 invalide (64 caractères hexadécimaux attendus)");
+    }
+    
+    Ok(Some(value.to_ascii_lowercase()))
+}
+
+/// Extension d'un nom de fichier, en minuscules
+pub(crate) fn file_extension(filename: &str) -> String {
+    match filename.rsplit_once('.') {
+        Some((_, extension)) => extension.to_lowercase(),
+        None => String::new(),
+    }
+}
+
 /// Détecter le format du fichier
-fn detect_file_format(filename: &str, content_type: Option<&str>) -> crate::models::ModelFormat {
-    let ext = filename.rsplit('.').next().unwrap_or("").to_lowercase();
+///
+/// Le contenu reconnu doit faire partie des formats que l'extension peut
+/// désigner (ex: `.onnx` sur un checkpoint PyTorch est refusé); il tranche
+/// aussi les extensions ambiguës comme `.bin`.
+pub(crate) fn detect_file_format(
+    filename: &str,
+    content_type: Option<&str>,
+    data: &[u8],
+) -> Result<crate::models::ModelFormat, String> {
+    use crate::models::ModelFormat;
+    
+    let extension = file_extension(filename);
+    let candidates = ModelFormat::for_extension(&extension);
+    let sniffed = crate::services::storage::detect_format_from_header(data);
     
-    match ext.as_str() {
-        "pt" | "pth" => crate::models::ModelFormat::PyTorch,
-        "bin" | "safetensors" => crate::models::ModelFormat::Safetensors,
-        "onnx" => crate::models::ModelFormat::Onnx,
-        "gguf" => crate::models::ModelFormat::Gguf,
-        _ => {
+    match (candidates.first(), sniffed) {
+        (Some(declared), None) => Ok(declared.clone()),
+        (Some(_), Some(sniffed)) => {
+            if candidates.iter().any(|format| std::mem::discriminant(format) == std::mem::discriminant(&sniffed)) {
+                Ok(sniffed)
+            } else {
+                Err(format!(
+                    "L'extension .{} ne correspond pas au contenu du fichier ({} détecté)",
+                    extension,
+                    sniffed.as_str()
+                ))
+            }
+        }
+        (None, Some(sniffed)) => Ok(sniffed),
+        (None, None) => {
             // Essayer avec le content-type
             if let Some(ct) = content_type {
                 if ct.contains("application/octet-stream") && filename.contains(".safetensors") {
-                    return crate::models::ModelFormat::Safetensors;
+                    return Ok(ModelFormat::Safetensors);
                 }
             }
-            crate::models::ModelFormat::PyTorch // Par défaut
+            Ok(ModelFormat::PyTorch) // Par défaut
         }
     }
 }
@@ -300,4 +522,207 @@ struct ListFilesQuery {
     format: Option<String>,
     page: Option<i64>,
     per_page: Option<i64>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SubscriptionPlan;
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+    use sha2::{Digest, Sha256};
+
+    const BOUNDARY: &str = "frontiere-de-test";
+
+    /// Corps multipart avec le champ `file`
+    fn multipart(filename: &str, data: &[u8]) -> Vec<u8> {
+        multipart_fields(&[("file", filename, data)])
+    }
+
+    /// Corps multipart avec plusieurs champs fichier (nom du champ, nom du fichier, contenu)
+    fn multipart_fields(fields: &[(&str, &str, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, filename, data) in fields {
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: application/octet-stream\r\n\r\n",
+                    BOUNDARY, name, filename
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    /// Nombre de fichiers sous `dir`, récursivement
+    fn count_files(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| if entry.path().is_dir() { count_files(&entry.path()) } else { 1 })
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn upload_is_checked_against_the_announced_sha256() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage_dir = testing::scratch_dir("storage");
+        let storage = testing::storage_in(&storage_dir);
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::from(storage))
+                .app_data(web::Data::new(testing::job_service(db.clone(), &config).await))
+                .app_data(web::Data::new(testing::billing_service(db.clone(), &config)))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let data = b"poids du modele";
+        let upload = |checksum: String| {
+            test::TestRequest::post()
+                .uri("/files/upload")
+                .insert_header(testing::bearer(&config, &user))
+                .insert_header(("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY)))
+                .insert_header(("X-Content-SHA256", checksum))
+                .set_payload(multipart("model.bin", data))
+                .to_request()
+        };
+
+        // Empreinte d'un autre contenu: refusé, rien n'est stocké
+        let altered = format!("{:x}", Sha256::digest(b"poids alteres"));
+        let response = test::call_service(&app, upload(altered)).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(count_files(&storage_dir), 0);
+        let files = db.list_user_files(user.id, None, 1, 100).await.unwrap();
+        assert!(files.is_empty());
+
+        let expected = format!("{:x}", Sha256::digest(data));
+        let response = test::call_service(&app, upload(expected.to_uppercase())).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let file: FileMetadata = test::read_body_json(response).await;
+        assert_eq!(file.file_size, data.len() as i64);
+        assert_eq!(count_files(&storage_dir), 1);
+    }
+
+    /// En-tête safetensors minimal: longueur du JSON puis le JSON
+    fn safetensors_bytes() -> Vec<u8> {
+        let header = br#"{"__metadata__":{}}"#;
+        let mut data = (header.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(header);
+        data
+    }
+
+    #[test]
+    fn declared_extension_must_match_the_sniffed_content() {
+        use crate::models::ModelFormat;
+        let pytorch = b"PK\x03\x04checkpoint torch";
+
+        let err = detect_file_format("model.onnx", None, pytorch).unwrap_err();
+        assert!(err.contains(".onnx"), "{}", err);
+        assert!(detect_file_format("model.gguf", None, &safetensors_bytes()).is_err());
+
+        assert!(matches!(detect_file_format("model.pt", None, pytorch), Ok(ModelFormat::PyTorch)));
+        assert!(matches!(detect_file_format("model.safetensors", None, &safetensors_bytes()), Ok(ModelFormat::Safetensors)));
+        // `.bin` est ambigu: le contenu tranche
+        assert!(matches!(detect_file_format("model.bin", None, pytorch), Ok(ModelFormat::PyTorch)));
+        assert!(matches!(detect_file_format("model.bin", None, &safetensors_bytes()), Ok(ModelFormat::Safetensors)));
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn upload_rejects_an_extension_that_contradicts_the_content() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::from(testing::storage()))
+                .app_data(web::Data::new(testing::job_service(db.clone(), &config).await))
+                .app_data(web::Data::new(testing::billing_service(db.clone(), &config)))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let upload = |filename: &str, data: &[u8]| {
+            test::TestRequest::post()
+                .uri("/files/upload")
+                .insert_header(testing::bearer(&config, &user))
+                .insert_header(("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY)))
+                .set_payload(multipart(filename, data))
+                .to_request()
+        };
+
+        let response = test::call_service(&app, upload("model.onnx", b"PK\x03\x04checkpoint torch")).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = test::call_service(&app, upload("notes.txt", b"texte")).await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let response = test::call_service(&app, upload("model.safetensors", &safetensors_bytes())).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let file: FileMetadata = test::read_body_json(response).await;
+        assert!(matches!(file.format, crate::models::ModelFormat::Safetensors));
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn onnx_upload_stores_the_model_and_its_external_data_or_nothing() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage_dir = testing::scratch_dir("storage");
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::from(testing::storage_in(&storage_dir)))
+                .app_data(web::Data::new(testing::job_service(db.clone(), &config).await))
+                .app_data(web::Data::new(testing::billing_service(db.clone(), &config)))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let upload = |data_name: &str| {
+            test::TestRequest::post()
+                .uri("/files/upload")
+                .insert_header(testing::bearer(&config, &user))
+                .insert_header(("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY)))
+                .set_payload(multipart_fields(&[
+                    ("file", "model.onnx", b"graphe onnx"),
+                    ("external_data", data_name, b"poids externes"),
+                ]))
+                .to_request()
+        };
+
+        // Données externes refusées: ni objet stocké, ni modèle sans ses poids
+        let response = test::call_service(&app, upload("..%2Fpoids.data")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(count_files(&storage_dir), 0);
+        assert!(db.list_user_files(user.id, None, 1, 100).await.unwrap().is_empty());
+
+        // Même forme de réponse qu'un upload simple, avec les données externes
+        let response = test::call_service(&app, upload("model.onnx.data")).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let uploaded: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(uploaded["filename"], "model.onnx");
+        assert_eq!(uploaded["external_data"][0]["filename"], "model.onnx.data");
+        assert_eq!(count_files(&storage_dir), 2);
+    }
+}
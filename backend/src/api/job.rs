@@ -1,10 +1,11 @@
 // api/job.rs
-use crate::models::{Job, NewJob, JobResult, PaginatedResponse};
+use crate::models::{Job, NewJob, JobSettings, JobSource, JobResult, JobSummary, Pagination, CancelledBy};
 use crate::api::AuthenticatedUser;
 use crate::core::job_service::JobService;
 use crate::core::billing_service::BillingService;
 use crate::services::storage::FileStorage;
-use actix_web::{web, HttpResponse, Responder};
+use crate::utils::config::Config;
+use actix_web::{web, HttpResponse, Responder, ResponseError};
 use validator::Validate;
 
 /// Configure les routes des jobs
@@ -16,14 +17,44 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .route("", web::post().to(create_job))
             // Lister les jobs
             .route("", web::get().to(list_jobs))
+            // Estimer le coût d'un job avant création
+            .route("/cost", web::get().to(estimate_job_cost))
+            // Statut groupé de plusieurs jobs (tableaux de bord)
+            .route("/status/batch", web::post().to(batch_job_status))
             // Obtenir un job spécifique
             .route("/{job_id}", web::get().to(get_job))
+            // Rapport de quantification
+            .route("/{job_id}/report", web::get().to(get_job_report))
+            // Relancer la mesure de qualité sans requantifier
+            .route("/{job_id}/validate", web::post().to(revalidate_job_quality))
+            // Position dans la queue et délais estimés
+            .route("/{job_id}/queue", web::get().to(get_job_queue_estimate))
             // Annuler un job
             .route("/{job_id}/cancel", web::post().to(cancel_job))
             // Télécharger le résultat
             .route("/{job_id}/download", web::get().to(download_result))
+            // Régénérer une URL de téléchargement expirée
+            .route("/{job_id}/download-url", web::post().to(download_result))
+            // Télécharger le résultat via l'API (sans URL directe, reprise par Range)
+            .route("/{job_id}/stream", web::get().to(stream_result))
+            // Télécharger tous les fichiers produits et le rapport (zip)
+            .route("/{job_id}/download-all", web::get().to(download_all))
+            // Journal d'exécution du job
+            .route("/{job_id}/logs", web::get().to(get_job_logs))
+            // Chronologie structurée du cycle de vie
+            .route("/{job_id}/timeline", web::get().to(get_job_timeline))
             // Obtenir la progression en temps réel (WebSocket/SSE)
-            .route("/{job_id}/progress", web::get().to(get_job_progress)),
+            .route("/{job_id}/progress", web::get().to(get_job_progress))
+            // Liens de partage publics du résultat
+            .route("/{job_id}/share", web::post().to(create_share_link))
+            .route("/{job_id}/share", web::get().to(list_share_links))
+            .route("/{job_id}/share/{share_id}", web::delete().to(revoke_share_link)),
+    );
+    
+    // Téléchargement via lien de partage: le token est la seule authentification
+    cfg.service(
+        web::scope("/shared")
+            .route("/{token}", web::get().to(download_shared_result)),
     );
 }
 
@@ -33,12 +64,13 @@ async fn create_job(
     job_service: web::Data<JobService>,
     billing_service: web::Data<BillingService>,
     storage: web::Data<FileStorage>,
+    config: web::Data<Config>,
     new_job: web::Json<NewJob>,
     req: actix_web::HttpRequest,
 ) -> impl Responder {
     // Validation
     if let Err(errors) = new_job.validate() {
-        return HttpResponse::BadRequest().json(errors);
+        return crate::utils::error::AppError::from(errors).error_response();
     }
     
     // Vérifier que l'utilisateur a suffisamment de crédits
@@ -53,6 +85,17 @@ async fn create_job(
         }
     }
     
+    // Champs omis: réglages par défaut de l'utilisateur, puis du système
+    let settings = match job_service.resolve_job_settings(user.id, &new_job).await {
+        Ok(settings) => settings,
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur serveur"),
+    };
+    
+    // Modèle référencé sur le hub Hugging Face
+    if new_job.source == JobSource::Huggingface {
+        return create_job_from_hub(&user, &job_service, &billing_service, &config, &new_job, settings).await;
+    }
+    
     // Extraire l'ID du fichier du header ou du body
     let file_id = match extract_file_id(&req) {
         Some(id) => id,
@@ -78,26 +121,126 @@ async fn create_job(
         user.id,
         file_id,
         new_job.name.clone(),
-        new_job.quantization_method.clone(),
-        new_job.output_format.clone(),
+        settings.quantization_method,
+        settings.output_format,
+        settings.config,
     ).await {
-        Ok(job) => {
-            // Consommer les crédits
-            billing_service.consume_job_credits(user.id, job.id).await.ok();
-            
-            HttpResponse::Created().json(job)
+        // Crédits débités par le service avant l'enregistrement du job
+        Ok(job) => HttpResponse::Created().json(job),
+        Err(e) => job_creation_error_response(e),
+    }
+}
+
+/// Créer un job à partir d'un dépôt Hugging Face
+async fn create_job_from_hub(
+    user: &AuthenticatedUser,
+    job_service: &JobService,
+    billing_service: &BillingService,
+    config: &Config,
+    new_job: &NewJob,
+    settings: JobSettings,
+) -> HttpResponse {
+    let repo_id = match &new_job.repo_id {
+        Some(repo_id) => repo_id.clone(),
+        None => return HttpResponse::BadRequest().json("repo_id requis pour une source Hugging Face"),
+    };
+    let revision = new_job.revision.clone().unwrap_or_else(|| "main".to_string());
+    
+    // Limite de taille selon le plan
+    let max_size_bytes = match billing_service.get_user_subscription(user.id).await {
+        Ok(subscription) => config.max_file_size_mb_for(&subscription.plan) * 1024 * 1024,
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification de l'abonnement"),
+    };
+    
+    match job_service.create_job_from_hub(
+        user.id,
+        new_job.name.clone(),
+        settings.quantization_method,
+        settings.output_format,
+        repo_id,
+        revision,
+        max_size_bytes,
+        settings.config,
+    ).await {
+        // Crédits débités une fois le dépôt validé, avant l'enregistrement du job
+        Ok(job) => HttpResponse::Created().json(job),
+        Err(crate::utils::error::AppError::NotFound(_)) => {
+            HttpResponse::NotFound().json("Dépôt Hugging Face introuvable")
         }
-        Err(e) => {
-            match e {
-                crate::utils::error::AppError::InvalidFileFormat => {
-                    HttpResponse::BadRequest().json("Format de fichier non supporté")
-                }
-                crate::utils::error::AppError::InsufficientCredits => {
-                    HttpResponse::PaymentRequired().json("Crédits insuffisants")
-                }
-                _ => HttpResponse::InternalServerError().json("Erreur lors de la création du job"),
-            }
+        Err(e) => job_creation_error_response(e),
+    }
+}
+
+/// Traduire une erreur de création de job (fichier ou dépôt) en réponse HTTP
+fn job_creation_error_response(e: crate::utils::error::AppError) -> HttpResponse {
+    match e {
+        crate::utils::error::AppError::InvalidFileFormat => {
+            HttpResponse::BadRequest().json("Format de fichier non supporté")
+        }
+        crate::utils::error::AppError::CorruptInput(reason) => {
+            HttpResponse::BadRequest().json(format!("Fichier source invalide: {}", reason))
+        }
+        crate::utils::error::AppError::AlreadyQuantized(detected) => {
+            HttpResponse::UnprocessableEntity().json(format!(
+                "Modèle déjà quantifié ({} détecté); allow_quantized_input pour forcer",
+                detected
+            ))
+        }
+        crate::utils::error::AppError::FileTooLarge => {
+            HttpResponse::PayloadTooLarge().json("Modèle trop volumineux pour votre plan")
+        }
+        crate::utils::error::AppError::InvalidCombination => {
+            HttpResponse::BadRequest().json("Méthode incompatible avec le format du modèle")
+        }
+        crate::utils::error::AppError::InsufficientCredits => {
+            HttpResponse::PaymentRequired().json("Crédits insuffisants")
+        }
+        crate::utils::error::AppError::ConcurrentModification => {
+            HttpResponse::Conflict().json("Solde de crédits modifié en parallèle, réessayez")
         }
+        crate::utils::error::AppError::OutputFormatNotInPlan(format) => {
+            HttpResponse::PaymentRequired().json(format!(
+                "Le format {} n'est pas inclus dans votre plan; passez à un plan supérieur pour l'obtenir",
+                format
+            ))
+        }
+        crate::utils::error::AppError::TooManyActiveJobs(limit) => {
+            HttpResponse::TooManyRequests().json(format!("Trop de jobs en cours (maximum {})", limit))
+        }
+        crate::utils::error::AppError::DuplicateJobName(suggestion) => {
+            HttpResponse::Conflict().json(serde_json::json!({
+                "error": "Un job actif porte déjà ce nom",
+                "suggested_name": suggestion,
+            }))
+        }
+        crate::utils::error::AppError::MethodUnavailable(detail) => {
+            HttpResponse::ServiceUnavailable().json(format!("Méthode indisponible sur ce serveur: {}", detail))
+        }
+        crate::utils::error::AppError::Validation(message) => {
+            HttpResponse::BadRequest().json(message)
+        }
+        _ => HttpResponse::InternalServerError().json("Erreur lors de la création du job"),
+    }
+}
+
+/// Estimer le coût d'un job (crédits et euros) avant sa création
+async fn estimate_job_cost(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    query: web::Query<JobCostQuery>,
+) -> impl Responder {
+    let method = match query.method.parse::<crate::models::QuantizationMethod>() {
+        Ok(method) => method,
+        Err(e) => return crate::utils::error::AppError::from(e).error_response(),
+    };
+    
+    if query.size < 0 {
+        return HttpResponse::BadRequest().json("La taille doit être positive");
+    }
+    
+    match job_service.estimate_cost(user.id, &method, query.size, query.parameter_count).await {
+        Ok(cost) => HttpResponse::Ok().json(cost),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
 }
 
@@ -105,24 +248,19 @@ async fn create_job(
 async fn list_jobs(
     user: AuthenticatedUser,
     job_service: web::Data<JobService>,
+    config: web::Data<Config>,
     query: web::Query<ListJobsQuery>,
 ) -> impl Responder {
-    match job_service.list_user_jobs(
-        user.id,
-        query.status.as_deref(),
-        query.page.unwrap_or(1),
-        query.per_page.unwrap_or(20),
-    ).await {
+    let pagination = match Pagination::from_params(query.page, query.per_page, config.pagination_max_per_page) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    match job_service.list_user_jobs(user.id, query.status.as_deref(), pagination).await {
         Ok(jobs) => {
             let total = jobs.len() as i64;
-            let response = PaginatedResponse {
-                items: jobs,
-                total,
-                page: query.page.unwrap_or(1),
-                per_page: query.per_page.unwrap_or(20),
-                total_pages: (total as f64 / query.per_page.unwrap_or(20) as f64).ceil() as i64,
-            };
-            HttpResponse::Ok().json(response)
+            let summaries: Vec<JobSummary> = jobs.iter().map(JobSummary::from).collect();
+            HttpResponse::Ok().json(pagination.response(summaries, total))
         }
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
@@ -133,6 +271,196 @@ async fn get_job(
     user: AuthenticatedUser,
     job_service: web::Data<JobService>,
     job_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    match job_service.get_job(*job_id).await {
+        Ok(job) => {
+            // Vérifier que l'utilisateur est propriétaire du job
+            if job.user_id != user.id {
+                return HttpResponse::Forbidden().json("Accès non autorisé");
+            }
+            
+            // 304 si le job n'a pas changé depuis la dernière lecture
+            crate::api::conditional_json(&req, &job.etag(), &job)
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Obtenir la position d'un job dans la queue et l'heure de fin estimée
+async fn get_job_queue_estimate(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    job_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    let job = match job_service.get_job(*job_id).await {
+        Ok(job) => job,
+        Err(crate::utils::error::AppError::JobNotFound) => {
+            return HttpResponse::NotFound().json("Job non trouvé");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur serveur"),
+    };
+    
+    // Vérifier que l'utilisateur est propriétaire du job
+    if job.user_id != user.id {
+        return HttpResponse::Forbidden().json("Accès non autorisé");
+    }
+    
+    match job_service.get_queue_estimate(&job).await {
+        Ok(estimate) => HttpResponse::Ok().json(estimate),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Obtenir le statut de plusieurs jobs en une requête
+async fn batch_job_status(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    request: web::Json<BatchStatusRequest>,
+) -> impl Responder {
+    if let Err(errors) = request.validate() {
+        return crate::utils::error::AppError::from(errors).error_response();
+    }
+    
+    match job_service.get_jobs_status(user.id, &request.job_ids).await {
+        Ok(statuses) => HttpResponse::Ok().json(statuses),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Obtenir le rapport de quantification d'un job
+async fn get_job_report(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    job_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    match job_service.get_job(*job_id).await {
+        Ok(job) => {
+            // Vérifier que l'utilisateur est propriétaire du job
+            if job.user_id != user.id {
+                return HttpResponse::Forbidden().json("Accès non autorisé");
+            }
+            
+            match &job.report {
+                Some(report) => crate::api::conditional_json(&req, &job.etag(), &report.0),
+                None => HttpResponse::NotFound().json("Rapport non disponible"),
+            }
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Relancer la mesure de qualité d'un job terminé
+///
+/// Les crédits débités sont rendus si la mesure échoue.
+async fn revalidate_job_quality(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    billing_service: web::Data<BillingService>,
+    config: web::Data<Config>,
+    job_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    // Propriétaire et job terminé, avant tout débit
+    let (job, _) = match job_service.get_job_output(user.id, *job_id).await {
+        Ok(output) => output,
+        Err(crate::utils::error::AppError::JobNotFound) => {
+            return HttpResponse::NotFound().json("Job non trouvé");
+        }
+        Err(crate::utils::error::AppError::Unauthorized) => {
+            return HttpResponse::Forbidden().json("Accès non autorisé");
+        }
+        Err(crate::utils::error::AppError::Validation(message)) => {
+            return HttpResponse::BadRequest().json(message);
+        }
+        Err(crate::utils::error::AppError::FileNotFound) => {
+            return HttpResponse::NotFound().json("Fichier résultat introuvable");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur serveur"),
+    };
+    
+    let cost = config.credit_cost_revalidation;
+    match billing_service.consume_revalidation_credits(user.id, &job.name, cost).await {
+        Ok(()) => {}
+        Err(crate::utils::error::AppError::InsufficientCredits) => {
+            return HttpResponse::PaymentRequired().json("Crédits insuffisants");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification des crédits"),
+    }
+    
+    match job_service.revalidate_quality(user.id, job.id).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            if cost > 0 {
+                let description = format!("Remboursement de la mesure de qualité: {}", job.name);
+                if let Err(refund_error) = billing_service.add_credits(user.id, cost, "refund", &description).await {
+                    log::warn!("Impossible de rembourser la mesure de qualité du job {}: {}", job.id, refund_error);
+                }
+            }
+            
+            match e {
+                crate::utils::error::AppError::Validation(message) => {
+                    HttpResponse::BadRequest().json(message)
+                }
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Modèle source ou résultat introuvable")
+                }
+                crate::utils::error::AppError::InsufficientDiskSpace(_) => {
+                    HttpResponse::ServiceUnavailable().json("Espace disque insuffisant, réessayez plus tard")
+                }
+                _ => HttpResponse::InternalServerError().json("Échec de la mesure de qualité"),
+            }
+        }
+    }
+}
+
+/// Obtenir la chronologie d'un job (créé, en queue, pris en charge, étapes, issue)
+async fn get_job_timeline(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    job_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match job_service.get_job(*job_id).await {
+        Ok(job) => {
+            if job.user_id != user.id {
+                return HttpResponse::Forbidden().json("Accès non autorisé");
+            }
+            
+            match job_service.get_job_timeline(job.id).await {
+                Ok(events) => HttpResponse::Ok().json(events),
+                Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Obtenir le journal d'exécution d'un job
+async fn get_job_logs(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    job_id: web::Path<uuid::Uuid>,
 ) -> impl Responder {
     match job_service.get_job(*job_id).await {
         Ok(job) => {
@@ -141,7 +469,10 @@ async fn get_job(
                 return HttpResponse::Forbidden().json("Accès non autorisé");
             }
             
-            HttpResponse::Ok().json(job)
+            match job_service.get_job_logs(job.id, false).await {
+                Ok(logs) => HttpResponse::Ok().json(logs),
+                Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
         }
         Err(e) => {
             match e {
@@ -154,12 +485,23 @@ async fn get_job(
     }
 }
 
-/// Annuler un job
+/// Annuler un job (motif facultatif dans le corps)
 async fn cancel_job(
     user: AuthenticatedUser,
     job_service: web::Data<JobService>,
     job_id: web::Path<uuid::Uuid>,
+    body: Option<web::Json<CancelJobRequest>>,
 ) -> impl Responder {
+    let reason = match body {
+        Some(body) => {
+            if let Err(errors) = body.validate() {
+                return crate::utils::error::AppError::from(errors).error_response();
+            }
+            body.into_inner().reason
+        }
+        None => None,
+    };
+    
     // Vérifier que l'utilisateur est propriétaire du job
     match job_service.get_job(*job_id).await {
         Ok(job) => {
@@ -173,7 +515,7 @@ async fn cancel_job(
             }
             
             // Annuler le job
-            match job_service.cancel_job(*job_id).await {
+            match job_service.cancel_job(*job_id, CancelledBy::User, reason).await {
                 Ok(_) => HttpResponse::Ok().json("Job annulé avec succès"),
                 Err(e) => HttpResponse::InternalServerError().json("Erreur lors de l'annulation"),
             }
@@ -190,48 +532,450 @@ async fn cancel_job(
 }
 
 /// Télécharger le résultat d'un job
+///
+/// Chaque appel signe une nouvelle URL: la route `POST /download-url` permet
+/// ainsi de régénérer un lien expiré sans relancer la quantification.
 async fn download_result(
     user: AuthenticatedUser,
     job_service: web::Data<JobService>,
     storage: web::Data<FileStorage>,
     job_id: web::Path<uuid::Uuid>,
 ) -> impl Responder {
-    match job_service.get_job(*job_id).await {
-        Ok(job) => {
-            // Vérifier que l'utilisateur est propriétaire du job
-            if job.user_id != user.id {
-                return HttpResponse::Forbidden().json("Accès non autorisé");
+    let (job, file) = match job_service.get_job_output(user.id, *job_id).await {
+        Ok(output) => output,
+        Err(e) => {
+            return match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::Validation(message) => {
+                    HttpResponse::BadRequest().json(message)
+                }
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Fichier résultat introuvable")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            };
+        }
+    };
+    
+    // Objet chiffré ou compressé: téléchargement restauré par le proxy de l'API
+    let download_url = match storage.requires_proxy_download(&file).await {
+        Ok(true) => Ok(format!("/api/jobs/{}/stream", job.id)),
+        // Durée configurée: l'URL expire bien à `expires_at`
+        Ok(false) => storage.generate_download_url(&file, storage.download_url_expiry_hours()).await,
+        Err(e) => Err(e),
+    };
+    match download_url {
+        Ok(download_url) => {
+            let response = crate::models::file::FileDownload {
+                id: job.id,
+                filename: format!("{}_{}.{}", job.name, job.id, job.output_format.extension()),
+                file_size: file.file_size,
+                download_url,
+                expires_at: storage.download_url_expires_at(),
+            };
+            HttpResponse::Ok().json(response)
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur de génération du lien"),
+    }
+}
+
+/// Télécharger le résultat à travers l'API
+///
+/// Alternative aux URLs présignées pour les modèles sensibles: chaque requête
+/// est authentifiée et limitée en débit, et l'en-tête `Range` permet de
+/// reprendre un téléchargement interrompu.
+async fn stream_result(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    storage: web::Data<FileStorage>,
+    cache: web::Data<crate::services::Cache>,
+    config: web::Data<Config>,
+    job_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    // Limite par utilisateur (Redis indisponible: on laisse passer)
+    let rate_key = format!("download:{}", user.id);
+    match cache.check_rate_limit(&rate_key, config.download_proxy_requests_per_minute, 60).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::TooManyRequests().json("Trop de téléchargements, réessayez dans une minute"),
+        Err(e) => log::warn!("Limitation des téléchargements indisponible: {}", e),
+    }
+    
+    let (job, file) = match job_service.get_job_output(user.id, *job_id).await {
+        Ok(output) => output,
+        Err(e) => {
+            return match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::Validation(message) => {
+                    HttpResponse::BadRequest().json(message)
+                }
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Fichier résultat introuvable")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            };
+        }
+    };
+    
+    stream_output(&storage, &job, &file, &req, None).await
+}
+
+/// Octets réservés sur le budget d'un lien de partage
+///
+/// Ceux qui n'ont pas été envoyés quand le corps est abandonné (client
+/// déconnecté, erreur de lecture, réponse d'erreur) sont rendus au lien.
+struct ShareLinkMeter {
+    job_service: web::Data<JobService>,
+    link_id: uuid::Uuid,
+    unsent: u64,
+}
+
+impl Drop for ShareLinkMeter {
+    fn drop(&mut self) {
+        if self.unsent == 0 {
+            return;
+        }
+        
+        let (job_service, link_id, unsent) = (self.job_service.clone(), self.link_id, self.unsent);
+        tokio::spawn(async move {
+            if let Err(e) = job_service.release_share_link_bytes(link_id, unsent).await {
+                log::warn!("Octets non servis du lien {} non rendus: {}", link_id, e);
             }
-            
-            // Vérifier que le job est terminé avec succès
-            if !job.is_completed() {
-                return HttpResponse::BadRequest().json("Le job n'est pas encore terminé");
+        });
+    }
+}
+
+/// Réponse de téléchargement du résultat, avec prise en charge de `Range`
+///
+/// `meter` décompte les octets réellement envoyés pour un lien de partage.
+async fn stream_output(
+    storage: &FileStorage,
+    job: &Job,
+    file: &crate::models::ModelFile,
+    req: &actix_web::HttpRequest,
+    meter: Option<ShareLinkMeter>,
+) -> HttpResponse {
+    use crate::utils::helpers::{parse_byte_range, ByteRange};
+    use actix_web::http::header;
+    
+    let size = file.file_size.max(0) as u64;
+    let range = req.headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    
+    let (start, end, partial) = match parse_byte_range(range, size) {
+        ByteRange::Full if size == 0 => (0, 0, false),
+        ByteRange::Full => (0, size - 1, false),
+        ByteRange::Partial(start, end) => (start, end, true),
+        ByteRange::Unsatisfiable => {
+            return HttpResponse::RangeNotSatisfiable()
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", size)))
+                .finish();
+        }
+    };
+    
+    let body = if size == 0 {
+        futures_util::StreamExt::boxed(futures_util::stream::empty())
+    } else {
+        match storage.stream_range(file, start, end).await {
+            Ok(body) => body,
+            Err(e) => {
+                log::error!("Lecture du résultat {} impossible: {}", job.id, e);
+                return HttpResponse::InternalServerError().json("Erreur de lecture du fichier");
             }
-            
-            // Obtenir l'URL de téléchargement
-            match storage.generate_download_url(job.output_file_id.unwrap()).await {
-                Ok(download_url) => {
-                    let response = crate::models::file::FileDownload {
-                        id: job.id,
-                        filename: format!("{}_{}.{}", job.name, job.id, job.output_format.extension()),
-                        file_size: job.quantized_size.unwrap_or(0),
-                        download_url,
-                        expires_at: chrono::Utc::now() + chrono::Duration::hours(24),
-                    };
-                    HttpResponse::Ok().json(response)
+        }
+    };
+    let body = match meter {
+        Some(mut meter) => futures_util::StreamExt::boxed(futures_util::StreamExt::map(body, move |chunk| {
+            if let Ok(bytes) = &chunk {
+                meter.unsent = meter.unsent.saturating_sub(bytes.len() as u64);
+            }
+            chunk
+        })),
+        None => body,
+    };
+    
+    let filename = format!("{}_{}.{}", job.name, job.id, job.output_format.extension());
+    let mut response = if partial {
+        let mut response = HttpResponse::PartialContent();
+        response.insert_header((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size)));
+        response
+    } else {
+        HttpResponse::Ok()
+    };
+    
+    // Taille annoncée: le corps est transmis en flux, sans être chargé en mémoire
+    let length = if size == 0 { 0 } else { end - start + 1 };
+    response
+        .content_type("application/octet-stream")
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            crate::utils::helpers::content_disposition_attachment(&filename),
+        ))
+        .no_chunking(length)
+        .streaming(body)
+}
+
+/// Créer un lien de partage public (token retourné une seule fois)
+async fn create_share_link(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    config: web::Data<Config>,
+    job_id: web::Path<uuid::Uuid>,
+    request: Option<web::Json<crate::models::NewJobShareLink>>,
+) -> impl Responder {
+    let request = request.map(|r| r.into_inner()).unwrap_or_default();
+    
+    match job_service.create_share_link(user.id, *job_id, &request, config.share_link_max_expiry_hours).await {
+        Ok((link, token)) => {
+            let url = format!("{}/api/shared/{}", config.api_base_url.trim_end_matches('/'), token);
+            HttpResponse::Created().json(crate::models::IssuedShareLink { link, token, url })
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
                 }
-                Err(e) => HttpResponse::InternalServerError().json("Erreur de génération du lien"),
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::Validation(message) => {
+                    HttpResponse::BadRequest().json(message)
+                }
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Fichier résultat introuvable")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
             }
         }
+    }
+}
+
+/// Lister les liens de partage d'un job
+async fn list_share_links(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    job_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match job_service.list_share_links(user.id, *job_id).await {
+        Ok(links) => HttpResponse::Ok().json(links),
+        Err(crate::utils::error::AppError::JobNotFound) => {
+            HttpResponse::NotFound().json("Job non trouvé")
+        }
+        Err(crate::utils::error::AppError::Unauthorized) => {
+            HttpResponse::Forbidden().json("Accès non autorisé")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Révoquer un lien de partage
+async fn revoke_share_link(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+) -> impl Responder {
+    let (job_id, share_id) = path.into_inner();
+    
+    match job_service.revoke_share_link(user.id, job_id, share_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(crate::utils::error::AppError::NotFound(_)) => {
+            HttpResponse::NotFound().json("Lien de partage non trouvé")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Télécharger un résultat partagé (sans authentification)
+///
+/// Le plafond du lien est un budget d'octets (`max_downloads` fois la taille
+/// du fichier): les reprises par `Range` paient ce qu'elles servent, et un
+/// téléchargement interrompu récupère les octets non envoyés.
+async fn download_shared_result(
+    job_service: web::Data<JobService>,
+    storage: web::Data<FileStorage>,
+    cache: web::Data<crate::services::Cache>,
+    config: web::Data<Config>,
+    token: web::Path<String>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    use actix_web::http::header;
+    
+    // Limite par adresse IP (Redis indisponible: on laisse passer)
+    let client = req.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_default();
+    let rate_key = format!("shared-download:{}", client);
+    match cache.check_rate_limit(&rate_key, config.download_proxy_requests_per_minute, 60).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::TooManyRequests().json("Trop de téléchargements, réessayez dans une minute"),
+        Err(e) => log::warn!("Limitation des téléchargements indisponible: {}", e),
+    }
+    
+    let range = req.headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    
+    match job_service.resolve_share_link(&token, range).await {
+        Ok(download) => {
+            let meter = ShareLinkMeter {
+                job_service: job_service.clone(),
+                link_id: download.link_id,
+                unsent: download.reserved_bytes,
+            };
+            stream_output(&storage, &download.job, &download.file, &req, Some(meter)).await
+        }
         Err(e) => {
             match e {
+                crate::utils::error::AppError::InvalidToken
+                | crate::utils::error::AppError::JobNotFound
+                | crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Lien de partage invalide")
+                }
+                crate::utils::error::AppError::TokenExpired => {
+                    HttpResponse::Gone().json("Lien de partage expiré, révoqué ou épuisé")
+                }
+                crate::utils::error::AppError::Validation(message) => {
+                    HttpResponse::BadRequest().json(message)
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Télécharger tous les fichiers produits par un job et son rapport, en zip
+///
+/// L'archive est construite au fil de l'eau: les fichiers sont lus par
+/// morceaux depuis le stockage et jamais gardés en entier en mémoire.
+async fn download_all(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    storage: web::Data<FileStorage>,
+    cache: web::Data<crate::services::Cache>,
+    config: web::Data<Config>,
+    job_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    use actix_web::http::header;
+    
+    // Même limite que le téléchargement proxy
+    let rate_key = format!("download:{}", user.id);
+    match cache.check_rate_limit(&rate_key, config.download_proxy_requests_per_minute, 60).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::TooManyRequests().json("Trop de téléchargements, réessayez dans une minute"),
+        Err(e) => log::warn!("Limitation des téléchargements indisponible: {}", e),
+    }
+    
+    let (job, artifacts) = match job_service.get_job_artifacts(user.id, *job_id).await {
+        Ok(output) => output,
+        Err(e) => {
+            return match e {
                 crate::utils::error::AppError::JobNotFound => {
                     HttpResponse::NotFound().json("Job non trouvé")
                 }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::Validation(message) => {
+                    HttpResponse::BadRequest().json(message)
+                }
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Fichier résultat introuvable")
+                }
                 _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            };
+        }
+    };
+    
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<web::Bytes, actix_web::Error>>(4);
+    let storage = storage.into_inner();
+    let archive_job = job.clone();
+    
+    tokio::spawn(async move {
+        if let Err(e) = write_result_archive(&storage, &archive_job, &artifacts, &tx).await {
+            log::error!("Archive du job {} interrompue: {}", archive_job.id, e);
+            let _ = tx.send(Err(actix_web::error::ErrorInternalServerError("archive interrompue"))).await;
+        }
+    });
+    
+    let filename = format!("{}_{}.zip", job.name, job.id);
+    HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            crate::utils::helpers::content_disposition_attachment(&filename),
+        ))
+        .streaming(tokio_stream::wrappers::ReceiverStream::new(rx))
+}
+
+/// Écrire l'archive d'un job dans le canal de la réponse
+///
+/// Une entrée par fichier produit (modèle, puis données externes sous leur
+/// nom d'origine), suivie de `report.json`.
+async fn write_result_archive(
+    storage: &FileStorage,
+    job: &Job,
+    artifacts: &[crate::models::ModelFile],
+    tx: &tokio::sync::mpsc::Sender<Result<web::Bytes, actix_web::Error>>,
+) -> crate::utils::error::Result<()> {
+    use crate::utils::zip::ZipStreamWriter;
+    
+    let send = |bytes: Vec<u8>| async move {
+        tx.send(Ok(web::Bytes::from(bytes)))
+            .await
+            .map_err(|_| crate::utils::error::AppError::Internal) // client déconnecté
+    };
+    
+    let mut zip = ZipStreamWriter::new();
+    
+    for (index, artifact) in artifacts.iter().enumerate() {
+        let name = if index == 0 {
+            crate::utils::helpers::sanitize_filename(&format!(
+                "{}_{}.{}", job.name, job.id, job.output_format.extension()
+            ))
+        } else {
+            artifact.original_filename.clone()
+        };
+        send(zip.start_entry(&name)).await?;
+        
+        // Fichier transmis en flux depuis le stockage (restauré une seule fois s'il est chiffré ou compressé)
+        let size = artifact.file_size.max(0) as u64;
+        if size > 0 {
+            let mut body = storage.stream_range(artifact, 0, size - 1).await?;
+            while let Some(chunk) = futures_util::StreamExt::next(&mut body).await {
+                let chunk = chunk?;
+                zip.data(&chunk);
+                send(chunk.to_vec()).await?;
             }
         }
+        
+        send(zip.finish_entry()).await?;
     }
+    
+    // Rapport (ou à défaut le résumé du job)
+    let report = match &job.report {
+        Some(report) => serde_json::to_vec_pretty(&report.0),
+        None => serde_json::to_vec_pretty(&job.to_result()),
+    }
+    .map_err(|e| crate::utils::error::AppError::SerializeError(e.to_string()))?;
+    
+    send(zip.start_entry("report.json")).await?;
+    zip.data(&report);
+    send(report).await?;
+    send(zip.finish_entry()).await?;
+    
+    send(zip.finish()).await
 }
 
 /// Obtenir la progression d'un job en temps réel
@@ -327,10 +1071,476 @@ fn extract_file_id(req: &actix_web::HttpRequest) -> Option<uuid::Uuid> {
     None
 }
 
+// Corps de la requête de statut groupé
+#[derive(Debug, serde::Deserialize, Validate)]
+struct BatchStatusRequest {
+    #[validate(length(min = 1, max = 100))]
+    job_ids: Vec<uuid::Uuid>,
+}
+
+// Corps (facultatif) de l'annulation d'un job
+#[derive(Debug, serde::Deserialize, Validate)]
+pub(crate) struct CancelJobRequest {
+    #[validate(length(max = 500))]
+    pub(crate) reason: Option<String>,
+}
+
+// Query parameters pour l'estimation de coût
+#[derive(Debug, serde::Deserialize)]
+struct JobCostQuery {
+    method: String,
+    /// Taille du modèle source en octets
+    size: i64,
+    /// Nombre de paramètres en milliards, si connu
+    parameter_count: Option<f64>,
+}
+
 // Query parameters pour la liste des jobs
 #[derive(Debug, serde::Deserialize)]
 struct ListJobsQuery {
     status: Option<String>,
     page: Option<i64>,
     per_page: Option<i64>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{JobStatus, QuantizationMethod, SubscriptionPlan};
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+
+    /// Application de test: routes des jobs et données partagées
+    macro_rules! init_app {
+        ($($data:expr),* $(,)?) => {
+            test::init_service(App::new()$(.app_data($data))*.configure(configure_routes)).await
+        };
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn failing_job_logs_are_returned_to_the_owner() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // Fichier enregistré sans objet stocké: la récupération du modèle échoue
+        let job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+        assert!(service.process_job(job.id).await.is_err());
+
+        let app = init_app!(web::Data::new(config.clone()), web::Data::new(service));
+        let request = test::TestRequest::get()
+            .uri(&format!("/jobs/{}/logs", job.id))
+            .insert_header(testing::bearer(&config, &user))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let logs: Vec<serde_json::Value> = test::read_body_json(response).await;
+        let failure = logs
+            .iter()
+            .find(|line| line["level"] == "error")
+            .expect("ligne d'erreur journalisée");
+        assert_eq!(failure["message"], "Échec de la récupération du modèle source");
+        // La sortie d'erreur brute est réservée aux admins
+        assert!(failure.get("stderr").is_none());
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn unchanged_job_is_not_modified_until_it_changes() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+
+        let app = init_app!(web::Data::new(config.clone()), web::Data::new(service));
+        let get_job = |etag: Option<&str>| {
+            let mut request = test::TestRequest::get()
+                .uri(&format!("/jobs/{}", job.id))
+                .insert_header(testing::bearer(&config, &user));
+            if let Some(etag) = etag {
+                request = request.insert_header(("If-None-Match", etag.to_string()));
+            }
+            request.to_request()
+        };
+
+        let response = test::call_service(&app, get_job(None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get("ETag").unwrap().to_str().unwrap().to_string();
+
+        let response = test::call_service(&app, get_job(Some(&etag))).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        db.update_job_status(job.id, &JobStatus::Processing, 10).await.unwrap();
+
+        let response = test::call_service(&app, get_job(Some(&etag))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get("ETag").unwrap().to_str().unwrap(), etag);
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn batch_status_lists_only_owned_jobs_and_caps_the_list() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let other = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let first = testing::create_job(&db, user.id, "first", QuantizationMethod::Int8).await;
+        let second = testing::create_job(&db, user.id, "second", QuantizationMethod::Int8).await;
+        let foreign = testing::create_job(&db, other.id, "foreign", QuantizationMethod::Int8).await;
+
+        let app = init_app!(web::Data::new(config.clone()), web::Data::new(service));
+        let request = test::TestRequest::post()
+            .uri("/jobs/status/batch")
+            .insert_header(testing::bearer(&config, &user))
+            .set_json(serde_json::json!({ "job_ids": [first.id, second.id, foreign.id] }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let statuses: serde_json::Map<String, serde_json::Value> = test::read_body_json(response).await;
+        let mut ids: Vec<&String> = statuses.keys().collect();
+        ids.sort();
+        let mut expected = vec![first.id.to_string(), second.id.to_string()];
+        expected.sort();
+        assert_eq!(ids, expected.iter().collect::<Vec<_>>());
+
+        let oversized: Vec<uuid::Uuid> = (0..101).map(|_| uuid::Uuid::new_v4()).collect();
+        let request = test::TestRequest::post()
+            .uri("/jobs/status/batch")
+            .insert_header(testing::bearer(&config, &user))
+            .set_json(serde_json::json!({ "job_ids": oversized }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn cost_endpoint_prices_int4_at_two_credits() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+
+        let app = init_app!(web::Data::new(config.clone()), web::Data::new(service));
+        for method in ["gptq", "awq"] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/jobs/cost?method={}&size=14000000000&parameter_count=7", method))
+                .insert_header(testing::bearer(&config, &user))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let cost: serde_json::Value = test::read_body_json(response).await;
+            assert_eq!(cost["credits"], 2, "méthode {}", method);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn impersonation_token_reads_the_target_jobs() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::job_service(db.clone(), &config).await;
+        let admin = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+
+        let app = init_app!(web::Data::new(config.clone()), web::Data::new(service));
+        let token = crate::utils::security::generate_impersonation_token(user.id, &user.email, admin.id, &config.jwt_secret, 15);
+        let request = test::TestRequest::get()
+            .uri(&format!("/jobs/{}", job.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["id"], job.id.to_string());
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn streamed_download_serves_ranges_to_the_owner_only() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage = testing::storage();
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let content: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        let output = testing::store_model(&db, &storage, user.id, &content).await;
+        let mut job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+        job.start();
+        job.complete(output.id, output.file_size);
+        db.update_job_completion(job.id, &job).await.unwrap();
+
+        let app = init_app!(
+            web::Data::new(config.clone()),
+            web::Data::new(service),
+            web::Data::from(storage),
+            web::Data::from(testing::cache().await),
+        );
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/jobs/{}/stream", job.id))
+            .insert_header(testing::bearer(&config, &user))
+            .insert_header(("Range", "bytes=1000-1099"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers().get("Content-Range").unwrap(), "bytes 1000-1099/4096");
+        let body = test::read_body(response).await;
+        assert_eq!(&body[..], &content[1000..1100]);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/jobs/{}/stream", job.id))
+            .insert_header(("Range", "bytes=1000-1099"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// Noms des entrées d'une archive zip, lus dans son répertoire central
+    fn zip_entry_names(archive: &[u8]) -> Vec<String> {
+        let u16_at = |at: usize| u16::from_le_bytes([archive[at], archive[at + 1]]) as usize;
+        let u32_at = |at: usize| u32::from_le_bytes(archive[at..at + 4].try_into().unwrap()) as usize;
+
+        let end = archive.len() - 22;
+        let mut at = u32_at(end + 16);
+        (0..u16_at(end + 10))
+            .map(|_| {
+                let name_len = u16_at(at + 28);
+                let name = String::from_utf8(archive[at + 46..at + 46 + name_len].to_vec()).unwrap();
+                at += 46 + name_len + u16_at(at + 30) + u16_at(at + 32);
+                name
+            })
+            .collect()
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn download_all_zips_every_produced_format_and_the_report() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage = testing::storage();
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // Résultat quantifié accompagné de son export GGUF
+        let output = testing::store_model(&db, &storage, user.id, b"poids quantifies").await;
+        let gguf_source = testing::scratch_dir("gguf").join("llama.gguf");
+        std::fs::write(&gguf_source, b"export gguf").unwrap();
+        let gguf = storage
+            .upload_job_artifact(output.id, user.id, "llama.gguf", &gguf_source.to_string_lossy(), crate::models::ModelFormat::Gguf)
+            .await
+            .unwrap();
+        db.create_file(&gguf).await.unwrap();
+
+        let mut job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Gptq).await;
+        job.start();
+        job.complete(output.id, output.file_size);
+        job.report = Some(sqlx::types::Json(crate::models::QuantizationReport::new(
+            1000, 250, Some(10.0), Some(10.2), None, None,
+        )));
+        db.update_job_completion(job.id, &job).await.unwrap();
+
+        let app = init_app!(
+            web::Data::new(config.clone()),
+            web::Data::new(service),
+            web::Data::from(storage),
+            web::Data::from(testing::cache().await),
+        );
+        let request = test::TestRequest::get()
+            .uri(&format!("/jobs/{}/download-all", job.id))
+            .insert_header(testing::bearer(&config, &user))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("Content-Type").unwrap(), "application/zip");
+
+        let archive = test::read_body(response).await;
+        let names = zip_entry_names(&archive);
+        assert_eq!(names.len(), 3, "{:?}", names);
+        assert!(names[0].starts_with(&format!("llama_{}.", job.id)), "{:?}", names);
+        assert_eq!(names[1], "llama.gguf");
+        assert_eq!(names[2], "report.json");
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn share_link_downloads_without_a_jwt_until_revoked() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage = testing::storage();
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let output = testing::store_model(&db, &storage, user.id, b"poids quantifies").await;
+        let mut job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Gptq).await;
+        job.start();
+        job.complete(output.id, output.file_size);
+        db.update_job_completion(job.id, &job).await.unwrap();
+
+        let app = init_app!(
+            web::Data::new(config.clone()),
+            web::Data::new(service),
+            web::Data::from(storage),
+            web::Data::from(testing::cache().await),
+        );
+
+        let request = test::TestRequest::post()
+            .uri(&format!("/jobs/{}/share", job.id))
+            .insert_header(testing::bearer(&config, &user))
+            .set_json(serde_json::json!({}))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let issued: serde_json::Value = test::read_body_json(response).await;
+        let token = issued["token"].as_str().unwrap().to_string();
+        assert!(issued["url"].as_str().unwrap().ends_with(&format!("/api/shared/{}", token)));
+
+        // Aucun en-tête d'authentification: le token suffit
+        let shared = || test::TestRequest::get().uri(&format!("/shared/{}", token)).to_request();
+        let response = test::call_service(&app, shared()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(&test::read_body(response).await[..], b"poids quantifies");
+
+        let request = test::TestRequest::delete()
+            .uri(&format!("/jobs/{}/share/{}", job.id, issued["id"].as_str().unwrap()))
+            .insert_header(testing::bearer(&config, &user))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+
+        let response = test::call_service(&app, shared()).await;
+        assert_eq!(response.status(), StatusCode::GONE);
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn share_link_range_resumes_stay_within_the_download_budget() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage = testing::storage();
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let output = testing::store_model(&db, &storage, user.id, b"poids quantifies").await;
+        let mut job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Gptq).await;
+        job.start();
+        job.complete(output.id, output.file_size);
+        db.update_job_completion(job.id, &job).await.unwrap();
+
+        let app = init_app!(
+            web::Data::new(config.clone()),
+            web::Data::new(service),
+            web::Data::from(storage),
+            web::Data::from(testing::cache().await),
+        );
+
+        let request = test::TestRequest::post()
+            .uri(&format!("/jobs/{}/share", job.id))
+            .insert_header(testing::bearer(&config, &user))
+            .set_json(serde_json::json!({ "max_downloads": 1 }))
+            .to_request();
+        let issued: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        let token = issued["token"].as_str().unwrap().to_string();
+
+        let shared = |range: &str| {
+            test::TestRequest::get()
+                .uri(&format!("/shared/{}", token))
+                .insert_header((actix_web::http::header::RANGE, range.to_string()))
+                .to_request()
+        };
+
+        // Téléchargement en deux morceaux: le seul autorisé par le plafond
+        let response = test::call_service(&app, shared("bytes=0-4")).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(&test::read_body(response).await[..], b"poids");
+        let response = test::call_service(&app, shared("bytes=5-")).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(&test::read_body(response).await[..], b" quantifies");
+
+        // Ni un nouveau téléchargement, ni une "reprise" qui resservirait le fichier
+        for range in ["bytes=0-", "bytes=1-", "bytes=-10"] {
+            let response = test::call_service(&app, shared(range)).await;
+            assert_eq!(response.status(), StatusCode::GONE, "{}", range);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn job_list_exposes_downloadable_and_never_a_url() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let report = crate::models::QuantizationReport::new(1000, 250, None, None, None, None);
+        let completed = testing::create_completed_job(&db, user.id, "termine", QuantizationMethod::Int8, report).await;
+        let pending = testing::create_job(&db, user.id, "en-attente", QuantizationMethod::Int8).await;
+
+        let app = init_app!(web::Data::new(config.clone()), web::Data::new(service));
+        let request = test::TestRequest::get()
+            .uri("/jobs")
+            .insert_header(testing::bearer(&config, &user))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = test::read_body(response).await;
+        assert!(!String::from_utf8_lossy(&body).to_lowercase().contains("url"));
+        let page: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let downloadable = |id: uuid::Uuid| {
+            page["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|item| item["id"] == id.to_string())
+                .map(|item| item["downloadable"].clone())
+                .unwrap()
+        };
+        assert_eq!(downloadable(completed.id), serde_json::json!(true));
+        assert_eq!(downloadable(pending.id), serde_json::json!(false));
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn oversized_json_body_is_rejected_with_413() {
+        let mut config = testing::config();
+        config.max_json_payload_kb = 1;
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let app = init_app!(
+            crate::api::json_config(config.max_json_payload_kb * 1024),
+            web::Data::new(config.clone()),
+            web::Data::new(testing::job_service(db.clone(), &config).await),
+            web::Data::new(testing::billing_service(db.clone(), &config)),
+            web::Data::from(testing::storage()),
+        );
+        let post = |body: serde_json::Value| {
+            test::TestRequest::post()
+                .uri("/jobs")
+                .insert_header(testing::bearer(&config, &user))
+                .set_json(body)
+                .to_request()
+        };
+
+        let oversized = serde_json::json!({ "name": "llama", "quantization_method": "int8", "padding": "x".repeat(8 * 1024) });
+        let response = test::call_service(&app, post(oversized)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Corps de taille normale: lu, puis traité par le handler (ici sans fichier d'entrée)
+        let response = test::call_service(&app, post(serde_json::json!({ "name": "llama", "quantization_method": "int8" }))).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let message: String = test::read_body_json(response).await;
+        assert_eq!(message, "ID de fichier requis");
+    }
+}
@@ -0,0 +1,148 @@
+// api/maintenance.rs
+use crate::api::AuthenticatedUser;
+use crate::utils::config::Config;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{http::header, web, HttpResponse};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Routes toujours servies: sondes, métriques et administration
+const EXEMPT_PREFIXES: &[&str] = &["/health", "/ready", "/metrics", "/api/admin"];
+
+/// Mode maintenance: activé par `MAINTENANCE_MODE` ou par un admin
+///
+/// Seules les requêtes HTTP sont refusées; les workers continuent de traiter
+/// les jobs en cours.
+pub struct MaintenanceMode {
+    enabled: AtomicBool,
+    retry_after_seconds: AtomicU64,
+}
+
+impl MaintenanceMode {
+    pub fn new(enabled: bool, retry_after_seconds: u64) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            retry_after_seconds: AtomicU64::new(retry_after_seconds),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn retry_after_seconds(&self) -> u64 {
+        self.retry_after_seconds.load(Ordering::SeqCst)
+    }
+
+    /// Activer ou désactiver; retourne l'état précédent
+    pub fn set(&self, enabled: bool, retry_after_seconds: Option<u64>) -> bool {
+        if let Some(seconds) = retry_after_seconds {
+            self.retry_after_seconds.store(seconds, Ordering::SeqCst);
+        }
+        self.enabled.swap(enabled, Ordering::SeqCst)
+    }
+
+    /// État courant (réponse des routes admin)
+    pub fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "enabled": self.is_enabled(),
+            "retry_after_seconds": self.retry_after_seconds(),
+        })
+    }
+}
+
+/// Middleware: 503 avec `Retry-After` pendant la maintenance
+///
+/// Les admins (token Bearer valide) et les routes de `EXEMPT_PREFIXES`
+/// passent toujours.
+pub async fn reject_during_maintenance(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let maintenance = match req.app_data::<web::Data<MaintenanceMode>>() {
+        Some(maintenance) if maintenance.is_enabled() => maintenance.clone(),
+        _ => return next.call(req).await.map(ServiceResponse::map_into_boxed_body),
+    };
+
+    let path = req.path();
+    let exempt = EXEMPT_PREFIXES
+        .iter()
+        .any(|prefix| path == *prefix || path.starts_with(&format!("{}/", prefix)));
+    if exempt || is_admin_request(&req) {
+        return next.call(req).await.map(ServiceResponse::map_into_boxed_body);
+    }
+
+    let retry_after = maintenance.retry_after_seconds();
+    let response = HttpResponse::ServiceUnavailable()
+        .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+        .json(serde_json::json!({
+            "error": "Service en maintenance, réessayez dans quelques minutes",
+            "code": "MAINTENANCE",
+            "details": { "retry_after_seconds": retry_after },
+        }));
+
+    Ok(req.into_response(response))
+}
+
+/// La requête porte-t-elle un token d'accès admin valide ?
+fn is_admin_request(req: &ServiceRequest) -> bool {
+    let Some(config) = req.app_data::<web::Data<Config>>() else {
+        return false;
+    };
+
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| crate::utils::security::verify_access_token(token.trim(), &config.jwt_secret).ok())
+        .map(|data| crate::api::admin::require_admin(&AuthenticatedUser::from_claims(&data.claims)).is_ok())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::User;
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, middleware::from_fn, test, App};
+
+    #[actix_web::test]
+    async fn maintenance_rejects_api_calls_but_not_health_or_admins() {
+        let config = testing::config();
+        let maintenance = web::Data::new(MaintenanceMode::new(true, 120));
+        let ok = || HttpResponse::Ok().finish();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(maintenance.clone())
+                .wrap(from_fn(reject_during_maintenance))
+                .route("/health", web::get().to(ok))
+                .route("/api/jobs", web::get().to(ok))
+                .route("/api/admin/maintenance", web::get().to(ok)),
+        )
+        .await;
+        let get = |uri: &str| test::TestRequest::get().uri(uri);
+
+        let response = test::call_service(&app, get("/api/jobs").to_request()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "120");
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["code"], "MAINTENANCE");
+
+        let response = test::call_service(&app, get("/health").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = test::call_service(&app, get("/api/admin/maintenance").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Un admin passe aussi sur les routes ordinaires
+        let admin = User::new("admin@quantization.com".to_string(), "MotDePasse123!");
+        let request = get("/api/jobs").insert_header(testing::bearer(&config, &admin)).to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+        maintenance.set(false, None);
+        let response = test::call_service(&app, get("/api/jobs").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
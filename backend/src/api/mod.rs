@@ -5,6 +5,12 @@ pub mod job;
 pub mod file;
 pub mod billing;
 pub mod admin;
+pub mod model;
+pub mod openapi;
+pub mod capabilities;
+pub mod quantization;
+pub mod maintenance;
+pub mod auth_middleware;
 
 use actix_web::{web, HttpResponse};
 
@@ -22,6 +28,14 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .configure(file::configure_routes)
             // Facturation
             .configure(billing::configure_routes)
+            // Modèles quantifiés
+            .configure(model::configure_routes)
+            // Description OpenAPI (publique)
+            .configure(openapi::configure_routes)
+            // Méthodes disponibles sur ce serveur (publique)
+            .configure(capabilities::configure_routes)
+            // Méthodes de quantification et leurs caractéristiques (publique)
+            .configure(quantization::configure_routes)
             // Admin (nécessite authentification admin)
             .configure(admin::configure_routes),
     );
@@ -32,7 +46,166 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
 pub struct AuthenticatedUser {
     pub id: uuid::Uuid,
     pub email: String,
+    /// Admin à l'origine de la requête si le token est une impersonation
+    pub impersonator_id: Option<uuid::Uuid>,
+    /// Scopes de la clé API utilisée (None: token de session, accès complet)
+    pub api_key_permissions: Option<Vec<String>>,
+}
+
+impl AuthenticatedUser {
+    /// Construire l'utilisateur à partir des claims d'un token d'accès vérifié
+    pub fn from_claims(claims: &crate::utils::security::AccessTokenClaims) -> Self {
+        Self {
+            id: claims.sub,
+            email: claims.email.clone(),
+            impersonator_id: claims.act,
+            api_key_permissions: None,
+        }
+    }
+
+    /// La requête est-elle faite par un admin au nom de l'utilisateur ?
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator_id.is_some()
+    }
+
+    /// La requête est-elle authentifiée par une clé API ?
+    pub fn is_api_key(&self) -> bool {
+        self.api_key_permissions.is_some()
+    }
+
+    /// La requête peut-elle modifier des données ?
+    ///
+    /// Une clé API n'écrit que si l'un de ses scopes est `write` ou `<ressource>:write`.
+    pub fn can_write(&self) -> bool {
+        match &self.api_key_permissions {
+            Some(permissions) => permissions
+                .iter()
+                .any(|scope| scope == "write" || scope.ends_with(":write")),
+            None => true,
+        }
+    }
+}
+
+/// Utilisateur rangé dans les extensions par `auth_middleware::require_auth`
+impl actix_web::FromRequest for AuthenticatedUser {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        use actix_web::HttpMessage;
+
+        std::future::ready(
+            req.extensions()
+                .get::<AuthenticatedUser>()
+                .cloned()
+                .ok_or_else(|| actix_web::error::ErrorUnauthorized("Authentification requise")),
+        )
+    }
+}
+
+/// Entrée d'audit pour une requête faite sous impersonation (None sinon)
+///
+/// Appelée par le middleware d'authentification pour chaque requête.
+pub fn impersonation_audit_entry(
+    req: &actix_web::HttpRequest,
+    user: &AuthenticatedUser,
+) -> Option<crate::models::AuditLog> {
+    let admin_id = user.impersonator_id?;
+    let mut entry = audit_entry(req, Some(admin_id), "impersonation.request", Some("user"), Some(user.id));
+    entry.message = Some(format!("{} {}", req.method(), req.path()));
+    Some(entry)
+}
+
+/// Refuser une action sensible sous impersonation (facturation), avec audit du refus
+pub async fn forbid_impersonation(
+    req: &actix_web::HttpRequest,
+    user: &AuthenticatedUser,
+    audit: &crate::services::audit::AuditRepository,
+    action: &str,
+) -> Option<HttpResponse> {
+    let admin_id = user.impersonator_id?;
+    let mut entry = audit_entry(req, Some(admin_id), "impersonation.denied", Some("user"), Some(user.id));
+    entry.message = Some(format!("Action refusée sous impersonation: {}", action));
+    audit.log(entry).await;
+
+    Some(HttpResponse::Forbidden().json("Action interdite en mode impersonation"))
 }
 
 /// Type de résultat standard pour les handlers
-pub type ApiResult<T> = Result<T, actix_web::Error>;
\ No newline at end of file
+pub type ApiResult<T> = Result<T, actix_web::Error>;
+
+/// Limite des corps JSON: 413 au-delà de `limit_bytes`, 400 si le JSON est invalide
+pub fn json_config(limit_bytes: usize) -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(limit_bytes)
+        .error_handler(move |err, _req| {
+            use actix_web::error::JsonPayloadError;
+
+            let response = match &err {
+                JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                    HttpResponse::PayloadTooLarge().json(format!(
+                        "Corps de requête trop volumineux (max {} Ko)",
+                        limit_bytes / 1024
+                    ))
+                }
+                _ => HttpResponse::BadRequest().json(format!("JSON invalide: {}", err)),
+            };
+            actix_web::error::InternalError::from_response(err, response).into()
+        })
+}
+
+/// Vérifier si l'en-tête `If-None-Match` correspond à l'ETag courant
+pub fn etag_matches(req: &actix_web::HttpRequest, etag: &str) -> bool {
+    req.headers()
+        .get(actix_web::http::header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value.split(',').map(str::trim).any(|candidate| {
+                candidate == "*" || candidate.trim_start_matches("W/") == etag
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Réponse 304 si le client possède déjà la version courante, sinon 200 avec l'ETag
+pub fn conditional_json<T: serde::Serialize>(
+    req: &actix_web::HttpRequest,
+    etag: &str,
+    body: &T,
+) -> HttpResponse {
+    if etag_matches(req, etag) {
+        return HttpResponse::NotModified()
+            .insert_header((actix_web::http::header::ETAG, etag.to_string()))
+            .finish();
+    }
+
+    HttpResponse::Ok()
+        .insert_header((actix_web::http::header::ETAG, etag.to_string()))
+        .json(body)
+}
+
+/// Construire une entrée d'audit à partir de la requête HTTP
+pub fn audit_entry(
+    req: &actix_web::HttpRequest,
+    actor: Option<uuid::Uuid>,
+    action: &str,
+    resource_type: Option<&str>,
+    resource_id: Option<uuid::Uuid>,
+) -> crate::models::AuditLog {
+    let ip_address = req.peer_addr().map(|addr| addr.ip().to_string());
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|ua| ua.to_str().ok())
+        .map(|ua| ua.to_string());
+
+    crate::models::AuditLog::new(
+        actor,
+        ip_address,
+        user_agent,
+        action.to_string(),
+        resource_type.map(|r| r.to_string()),
+        resource_id,
+        None,
+    )
+}
\ No newline at end of file
@@ -0,0 +1,596 @@
+// api/model.rs
+use crate::api::AuthenticatedUser;
+use crate::core::job_service::{AnalysisOutcome, JobService};
+use crate::models::Pagination;
+use crate::utils::config::Config;
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+
+/// Configure les routes des modèles quantifiés
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/models")
+            .wrap(crate::api::auth_middleware::require_auth())
+            // Lister les modèles stockés
+            .route("", web::get().to(list_models))
+            // Comparer deux variantes quantifiées
+            .route("/compare", web::get().to(compare_models))
+            // Upload direct vers le stockage: URL signée puis confirmation
+            .route("/upload-url", web::post().to(create_upload_url))
+            .route("/upload-complete", web::post().to(complete_upload))
+            // Supprimer un modèle stocké
+            .route("/{model_id}", web::delete().to(delete_model))
+            // Télécharger le modèle original (non quantifié)
+            .route("/{model_id}/download", web::get().to(download_model))
+            // Lancer l'analyse détaillée (asynchrone pour les gros modèles)
+            .route("/{model_id}/analyze", web::post().to(analyze_model))
+            // État et résultat de l'analyse
+            .route("/{model_id}/analysis", web::get().to(get_model_analysis)),
+    );
+}
+
+/// Lister les modèles stockés de l'utilisateur
+async fn list_models(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    config: web::Data<Config>,
+    query: web::Query<ListModelsQuery>,
+) -> impl Responder {
+    let pagination = match Pagination::from_params(query.page, query.per_page, config.pagination_max_per_page) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    match job_service.list_models(user.id, query.format.as_deref(), pagination).await {
+        Ok((models, total)) => HttpResponse::Ok().json(pagination.response(models, total)),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Supprimer un modèle stocké
+async fn delete_model(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    model_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match job_service.delete_model(user.id, *model_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Modèle non trouvé")
+                }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::FileInUse => {
+                    HttpResponse::Conflict().json("Modèle utilisé par un job en cours")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur lors de la suppression"),
+            }
+        }
+    }
+}
+
+/// Clé de cache d'un upload direct en attente
+fn pending_upload_key(upload_id: uuid::Uuid) -> String {
+    format!("direct_upload:{}", upload_id)
+}
+
+/// Obtenir une URL PUT signée pour uploader un modèle directement vers le stockage
+///
+/// La clé est allouée par le serveur; l'extension et le quota sont vérifiés
+/// sur la taille annoncée, puis à nouveau sur l'objet réel à la confirmation.
+async fn create_upload_url(
+    user: AuthenticatedUser,
+    storage: web::Data<crate::services::storage::FileStorage>,
+    billing_service: web::Data<crate::core::billing_service::BillingService>,
+    cache: web::Data<crate::services::Cache>,
+    config: web::Data<Config>,
+    request: web::Json<crate::models::DirectUploadRequest>,
+) -> impl Responder {
+    let request = request.into_inner();
+    
+    if request.filename.trim().is_empty() {
+        return HttpResponse::BadRequest().json("Nom de fichier requis");
+    }
+    
+    let extension = crate::api::file::file_extension(&request.filename);
+    if !config.allowed_upload_extensions.iter().any(|allowed| *allowed == extension) {
+        return HttpResponse::UnsupportedMediaType().json(format!(
+            "Extension non autorisée (acceptées: {})",
+            config.allowed_upload_extensions.join(", ")
+        ));
+    }
+    
+    if request.file_size <= 0 {
+        return HttpResponse::BadRequest().json("Taille de fichier invalide");
+    }
+    let checksum = request.checksum_sha256.trim().to_ascii_lowercase();
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return HttpResponse::BadRequest().json("checksum_sha256 invalide (64 caractères hexadécimaux attendus)");
+    }
+    if request.file_size as u64 > config.max_file_size_mb * 1024 * 1024 {
+        return HttpResponse::PayloadTooLarge().json("Fichier trop volumineux");
+    }
+    
+    match billing_service.check_storage_quota(user.id, request.file_size).await {
+        Ok(_) => {}
+        Err(crate::utils::error::AppError::StorageQuotaExceeded) => {
+            return HttpResponse::PayloadTooLarge().json("Quota de stockage dépassé");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification du quota"),
+    }
+    
+    let expires_in_seconds = config.direct_upload_url_expiry_minutes * 60;
+    let presigned = match storage
+        .create_upload_url(&request.filename, request.file_size, &checksum, expires_in_seconds)
+        .await
+    {
+        Ok(allocated) => allocated,
+        Err(crate::utils::error::AppError::Validation(message)) => {
+            return HttpResponse::ServiceUnavailable().json(message);
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur lors de la création de l'URL d'upload"),
+    };
+    
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(expires_in_seconds as i64);
+    let pending = crate::models::PendingUpload {
+        user_id: user.id,
+        filename: request.filename,
+        staging_key: presigned.staging_key,
+        file_size: request.file_size,
+        checksum_sha256: checksum,
+        expires_at,
+    };
+    
+    // Confirmation possible un peu après l'expiration de l'URL (fin du PUT en cours)
+    let ttl = (expires_in_seconds * 2) as usize;
+    if cache.set_ex(&pending_upload_key(presigned.file_id), &pending, ttl).await.is_err() {
+        return HttpResponse::InternalServerError().json("Erreur serveur");
+    }
+    
+    HttpResponse::Created().json(crate::models::DirectUploadUrl {
+        upload_id: presigned.file_id,
+        upload_url: presigned.url,
+        headers: presigned.headers,
+        expires_at,
+    })
+}
+
+/// Confirmer un upload direct et enregistrer le modèle
+///
+/// Taille et empreinte sont vérifiées par S3 (aucun octet ne transite par
+/// l'API hormis l'en-tête lu pour le format); l'objet accepté est copié vers
+/// une clé définitive que l'URL signée ne peut plus atteindre. Un objet
+/// refusé est supprimé du stockage.
+async fn complete_upload(
+    user: AuthenticatedUser,
+    storage: web::Data<crate::services::storage::FileStorage>,
+    job_service: web::Data<JobService>,
+    billing_service: web::Data<crate::core::billing_service::BillingService>,
+    cache: web::Data<crate::services::Cache>,
+    request: web::Json<crate::models::DirectUploadComplete>,
+) -> impl Responder {
+    use crate::utils::error::AppError;
+    
+    let request = request.into_inner();
+    let cache_key = pending_upload_key(request.upload_id);
+    
+    let pending = match cache.get::<crate::models::PendingUpload>(&cache_key).await {
+        Ok(Some(pending)) if pending.user_id == user.id => pending,
+        Ok(_) => return HttpResponse::NotFound().json("Upload inconnu ou expiré"),
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur serveur"),
+    };
+    
+    let object = match storage
+        .inspect_direct_upload(&pending.staging_key, pending.file_size, &pending.checksum_sha256)
+        .await
+    {
+        Ok(object) => object,
+        Err(AppError::FileNotFound) => {
+            return HttpResponse::Conflict().json("Fichier absent du stockage: l'upload n'est pas terminé");
+        }
+        Err(AppError::FileTooLarge) => {
+            cache.delete(&cache_key).await.ok();
+            return HttpResponse::PayloadTooLarge().json("Fichier trop volumineux");
+        }
+        Err(AppError::Validation(message)) => {
+            cache.delete(&cache_key).await.ok();
+            return HttpResponse::UnprocessableEntity().json(message);
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de lecture du fichier"),
+    };
+    
+    let format = match crate::api::file::detect_file_format(&pending.filename, None, &object.header) {
+        Ok(format) => format,
+        Err(message) => {
+            discard_pending_upload(&storage, &cache, &cache_key, &pending.staging_key).await;
+            return HttpResponse::UnprocessableEntity().json(message);
+        }
+    };
+    
+    match billing_service.check_storage_quota(user.id, pending.file_size).await {
+        Ok(_) => {}
+        Err(AppError::StorageQuotaExceeded) => {
+            discard_pending_upload(&storage, &cache, &cache_key, &pending.staging_key).await;
+            return HttpResponse::PayloadTooLarge().json("Quota de stockage dépassé");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification du quota"),
+    }
+    
+    let expires_at = match billing_service.file_expiry_for(user.id).await {
+        Ok(expires_at) => expires_at,
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur de vérification de l'abonnement"),
+    };
+    
+    let storage_key = match storage
+        .promote_direct_upload(&pending.staging_key, &object.etag, request.upload_id, &pending.filename)
+        .await
+    {
+        Ok(storage_key) => storage_key,
+        Err(_) => return HttpResponse::InternalServerError().json("Erreur lors de l'enregistrement du fichier"),
+    };
+    cache.delete(&cache_key).await.ok();
+    
+    let file = crate::models::ModelFile::new(
+        user.id,
+        pending.filename,
+        pending.file_size,
+        pending.checksum_sha256,
+        format,
+        storage.bucket().to_string(),
+        storage_key,
+    )
+    .with_id(request.upload_id)
+    .with_expiry(expires_at);
+    
+    match job_service.register_uploaded_file(file).await {
+        Ok(metadata) => HttpResponse::Created().json(metadata),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur lors de l'enregistrement du fichier"),
+    }
+}
+
+/// Refus définitif d'un upload direct: l'objet et l'entrée en attente sont supprimés
+async fn discard_pending_upload(
+    storage: &crate::services::storage::FileStorage,
+    cache: &crate::services::Cache,
+    cache_key: &str,
+    storage_key: &str,
+) {
+    if let Err(e) = storage.discard_direct_upload(storage_key).await {
+        log::warn!("Suppression de l'upload direct {} impossible: {}", storage_key, e);
+    }
+    cache.delete(cache_key).await.ok();
+}
+
+/// Télécharger le modèle original tel qu'uploadé (déchiffré)
+///
+/// Réservé au propriétaire (ou à un admin), soumis à la même limite que les
+/// téléchargements de résultats. 410 si la rétention du fichier est échue.
+async fn download_model(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    cache: web::Data<crate::services::Cache>,
+    config: web::Data<Config>,
+    model_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    use actix_web::http::header;
+    
+    let rate_key = format!("download:{}", user.id);
+    match cache.check_rate_limit(&rate_key, config.download_proxy_requests_per_minute, 60).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::TooManyRequests().json("Trop de téléchargements, réessayez dans une minute"),
+        Err(e) => log::warn!("Limitation des téléchargements indisponible: {}", e),
+    }
+    
+    let is_admin = crate::api::admin::require_admin(&user).is_ok();
+    
+    match job_service.download_original_model(user.id, *model_id, is_admin).await {
+        Ok((file, data)) => HttpResponse::Ok()
+            .content_type("application/octet-stream")
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                crate::utils::helpers::content_disposition_attachment(&file.original_filename),
+            ))
+            .body(data),
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Modèle non trouvé")
+                }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::FileExpired => {
+                    HttpResponse::Gone().json("Modèle expiré et supprimé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur lors du téléchargement"),
+            }
+        }
+    }
+}
+
+/// Analyser un modèle stocké
+///
+/// 200 avec le résultat si le modèle est sous `SYNC_ANALYSIS_MAX_SIZE_MB`,
+/// sinon 202 avec l'URL de suivi.
+async fn analyze_model(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    config: web::Data<Config>,
+    model_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    let sync_max_bytes = config.sync_analysis_max_size_mb * 1024 * 1024;
+    
+    match job_service.request_file_analysis(user.id, *model_id, sync_max_bytes).await {
+        Ok(AnalysisOutcome::Completed(analysis)) => HttpResponse::Ok().json(analysis),
+        Ok(AnalysisOutcome::Accepted(analysis)) => {
+            let status_url = format!("/api/models/{}/analysis", model_id);
+            HttpResponse::Accepted()
+                .insert_header((actix_web::http::header::LOCATION, status_url.clone()))
+                .json(serde_json::json!({
+                    "status": analysis.status,
+                    "status_url": status_url,
+                }))
+        }
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Modèle non trouvé")
+                }
+                crate::utils::error::AppError::InsufficientDiskSpace(_) => {
+                    HttpResponse::InsufficientStorage().json("Espace disque insuffisant pour l'analyse")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur lors de l'analyse"),
+            }
+        }
+    }
+}
+
+/// État de l'analyse d'un modèle
+async fn get_model_analysis(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    model_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match job_service.get_file_analysis(user.id, *model_id).await {
+        Ok(analysis) => HttpResponse::Ok().json(analysis),
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::FileNotFound => {
+                    HttpResponse::NotFound().json("Modèle non trouvé")
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+/// Comparer deux variantes quantifiées côte à côte
+async fn compare_models(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    query: web::Query<CompareModelsQuery>,
+) -> impl Responder {
+    match job_service.compare_jobs(user.id, query.job_a, query.job_b).await {
+        Ok(comparison) => HttpResponse::Ok().json(comparison),
+        Err(e) => {
+            match e {
+                crate::utils::error::AppError::JobNotFound => {
+                    HttpResponse::NotFound().json("Job non trouvé")
+                }
+                crate::utils::error::AppError::Unauthorized => {
+                    HttpResponse::Forbidden().json("Accès non autorisé")
+                }
+                crate::utils::error::AppError::Validation(msg) => {
+                    HttpResponse::BadRequest().json(msg)
+                }
+                _ => HttpResponse::InternalServerError().json("Erreur serveur"),
+            }
+        }
+    }
+}
+
+// Query parameters pour la comparaison
+#[derive(Debug, serde::Deserialize)]
+struct CompareModelsQuery {
+    job_a: uuid::Uuid,
+    job_b: uuid::Uuid,
+}
+
+// Query parameters pour la liste des modèles
+#[derive(Debug, serde::Deserialize)]
+struct ListModelsQuery {
+    format: Option<String>,
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ModelFormat, SubscriptionPlan};
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn small_models_analyze_inline_and_large_ones_in_the_background() {
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(testing::PIPELINE_SCRIPTS);
+        config.sync_analysis_max_size_mb = 1;
+        let db = testing::database().await;
+        let storage = testing::storage();
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let small = testing::create_stored_file(&db, &storage, user.id, 1024).await;
+        // Taille déclarée seulement: l'analyse de fond n'est pas attendue ici
+        let large = testing::create_file(&db, user.id, ModelFormat::Safetensors, 50 * 1024 * 1024).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(service))
+                .configure(configure_routes),
+        )
+        .await;
+        let post = |id: uuid::Uuid| {
+            test::TestRequest::post()
+                .uri(&format!("/models/{}/analyze", id))
+                .insert_header(testing::bearer(&config, &user))
+                .to_request()
+        };
+
+        let response = test::call_service(&app, post(small.id)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let analysis: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(analysis["status"], "completed");
+        assert_eq!(analysis["result"]["analysis"]["model_type"], "llama");
+
+        let response = test::call_service(&app, post(large.id)).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let status_url = format!("/api/models/{}/analysis", large.id);
+        assert_eq!(response.headers().get("Location").unwrap().to_str().unwrap(), status_url);
+        let accepted: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(accepted["status_url"], status_url.as_str());
+
+        // Point de suivi (monté ici sans le préfixe /api)
+        let request = test::TestRequest::get()
+            .uri(&format!("/models/{}/analysis", large.id))
+            .insert_header(testing::bearer(&config, &user))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let status: serde_json::Value = test::read_body_json(response).await;
+        assert!(status["status"].is_string(), "{}", status);
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn owner_downloads_the_original_until_it_expires() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage = testing::storage();
+        let service = web::Data::new(testing::job_service_with_storage(db.clone(), &config, storage.clone()).await);
+        let owner = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let stranger = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let model = testing::store_model(&db, &storage, owner.id, b"poids d'origine").await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(service.clone())
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+        let download = |user: &crate::models::User| {
+            test::TestRequest::get()
+                .uri(&format!("/models/{}/download", model.id))
+                .insert_header(testing::bearer(&config, user))
+                .to_request()
+        };
+
+        let response = test::call_service(&app, download(&owner)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(&test::read_body(response).await[..], b"poids d'origine");
+
+        let response = test::call_service(&app, download(&stranger)).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Rétention échue puis purge: le modèle a disparu
+        sqlx::query("UPDATE model_files SET expires_at = NOW() - INTERVAL '1 day' WHERE id = $1")
+            .bind(model.id)
+            .execute(db.write_pool())
+            .await
+            .unwrap();
+        assert!(service.purge_expired_files().await.unwrap() >= 1);
+
+        let response = test::call_service(&app, download(&owner)).await;
+        assert_eq!(response.status(), StatusCode::GONE);
+    }
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL, Redis et S3 (TEST_DATABASE_URL, TEST_REDIS_URL, TEST_S3_ENDPOINT)"]
+    async fn direct_upload_is_registered_once_the_object_exists() {
+        use sha2::{Digest, Sha256};
+
+        let config = testing::config();
+        let db = testing::database().await;
+        let storage = testing::s3_storage();
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(service))
+                .app_data(web::Data::new(testing::billing_service(db.clone(), &config)))
+                .app_data(web::Data::from(testing::cache().await))
+                .configure(configure_routes),
+        )
+        .await;
+
+        // Safetensors minimal: longueur de l'en-tête JSON puis l'en-tête
+        let header = br#"{"__metadata__":{}}"#;
+        let mut data = (header.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(header);
+
+        let checksum = format!("{:x}", Sha256::digest(&data));
+
+        let request = test::TestRequest::post()
+            .uri("/models/upload-url")
+            .insert_header(testing::bearer(&config, &user))
+            .set_json(serde_json::json!({ "filename": "model.safetensors", "file_size": data.len(), "checksum_sha256": checksum }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let allocated: crate::models::DirectUploadUrl = test::read_body_json(response).await;
+
+        let complete = || {
+            test::TestRequest::post()
+                .uri("/models/upload-complete")
+                .insert_header(testing::bearer(&config, &user))
+                .set_json(serde_json::json!({ "upload_id": allocated.upload_id }))
+                .to_request()
+        };
+
+        // Rien n'a encore été déposé
+        let response = test::call_service(&app, complete()).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let put = |body: Vec<u8>| {
+            let mut request = reqwest::Client::new().put(&allocated.upload_url).body(body);
+            for (name, value) in &allocated.headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            request.send()
+        };
+
+        // Un autre contenu que celui annoncé est refusé par la signature
+        let mut tampered = data.clone();
+        tampered[9] = b'[';
+        assert!(!put(tampered).await.unwrap().status().is_success());
+
+        // Le client dépose l'objet directement sur l'URL signée
+        let response = put(data.clone()).await.unwrap();
+        assert!(response.status().is_success(), "{}", response.status());
+
+        let response = test::call_service(&app, complete()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let file = db.get_file(allocated.upload_id).await.unwrap();
+        assert_eq!(file.user_id, user.id);
+        assert_eq!(file.file_size, data.len() as i64);
+        assert_eq!(file.checksum_sha256, checksum);
+        assert!(matches!(file.format, ModelFormat::Safetensors));
+
+        // La clé référencée n'est pas celle de l'URL signée, toujours valide
+        assert!(!file.storage_path.starts_with("incoming/"), "{}", file.storage_path);
+        put(data.clone()).await.unwrap();
+        assert_eq!(storage.download_file(&file).await.unwrap(), data);
+    }
+}
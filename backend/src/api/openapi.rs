@@ -0,0 +1,1138 @@
+// api/openapi.rs
+use crate::models::{
+    JobStatus, QuantizationMethod, QuantizationPreset, ModelFormat, JobSource, SubscriptionPlan, SubscriptionStatus,
+    AnalysisStatus, CancelledBy, MAX_CALIBRATION_SAMPLES,
+};
+use actix_web::{web, HttpResponse, Responder};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+/// Configure la route de la description OpenAPI (publique)
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/openapi.json", web::get().to(get_openapi));
+}
+
+/// Description OpenAPI 3 de l'API
+async fn get_openapi() -> impl Responder {
+    HttpResponse::Ok().json(document())
+}
+
+/// Document OpenAPI, construit au premier appel
+pub fn document() -> &'static Value {
+    static DOCUMENT: OnceLock<Value> = OnceLock::new();
+    DOCUMENT.get_or_init(build_document)
+}
+
+fn build_document() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Quantization Platform API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": env!("CARGO_PKG_DESCRIPTION"),
+        },
+        "servers": [{ "url": "/api" }],
+        "paths": paths(),
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+            },
+        },
+    })
+}
+
+// === OPÉRATIONS ===
+
+/// Une opération d'un chemin (méthode HTTP)
+struct Operation {
+    value: Map<String, Value>,
+    responses: Map<String, Value>,
+    parameters: Vec<Value>,
+}
+
+impl Operation {
+    fn new(tag: &str, summary: &str) -> Self {
+        let mut value = Map::new();
+        value.insert("tags".to_string(), json!([tag]));
+        value.insert("summary".to_string(), json!(summary));
+        Self { value, responses: Map::new(), parameters: Vec::new() }
+    }
+
+    /// Requête authentifiée (token Bearer)
+    fn authenticated(mut self) -> Self {
+        self.value.insert("security".to_string(), json!([{ "bearerAuth": [] }]));
+        self
+    }
+
+    /// Corps JSON décrit par un schéma
+    fn body(mut self, schema: &str) -> Self {
+        self.value.insert("requestBody".to_string(), json!({
+            "required": true,
+            "content": { "application/json": { "schema": reference(schema) } },
+        }));
+        self
+    }
+
+    /// Opération conservée pour compatibilité, remplacée par `successor`
+    fn deprecated(mut self, successor: &str) -> Self {
+        self.value.insert("deprecated".to_string(), json!(true));
+        self.value.insert("description".to_string(), json!(format!("Obsolète: utiliser `{}`.", successor)));
+        self
+    }
+
+    fn path_param(mut self, name: &str) -> Self {
+        self.parameters.push(json!({
+            "name": name, "in": "path", "required": true, "schema": uuid(),
+        }));
+        self
+    }
+
+    /// Paramètre de chemin opaque (token)
+    fn string_path_param(mut self, name: &str) -> Self {
+        self.parameters.push(json!({
+            "name": name, "in": "path", "required": true, "schema": string(),
+        }));
+        self
+    }
+
+    fn query_param(mut self, name: &str, schema: Value) -> Self {
+        self.parameters.push(json!({
+            "name": name, "in": "query", "required": false, "schema": schema,
+        }));
+        self
+    }
+
+    /// Réponse sans corps décrit (ou message d'erreur)
+    fn status(mut self, code: u16, description: &str) -> Self {
+        let content = if code >= 400 {
+            json!({ "description": description, "content": { "application/json": { "schema": reference("Error") } } })
+        } else {
+            json!({ "description": description })
+        };
+        self.responses.insert(code.to_string(), content);
+        self
+    }
+
+    /// Réponse JSON décrite par un schéma
+    fn returns(mut self, code: u16, description: &str, schema: Value) -> Self {
+        self.responses.insert(code.to_string(), json!({
+            "description": description,
+            "content": { "application/json": { "schema": schema } },
+        }));
+        self
+    }
+
+    fn build(mut self) -> Value {
+        if !self.parameters.is_empty() {
+            self.value.insert("parameters".to_string(), Value::Array(self.parameters));
+        }
+        self.value.insert("responses".to_string(), Value::Object(self.responses));
+        Value::Object(self.value)
+    }
+}
+
+fn paths() -> Value {
+    let mut paths = Map::new();
+    let mut add = |path: &str, method: &str, operation: Operation| {
+        let entry = paths.entry(path.to_string()).or_insert_with(|| json!({}));
+        entry[method] = operation.build();
+    };
+
+    // Authentification
+    add("/auth/register", "post", Operation::new("auth", "Créer un compte")
+        .body("NewUser")
+        .returns(201, "Compte créé", reference("AuthToken"))
+        .status(400, "Données invalides")
+        .status(409, "Email déjà utilisé"));
+    add("/auth/login", "post", Operation::new("auth", "Se connecter")
+        .body("UserLogin")
+        .returns(200, "Connexion réussie", reference("AuthToken"))
+        .status(401, "Identifiants invalides"));
+    add("/auth/google", "post", Operation::new("auth", "Se connecter avec Google")
+        .body("GoogleAuth")
+        .returns(200, "Connexion réussie", reference("AuthToken"))
+        .status(401, "Token Google invalide"));
+    add("/auth/refresh", "post", Operation::new("auth", "Renouveler le token d'accès")
+        .body("RefreshTokenRequest")
+        .returns(200, "Nouveau token", reference("AuthToken"))
+        .status(401, "Token de rafraîchissement invalide"));
+    add("/auth/logout", "post", Operation::new("auth", "Se déconnecter")
+        .authenticated()
+        .status(200, "Déconnecté"));
+
+    // Jobs
+    add("/jobs", "post", Operation::new("jobs", "Créer un job de quantification")
+        .authenticated()
+        .body("NewJob")
+        .returns(201, "Job créé", reference("Job"))
+        .status(400, "Paramètres invalides")
+        .status(402, "Crédits insuffisants ou format de sortie hors plan")
+        .status(409, "Nom déjà porté par un job actif (si unique_job_names)")
+        .status(422, "Modèle déjà quantifié")
+        .status(429, "Trop de jobs actifs")
+        .status(503, "Méthode indisponible sur ce serveur"));
+    add("/jobs", "get", Operation::new("jobs", "Lister ses jobs")
+        .authenticated()
+        .query_param("status", reference("JobStatus"))
+        .query_param("page", integer())
+        .query_param("per_page", integer())
+        .returns(200, "Page de jobs", paginated("JobSummary")));
+    add("/jobs/cost", "get", Operation::new("jobs", "Estimer le coût d'un job")
+        .authenticated()
+        .query_param("method", reference("QuantizationMethod"))
+        .query_param("size", integer())
+        .query_param("parameter_count", number())
+        .returns(200, "Coût estimé", reference("JobCost"))
+        .status(400, "Paramètres invalides"));
+    add("/jobs/{job_id}", "get", Operation::new("jobs", "Obtenir un job")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "Job", reference("JobResult"))
+        .status(304, "Job inchangé (If-None-Match)")
+        .status(403, "Accès non autorisé")
+        .status(404, "Job non trouvé"));
+    add("/jobs/{job_id}/report", "get", Operation::new("jobs", "Rapport de quantification")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "Rapport", reference("QuantizationReport"))
+        .status(404, "Job ou rapport non trouvé"));
+    add("/jobs/{job_id}/validate", "post", Operation::new("jobs", "Relancer la mesure de qualité d'un job terminé")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "Rapport mis à jour", reference("QuantizationReport"))
+        .status(400, "Job non terminé ou sans rapport")
+        .status(402, "Crédits insuffisants")
+        .status(403, "Accès non autorisé")
+        .status(404, "Job ou fichier non trouvé"));
+    add("/jobs/{job_id}/cancel", "post", Operation::new("jobs", "Annuler un job (corps facultatif: {\"reason\": \"...\"})")
+        .authenticated()
+        .path_param("job_id")
+        .status(200, "Job annulé")
+        .status(404, "Job non trouvé")
+        .status(412, "Job non annulable"));
+    add("/jobs/{job_id}/download", "get", Operation::new("jobs", "URL de téléchargement du résultat")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "URL signée", reference("FileDownload"))
+        .status(404, "Job ou résultat non trouvé"));
+    add("/jobs/{job_id}/stream", "get", Operation::new("jobs", "Télécharger le résultat (Range supporté)")
+        .authenticated()
+        .path_param("job_id")
+        .status(200, "Fichier complet")
+        .status(206, "Plage demandée")
+        .status(404, "Job ou résultat non trouvé")
+        .status(416, "Plage non satisfiable")
+        .status(429, "Trop de téléchargements"));
+    add("/jobs/{job_id}/download-all", "get", Operation::new("jobs", "Télécharger tous les fichiers produits et le rapport (zip)")
+        .authenticated()
+        .path_param("job_id")
+        .status(200, "Archive zip")
+        .status(400, "Job non terminé")
+        .status(404, "Job ou résultat non trouvé")
+        .status(429, "Trop de téléchargements"));
+    add("/jobs/{job_id}/logs", "get", Operation::new("jobs", "Journal d'exécution")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "Lignes du journal", array(reference("JobLog")))
+        .status(404, "Job non trouvé"));
+    add("/jobs/{job_id}/timeline", "get", Operation::new("jobs", "Chronologie du cycle de vie")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "Événements dans l'ordre", array(reference("JobEvent")))
+        .status(403, "Accès non autorisé")
+        .status(404, "Job non trouvé"));
+    add("/jobs/{job_id}/share", "post", Operation::new("jobs", "Créer un lien de partage public du résultat (corps facultatif)")
+        .authenticated()
+        .path_param("job_id")
+        .body("NewJobShareLink")
+        .returns(201, "Lien créé (token affiché une seule fois)", reference("IssuedShareLink"))
+        .status(400, "Job non terminé ou paramètres invalides")
+        .status(403, "Accès non autorisé")
+        .status(404, "Job ou résultat non trouvé"));
+    add("/jobs/{job_id}/share", "get", Operation::new("jobs", "Lister les liens de partage")
+        .authenticated()
+        .path_param("job_id")
+        .returns(200, "Liens (sans token)", array(reference("JobShareLink")))
+        .status(403, "Accès non autorisé")
+        .status(404, "Job non trouvé"));
+    add("/jobs/{job_id}/share/{share_id}", "delete", Operation::new("jobs", "Révoquer un lien de partage")
+        .authenticated()
+        .path_param("job_id")
+        .path_param("share_id")
+        .status(204, "Lien révoqué")
+        .status(404, "Lien non trouvé"));
+    add("/shared/{token}", "get", Operation::new("jobs", "Télécharger un résultat partagé (sans authentification, Range supporté)")
+        .string_path_param("token")
+        .status(200, "Fichier complet")
+        .status(206, "Plage demandée")
+        .status(404, "Lien invalide")
+        .status(410, "Lien expiré, révoqué ou épuisé")
+        .status(416, "Plage non satisfiable")
+        .status(429, "Trop de téléchargements"));
+
+    // Abonnements et crédits
+    add("/billing/plans", "get", Operation::new("billing", "Lister les plans")
+        .returns(200, "Plans disponibles", array(reference("PlanInfo"))));
+    add("/billing/subscription", "get", Operation::new("billing", "Abonnement courant")
+        .authenticated()
+        .returns(200, "Abonnement", reference("Subscription")));
+    add("/billing/subscription", "post", Operation::new("billing", "Changer de plan")
+        .authenticated()
+        .body("UpdateSubscriptionRequest")
+        .returns(200, "Abonnement mis à jour", reference("Subscription"))
+        .status(400, "Plan invalide")
+        .status(402, "Paiement refusé"));
+    add("/billing/subscription/cancel", "post", Operation::new("billing", "Annuler l'abonnement")
+        .authenticated()
+        .returns(200, "Abonnement annulé", reference("Subscription"))
+        .status(404, "Aucun abonnement actif"));
+    add("/billing/subscription/projection", "get", Operation::new("billing", "Projection de la consommation")
+        .authenticated()
+        .returns(200, "Projection", reference("UsageProjection"))
+        .status(404, "Aucun abonnement"));
+    add("/billing/credits", "get", Operation::new("billing", "Solde de crédits")
+        .authenticated()
+        .returns(200, "Crédits", reference("CreditInfo")));
+    add("/billing/credits/history", "get", Operation::new("billing", "Historique des crédits")
+        .authenticated()
+        .query_param("page", integer())
+        .query_param("per_page", integer())
+        .returns(200, "Transactions", array(reference("CreditTransaction"))));
+    add("/billing/checkout", "post", Operation::new("billing", "Créer une session de paiement")
+        .authenticated()
+        .body("CreateCheckoutRequest")
+        .returns(200, "Session créée", json!({ "type": "object" }))
+        .status(400, "Plan invalide"));
+
+    // Modèles quantifiés
+    add("/models", "get", Operation::new("models", "Lister ses modèles quantifiés")
+        .authenticated()
+        .query_param("format", reference("ModelFormat"))
+        .query_param("page", integer())
+        .query_param("per_page", integer())
+        .returns(200, "Page de modèles", paginated("Job")));
+    add("/models/compare", "get", Operation::new("models", "Comparer deux variantes")
+        .authenticated()
+        .query_param("job_a", uuid())
+        .query_param("job_b", uuid())
+        .returns(200, "Comparaison", json!({ "type": "object" }))
+        .status(404, "Modèle non trouvé"));
+    add("/models/upload-url", "post", Operation::new("models", "Obtenir une URL d'upload direct")
+        .authenticated()
+        .body("DirectUploadRequest")
+        .returns(201, "URL PUT signée", reference("DirectUploadUrl"))
+        .status(413, "Fichier trop volumineux ou quota dépassé")
+        .status(415, "Extension non autorisée")
+        .status(503, "Upload direct indisponible"));
+    add("/models/upload-complete", "post", Operation::new("models", "Confirmer un upload direct")
+        .authenticated()
+        .body("DirectUploadComplete")
+        .returns(201, "Modèle enregistré", reference("FileMetadata"))
+        .status(404, "Upload inconnu ou expiré")
+        .status(409, "Fichier absent du stockage")
+        .status(413, "Fichier trop volumineux ou quota dépassé")
+        .status(422, "Empreinte ou format invalide"));
+    add("/models/{model_id}", "delete", Operation::new("models", "Supprimer un modèle")
+        .authenticated()
+        .path_param("model_id")
+        .status(204, "Modèle supprimé")
+        .status(403, "Accès non autorisé")
+        .status(404, "Modèle non trouvé")
+        .status(409, "Modèle utilisé par un job actif"));
+    add("/models/{model_id}/download", "get", Operation::new("models", "Télécharger le modèle original")
+        .authenticated()
+        .path_param("model_id")
+        .status(200, "Fichier original")
+        .status(403, "Accès non autorisé")
+        .status(404, "Modèle non trouvé")
+        .status(410, "Modèle expiré et supprimé")
+        .status(429, "Trop de téléchargements"));
+    add("/models/{model_id}/analyze", "post", Operation::new("models", "Analyser un modèle")
+        .authenticated()
+        .path_param("model_id")
+        .returns(200, "Analyse terminée", reference("FileAnalysis"))
+        .status(202, "Analyse lancée en tâche de fond (voir Location)")
+        .status(404, "Modèle non trouvé"));
+    add("/models/{model_id}/analysis", "get", Operation::new("models", "État de l'analyse")
+        .authenticated()
+        .path_param("model_id")
+        .returns(200, "Analyse", reference("FileAnalysis"))
+        .status(404, "Modèle non trouvé"));
+
+    // Notifications
+    add("/users/me/notifications", "get", Operation::new("notifications", "Boîte de réception in-app")
+        .authenticated()
+        .query_param("page", integer())
+        .query_param("per_page", integer())
+        .returns(200, "Notifications, les plus récentes d'abord", reference("NotificationInbox")));
+    add("/users/me/notifications", "put", Operation::new("notifications", "Modifier les préférences de notification")
+        .authenticated()
+        .deprecated("PUT /users/me/notification-preferences")
+        .body("UpdateNotificationPreferences")
+        .returns(200, "Préférences", reference("NotificationPreferences")));
+    add("/users/me/notifications/{notification_id}/read", "post", Operation::new("notifications", "Marquer une notification comme lue")
+        .authenticated()
+        .path_param("notification_id")
+        .status(204, "Notification lue")
+        .status(404, "Notification non trouvée"));
+    add("/users/me/notification-preferences", "get", Operation::new("notifications", "Préférences de notification")
+        .authenticated()
+        .returns(200, "Préférences", reference("NotificationPreferences")));
+    add("/users/me/notification-preferences", "put", Operation::new("notifications", "Modifier les préférences de notification")
+        .authenticated()
+        .body("UpdateNotificationPreferences")
+        .returns(200, "Préférences", reference("NotificationPreferences")));
+
+    // Capacités
+    add("/capabilities", "get", Operation::new("capabilities", "Méthodes disponibles sur ce serveur")
+        .returns(200, "Capacités", reference("Capabilities"))
+        .status(503, "Capacités pas encore sondées"));
+    add("/quantization/methods", "get", Operation::new("capabilities", "Méthodes de quantification supportées")
+        .returns(200, "Méthodes", array(reference("QuantizationMethodInfo"))));
+    add("/quantization/presets", "get", Operation::new("capabilities", "Préréglages de quantification")
+        .returns(200, "Préréglages", array(reference("QuantizationPresetInfo"))));
+
+    Value::Object(paths)
+}
+
+// === SCHÉMAS ===
+
+fn schemas() -> Value {
+    json!({
+        "Error": string(),
+
+        // Énumérations (valeurs telles que sérialisées par serde)
+        "JobStatus": enumeration(&[
+            JobStatus::Pending, JobStatus::Processing, JobStatus::Completed,
+            JobStatus::Failed, JobStatus::Cancelled,
+        ]),
+        "QuantizationMethod": enumeration(&QuantizationMethod::ALL),
+        "QuantizationPreset": enumeration(&QuantizationPreset::ALL),
+        "ModelFormat": enumeration(&[
+            ModelFormat::PyTorch, ModelFormat::Onnx, ModelFormat::Safetensors, ModelFormat::Gguf,
+        ]),
+        "JobSource": enumeration(&[JobSource::Upload, JobSource::Huggingface]),
+        "CancelledBy": enumeration(&[CancelledBy::User, CancelledBy::Admin, CancelledBy::System]),
+        "SubscriptionPlan": enumeration(&[
+            SubscriptionPlan::Free, SubscriptionPlan::Starter, SubscriptionPlan::Pro,
+        ]),
+        "SubscriptionStatus": enumeration(&[
+            SubscriptionStatus::Active, SubscriptionStatus::PastDue,
+            SubscriptionStatus::Cancelled, SubscriptionStatus::Trialing,
+        ]),
+        "AnalysisStatus": enumeration(&[
+            AnalysisStatus::Pending, AnalysisStatus::Running,
+            AnalysisStatus::Completed, AnalysisStatus::Failed,
+        ]),
+
+        // Authentification
+        "NewUser": object(&["email", "password"], &[
+            ("email", json!({ "type": "string", "format": "email" })),
+            ("password", json!({ "type": "string", "minLength": 8 })),
+        ]),
+        "UserLogin": object(&["email", "password"], &[
+            ("email", json!({ "type": "string", "format": "email" })),
+            ("password", string()),
+        ]),
+        "GoogleAuth": object(&["google_token"], &[("google_token", string())]),
+        "RefreshTokenRequest": object(&["refresh_token"], &[("refresh_token", string())]),
+        "AuthToken": object(&["access_token", "refresh_token", "token_type", "expires_in"], &[
+            ("access_token", string()),
+            ("refresh_token", string()),
+            ("token_type", string()),
+            ("expires_in", integer()),
+        ]),
+
+        // Jobs
+        "LayerOverride": object(&["pattern"], &[
+            ("pattern", string()),
+            ("bits", nullable(integer())),
+        ]),
+        "QuantizationConfig": object(&[], &[
+            ("layer_overrides", array(reference("LayerOverride"))),
+            ("bits", nullable(integer())),
+            ("group_size", json!({ "type": "integer", "enum": [32, 64, 128, 256], "nullable": true })),
+            ("calibration_samples", json!({ "type": "integer", "minimum": 1, "maximum": MAX_CALIBRATION_SAMPLES, "nullable": true })),
+            ("allow_quantized_input", json!({ "type": "boolean", "default": false })),
+            ("max_quality_loss_percent", nullable(number())),
+            ("gguf_export", json!({ "type": "string", "enum": ["q4_0", "q5_0"], "nullable": true })),
+        ]),
+        "NewJob": object(&["name"], &[
+            ("name", json!({ "type": "string", "minLength": 1, "maxLength": 100 })),
+            ("preset", nullable(reference("QuantizationPreset"))),
+            ("quantization_method", nullable(reference("QuantizationMethod"))),
+            ("output_format", nullable(reference("ModelFormat"))),
+            ("source", reference("JobSource")),
+            ("repo_id", nullable(string())),
+            ("revision", nullable(string())),
+            ("config", reference("QuantizationConfig")),
+        ]),
+        "Job": object(&["id", "name", "status", "progress", "quantization_method", "created_at"], &[
+            ("id", uuid()),
+            ("user_id", uuid()),
+            ("name", string()),
+            ("status", reference("JobStatus")),
+            ("progress", integer()),
+            ("quantization_method", reference("QuantizationMethod")),
+            ("input_format", reference("ModelFormat")),
+            ("output_format", reference("ModelFormat")),
+            ("input_file_id", nullable(uuid())),
+            ("source_repo_id", nullable(string())),
+            ("source_revision", nullable(string())),
+            ("output_file_id", nullable(uuid())),
+            ("error_message", nullable(string())),
+            ("retry_count", integer()),
+            ("error_history", array(reference("JobError"))),
+            ("cancelled_by", nullable(reference("CancelledBy"))),
+            ("cancellation_reason", nullable(string())),
+            ("original_size", nullable(integer())),
+            ("quantized_size", nullable(integer())),
+            ("processing_time", nullable(integer())),
+            ("credits_used", integer()),
+            ("created_at", date_time()),
+            ("updated_at", date_time()),
+            ("started_at", nullable(date_time())),
+            ("completed_at", nullable(date_time())),
+            ("report", nullable(reference("QuantizationReport"))),
+            ("quantization_config", nullable(reference("QuantizationConfig"))),
+        ]),
+        "JobSummary": object(&["id", "name", "status", "progress", "quantization_method", "downloadable", "created_at"], &[
+            ("id", uuid()),
+            ("name", string()),
+            ("status", reference("JobStatus")),
+            ("progress", integer()),
+            ("quantization_method", reference("QuantizationMethod")),
+            ("input_format", reference("ModelFormat")),
+            ("output_format", reference("ModelFormat")),
+            ("original_size", nullable(integer())),
+            ("quantized_size", nullable(integer())),
+            ("compression_ratio", nullable(number())),
+            ("credits_used", integer()),
+            ("downloadable", json!({ "type": "boolean" })),
+            ("created_at", date_time()),
+            ("completed_at", nullable(date_time())),
+        ]),
+        "JobError": object(&["timestamp", "message"], &[
+            ("timestamp", date_time()),
+            ("message", string()),
+        ]),
+        "JobResult": object(&["id", "status", "progress", "created_at"], &[
+            ("id", uuid()),
+            ("status", reference("JobStatus")),
+            ("progress", integer()),
+            ("error_message", nullable(string())),
+            ("retry_count", integer()),
+            ("error_history", array(reference("JobError"))),
+            ("cancelled_by", nullable(reference("CancelledBy"))),
+            ("cancellation_reason", nullable(string())),
+            ("original_size", nullable(integer())),
+            ("quantized_size", nullable(integer())),
+            ("compression_ratio", nullable(number())),
+            ("downloadable", json!({ "type": "boolean" })),
+            ("created_at", date_time()),
+            ("completed_at", nullable(date_time())),
+        ]),
+        "QuantizationReport": object(&["original_size", "quantized_size", "size_reduction_percent"], &[
+            ("original_size", integer()),
+            ("quantized_size", integer()),
+            ("size_reduction_percent", number()),
+            ("perplexity_before", nullable(number())),
+            ("perplexity_after", nullable(number())),
+            ("latency_before_ms", nullable(number())),
+            ("latency_after_ms", nullable(number())),
+            ("converted_from", nullable(reference("ModelFormat"))),
+            ("overridden_layers", array(string())),
+            ("calibration_samples", nullable(integer())),
+            ("peak_memory_bytes", nullable(integer())),
+            ("model_architecture", string()),
+            ("stage_timings", reference("StageTimings")),
+            ("experimental", json!({ "type": "boolean" })),
+            ("warnings", array(string())),
+        ]),
+        "StageTimings": object(&[], &[
+            ("download", nullable(number())),
+            ("analyze", nullable(number())),
+            ("quantize", nullable(number())),
+            ("validate", nullable(number())),
+            ("export", nullable(number())),
+            ("upload", nullable(number())),
+        ]),
+        "JobLog": object(&["id", "job_id", "level", "message", "created_at"], &[
+            ("id", uuid()),
+            ("job_id", uuid()),
+            ("level", json!({ "type": "string", "enum": ["info", "warn", "error"] })),
+            ("message", string()),
+            ("created_at", date_time()),
+        ]),
+        "JobEvent": object(&["id", "event", "created_at"], &[
+            ("id", uuid()),
+            ("event", json!({
+                "type": "string",
+                "enum": ["created", "queued", "picked_up", "stage_started", "completed", "failed", "cancelled"],
+            })),
+            ("stage", nullable(string())),
+            ("worker_id", nullable(string())),
+            ("message", nullable(string())),
+            ("created_at", date_time()),
+        ]),
+        "JobCost": object(&["credits", "estimated_eur"], &[
+            ("credits", integer()),
+            ("estimated_eur", number()),
+        ]),
+        "FileDownload": object(&["id", "filename", "file_size", "download_url", "expires_at"], &[
+            ("id", uuid()),
+            ("filename", string()),
+            ("file_size", integer()),
+            ("download_url", string()),
+            ("expires_at", date_time()),
+        ]),
+        "NewJobShareLink": object(&[], &[
+            ("expires_in_hours", integer()),
+            ("max_downloads", integer()),
+        ]),
+        "JobShareLink": object(&["id", "job_id", "user_id", "expires_at", "download_count", "created_at"], &[
+            ("id", uuid()),
+            ("job_id", uuid()),
+            ("user_id", uuid()),
+            ("expires_at", date_time()),
+            ("max_downloads", nullable(integer())),
+            ("download_count", integer()),
+            ("revoked_at", nullable(date_time())),
+            ("created_at", date_time()),
+        ]),
+        "IssuedShareLink": object(&["id", "job_id", "user_id", "expires_at", "download_count", "created_at", "token", "url"], &[
+            ("id", uuid()),
+            ("job_id", uuid()),
+            ("user_id", uuid()),
+            ("expires_at", date_time()),
+            ("max_downloads", nullable(integer())),
+            ("download_count", integer()),
+            ("revoked_at", nullable(date_time())),
+            ("created_at", date_time()),
+            ("token", string()),
+            ("url", string()),
+        ]),
+
+        // Abonnements
+        "PlanInfo": object(&["plan", "name", "price_monthly", "credits_per_month", "features"], &[
+            ("plan", reference("SubscriptionPlan")),
+            ("name", string()),
+            ("price_monthly", integer()),
+            ("credits_per_month", integer()),
+            ("features", array(string())),
+        ]),
+        "Subscription": object(&["id", "plan", "status", "current_period_start", "current_period_end"], &[
+            ("id", uuid()),
+            ("user_id", uuid()),
+            ("plan", reference("SubscriptionPlan")),
+            ("status", reference("SubscriptionStatus")),
+            ("current_period_start", date_time()),
+            ("current_period_end", date_time()),
+            ("stripe_subscription_id", nullable(string())),
+            ("stripe_price_id", nullable(string())),
+            ("cancelled_at", nullable(date_time())),
+            ("trial_ends_at", nullable(date_time())),
+            ("pending_plan", nullable(reference("SubscriptionPlan"))),
+            ("created_at", date_time()),
+            ("updated_at", date_time()),
+        ]),
+        "UpdateSubscriptionRequest": object(&["plan"], &[
+            ("plan", string()),
+            ("payment_method_id", nullable(string())),
+        ]),
+        "CreateCheckoutRequest": object(&["plan", "success_url", "cancel_url"], &[
+            ("plan", string()),
+            ("success_url", string()),
+            ("cancel_url", string()),
+        ]),
+        "DirectUploadRequest": object(&["filename", "file_size", "checksum_sha256"], &[
+            ("filename", string()),
+            ("file_size", integer()),
+            ("checksum_sha256", string()),
+        ]),
+        "DirectUploadUrl": object(&["upload_id", "upload_url", "headers", "expires_at"], &[
+            ("upload_id", uuid()),
+            ("upload_url", string()),
+            ("headers", json!({ "type": "object", "additionalProperties": string() })),
+            ("expires_at", date_time()),
+        ]),
+        "DirectUploadComplete": object(&["upload_id"], &[
+            ("upload_id", uuid()),
+        ]),
+        "FileMetadata": object(&["id", "filename", "file_size", "format", "created_at"], &[
+            ("id", uuid()),
+            ("filename", string()),
+            ("file_size", integer()),
+            ("format", reference("ModelFormat")),
+            ("model_type", nullable(string())),
+            ("architecture", nullable(string())),
+            ("parameter_count", nullable(number())),
+            ("created_at", date_time()),
+        ]),
+        "CreditInfo": object(&["total_credits", "used_credits", "unlimited"], &[
+            ("total_credits", integer()),
+            ("used_credits", integer()),
+            ("remaining_credits", nullable(integer())),
+            ("unlimited", json!({ "type": "boolean" })),
+            ("reset_date", nullable(date_time())),
+        ]),
+        "UsageProjection": object(&["plan", "credits_per_day", "period_end", "will_exhaust"], &[
+            ("plan", reference("SubscriptionPlan")),
+            ("window_days", integer()),
+            ("credits_per_day", number()),
+            ("jobs_per_day", number()),
+            ("remaining_credits", nullable(integer())),
+            ("period_end", date_time()),
+            ("projected_credits", number()),
+            ("will_exhaust", json!({ "type": "boolean" })),
+            ("exhausted_at", nullable(date_time())),
+            ("suggested_plan", nullable(reference("SubscriptionPlan"))),
+        ]),
+        "CreditTransaction": object(&["id", "transaction_type", "amount", "balance_after", "created_at"], &[
+            ("id", uuid()),
+            ("user_id", uuid()),
+            ("transaction_type", string()),
+            ("amount", integer()),
+            ("balance_after", integer()),
+            ("job_id", nullable(uuid())),
+            ("description", nullable(string())),
+            ("created_at", date_time()),
+        ]),
+
+        // Modèles
+        "FileAnalysis": object(&["file_id"], &[
+            ("file_id", uuid()),
+            ("status", nullable(reference("AnalysisStatus"))),
+            ("result", nullable(json!({ "type": "object" }))),
+            ("error", nullable(string())),
+            ("analyzed_at", nullable(date_time())),
+        ]),
+
+        // Capacités
+        "MethodCapability": object(&["method", "available"], &[
+            ("method", reference("QuantizationMethod")),
+            ("available", json!({ "type": "boolean" })),
+            ("reason", nullable(string())),
+        ]),
+        "QuantizationMethodInfo": object(&["method", "bits", "credit_cost", "available"], &[
+            ("method", reference("QuantizationMethod")),
+            ("bits", integer()),
+            ("credit_cost", integer()),
+            ("requires_gpu", json!({ "type": "boolean" })),
+            ("requires_calibration", json!({ "type": "boolean" })),
+            ("tunable", json!({ "type": "boolean" })),
+            ("output_formats", array(reference("ModelFormat"))),
+            ("size_reduction_min_percent", integer()),
+            ("size_reduction_max_percent", integer()),
+            ("available", json!({ "type": "boolean" })),
+            ("unavailable_reason", nullable(string())),
+        ]),
+        "QuantizationPresetInfo": object(&["preset", "description", "quantization_method", "config"], &[
+            ("preset", reference("QuantizationPreset")),
+            ("description", string()),
+            ("quantization_method", reference("QuantizationMethod")),
+            ("config", reference("QuantizationConfig")),
+        ]),
+        "Notification": object(&["id", "type", "title", "body", "read", "created_at"], &[
+            ("id", uuid()),
+            ("type", string()),
+            ("title", string()),
+            ("body", string()),
+            ("read", json!({ "type": "boolean" })),
+            ("created_at", date_time()),
+        ]),
+        "NotificationInbox": object(&["items", "total", "page", "per_page", "total_pages", "unread_count"], &[
+            ("items", array(reference("Notification"))),
+            ("total", integer()),
+            ("page", integer()),
+            ("per_page", integer()),
+            ("total_pages", integer()),
+            ("unread_count", integer()),
+        ]),
+        "NotificationPreferences": object(&["job_complete_email", "job_failed_email", "job_complete_sms", "file_expiry_email", "marketing", "updated_at"], &[
+            ("job_complete_email", json!({ "type": "boolean" })),
+            ("job_failed_email", json!({ "type": "boolean" })),
+            ("job_complete_sms", json!({ "type": "boolean" })),
+            ("file_expiry_email", json!({ "type": "boolean" })),
+            ("marketing", json!({ "type": "boolean" })),
+            ("updated_at", date_time()),
+        ]),
+        "UpdateNotificationPreferences": object(&[], &[
+            ("job_complete_email", json!({ "type": "boolean" })),
+            ("job_failed_email", json!({ "type": "boolean" })),
+            ("job_complete_sms", json!({ "type": "boolean" })),
+            ("file_expiry_email", json!({ "type": "boolean" })),
+            ("marketing", json!({ "type": "boolean" })),
+        ]),
+        "Capabilities": object(&["gpu_enabled", "methods", "checked_at"], &[
+            ("gpu_enabled", json!({ "type": "boolean" })),
+            ("methods", array(reference("MethodCapability"))),
+            ("checked_at", date_time()),
+        ]),
+    })
+}
+
+/// Page de résultats (`PaginatedResponse<T>`)
+fn paginated(item: &str) -> Value {
+    object(&["items", "total", "page", "per_page", "total_pages"], &[
+        ("items", array(reference(item))),
+        ("total", integer()),
+        ("page", integer()),
+        ("per_page", integer()),
+        ("total_pages", integer()),
+    ])
+}
+
+/// Énumération dont les valeurs sont celles produites par serde
+fn enumeration<T: Serialize>(variants: &[T]) -> Value {
+    let values: Vec<Value> = variants
+        .iter()
+        .filter_map(|variant| serde_json::to_value(variant).ok())
+        .collect();
+    json!({ "type": "string", "enum": values })
+}
+
+fn object(required: &[&str], properties: &[(&str, Value)]) -> Value {
+    let properties: Map<String, Value> = properties
+        .iter()
+        .map(|(name, schema)| (name.to_string(), schema.clone()))
+        .collect();
+
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn nullable(schema: Value) -> Value {
+    // OpenAPI 3.0: une référence ne peut pas porter `nullable` directement
+    json!({ "allOf": [schema], "nullable": true })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer" })
+}
+
+fn number() -> Value {
+    json!({ "type": "number" })
+}
+
+fn uuid() -> Value {
+    json!({ "type": "string", "format": "uuid" })
+}
+
+fn date_time() -> Value {
+    json!({ "type": "string", "format": "date-time" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+
+    /// Références `$ref` du document, récursivement
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(target)) = map.get("$ref") {
+                    refs.push(target);
+                }
+                map.values().for_each(|child| collect_refs(child, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|child| collect_refs(child, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn job_creation_is_described_with_its_request_schema() {
+        let doc = document();
+        let create = &doc["paths"]["/jobs"]["post"];
+        assert_eq!(
+            create["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/NewJob"
+        );
+        assert!(create["responses"]["201"].is_object());
+        assert!(doc["components"]["schemas"]["NewJob"]["properties"]["name"].is_object());
+    }
+
+    /// Schéma désigné par une référence (ou le schéma lui-même)
+    fn resolve<'a>(doc: &'a Value, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(target) => &doc["components"]["schemas"][target.trim_start_matches("#/components/schemas/")],
+            None => schema,
+        }
+    }
+
+    /// Exemple conforme au schéma, avec toutes ses propriétés renseignées
+    fn sample(doc: &Value, schema: &Value) -> Value {
+        let schema = resolve(doc, schema);
+        if let Some(inner) = schema["allOf"].get(0) {
+            return sample(doc, inner);
+        }
+        if let Some(first) = schema["enum"].get(0) {
+            return first.clone();
+        }
+
+        match schema["type"].as_str() {
+            Some("string") => match schema["format"].as_str() {
+                Some("uuid") => json!("00000000-0000-0000-0000-000000000001"),
+                Some("date-time") => json!("2025-01-01T00:00:00Z"),
+                Some("email") => json!("alice@example.com"),
+                _ => json!("exemple"),
+            },
+            Some("integer") => schema.get("minimum").cloned().unwrap_or(json!(1)),
+            Some("number") => json!(1.5),
+            Some("boolean") => json!(true),
+            Some("array") => json!([sample(doc, &schema["items"])]),
+            _ => {
+                let mut object = Map::new();
+                for (name, property) in schema["properties"].as_object().into_iter().flatten() {
+                    object.insert(name.clone(), sample(doc, property));
+                }
+                if let Some(values) = schema.get("additionalProperties") {
+                    object.insert("cle".to_string(), sample(doc, values));
+                }
+                Value::Object(object)
+            }
+        }
+    }
+
+    /// Écarts entre une valeur sérialisée et son schéma
+    fn check(doc: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+        let schema = resolve(doc, schema);
+        if value.is_null() {
+            if schema["nullable"] != true {
+                errors.push(format!("{}: null non déclaré (nullable)", path));
+            }
+            return;
+        }
+        if let Some(inner) = schema["allOf"].get(0) {
+            return check(doc, inner, value, path, errors);
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            if !values.contains(value) {
+                errors.push(format!("{}: {} absent de l'énumération", path, value));
+            }
+            return;
+        }
+
+        let conforms = match schema["type"].as_str() {
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("number") => value.is_number(),
+            Some("boolean") => value.is_boolean(),
+            Some("array") => match value.as_array() {
+                Some(items) => {
+                    for (index, item) in items.iter().enumerate() {
+                        check(doc, &schema["items"], item, &format!("{}[{}]", path, index), errors);
+                    }
+                    true
+                }
+                None => false,
+            },
+            Some("object") => match value.as_object() {
+                Some(object) => {
+                    let properties = schema["properties"].as_object();
+                    for required in schema["required"].as_array().into_iter().flatten() {
+                        let required = required.as_str().unwrap_or_default();
+                        if !object.contains_key(required) {
+                            errors.push(format!("{}.{}: requis mais absent", path, required));
+                        }
+                    }
+                    for (name, field) in object {
+                        let field_path = format!("{}.{}", path, name);
+                        match (properties.and_then(|p| p.get(name)), schema.get("additionalProperties")) {
+                            (Some(property), _) => check(doc, property, field, &field_path, errors),
+                            (None, Some(values)) => check(doc, values, field, &field_path, errors),
+                            (None, None) if properties.is_some() => {
+                                errors.push(format!("{}: propriété non documentée", field_path));
+                            }
+                            (None, None) => {}
+                        }
+                    }
+                    true
+                }
+                None => false,
+            },
+            _ => true,
+        };
+        if !conforms {
+            errors.push(format!("{}: {} n'est pas du type {}", path, value, schema["type"]));
+        }
+    }
+
+    /// Exemple du schéma -> `T` -> JSON, comparé au schéma
+    ///
+    /// Chaque propriété est aussi envoyée à `null`: si `T` l'accepte (champ
+    /// optionnel), ce qu'il en sérialise doit rester conforme. `patch`
+    /// complète l'exemple des champs lus mais jamais sérialisés.
+    fn round_trip<T>(name: &str, patch: impl Fn(&mut Value)) -> Vec<String>
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+    {
+        let doc = document();
+        let schema = reference(name);
+        let mut input = sample(doc, &schema);
+        patch(&mut input);
+
+        let mut errors = Vec::new();
+        match serde_json::from_value::<T>(input.clone()) {
+            Ok(parsed) => check(doc, &schema, &serde_json::to_value(parsed).unwrap(), name, &mut errors),
+            Err(e) => errors.push(format!("{}: exemple du schéma refusé par la structure ({})", name, e)),
+        }
+
+        let properties: Vec<String> = input.as_object().map(|o| o.keys().cloned().collect()).unwrap_or_default();
+        for property in properties {
+            let mut with_null = input.clone();
+            with_null[&property] = Value::Null;
+            if let Ok(parsed) = serde_json::from_value::<T>(with_null) {
+                let path = format!("{} ({} = null)", name, property);
+                check(doc, &schema, &serde_json::to_value(parsed).unwrap(), &path, &mut errors);
+            }
+        }
+        errors
+    }
+
+    /// Corps de requête: l'exemple du schéma est accepté, et chaque propriété
+    /// documentée est bien lue (un type erroné sur l'une d'elles est refusé)
+    fn read_by<T: serde::de::DeserializeOwned>(name: &str) -> Vec<String> {
+        let doc = document();
+        let schema = reference(name);
+        let input = sample(doc, &schema);
+
+        let mut errors = Vec::new();
+        if let Err(e) = serde_json::from_value::<T>(input.clone()) {
+            errors.push(format!("{}: exemple du schéma refusé par la structure ({})", name, e));
+        }
+        for (property, property_schema) in resolve(doc, &schema)["properties"].as_object().into_iter().flatten() {
+            let mut wrong = input.clone();
+            wrong[property] = if sample(doc, property_schema).is_string() { json!(true) } else { json!("type erroné") };
+            if serde_json::from_value::<T>(wrong).is_ok() {
+                errors.push(format!("{}.{}: propriété ignorée par la structure", name, property));
+            }
+        }
+        errors
+    }
+
+    #[test]
+    fn every_schema_matches_its_struct() {
+        use crate::models::*;
+
+        let mut checked = Vec::new();
+        let mut errors = Vec::new();
+        let mut response = |name: &'static str, found: Vec<String>| {
+            checked.push(name);
+            errors.extend(found);
+        };
+
+        response("AuthToken", round_trip::<AuthToken>("AuthToken", |_| {}));
+        response("LayerOverride", round_trip::<LayerOverride>("LayerOverride", |_| {}));
+        response("QuantizationConfig", round_trip::<QuantizationConfig>("QuantizationConfig", |_| {}));
+        response("Job", round_trip::<Job>("Job", |_| {}));
+        response("JobSummary", round_trip::<JobSummary>("JobSummary", |_| {}));
+        response("JobError", round_trip::<JobError>("JobError", |_| {}));
+        response("JobResult", round_trip::<JobResult>("JobResult", |_| {}));
+        response("QuantizationReport", round_trip::<QuantizationReport>("QuantizationReport", |_| {}));
+        response("StageTimings", round_trip::<StageTimings>("StageTimings", |_| {}));
+        response("JobLog", round_trip::<JobLog>("JobLog", |_| {}));
+        response("JobEvent", round_trip::<JobEvent>("JobEvent", |event| {
+            event["job_id"] = json!("00000000-0000-0000-0000-000000000002");
+        }));
+        response("JobCost", round_trip::<JobCost>("JobCost", |_| {}));
+        response("FileDownload", round_trip::<FileDownload>("FileDownload", |_| {}));
+        response("JobShareLink", round_trip::<JobShareLink>("JobShareLink", |_| {}));
+        response("PlanInfo", round_trip::<PlanInfo>("PlanInfo", |_| {}));
+        response("Subscription", round_trip::<Subscription>("Subscription", |_| {}));
+        response("DirectUploadUrl", round_trip::<DirectUploadUrl>("DirectUploadUrl", |_| {}));
+        response("FileMetadata", round_trip::<FileMetadata>("FileMetadata", |_| {}));
+        response("CreditInfo", round_trip::<CreditInfo>("CreditInfo", |_| {}));
+        response("UsageProjection", round_trip::<UsageProjection>("UsageProjection", |_| {}));
+        response("CreditTransaction", round_trip::<CreditTransaction>("CreditTransaction", |_| {}));
+        response("FileAnalysis", round_trip::<FileAnalysis>("FileAnalysis", |_| {}));
+        response("MethodCapability", round_trip::<MethodCapability>("MethodCapability", |_| {}));
+        response("QuantizationMethodInfo", round_trip::<QuantizationMethodInfo>("QuantizationMethodInfo", |_| {}));
+        response("QuantizationPresetInfo", round_trip::<QuantizationPresetInfo>("QuantizationPresetInfo", |_| {}));
+        response("Notification", round_trip::<Notification>("Notification", |notification| {
+            notification["user_id"] = json!("00000000-0000-0000-0000-000000000002");
+        }));
+        response("NotificationInbox", round_trip::<NotificationInbox>("NotificationInbox", |inbox| {
+            inbox["items"][0]["user_id"] = json!("00000000-0000-0000-0000-000000000002");
+        }));
+        response("NotificationPreferences", round_trip::<NotificationPreferences>("NotificationPreferences", |preferences| {
+            preferences["user_id"] = json!("00000000-0000-0000-0000-000000000002");
+        }));
+        response("Capabilities", round_trip::<Capabilities>("Capabilities", |_| {}));
+
+        response("NewUser", read_by::<NewUser>("NewUser"));
+        response("UserLogin", read_by::<UserLogin>("UserLogin"));
+        response("GoogleAuth", read_by::<GoogleAuth>("GoogleAuth"));
+        response("RefreshTokenRequest", read_by::<crate::api::auth::RefreshTokenRequest>("RefreshTokenRequest"));
+        response("NewJob", read_by::<NewJob>("NewJob"));
+        response("NewJobShareLink", read_by::<NewJobShareLink>("NewJobShareLink"));
+        response("UpdateSubscriptionRequest", read_by::<crate::api::billing::UpdateSubscriptionRequest>("UpdateSubscriptionRequest"));
+        response("CreateCheckoutRequest", read_by::<crate::api::billing::CreateCheckoutRequest>("CreateCheckoutRequest"));
+        response("DirectUploadRequest", read_by::<DirectUploadRequest>("DirectUploadRequest"));
+        response("DirectUploadComplete", read_by::<DirectUploadComplete>("DirectUploadComplete"));
+        response("UpdateNotificationPreferences", read_by::<UpdateNotificationPreferences>("UpdateNotificationPreferences"));
+
+        // Sérialisé seulement: construit à partir d'un lien lu depuis son schéma
+        let doc = document();
+        let link: JobShareLink = serde_json::from_value(sample(doc, &reference("JobShareLink"))).unwrap();
+        let unlimited = JobShareLink { max_downloads: None, revoked_at: None, ..link.clone() };
+        let mut issued_errors = Vec::new();
+        for link in [link, unlimited] {
+            let issued = IssuedShareLink { link, token: "token".to_string(), url: "https://exemple".to_string() };
+            let value = serde_json::to_value(issued).unwrap();
+            check(doc, &reference("IssuedShareLink"), &value, "IssuedShareLink", &mut issued_errors);
+        }
+        response("IssuedShareLink", issued_errors);
+
+        assert!(errors.is_empty(), "schémas OpenAPI et structures divergent:\n{}", errors.join("\n"));
+
+        // Aucun schéma d'objet oublié (les énumérations viennent déjà de serde)
+        for (name, schema) in doc["components"]["schemas"].as_object().unwrap() {
+            if schema["type"] == "object" {
+                assert!(checked.contains(&name.as_str()), "schéma {} non vérifié contre sa structure", name);
+            }
+        }
+    }
+
+    #[test]
+    fn every_reference_resolves_to_a_schema() {
+        let doc = document();
+        let mut refs = Vec::new();
+        collect_refs(doc, &mut refs);
+        assert!(!refs.is_empty());
+
+        for target in refs {
+            let name = target.strip_prefix("#/components/schemas/").unwrap_or(target);
+            assert!(doc["components"]["schemas"][name].is_object(), "schéma manquant: {}", target);
+        }
+    }
+
+    #[actix_web::test]
+    async fn document_is_served_without_authentication() {
+        let app = test::init_service(App::new().configure(configure_routes)).await;
+        let response = test::call_service(&app, test::TestRequest::get().uri("/openapi.json").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let served: Value = test::read_body_json(response).await;
+        assert_eq!(served["openapi"], "3.0.3");
+    }
+}
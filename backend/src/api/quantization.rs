@@ -0,0 +1,65 @@
+// api/quantization.rs
+use crate::core::JobService;
+use actix_web::{web, HttpResponse, Responder};
+
+/// Configure les routes de description des méthodes de quantification
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/quantization")
+            // Méthodes supportées (coût, GPU, calibration, disponibilité)
+            .route("/methods", web::get().to(list_methods))
+            // Préréglages (balanced, max-compression, max-quality)
+            .route("/presets", web::get().to(list_presets)),
+    );
+}
+
+/// Lister les méthodes de quantification pour l'interface
+async fn list_methods(job_service: web::Data<JobService>) -> impl Responder {
+    HttpResponse::Ok().json(job_service.list_quantization_methods())
+}
+
+/// Lister les préréglages et la configuration qu'ils produisent
+async fn list_presets(job_service: web::Data<JobService>) -> impl Responder {
+    HttpResponse::Ok().json(job_service.list_quantization_presets())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn methods_list_int8_cost_and_gpu_requirements() {
+        let db = testing::database().await;
+        let config = testing::config();
+
+        // Aucun backend GPTQ/AWQ installé
+        let quantizer = testing::quantizer_with_python(&config, &testing::python_with_modules(&[]));
+        quantizer.probe_capabilities().await;
+        let service = testing::job_service_with_quantizer(db, &config, quantizer).await;
+
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(service)).configure(configure_routes),
+        )
+        .await;
+        let request = test::TestRequest::get().uri("/quantization/methods").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let methods: Vec<serde_json::Value> = test::read_body_json(response).await;
+        let method = |name: &str| methods.iter().find(|m| m["method"] == name).cloned().unwrap();
+
+        let int8 = method("int8");
+        assert_eq!(int8["credit_cost"], 1);
+        assert_eq!(int8["requires_gpu"], false);
+        assert_eq!(int8["available"], true);
+
+        let gptq = method("gptq");
+        assert_eq!(gptq["requires_gpu"], true);
+        assert_eq!(gptq["requires_calibration"], true);
+        assert_eq!(gptq["available"], false);
+        assert!(gptq["unavailable_reason"].is_string());
+    }
+}
@@ -1,8 +1,13 @@
 // api/user.rs
-use crate::models::{UserProfile, AuthToken};
-use crate::api::AuthenticatedUser;
+use crate::models::{UserProfile, AuthToken, UpdateNotificationPreferences, UpdateQuantizationPreferences, NewUserWebhook, Pagination};
+use crate::api::{forbid_impersonation, AuthenticatedUser};
 use crate::core::user_service::UserService;
-use actix_web::{web, HttpResponse, Responder};
+use crate::core::notification_service::NotificationService;
+use crate::core::billing_service::BillingService;
+use crate::core::job_service::JobService;
+use crate::services::audit::AuditRepository;
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use validator::Validate;
 
 /// Configure les routes utilisateur
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
@@ -17,6 +22,7 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .route("/api-keys", web::get().to(list_api_keys))
             .route("/api-keys", web::post().to(create_api_key))
             .route("/api-keys/{key_id}", web::delete().to(delete_api_key))
+            .route("/api-keys/{key_id}/rotate", web::post().to(rotate_api_key))
             // Paramètres
             .route("/settings", web::get().to(get_settings))
             .route("/settings", web::put().to(update_settings))
@@ -25,6 +31,38 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             // Supprimer compte
             .route("/delete-account", web::post().to(delete_account)),
     );
+    
+    cfg.service(
+        web::scope("/users/me")
+            .wrap(crate::api::auth_middleware::require_auth())
+            // Boîte de réception in-app
+            .route("/notifications", web::get().to(list_notifications))
+            .route("/notifications/{notification_id}/read", web::post().to(mark_notification_read))
+            // Préférences de notification
+            .route("/notification-preferences", web::get().to(get_notification_preferences))
+            .route("/notification-preferences", web::put().to(update_notification_preferences))
+            // Ancien chemin des préférences, conservé pour les clients existants
+            .route("/notifications", web::put().to(update_notification_preferences_deprecated))
+            // Réglages de quantification par défaut
+            .route("/preferences", web::get().to(get_quantization_preferences))
+            .route("/preferences", web::put().to(update_quantization_preferences))
+            // Espace de stockage utilisé
+            .route("/storage", web::get().to(get_storage_usage))
+            // Webhooks (événements d'abonnement)
+            .route("/webhooks", web::get().to(list_webhooks))
+            .route("/webhooks", web::post().to(register_webhook))
+            .route("/webhooks/{webhook_id}", web::delete().to(delete_webhook)),
+    );
+    
+    // Clés API: chemins de référence (`/user/api-keys` reste servi à l'identique)
+    cfg.service(
+        web::scope("/keys")
+            .wrap(crate::api::auth_middleware::require_auth())
+            .route("", web::get().to(list_api_keys))
+            .route("", web::post().to(create_api_key))
+            .route("/{key_id}", web::delete().to(delete_api_key))
+            .route("/{key_id}/rotate", web::post().to(rotate_api_key)),
+    );
 }
 
 /// Obtenir le profil utilisateur
@@ -79,8 +117,14 @@ async fn list_api_keys(
 async fn create_api_key(
     user: AuthenticatedUser,
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<CreateApiKeyRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "api_key.create").await {
+        return denied;
+    }
+    
     match user_service.create_api_key(user.id, &request.name, &request.permissions).await {
         Ok(api_key) => HttpResponse::Created().json(api_key),
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
@@ -91,13 +135,19 @@ async fn create_api_key(
 async fn delete_api_key(
     user: AuthenticatedUser,
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     key_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "api_key.delete").await {
+        return denied;
+    }
+    
     match user_service.delete_api_key(user.id, *key_id).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => {
             match e {
-                crate::utils::error::AppError::NotFound => {
+                crate::utils::error::AppError::NotFound(_) => {
                     HttpResponse::NotFound().json("Clé API non trouvée")
                 }
                 _ => HttpResponse::InternalServerError().json("Erreur serveur"),
@@ -106,6 +156,70 @@ async fn delete_api_key(
     }
 }
 
+/// Remplacer le secret d'une clé API (l'ancien est invalidé immédiatement)
+async fn rotate_api_key(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
+    key_id: web::Path<uuid::Uuid>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "api_key.rotate").await {
+        return denied;
+    }
+    
+    match user_service.rotate_api_key(user.id, *key_id).await {
+        Ok(api_key) => HttpResponse::Ok().json(api_key),
+        Err(crate::utils::error::AppError::NotFound(_)) => {
+            HttpResponse::NotFound().json("Clé API non trouvée")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Lister les webhooks enregistrés
+async fn list_webhooks(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+) -> impl Responder {
+    match user_service.list_webhooks(user.id).await {
+        Ok(webhooks) => HttpResponse::Ok().json(webhooks),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Enregistrer un webhook pour certains types d'événements
+async fn register_webhook(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+    request: web::Json<NewUserWebhook>,
+) -> impl Responder {
+    if let Err(errors) = request.validate() {
+        return crate::utils::error::AppError::from(errors).error_response();
+    }
+    
+    match user_service.register_webhook(user.id, &request).await {
+        Ok(webhook) => HttpResponse::Created().json(webhook),
+        Err(e @ crate::utils::error::AppError::Validation(_)) => e.error_response(),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Supprimer un webhook
+async fn delete_webhook(
+    user: AuthenticatedUser,
+    user_service: web::Data<UserService>,
+    webhook_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match user_service.delete_webhook(user.id, *webhook_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(crate::utils::error::AppError::NotFound(_)) => {
+            HttpResponse::NotFound().json("Webhook non trouvé")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
 /// Obtenir les paramètres utilisateur
 async fn get_settings(
     user: AuthenticatedUser,
@@ -123,18 +237,142 @@ async fn update_settings(
     user_service: web::Data<UserService>,
     settings: web::Json<UserSettings>,
 ) -> impl Responder {
+    if let Some(method) = &settings.default_quantization_method {
+        if let Err(e) = crate::utils::validation::validate_quantization_method(method) {
+            return e.error_response();
+        }
+    }
+    
     match user_service.update_user_settings(user.id, settings.into_inner()).await {
         Ok(updated_settings) => HttpResponse::Ok().json(updated_settings),
         Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
     }
 }
 
+/// Obtenir l'espace de stockage utilisé et le quota du plan
+async fn get_storage_usage(
+    user: AuthenticatedUser,
+    billing_service: web::Data<BillingService>,
+) -> impl Responder {
+    match billing_service.get_storage_usage(user.id).await {
+        Ok(usage) => HttpResponse::Ok().json(usage),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Lister les notifications in-app (plus récentes d'abord, avec le nombre de non lues)
+async fn list_notifications(
+    user: AuthenticatedUser,
+    notification_service: web::Data<NotificationService>,
+    config: web::Data<crate::utils::config::Config>,
+    query: web::Query<NotificationListQuery>,
+) -> impl Responder {
+    let pagination = match Pagination::from_params(query.page, query.per_page, config.pagination_max_per_page) {
+        Ok(pagination) => pagination,
+        Err(e) => return e.error_response(),
+    };
+    
+    match notification_service.list_inbox(user.id, pagination).await {
+        Ok(inbox) => HttpResponse::Ok().json(inbox),
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Marquer une notification comme lue
+async fn mark_notification_read(
+    user: AuthenticatedUser,
+    notification_service: web::Data<NotificationService>,
+    notification_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    match notification_service.mark_read(user.id, *notification_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(crate::utils::error::AppError::NotFound(_)) => {
+            HttpResponse::NotFound().json("Notification non trouvée")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Obtenir les préférences de notification
+async fn get_notification_preferences(
+    user: AuthenticatedUser,
+    notification_service: web::Data<NotificationService>,
+) -> impl Responder {
+    match notification_service.get_preferences(user.id).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Mettre à jour les préférences de notification
+async fn update_notification_preferences(
+    user: AuthenticatedUser,
+    notification_service: web::Data<NotificationService>,
+    update: web::Json<UpdateNotificationPreferences>,
+) -> impl Responder {
+    match notification_service.update_preferences(user.id, &update).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Modifier les préférences via l'ancien chemin `PUT /users/me/notifications`
+///
+/// Obsolète depuis l'arrivée de la boîte de réception sur ce chemin: la
+/// réponse l'annonce (`Deprecation`) et désigne le nouveau chemin (`Link`).
+async fn update_notification_preferences_deprecated(
+    user: AuthenticatedUser,
+    notification_service: web::Data<NotificationService>,
+    update: web::Json<UpdateNotificationPreferences>,
+) -> impl Responder {
+    update_notification_preferences(user, notification_service, update)
+        .await
+        .customize()
+        .insert_header(("Deprecation", "true"))
+        .insert_header((
+            actix_web::http::header::LINK,
+            "</api/users/me/notification-preferences>; rel=\"successor-version\"",
+        ))
+}
+
+/// Obtenir les réglages de quantification par défaut
+async fn get_quantization_preferences(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+) -> impl Responder {
+    match job_service.get_quantization_preferences(user.id).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
+/// Remplacer les réglages de quantification par défaut
+async fn update_quantization_preferences(
+    user: AuthenticatedUser,
+    job_service: web::Data<JobService>,
+    update: web::Json<UpdateQuantizationPreferences>,
+) -> impl Responder {
+    match job_service.update_quantization_preferences(user.id, update.into_inner()).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(crate::utils::error::AppError::Validation(message)) => {
+            HttpResponse::BadRequest().json(message)
+        }
+        Err(e) => HttpResponse::InternalServerError().json("Erreur serveur"),
+    }
+}
+
 /// Changer le mot de passe
 async fn change_password(
     user: AuthenticatedUser,
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<ChangePasswordRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "user.change_password").await {
+        return denied;
+    }
+    
     match user_service.change_password(user.id, &request.current_password, &request.new_password).await {
         Ok(_) => HttpResponse::Ok().json("Mot de passe changé avec succès"),
         Err(e) => {
@@ -152,8 +390,14 @@ async fn change_password(
 async fn delete_account(
     user: AuthenticatedUser,
     user_service: web::Data<UserService>,
+    audit: web::Data<AuditRepository>,
     request: web::Json<DeleteAccountRequest>,
+    req: actix_web::HttpRequest,
 ) -> impl Responder {
+    if let Some(denied) = forbid_impersonation(&req, &user, &audit, "user.delete_account").await {
+        return denied;
+    }
+    
     match user_service.delete_user_account(user.id, &request.password).await {
         Ok(_) => HttpResponse::Ok().json("Compte supprimé avec succès"),
         Err(e) => {
@@ -197,4 +441,58 @@ struct ChangePasswordRequest {
 #[derive(Debug, serde::Deserialize)]
 struct DeleteAccountRequest {
     password: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NotificationListQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SubscriptionPlan;
+    use crate::utils::testing;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn impersonation_token_cannot_touch_credentials_or_the_account() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let admin = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let user_service = testing::user_service(db.clone(), &config).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(user_service))
+                .app_data(web::Data::new(AuditRepository::new(&db)))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let token = crate::utils::security::generate_impersonation_token(user.id, &user.email, admin.id, &config.jwt_secret, 15);
+        let requests = [
+            ("/keys", serde_json::json!({ "name": "support", "permissions": ["read"] })),
+            ("/user/change-password", serde_json::json!({ "current_password": "MotDePasse123!", "new_password": "Autre-MotDePasse456!" })),
+            ("/user/delete-account", serde_json::json!({ "password": "MotDePasse123!" })),
+        ];
+        for (uri, body) in requests {
+            let request = test::TestRequest::post()
+                .uri(uri)
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(body)
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", uri);
+        }
+
+        // Aucune clé créée, mot de passe et compte intacts
+        let user_service = testing::user_service(db.clone(), &config).await;
+        assert!(user_service.get_user_api_keys(user.id).await.unwrap().is_empty());
+        assert!(user_service.authenticate_user(&user.email, "MotDePasse123!").await.is_ok());
+    }
+}
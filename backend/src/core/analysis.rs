@@ -0,0 +1,368 @@
+// core/analysis.rs
+use crate::core::quantization_service::ModelAnalysis;
+use crate::models::{QuantizationMethod, ModelFormat, QuantizationReport};
+use crate::utils::error::{AppError, Result};
+use std::fmt;
+
+/// Famille d'architecture détectée lors de l'analyse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchitectureFamily {
+    Transformer,
+    Cnn,
+    Rnn,
+    Unknown,
+}
+
+impl ArchitectureFamily {
+    /// Déduire la famille à partir du type et de l'architecture du modèle
+    pub fn detect(analysis: &ModelAnalysis) -> Self {
+        let haystack = format!("{} {}", analysis.model_type, analysis.architecture).to_lowercase();
+
+        const TRANSFORMER_HINTS: &[&str] = &[
+            "transformer", "llama", "mistral", "gpt", "bert", "t5", "falcon",
+            "qwen", "phi", "gemma", "whisper", "vit", "bloom", "opt",
+        ];
+        const CNN_HINTS: &[&str] = &[
+            "cnn", "conv", "resnet", "vgg", "efficientnet", "mobilenet", "yolo", "unet",
+        ];
+        const RNN_HINTS: &[&str] = &["rnn", "lstm", "gru"];
+
+        if TRANSFORMER_HINTS.iter().any(|hint| haystack.contains(hint)) {
+            ArchitectureFamily::Transformer
+        } else if CNN_HINTS.iter().any(|hint| haystack.contains(hint)) {
+            ArchitectureFamily::Cnn
+        } else if RNN_HINTS.iter().any(|hint| haystack.contains(hint)) {
+            ArchitectureFamily::Rnn
+        } else {
+            ArchitectureFamily::Unknown
+        }
+    }
+}
+
+impl fmt::Display for ArchitectureFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArchitectureFamily::Transformer => "transformer",
+            ArchitectureFamily::Cnn => "CNN",
+            ArchitectureFamily::Rnn => "RNN",
+            ArchitectureFamily::Unknown => "inconnue",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Architecture à inscrire au rapport, d'après l'analyse du modèle source
+///
+/// Reprend le nom lu par l'analyse (config.json, métadonnées ONNX, en-tête
+/// safetensors), à défaut le type de modèle; sans nom exploitable, le rapport
+/// indique `unknown` plutôt qu'une supposition.
+pub fn reported_architecture(analysis: &ModelAnalysis) -> String {
+    const PLACEHOLDERS: &[&str] = &["", "unknown", "none", "null", "n/a", "inconnue"];
+
+    [analysis.architecture.as_str(), analysis.model_type.as_str()]
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .find(|name| !PLACEHOLDERS.contains(&name.as_str()))
+        .unwrap_or_else(|| QuantizationReport::UNKNOWN_ARCHITECTURE.to_string())
+}
+
+/// Familles d'architecture acceptées par une méthode (None = toutes)
+pub fn supported_families(method: &QuantizationMethod) -> Option<&'static [ArchitectureFamily]> {
+    match method {
+        // Méthodes génériques
+        QuantizationMethod::Int8 => None,
+        // Méthodes propres aux LLM (couches linéaires d'attention)
+        QuantizationMethod::Gptq
+        | QuantizationMethod::Awq
+        | QuantizationMethod::GgufQ4_0
+        | QuantizationMethod::GgufQ5_0 => Some(&[ArchitectureFamily::Transformer]),
+    }
+}
+
+/// Proportion de poids aberrants au-delà de laquelle AWQ est conseillé
+pub const OUTLIER_RATIO_THRESHOLD: f64 = 0.001;
+
+/// Méthode conseillée pour un modèle analysé
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MethodRecommendation {
+    pub method: QuantizationMethod,
+    pub reason: String,
+}
+
+/// Conseiller une méthode selon l'architecture et la distribution des poids
+///
+/// Les poids aberrants dégradent GPTQ et GGUF; AWQ protège les canaux saillants.
+pub fn recommend_method(analysis: &ModelAnalysis) -> MethodRecommendation {
+    if ArchitectureFamily::detect(analysis) != ArchitectureFamily::Transformer {
+        return MethodRecommendation {
+            method: QuantizationMethod::Int8,
+            reason: "Architecture non transformer: seule la quantification INT8 est générique".to_string(),
+        };
+    }
+
+    match &analysis.weight_stats {
+        Some(stats) if stats.outlier_ratio > OUTLIER_RATIO_THRESHOLD => MethodRecommendation {
+            method: QuantizationMethod::Awq,
+            reason: format!(
+                "{:.3}% de poids aberrants: AWQ préserve mieux les canaux saillants",
+                stats.outlier_ratio * 100.0
+            ),
+        },
+        _ => MethodRecommendation {
+            method: QuantizationMethod::Gptq,
+            reason: "Distribution des poids régulière: GPTQ offre le meilleur compromis 4-bit".to_string(),
+        },
+    }
+}
+
+/// Vérifier la compatibilité méthode / architecture avant la quantification
+///
+/// Une architecture non reconnue n'est pas bloquante: le script Python reste juge.
+pub fn check_method_compatibility(method: &QuantizationMethod, analysis: &ModelAnalysis) -> Result<()> {
+    let detected = ArchitectureFamily::detect(analysis);
+    if detected == ArchitectureFamily::Unknown {
+        return Ok(());
+    }
+
+    match supported_families(method) {
+        Some(families) if !families.contains(&detected) => {
+            let required = families
+                .iter()
+                .map(|family| family.to_string())
+                .collect::<Vec<_>>()
+                .join(" ou ");
+
+            Err(AppError::IncompatibleArchitecture(format!(
+                "{:?} requiert une architecture {}; architecture détectée: {} ({})",
+                method, required, detected, analysis.architecture
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Vérifier que la taille de groupe divise la dimension cachée du modèle
+///
+/// Sans dimension connue, la vérification est laissée au script.
+pub fn check_group_size_compatibility(group_size: Option<u32>, analysis: &ModelAnalysis) -> Result<()> {
+    let (Some(group_size), Some(hidden_size)) = (group_size, analysis.hidden_size) else {
+        return Ok(());
+    };
+
+    if hidden_size > 0 && hidden_size as u32 % group_size != 0 {
+        return Err(AppError::IncompatibleArchitecture(format!(
+            "Taille de groupe {} incompatible avec la dimension cachée du modèle ({})",
+            group_size, hidden_size
+        )));
+    }
+
+    Ok(())
+}
+
+/// Quantification déjà présente dans un modèle source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingQuantization {
+    /// Poids entiers sur n bits
+    Int(u8),
+    /// Poids GPTQ (qweight/qzeros + g_idx)
+    Gptq,
+    /// Poids AWQ (qweight/qzeros sans g_idx)
+    Awq,
+}
+
+impl fmt::Display for ExistingQuantization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExistingQuantization::Int(bits) => write!(f, "INT{}", bits),
+            ExistingQuantization::Gptq => write!(f, "INT4 GPTQ"),
+            ExistingQuantization::Awq => write!(f, "INT4 AWQ"),
+        }
+    }
+}
+
+/// Opérateurs ONNX propres aux modèles quantifiés, avec leur précision
+const ONNX_QUANTIZED_OPS: &[(&[u8], u8)] = &[
+    (b"MatMulNBits", 4),
+    (b"DequantizeLinear", 8),
+    (b"QLinearMatMul", 8),
+    (b"MatMulInteger", 8),
+];
+
+/// Détecter une quantification existante à partir de l'en-tête d'un fichier
+///
+/// Safetensors: métadonnées et noms/types des tenseurs; ONNX: opérateurs de
+/// (dé)quantification. Les autres formats ne sont pas inspectés ici (l'analyse
+/// du worker prend le relais).
+pub fn detect_existing_quantization(format: &ModelFormat, header: &[u8]) -> Option<ExistingQuantization> {
+    match format {
+        ModelFormat::Safetensors => detect_safetensors_quantization(header),
+        ModelFormat::Onnx => ONNX_QUANTIZED_OPS
+            .iter()
+            .find(|(op, _)| header.windows(op.len()).any(|window| window == *op))
+            .map(|(_, bits)| ExistingQuantization::Int(*bits)),
+        ModelFormat::PyTorch | ModelFormat::Gguf => None,
+    }
+}
+
+fn detect_safetensors_quantization(header: &[u8]) -> Option<ExistingQuantization> {
+    let len = u64::from_le_bytes(header.get(..8)?.try_into().ok()?) as usize;
+    let json = header.get(8..8usize.checked_add(len)?)?;
+    let tensors: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(json).ok()?;
+
+    // Méthode déclarée dans les métadonnées (ex: "quant_method": "awq")
+    if let Some(metadata) = tensors.get("__metadata__").and_then(|m| m.as_object()) {
+        for (key, value) in metadata {
+            let value = value.as_str().unwrap_or_default().to_lowercase();
+            if key.to_lowercase().contains("quant") {
+                if value.contains("gptq") {
+                    return Some(ExistingQuantization::Gptq);
+                }
+                if value.contains("awq") {
+                    return Some(ExistingQuantization::Awq);
+                }
+            }
+        }
+    }
+
+    let names: Vec<&str> = tensors.keys().map(String::as_str).collect();
+    if names.iter().any(|name| name.ends_with(".qweight")) {
+        return if names.iter().any(|name| name.ends_with(".g_idx")) {
+            Some(ExistingQuantization::Gptq)
+        } else {
+            Some(ExistingQuantization::Awq)
+        };
+    }
+
+    // bitsandbytes 4-bit: poids empaquetés et état de quantification par tenseur
+    if names.iter().any(|name| name.contains(".quant_state.") || name.ends_with(".absmax")) {
+        return Some(ExistingQuantization::Int(4));
+    }
+
+    // Poids stockés en entiers 8 bits
+    let weight_dtypes: Vec<&str> = tensors
+        .iter()
+        .filter(|(name, _)| name.ends_with(".weight"))
+        .filter_map(|(_, tensor)| tensor.get("dtype").and_then(|d| d.as_str()))
+        .collect();
+    let int8_weights = weight_dtypes.iter().filter(|dtype| matches!(**dtype, "I8" | "U8")).count();
+    if int8_weights > 0 && int8_weights * 2 >= weight_dtypes.len() {
+        return Some(ExistingQuantization::Int(8));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Analyse minimale d'un modèle
+    fn analysis(model_type: &str, architecture: &str) -> ModelAnalysis {
+        ModelAnalysis {
+            model_type: model_type.to_string(),
+            architecture: architecture.to_string(),
+            parameter_count: 7.0,
+            quantization_bits: None,
+            layers: 32,
+            vocab_size: None,
+            context_length: None,
+            hidden_size: Some(4096),
+            file_size_bytes: 1024,
+            supported_quantizations: Vec::new(),
+            weight_stats: None,
+        }
+    }
+
+    #[test]
+    fn awq_on_a_cnn_is_rejected() {
+        let cnn = analysis("resnet", "ResNet50");
+        assert_eq!(ArchitectureFamily::detect(&cnn), ArchitectureFamily::Cnn);
+
+        let err = check_method_compatibility(&QuantizationMethod::Awq, &cnn).unwrap_err();
+        match err {
+            AppError::IncompatibleArchitecture(message) => {
+                assert!(message.contains("transformer"), "{}", message);
+                assert!(message.contains("CNN"), "{}", message);
+            }
+            other => panic!("erreur inattendue: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gptq_on_a_transformer_passes() {
+        let llama = analysis("llama", "LlamaForCausalLM");
+        assert_eq!(ArchitectureFamily::detect(&llama), ArchitectureFamily::Transformer);
+        assert!(check_method_compatibility(&QuantizationMethod::Gptq, &llama).is_ok());
+    }
+
+    #[test]
+    fn generic_methods_and_unknown_architectures_are_not_blocked() {
+        assert!(check_method_compatibility(&QuantizationMethod::Int8, &analysis("resnet", "ResNet50")).is_ok());
+        assert!(check_method_compatibility(&QuantizationMethod::Awq, &analysis("", "")).is_ok());
+    }
+
+    #[test]
+    fn outlier_ratio_steers_the_recommendation() {
+        use crate::core::quantization_service::WeightStats;
+
+        let mut llama = analysis("llama", "LlamaForCausalLM");
+        assert!(matches!(recommend_method(&llama).method, QuantizationMethod::Gptq));
+
+        llama.weight_stats = Some(WeightStats { layers: Vec::new(), outlier_ratio: OUTLIER_RATIO_THRESHOLD / 2.0 });
+        assert!(matches!(recommend_method(&llama).method, QuantizationMethod::Gptq));
+
+        llama.weight_stats = Some(WeightStats { layers: Vec::new(), outlier_ratio: OUTLIER_RATIO_THRESHOLD * 4.0 });
+        assert!(matches!(recommend_method(&llama).method, QuantizationMethod::Awq));
+    }
+
+    /// En-tête safetensors (longueur + JSON) décrivant `tensors`
+    fn safetensors_header(tensors: serde_json::Value) -> Vec<u8> {
+        let json = tensors.to_string();
+        let mut header = (json.len() as u64).to_le_bytes().to_vec();
+        header.extend_from_slice(json.as_bytes());
+        header
+    }
+
+    #[test]
+    fn quantized_safetensors_are_detected() {
+        let int4 = safetensors_header(serde_json::json!({
+            "layer.weight": { "dtype": "U8" },
+            "layer.weight.absmax": { "dtype": "F32" },
+        }));
+        let gptq = safetensors_header(serde_json::json!({
+            "layer.qweight": { "dtype": "I32" },
+            "layer.g_idx": { "dtype": "I32" },
+        }));
+        let float = safetensors_header(serde_json::json!({
+            "layer.weight": { "dtype": "F16" },
+        }));
+
+        assert_eq!(detect_existing_quantization(&ModelFormat::Safetensors, &int4).unwrap().to_string(), "INT4");
+        assert_eq!(detect_existing_quantization(&ModelFormat::Safetensors, &gptq).unwrap().to_string(), "INT4 GPTQ");
+        assert!(detect_existing_quantization(&ModelFormat::Safetensors, &float).is_none());
+        assert!(detect_existing_quantization(&ModelFormat::Gguf, &int4).is_none());
+    }
+
+    #[test]
+    fn group_size_must_divide_the_hidden_size() {
+        let llama = analysis("llama", "LlamaForCausalLM");
+        let mut odd = analysis("llama", "LlamaForCausalLM");
+        odd.hidden_size = Some(4160);
+
+        assert!(check_group_size_compatibility(Some(64), &llama).is_ok());
+        assert!(matches!(
+            check_group_size_compatibility(Some(256), &odd),
+            Err(AppError::IncompatibleArchitecture(_))
+        ));
+        assert!(check_group_size_compatibility(None, &llama).is_ok());
+    }
+
+    #[test]
+    fn report_architecture_falls_back_to_unknown() {
+        assert_eq!(reported_architecture(&analysis("llama", "LlamaForCausalLM")), "llamaforcausallm");
+        // Architecture absente: le type de modèle suffit
+        assert_eq!(reported_architecture(&analysis("mistral", "")), "mistral");
+        assert_eq!(reported_architecture(&analysis("unknown", "n/a")), "unknown");
+        assert_eq!(reported_architecture(&analysis("", " ")), "unknown");
+    }
+}
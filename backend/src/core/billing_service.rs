@@ -1,20 +1,31 @@
 // core/billing_service.rs
 use crate::models::{
-    Subscription, SubscriptionPlan, SubscriptionStatus,
-    CreditInfo, CreditTransaction, PlanInfo,
+    Subscription, SubscriptionPlan, SubscriptionStatus, StripeSubscriptionState,
+    CreditInfo, CreditTransaction, PlanInfo, RetentionPolicy, AuditLog,
+    StorageQuotaPolicy, StorageUsage, CheckoutSession, CheckoutSessionStatus,
+    UsageProjection, USAGE_PROJECTION_WINDOW_DAYS, Pagination, Job,
 };
 use crate::services::database::Database;
+use crate::services::audit::AuditRepository;
+use crate::services::webhook::WebhookNotifier;
 use crate::utils::error::{AppError, Result};
 use uuid::Uuid;
 use chrono::{Utc, DateTime, Duration};
 use std::sync::Arc;
 
+/// Tentatives d'un mouvement de crédits avant d'abandonner sur conflit
+const MAX_CREDIT_UPDATE_ATTEMPTS: usize = 5;
+
 pub struct BillingService {
     db: Arc<Database>,
     stripe_secret_key: String,
     stripe_webhook_secret: String,
     stripe_currency: String,
     stripe_trial_days: i64,
+    retention: RetentionPolicy,
+    storage_quota: StorageQuotaPolicy,
+    webhooks: Option<Arc<WebhookNotifier>>,
+    stripe_api_base: Option<String>,
 }
 
 impl BillingService {
@@ -24,6 +35,8 @@ impl BillingService {
         stripe_webhook_secret: String,
         stripe_currency: String,
         stripe_trial_days: i64,
+        retention: RetentionPolicy,
+        storage_quota: StorageQuotaPolicy,
     ) -> Self {
         Self {
             db,
@@ -31,6 +44,49 @@ impl BillingService {
             stripe_webhook_secret,
             stripe_currency,
             stripe_trial_days,
+            retention,
+            storage_quota,
+            webhooks: None,
+            stripe_api_base: None,
+        }
+    }
+
+    /// Émettre les événements d'abonnement vers les webhooks des utilisateurs
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookNotifier>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Adresser les appels Stripe à une autre API (serveur de test)
+    pub fn with_stripe_api_base(mut self, url: impl Into<String>) -> Self {
+        self.stripe_api_base = Some(url.into());
+        self
+    }
+
+    /// Client Stripe, vers l'API configurée le cas échéant
+    fn stripe_client(&self) -> stripe::Client {
+        match &self.stripe_api_base {
+            Some(url) => stripe::Client::from_url(url.as_str(), self.stripe_secret_key.as_str()),
+            None => stripe::Client::new(&self.stripe_secret_key),
+        }
+    }
+
+    /// Notifier les webhooks abonnés à un événement d'abonnement (best effort)
+    fn notify_subscription(
+        &self,
+        event_type: &'static str,
+        subscription: &Subscription,
+        previous_plan: Option<&SubscriptionPlan>,
+    ) {
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.emit(subscription.user_id, event_type, serde_json::json!({
+                "subscription_id": subscription.id,
+                "plan": subscription.plan,
+                "previous_plan": previous_plan,
+                "pending_plan": subscription.pending_plan,
+                "status": subscription.status,
+                "current_period_end": subscription.current_period_end,
+            }));
         }
     }
 
@@ -39,6 +95,29 @@ impl BillingService {
         self.db.get_user_subscription(user_id).await
     }
 
+    /// Date d'expiration d'un nouveau fichier selon le plan de son propriétaire
+    pub async fn file_expiry_for(&self, user_id: Uuid) -> Result<DateTime<Utc>> {
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        Ok(self.retention.expires_at(&subscription.plan, Utc::now()))
+    }
+
+    /// Espace de stockage utilisé et quota du plan de l'utilisateur
+    pub async fn get_storage_usage(&self, user_id: Uuid) -> Result<StorageUsage> {
+        let used = self.db.get_user_storage_used(user_id).await?;
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        Ok(StorageUsage::new(used, self.storage_quota.bytes_for(&subscription.plan)))
+    }
+
+    /// Vérifier qu'un nouveau fichier de `additional_bytes` tient dans le quota
+    pub async fn check_storage_quota(&self, user_id: Uuid, additional_bytes: i64) -> Result<()> {
+        let usage = self.get_storage_usage(user_id).await?;
+        if usage.allows(additional_bytes) {
+            Ok(())
+        } else {
+            Err(AppError::StorageQuotaExceeded)
+        }
+    }
+
     /// Créer un abonnement gratuit
     pub async fn create_free_subscription(&self, user_id: Uuid) -> Result<Subscription> {
         let subscription = Subscription::new_free(user_id);
@@ -66,6 +145,27 @@ impl BillingService {
             _ => return Err(AppError::InvalidPlan),
         };
 
+        // Même plan: annule une éventuelle rétrogradation programmée
+        if new_plan == current_sub.plan {
+            let mut updated_sub = current_sub;
+            if updated_sub.pending_plan.take().is_some() {
+                updated_sub.updated_at = Utc::now();
+                self.db.update_subscription(&updated_sub).await?;
+            }
+            return Ok(updated_sub);
+        }
+
+        // Rétrogradation: programmée pour la fin de la période déjà payée
+        if new_plan.is_lower_than(&current_sub.plan) {
+            let mut updated_sub = current_sub;
+            updated_sub.schedule_downgrade(new_plan);
+            self.db.update_subscription(&updated_sub).await?;
+            self.notify_subscription("subscription.downgrade_scheduled", &updated_sub, None);
+            return Ok(updated_sub);
+        }
+
+        let previous_plan = current_sub.plan.clone();
+
         // Si l'utilisateur passe de Free à payant
         if matches!(current_sub.plan, SubscriptionPlan::Free) && !matches!(new_plan, SubscriptionPlan::Free) {
             // Créer un client Stripe si nécessaire
@@ -79,16 +179,24 @@ impl BillingService {
             ).await?;
 
             // Mettre à jour l'abonnement en base
+            // Les crédits du plan sont accordés dès le début de l'essai
             let mut updated_sub = current_sub;
             updated_sub.upgrade(new_plan, Some(stripe_sub_id));
+            if self.stripe_trial_days > 0 {
+                updated_sub.start_trial(self.stripe_trial_days);
+            }
             self.db.update_subscription(&updated_sub).await?;
 
+            // Étendre la rétention des fichiers existants
+            self.db.extend_user_files_expiry(user_id, self.retention.days_for(&new_plan)).await?;
+
             // Ajouter les crédits du nouveau plan
             let credits = new_plan.info().credits_per_month;
             if credits > 0 {
                 self.add_credits(user_id, credits, "subscription_upgrade", &format!("Mise à jour vers plan {:?}", new_plan)).await?;
             }
 
+            self.notify_subscription("subscription.upgraded", &updated_sub, Some(&previous_plan));
             Ok(updated_sub)
         } else {
             // Changer de plan payant
@@ -96,9 +204,14 @@ impl BillingService {
             
             let mut updated_sub = current_sub;
             updated_sub.plan = new_plan;
+            updated_sub.pending_plan = None;
             updated_sub.updated_at = Utc::now();
             self.db.update_subscription(&updated_sub).await?;
 
+            // Étendre la rétention des fichiers existants (jamais raccourcie)
+            self.db.extend_user_files_expiry(user_id, self.retention.days_for(&updated_sub.plan)).await?;
+
+            self.notify_subscription("subscription.upgraded", &updated_sub, Some(&previous_plan));
             Ok(updated_sub)
         }
     }
@@ -117,14 +230,17 @@ impl BillingService {
         }
 
         // Rétrograder vers Free
+        let previous_plan = subscription.plan.clone();
         subscription.plan = SubscriptionPlan::Free;
         subscription.status = SubscriptionStatus::Cancelled;
         subscription.cancelled_at = Some(Utc::now());
         subscription.updated_at = Utc::now();
         subscription.stripe_subscription_id = None;
         subscription.stripe_price_id = None;
+        subscription.pending_plan = None;
 
         self.db.update_subscription(&subscription).await?;
+        self.notify_subscription("subscription.cancelled", &subscription, Some(&previous_plan));
 
         Ok(())
     }
@@ -133,16 +249,20 @@ impl BillingService {
     pub async fn get_user_credits(&self, user_id: Uuid) -> Result<CreditInfo> {
         let total_credits = self.db.get_user_total_credits(user_id).await?;
         let used_credits = self.db.get_user_used_credits(user_id).await?;
-        let remaining_credits = total_credits - used_credits;
         
         // Date de réinitialisation (fin du mois pour les plans payants)
         let subscription = self.db.get_user_subscription(user_id).await?;
         let reset_date = subscription.current_period_end;
 
+        // Plan illimité: pas de solde affiché (la consommation reste tracée)
+        let unlimited = subscription.plan.has_unlimited_credits();
+        let remaining_credits = (!unlimited).then_some(total_credits - used_credits);
+
         Ok(CreditInfo {
             total_credits,
             used_credits,
             remaining_credits,
+            unlimited,
             reset_date,
         })
     }
@@ -150,29 +270,84 @@ impl BillingService {
     /// Vérifier si un utilisateur a suffisamment de crédits
     pub async fn check_user_credits(&self, user_id: Uuid) -> Result<bool> {
         let credits = self.get_user_credits(user_id).await?;
-        Ok(credits.remaining_credits > 0)
+        Ok(credits.covers(1))
     }
 
     /// Consommer des crédits pour un job
     pub async fn consume_job_credits(&self, user_id: Uuid, job_id: Uuid) -> Result<()> {
         let job = self.db.get_job(job_id).await?;
-        let credits_needed = job.credits_used;
 
-        // Vérifier les crédits disponibles
-        let current_credits = self.get_user_credits(user_id).await?;
-        if current_credits.remaining_credits < credits_needed {
-            return Err(AppError::InsufficientCredits);
+        self.debit_credits(
+            user_id,
+            job.credits_used,
+            "consumption",
+            &format!("Job de quantification: {}", job.name),
+        ).await
+    }
+
+    /// Débiter le coût d'un job (avant son enregistrement, cf. `JobService::submit_job`)
+    pub async fn debit_job_credits(&self, job: &Job) -> Result<()> {
+        if job.credits_used <= 0 {
+            return Ok(());
         }
 
-        // Débiter les crédits
-        self.db.create_credit_transaction(
-            user_id,
+        self.debit_credits(
+            job.user_id,
+            job.credits_used,
             "consumption",
-            -credits_needed,
             &format!("Job de quantification: {}", job.name),
-        ).await?;
+        ).await
+    }
 
-        Ok(())
+    /// Débiter une nouvelle mesure de qualité (gratuite si `amount` est nul)
+    pub async fn consume_revalidation_credits(&self, user_id: Uuid, job_name: &str, amount: i32) -> Result<()> {
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        self.debit_credits(
+            user_id,
+            amount,
+            "consumption",
+            &format!("Nouvelle mesure de qualité: {}", job_name),
+        ).await
+    }
+
+    /// Débiter des crédits sans jamais passer sous zéro
+    ///
+    /// Lecture du solde et écriture sous verrou optimiste (version de
+    /// l'abonnement): deux débits concurrents ne peuvent pas dépenser le même
+    /// solde. Sur conflit, la lecture est refaite, au plus
+    /// `MAX_CREDIT_UPDATE_ATTEMPTS` fois.
+    async fn debit_credits(
+        &self,
+        user_id: Uuid,
+        amount: i32,
+        transaction_type: &str,
+        description: &str,
+    ) -> Result<()> {
+        for _ in 0..MAX_CREDIT_UPDATE_ATTEMPTS {
+            let version = self.db.get_subscription_version(user_id).await?;
+
+            // Plan illimité: débit enregistré pour l'historique, sans contrôle de solde
+            let current_credits = self.get_user_credits(user_id).await?;
+            if !current_credits.covers(amount) {
+                return Err(AppError::InsufficientCredits);
+            }
+
+            if self.db.create_credit_transaction_if_version(
+                user_id,
+                version,
+                transaction_type,
+                -amount,
+                description,
+            ).await? {
+                return Ok(());
+            }
+        }
+
+        log::warn!("Débit de crédits abandonné après {} conflits (utilisateur {})", MAX_CREDIT_UPDATE_ATTEMPTS, user_id);
+        Err(AppError::ConcurrentModification)
     }
 
     /// Ajouter des crédits à un utilisateur
@@ -191,14 +366,61 @@ impl BillingService {
         ).await
     }
 
+    /// Ajuster manuellement les crédits d'un utilisateur (geste commercial, correction)
+    ///
+    /// Un ajustement négatif ne peut pas rendre le solde négatif.
+    pub async fn adjust_credits(&self, user_id: Uuid, amount: i32, reason: &str) -> Result<CreditInfo> {
+        if amount == 0 {
+            return Err(AppError::Validation("Le montant doit être non nul".to_string()));
+        }
+
+        // Vérifier que l'utilisateur existe
+        self.db.get_user_by_id(user_id).await?;
+
+        if amount < 0 {
+            self.debit_credits(user_id, -amount, "admin_adjustment", reason).await?;
+        } else {
+            self.add_credits(user_id, amount, "admin_adjustment", reason).await?;
+        }
+
+        self.get_user_credits(user_id).await
+    }
+
+    /// Projeter la consommation jusqu'à la fin de la période
+    ///
+    /// Moyenne des débits sur `USAGE_PROJECTION_WINDOW_DAYS` jours; sans fin
+    /// de période connue (plan gratuit), l'horizon est d'un mois.
+    pub async fn get_usage_projection(&self, user_id: Uuid) -> Result<UsageProjection> {
+        let now = Utc::now();
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        let credits = self.get_user_credits(user_id).await?;
+
+        let since = now - Duration::days(USAGE_PROJECTION_WINDOW_DAYS);
+        let (credits_consumed, jobs) = self.db.get_credit_consumption_since(user_id, since).await?;
+
+        let period_end = if subscription.current_period_end > now {
+            subscription.current_period_end
+        } else {
+            now + Duration::days(30)
+        };
+
+        Ok(UsageProjection::new(
+            subscription.plan,
+            credits.remaining_credits,
+            credits_consumed,
+            jobs,
+            period_end,
+            now,
+        ))
+    }
+
     /// Obtenir l'historique des crédits
     pub async fn get_credit_history(
         &self,
         user_id: Uuid,
-        page: i64,
-        per_page: i64,
+        pagination: Pagination,
     ) -> Result<Vec<CreditTransaction>> {
-        self.db.get_user_credit_transactions(user_id, page, per_page).await
+        self.db.get_user_credit_transactions(user_id, pagination).await
     }
 
     /// Réinitialiser les crédits mensuels
@@ -207,6 +429,140 @@ impl BillingService {
         Ok(reset_count)
     }
 
+    /// Appliquer les rétrogradations programmées arrivées en fin de période
+    ///
+    /// À lancer avant la réconciliation Stripe, qui avance la fin de période
+    /// au renouvellement.
+    pub async fn apply_pending_plan_changes(&self, audit: &AuditRepository) -> Result<u64> {
+        let subscriptions = self.db.list_due_pending_plans(Utc::now()).await?;
+        let mut applied = 0;
+
+        for mut subscription in subscriptions {
+            let previous_plan = subscription.plan.clone();
+            let stripe_id = subscription.stripe_subscription_id.clone();
+            let Some(new_plan) = subscription.apply_pending_plan() else {
+                continue;
+            };
+
+            // Stripe d'abord: en cas d'échec, le changement sera retenté au prochain passage
+            let stripe_result = if new_plan == SubscriptionPlan::Free {
+                match stripe_id.as_deref() {
+                    Some(stripe_id) => self.cancel_stripe_subscription(stripe_id).await,
+                    None => Ok(()),
+                }
+            } else {
+                self.change_stripe_plan(stripe_id.as_deref(), &new_plan).await
+            };
+            if let Err(e) = stripe_result {
+                log::warn!(
+                    "Rétrogradation programmée de l'utilisateur {} reportée: {}",
+                    subscription.user_id, e
+                );
+                continue;
+            }
+
+            self.db.update_subscription(&subscription).await?;
+            applied += 1;
+            self.notify_subscription("subscription.downgraded", &subscription, Some(&previous_plan));
+
+            let entry = AuditLog::new(
+                None,
+                None,
+                None,
+                "subscription.downgrade_applied".to_string(),
+                Some("subscription".to_string()),
+                Some(subscription.id),
+                Some(format!(
+                    "Plan {:?} → {:?} en fin de période pour l'utilisateur {}",
+                    previous_plan, new_plan, subscription.user_id
+                )),
+            );
+            audit.log(entry).await;
+        }
+
+        Ok(applied)
+    }
+
+    /// Réconcilier les abonnements locaux avec l'état Stripe (webhooks manqués)
+    pub async fn reconcile_subscriptions(&self, audit: &AuditRepository) -> Result<u64> {
+        let subscriptions = self.db.list_stripe_subscriptions().await?;
+        let mut corrected = 0;
+
+        for mut subscription in subscriptions {
+            let stripe_id = match subscription.stripe_subscription_id.clone() {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let remote = match self.fetch_stripe_subscription(&stripe_id).await {
+                Ok(remote) => remote,
+                Err(e) => {
+                    log::warn!("Réconciliation impossible pour l'abonnement {}: {}", stripe_id, e);
+                    continue;
+                }
+            };
+
+            let before = serde_json::to_value(&subscription).unwrap_or_default();
+            let trial_failed = subscription.trial_payment_failed(&remote);
+            let trial_plan = subscription.plan.clone();
+            if !subscription.reconcile_with(&remote) {
+                continue;
+            }
+
+            if let Err(e) = self.db.update_subscription(&subscription).await {
+                log::error!("Correction de l'abonnement {} non enregistrée: {}", stripe_id, e);
+                continue;
+            }
+            corrected += 1;
+
+            if trial_failed {
+                self.end_failed_trial(subscription.user_id, &stripe_id, &trial_plan).await;
+                self.notify_subscription("subscription.payment_failed", &subscription, Some(&trial_plan));
+            }
+
+            log::warn!(
+                "Abonnement {} désynchronisé de Stripe, corrigé ({:?}, plan {:?})",
+                stripe_id, subscription.status, subscription.plan
+            );
+
+            let entry = AuditLog::new(
+                None,
+                None,
+                None,
+                "subscription.reconciled".to_string(),
+                Some("subscription".to_string()),
+                Some(subscription.id),
+                Some(format!("Abonnement de l'utilisateur {} aligné sur Stripe", subscription.user_id)),
+            ).with_changes(before, serde_json::to_value(&subscription).unwrap_or_default());
+            audit.log(entry).await;
+        }
+
+        Ok(corrected)
+    }
+
+    /// Clore un essai non converti: arrêter les relances Stripe et reprendre
+    /// les crédits d'essai non consommés (best effort)
+    async fn end_failed_trial(&self, user_id: Uuid, stripe_id: &str, trial_plan: &SubscriptionPlan) {
+        if let Err(e) = self.cancel_stripe_subscription(stripe_id).await {
+            log::warn!("Impossible d'annuler l'abonnement Stripe {} après l'essai: {}", stripe_id, e);
+        }
+
+        let unused = match self.get_user_credits(user_id).await {
+            Ok(credits) => credits.remaining_credits.unwrap_or(0).min(trial_plan.info().credits_per_month),
+            Err(e) => {
+                log::warn!("Crédits d'essai de l'utilisateur {} non repris: {}", user_id, e);
+                return;
+            }
+        };
+
+        if unused > 0 {
+            let description = format!("Fin de l'essai {:?} sans paiement", trial_plan);
+            if let Err(e) = self.add_credits(user_id, -unused, "trial_expired", &description).await {
+                log::warn!("Crédits d'essai de l'utilisateur {} non repris: {}", user_id, e);
+            }
+        }
+    }
+
     /// Gérer un webhook Stripe
     pub async fn handle_stripe_webhook(
         &self,
@@ -215,12 +571,16 @@ impl BillingService {
     ) -> Result<()> {
         use stripe::{Webhook, Event};
         
-        // Vérifier la signature
+        // Vérifier la signature sur le corps brut (toute altération la rend invalide)
+        let payload = std::str::from_utf8(payload).map_err(|_| AppError::InvalidSignature)?;
         let event = Webhook::construct_event(
             payload,
             signature,
             &self.stripe_webhook_secret,
-        ).map_err(|e| AppError::StripeError(e.to_string()))?;
+        ).map_err(|e| {
+            log::warn!("Webhook Stripe rejeté: {}", e);
+            AppError::InvalidSignature
+        })?;
 
         match event {
             Event::PaymentIntentSucceeded(payment_intent) => {
@@ -260,9 +620,9 @@ impl BillingService {
         let plan_info = plan.info();
         let price_id = self.get_stripe_price_id(&plan).await?;
 
-        use stripe::{CheckoutSession, CheckoutSessionMode, Client, CreateCheckoutSession, CreateCheckoutSessionLineItems, CreateCheckoutSessionPaymentMethodType, CreateCheckoutSessionLineItemsPriceData, CreateCheckoutSessionLineItemsPriceDataProductData, Currency};
+        use stripe::{CheckoutSessionMode, CreateCheckoutSession, CreateCheckoutSessionLineItems, CreateCheckoutSessionPaymentMethodType, CreateCheckoutSessionLineItemsPriceData, CreateCheckoutSessionLineItemsPriceDataProductData, Currency};
         
-        let client = Client::new(&self.stripe_secret_key);
+        let client = self.stripe_client();
         
         let mut create_session = CreateCheckoutSession::new();
         create_session.mode = Some(CheckoutSessionMode::Subscription);
@@ -281,20 +641,60 @@ impl BillingService {
         create_session.line_items = Some(vec![line_item]);
 
         // Créer la session
-        let session = CheckoutSession::create(&client, create_session)
+        let session = stripe::CheckoutSession::create(&client, create_session)
             .await
             .map_err(|e| AppError::StripeError(e.to_string()))?;
 
+        // Persister la session en attente pour suivre les paiements abandonnés
+        let expires_at = DateTime::<Utc>::from_timestamp(session.expires_at, 0)
+            .unwrap_or_else(|| Utc::now() + Duration::hours(24));
+        let pending = CheckoutSession::new_pending(user_id, session.id.to_string(), plan, expires_at);
+        self.db.create_checkout_session(&pending).await?;
+
         Ok(session.url.unwrap_or_default())
     }
 
+    /// Obtenir l'état d'une session de checkout (rafraîchi depuis Stripe si en attente)
+    pub async fn get_checkout_session(&self, user_id: Uuid, session_id: &str) -> Result<CheckoutSession> {
+        let mut session = self.db.get_checkout_session(session_id).await?;
+        if session.user_id != user_id {
+            return Err(AppError::NotFound("Session de checkout non trouvée".to_string()));
+        }
+
+        if session.status != CheckoutSessionStatus::Pending {
+            return Ok(session);
+        }
+
+        match self.fetch_stripe_checkout_status(session_id).await {
+            Ok(Some(CheckoutSessionStatus::Completed)) => session.complete(),
+            Ok(Some(CheckoutSessionStatus::Expired)) => session.expire(),
+            Ok(_) if session.is_stale(Utc::now()) => session.expire(),
+            Ok(_) => return Ok(session),
+            Err(e) => {
+                log::warn!("Statut Stripe indisponible pour la session {}: {}", session_id, e);
+                if !session.is_stale(Utc::now()) {
+                    return Ok(session);
+                }
+                session.expire();
+            }
+        }
+
+        self.db.update_checkout_session(&session).await?;
+        Ok(session)
+    }
+
+    /// Expirer localement les sessions de checkout abandonnées
+    pub async fn expire_stale_checkout_sessions(&self) -> Result<u64> {
+        self.db.expire_stale_checkout_sessions(Utc::now()).await
+    }
+
     // === Méthodes privées Stripe ===
 
     async fn create_stripe_customer(&self, user_id: Uuid) -> Result<String> {
-        use stripe::{Customer, CreateCustomer, Client};
+        use stripe::{Customer, CreateCustomer};
         
         let user = self.db.get_user_by_id(user_id).await?;
-        let client = Client::new(&self.stripe_secret_key);
+        let client = self.stripe_client();
         
         let mut create_customer = CreateCustomer::new();
         create_customer.email = Some(&user.email);
@@ -321,9 +721,9 @@ impl BillingService {
         plan: &SubscriptionPlan,
         payment_method_id: Option<&str>,
     ) -> Result<String> {
-        use stripe::{Subscription, CreateSubscription, Client, CreateSubscriptionItems};
+        use stripe::{Subscription, CreateSubscription, CreateSubscriptionItems};
         
-        let client = Client::new(&self.stripe_secret_key);
+        let client = self.stripe_client();
         let price_id = self.get_stripe_price_id(plan).await?;
         
         let mut create_sub = CreateSubscription::new(customer_id);
@@ -368,9 +768,9 @@ impl BillingService {
         new_plan: &SubscriptionPlan,
     ) -> Result<()> {
         if let Some(sub_id) = subscription_id {
-            use stripe::{Subscription, UpdateSubscription, Client};
+            use stripe::{Subscription, UpdateSubscription};
             
-            let client = Client::new(&self.stripe_secret_key);
+            let client = self.stripe_client();
             let new_price_id = self.get_stripe_price_id(new_plan).await?;
             
             let mut update_sub = UpdateSubscription::default();
@@ -387,10 +787,77 @@ impl BillingService {
         Ok(())
     }
 
+    async fn fetch_stripe_subscription(&self, subscription_id: &str) -> Result<StripeSubscriptionState> {
+        use stripe::{Subscription, SubscriptionId};
+        
+        let client = self.stripe_client();
+        let id: SubscriptionId = subscription_id
+            .parse()
+            .map_err(|_| AppError::StripeError(format!("ID d'abonnement invalide: {}", subscription_id)))?;
+        
+        let subscription = Subscription::retrieve(&client, &id, &[])
+            .await
+            .map_err(|e| AppError::StripeError(e.to_string()))?;
+        
+        let status = match subscription.status {
+            stripe::SubscriptionStatus::Active => SubscriptionStatus::Active,
+            stripe::SubscriptionStatus::Trialing => SubscriptionStatus::Trialing,
+            stripe::SubscriptionStatus::PastDue
+            | stripe::SubscriptionStatus::Unpaid
+            | stripe::SubscriptionStatus::Incomplete
+            | stripe::SubscriptionStatus::Paused => SubscriptionStatus::PastDue,
+            stripe::SubscriptionStatus::Canceled
+            | stripe::SubscriptionStatus::IncompleteExpired => SubscriptionStatus::Cancelled,
+        };
+        
+        let current_period_end = DateTime::<Utc>::from_timestamp(subscription.current_period_end, 0)
+            .unwrap_or_else(Utc::now);
+        
+        let price_id = subscription.items.data.first()
+            .and_then(|item| item.price.as_ref())
+            .map(|price| price.id.to_string());
+        
+        let plan = match &price_id {
+            Some(id) if *id == self.get_stripe_price_id(&SubscriptionPlan::Starter).await? => Some(SubscriptionPlan::Starter),
+            Some(id) if *id == self.get_stripe_price_id(&SubscriptionPlan::Pro).await? => Some(SubscriptionPlan::Pro),
+            _ => None,
+        };
+        
+        let trial_end = subscription.trial_end
+            .and_then(|timestamp| DateTime::<Utc>::from_timestamp(timestamp, 0));
+        
+        Ok(StripeSubscriptionState {
+            status,
+            current_period_end,
+            plan,
+            price_id,
+            trial_end,
+        })
+    }
+
+    async fn fetch_stripe_checkout_status(&self, session_id: &str) -> Result<Option<CheckoutSessionStatus>> {
+        use stripe::CheckoutSessionId;
+        
+        let client = self.stripe_client();
+        let id: CheckoutSessionId = session_id
+            .parse()
+            .map_err(|_| AppError::StripeError(format!("ID de session invalide: {}", session_id)))?;
+        
+        let session = stripe::CheckoutSession::retrieve(&client, &id, &[])
+            .await
+            .map_err(|e| AppError::StripeError(e.to_string()))?;
+        
+        Ok(match session.status {
+            Some(stripe::CheckoutSessionStatus::Complete) => Some(CheckoutSessionStatus::Completed),
+            Some(stripe::CheckoutSessionStatus::Expired) => Some(CheckoutSessionStatus::Expired),
+            _ => None,
+        })
+    }
+
     async fn cancel_stripe_subscription(&self, subscription_id: &str) -> Result<()> {
-        use stripe::{Subscription, CancelSubscription, Client};
+        use stripe::{Subscription, CancelSubscription};
         
-        let client = Client::new(&self.stripe_secret_key);
+        let client = self.stripe_client();
         let cancel_sub = CancelSubscription::default();
         
         Subscription::cancel(&client, subscription_id, cancel_sub)
@@ -415,8 +882,367 @@ impl BillingService {
         Ok(())
     }
 
+    /// Échec de paiement: prévenir les webhooks de l'utilisateur
+    ///
+    /// Les relances sont laissées à Stripe; le passage en `past_due` qui en
+    /// découle est repris par `reconcile_subscriptions`.
     async fn handle_payment_failed(&self, charge: stripe::Charge) -> Result<()> {
-        // TODO: Implémenter la logique d'échec de paiement
+        let Some(customer_id) = charge.customer.as_ref().map(|customer| customer.id().to_string()) else {
+            return Ok(());
+        };
+        let Some(user_id) = self.db.get_user_id_by_stripe_customer(&customer_id).await? else {
+            log::warn!("Échec de paiement pour un client Stripe inconnu: {}", customer_id);
+            return Ok(());
+        };
+
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        self.notify_subscription("subscription.payment_failed", &subscription, None);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelFile;
+    use crate::utils::testing;
+
+    /// Écart (en secondes) entre une expiration et `created_at + days`
+    fn drift_seconds(file: &ModelFile, days: i64) -> i64 {
+        let expected = file.created_at + chrono::Duration::days(days);
+        (file.expires_at.expect("expiration") - expected).num_seconds().abs()
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn free_file_expiry_extends_on_pro_upgrade() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+
+        let expires_at = billing.file_expiry_for(user.id).await.unwrap();
+        let file = ModelFile::new(
+            user.id,
+            "model.bin".to_string(),
+            1024,
+            "0".repeat(64),
+            crate::models::ModelFormat::Safetensors,
+            "test".to_string(),
+            format!("models/{}/model.bin", Uuid::new_v4()),
+        )
+        .with_expiry(expires_at);
+        let file = db.create_file(&file).await.unwrap();
+        assert!(drift_seconds(&file, 7) <= 5);
+
+        // Plan payant hors Stripe: le passage à Pro ne contacte pas Stripe
+        let mut subscription = db.get_user_subscription(user.id).await.unwrap();
+        subscription.upgrade(SubscriptionPlan::Starter, None);
+        db.update_subscription(&subscription).await.unwrap();
+
+        billing.update_subscription(user.id, "pro", None).await.unwrap();
+
+        let file = db.get_file(file.id).await.unwrap();
+        assert!(drift_seconds(&file, 90) <= 5);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn manual_adjustment_grants_credits_and_refuses_over_deduction() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+
+        let credits = billing.adjust_credits(user.id, 5, "Geste commercial").await.unwrap();
+        assert_eq!(credits.remaining_credits, Some(5));
+
+        assert!(matches!(
+            billing.adjust_credits(user.id, -10, "Correction").await,
+            Err(AppError::InsufficientCredits)
+        ));
+        assert_eq!(billing.get_user_credits(user.id).await.unwrap().remaining_credits, Some(5));
+
+        let credits = billing.adjust_credits(user.id, -5, "Correction").await.unwrap();
+        assert_eq!(credits.remaining_credits, Some(0));
+        assert_eq!((credits.total_credits, credits.used_credits), (5, 5));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn uploads_beyond_the_storage_quota_are_rejected_until_files_are_deleted() {
+        let mut config = testing::config();
+        config.free_user_storage_quota_mb = 1;
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let jobs = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+
+        let file = testing::create_stored_file(&db, &testing::storage(), user.id, 768 * 1024).await;
+        assert_eq!(billing.get_storage_usage(user.id).await.unwrap().used_bytes, 768 * 1024);
+
+        assert!(matches!(
+            billing.check_storage_quota(user.id, 512 * 1024).await,
+            Err(AppError::StorageQuotaExceeded)
+        ));
+
+        jobs.delete_model(user.id, file.id).await.unwrap();
+
+        assert_eq!(billing.get_storage_usage(user.id).await.unwrap().used_bytes, 0);
+        assert!(billing.check_storage_quota(user.id, 512 * 1024).await.is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn stale_pending_checkout_session_is_expired() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+
+        let stale = CheckoutSession::new_pending(
+            user.id,
+            format!("cs_test_{}", Uuid::new_v4()),
+            SubscriptionPlan::Starter,
+            Utc::now() - Duration::minutes(5),
+        );
+        let fresh = CheckoutSession::new_pending(
+            user.id,
+            format!("cs_test_{}", Uuid::new_v4()),
+            SubscriptionPlan::Starter,
+            Utc::now() + Duration::hours(24),
+        );
+        db.create_checkout_session(&stale).await.unwrap();
+        db.create_checkout_session(&fresh).await.unwrap();
+
+        assert!(billing.expire_stale_checkout_sessions().await.unwrap() >= 1);
+
+        let stale = db.get_checkout_session(&stale.stripe_session_id).await.unwrap();
+        assert_eq!(stale.status, CheckoutSessionStatus::Expired);
+        let fresh = db.get_checkout_session(&fresh.stripe_session_id).await.unwrap();
+        assert_eq!(fresh.status, CheckoutSessionStatus::Pending);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn parallel_debits_never_spend_the_same_credits_twice() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let billing = Arc::new(testing::billing_service(db.clone(), &config));
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+        testing::grant_credits(&db, user.id, 5).await;
+        let job = testing::create_job(&db, user.id, "llama", crate::models::QuantizationMethod::Int8).await;
+        let available = billing.get_user_credits(user.id).await.unwrap().remaining_credits.unwrap();
+
+        // Plus de débits simultanés que de crédits disponibles
+        let debits: Vec<_> = (0..available + 5)
+            .map(|_| {
+                let billing = billing.clone();
+                tokio::spawn(async move { billing.consume_job_credits(user.id, job.id).await })
+            })
+            .collect();
+        let mut succeeded = 0;
+        for debit in debits {
+            match debit.await.unwrap() {
+                Ok(()) => succeeded += 1,
+                Err(AppError::InsufficientCredits | AppError::ConcurrentModification) => {}
+                Err(e) => panic!("débit en erreur: {:?}", e),
+            }
+        }
+
+        let remaining = billing.get_user_credits(user.id).await.unwrap().remaining_credits.unwrap();
+        assert!(succeeded <= available, "{} débits pour {} crédits", succeeded, available);
+        assert_eq!(remaining, available - succeeded);
+        assert!(remaining >= 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn usage_projection_uses_the_seeded_consumption() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        testing::grant_credits(&db, user.id, 25).await;
+        for _ in 0..10 {
+            db.create_credit_transaction(user.id, "consumption", -2, "Job de quantification: seed").await.unwrap();
+        }
+
+        let projection = billing.get_usage_projection(user.id).await.unwrap();
+
+        assert_eq!(projection.remaining_credits, Some(5));
+        assert!((projection.credits_per_day - 20.0 / 30.0).abs() < 1e-9);
+        assert!((projection.jobs_per_day - 10.0 / 30.0).abs() < 1e-9);
+        // Période d'un mois restante: ~20 crédits projetés pour 5 disponibles
+        assert!(projection.projected_credits > 19.0);
+        assert!(projection.will_exhaust);
+        let exhausted_in = projection.exhausted_at.unwrap() - Utc::now();
+        assert!((exhausted_in.num_hours() - 180).abs() <= 1);
+        assert_eq!(projection.suggested_plan, Some(SubscriptionPlan::Pro));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn downgrade_is_scheduled_and_applied_at_period_end() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let audit = AuditRepository::new(&db);
+        // Plan payant hors Stripe: aucun appel Stripe au changement
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let subscription = billing.update_subscription(user.id, "starter", None).await.unwrap();
+        assert_eq!(subscription.plan, SubscriptionPlan::Pro);
+        assert_eq!(subscription.pending_plan, Some(SubscriptionPlan::Starter));
+
+        // Période en cours: rien n'est appliqué
+        billing.apply_pending_plan_changes(&audit).await.unwrap();
+        assert_eq!(db.get_user_subscription(user.id).await.unwrap().plan, SubscriptionPlan::Pro);
+
+        let mut subscription = db.get_user_subscription(user.id).await.unwrap();
+        subscription.current_period_end = Utc::now() - Duration::minutes(1);
+        db.update_subscription(&subscription).await.unwrap();
+
+        assert!(billing.apply_pending_plan_changes(&audit).await.unwrap() >= 1);
+        let subscription = db.get_user_subscription(user.id).await.unwrap();
+        assert_eq!(subscription.plan, SubscriptionPlan::Starter);
+        assert_eq!(subscription.pending_plan, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn cancellation_fires_a_signed_subscription_webhook() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let config = testing::config();
+        let db = testing::database().await;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("X-Webhook-Event", "subscription.cancelled"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        db.create_user_webhook(user.id, &server.uri(), "secret-webhook", &["subscription.cancelled".to_string()])
+            .await
+            .unwrap();
+        let billing = testing::billing_service(db.clone(), &config)
+            .with_webhooks(Arc::new(WebhookNotifier::new(db.clone(), 5).allowing_private_destinations()));
+
+        billing.cancel_subscription(user.id).await.unwrap();
+
+        // Envoi en tâche de fond
+        let mut requests = Vec::new();
+        for _ in 0..50 {
+            requests = server.received_requests().await.unwrap_or_default();
+            if !requests.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(requests.len(), 1);
+
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["type"], "subscription.cancelled");
+        assert_eq!(body["data"]["plan"], "Free");
+        assert_eq!(body["data"]["previous_plan"], "Starter");
+
+        let signature = requests[0]
+            .headers
+            .iter()
+            .find(|(name, _)| name.as_str().eq_ignore_ascii_case("X-Webhook-Signature"))
+            .map(|(_, values)| values.last().as_str())
+            .unwrap();
+        let (timestamp, v1) = signature.trim_start_matches("t=").split_once(",v1=").unwrap();
+        let expected = crate::utils::security::sign_webhook_payload(
+            "secret-webhook",
+            timestamp.parse().unwrap(),
+            std::str::from_utf8(&requests[0].body).unwrap(),
+        );
+        assert_eq!(v1, expected);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn pro_credits_are_unlimited_past_a_thousand_jobs() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // 1000 jobs déjà débités, sans aucun crédit accordé
+        for index in 0..1000 {
+            db.create_credit_transaction(user.id, "consumption", -1, &format!("Job de quantification: {}", index))
+                .await
+                .unwrap();
+        }
+        assert!(billing.check_user_credits(user.id).await.unwrap());
+
+        let job = testing::create_job(&db, user.id, "job-1001", crate::models::QuantizationMethod::Int8).await;
+        billing.consume_job_credits(user.id, job.id).await.unwrap();
+
+        let credits = billing.get_user_credits(user.id).await.unwrap();
+        assert!(credits.unlimited);
+        assert_eq!(credits.remaining_credits, None);
+        assert_eq!(credits.used_credits, 1001);
+        // Affichage: "unlimited" plutôt qu'un solde négatif
+        let json = serde_json::to_value(&credits).unwrap();
+        assert_eq!(json["remaining_credits"], serde_json::Value::Null);
+        assert_eq!(json["unlimited"], true);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn reconciliation_reactivates_a_row_still_active_on_stripe() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let config = testing::config();
+        let db = testing::database().await;
+        let stripe = MockServer::start().await;
+        let stripe_id = format!("sub_{}", Uuid::new_v4().simple());
+        let period_end = (Utc::now() + Duration::days(20)).timestamp();
+        Mock::given(method("GET"))
+            .and(path_regex(format!("/subscriptions/{}$", stripe_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": stripe_id,
+                "object": "subscription",
+                "automatic_tax": { "enabled": false },
+                "billing_cycle_anchor": period_end,
+                "cancel_at_period_end": false,
+                "collection_method": "charge_automatically",
+                "created": period_end - 30 * 86400,
+                "currency": "eur",
+                "current_period_start": period_end - 30 * 86400,
+                "current_period_end": period_end,
+                "customer": "cus_test",
+                "items": { "object": "list", "data": [], "has_more": false, "url": "/v1/subscription_items" },
+                "livemode": false,
+                "metadata": {},
+                "start_date": period_end - 30 * 86400,
+                "status": "active",
+            })))
+            .mount(&stripe)
+            .await;
+
+        // Ligne locale désactivée alors que Stripe facture toujours
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let mut subscription = db.get_user_subscription(user.id).await.unwrap();
+        subscription.stripe_subscription_id = Some(stripe_id.clone());
+        subscription.cancel();
+        db.update_subscription(&subscription).await.unwrap();
+
+        let billing = testing::billing_service(db.clone(), &config).with_stripe_api_base(stripe.uri());
+        let audit = AuditRepository::new(&db);
+
+        // Les autres abonnements de la base échouent côté Stripe (404) sans interrompre la passe
+        assert!(billing.reconcile_subscriptions(&audit).await.unwrap() >= 1);
+
+        let subscription = db.get_user_subscription(user.id).await.unwrap();
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert_eq!(subscription.plan, SubscriptionPlan::Pro);
+        assert_eq!(subscription.cancelled_at, None);
+        assert_eq!(subscription.current_period_end.timestamp(), period_end);
+    }
+}
@@ -1,27 +1,64 @@
 // core/job_service.rs
 use crate::models::{
-    Job, JobStatus, QuantizationMethod, ModelFormat,
-    NewJob, JobResult, FileMetadata
+    Job, JobStatus, JobLog, JobEvent, JobEventType, JobStatusSummary, QuantizationMethod, ModelFormat,
+    NewJob, JobSettings, JobResult, FileMetadata, QuantizationConfig, ModelFile, ActiveJobPolicy, CreditCostPolicy, OutputFormatPolicy, JobMemoryPolicy,
+    QuantizationReport, StageTimings, ComparedVariant, JobComparison, ScalingSignal, WorkerStatus, JobCost, AnalysisStatus, FileAnalysis,
+    JobQueueEstimate, QuantizationPreferences, UpdateQuantizationPreferences, Capabilities,
+    SubscriptionPlan, CancelledBy, QuantizationMethodInfo, QuantizationPreset, QuantizationPresetInfo,
+    JobShareLink, NewJobShareLink, DEFAULT_SHARE_LINK_HOURS, Pagination, UploadedModel,
 };
 use crate::services::{
     database::Database,
-    queue::JobQueue,
+    queue::{JobQueue, ProgressEvent, ProgressStage},
     storage::FileStorage,
+    external::HuggingFaceClient,
+    cache::Cache,
 };
+use crate::utils::workspace::TempWorkspace;
+use crate::utils::ids::{IdProvider, default_id_provider};
 use crate::utils::error::{AppError, Result};
-use crate::core::quantization_service::QuantizationService;
+use crate::core::quantization_service::{QuantizationService, ModelAnalysis};
+use crate::core::analysis::detect_existing_quantization;
+use crate::core::notification_service::NotificationService;
+use crate::core::billing_service::BillingService;
 use uuid::Uuid;
 use chrono::Utc;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use tokio::sync::RwLock;
 
+/// Clés vérifiées en base par requête lors du nettoyage des objets orphelins
+const ORPHAN_LOOKUP_BATCH: usize = 1000;
+
+/// Marge du marqueur de traitement au-delà du timeout de quantification
+/// (récupération du modèle, analyse, validation, envoi du résultat)
+const PROCESSING_MARKER_GRACE_SECONDS: u64 = 30 * 60;
+
 pub struct JobService {
     db: Arc<Database>,
     queue: Arc<JobQueue>,
     storage: Arc<FileStorage>,
     quantizer: Arc<QuantizationService>,
+    hub_client: Arc<HuggingFaceClient>,
+    cache: Arc<Cache>,
     max_concurrent_jobs: usize,
+    job_log_max_lines: i64,
+    analysis_cache_ttl: usize,
+    active_job_limits: ActiveJobPolicy,
+    credit_costs: CreditCostPolicy,
+    output_formats: OutputFormatPolicy,
+    memory_limits: JobMemoryPolicy,
     active_jobs: RwLock<Vec<Uuid>>,
+    /// Pause de maintenance: partagé entre les clones (tous les consommateurs)
+    paused: Arc<AtomicBool>,
+    /// Précisions 2/3 bits acceptées (`ENABLE_EXPERIMENTAL_QUANTIZATION`)
+    experimental_quantization: bool,
+    ids: Arc<dyn IdProvider>,
+    /// Identifiant de ce worker dans la chronologie des jobs (hôte:pid)
+    worker_id: String,
+    /// Débit des crédits à la création (None: aucun débit, donc aucun remboursement)
+    billing: Option<Arc<BillingService>>,
 }
 
 impl JobService {
@@ -30,18 +67,61 @@ impl JobService {
         queue: Arc<JobQueue>,
         storage: Arc<FileStorage>,
         quantizer: Arc<QuantizationService>,
+        hub_client: Arc<HuggingFaceClient>,
+        cache: Arc<Cache>,
         max_concurrent_jobs: usize,
+        job_log_max_lines: i64,
+        analysis_cache_ttl: usize,
+        active_job_limits: ActiveJobPolicy,
+        credit_costs: CreditCostPolicy,
+        output_formats: OutputFormatPolicy,
+        memory_limits: JobMemoryPolicy,
     ) -> Self {
         Self {
             db,
             queue,
             storage,
             quantizer,
+            hub_client,
+            cache,
             max_concurrent_jobs,
+            job_log_max_lines,
+            analysis_cache_ttl,
+            active_job_limits,
+            credit_costs,
+            output_formats,
+            memory_limits,
             active_jobs: RwLock::new(Vec::new()),
+            paused: Arc::new(AtomicBool::new(false)),
+            experimental_quantization: false,
+            ids: default_id_provider(),
+            worker_id: format!(
+                "{}:{}",
+                std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string()),
+                std::process::id()
+            ),
+            billing: None,
         }
     }
 
+    /// Remplacer la source d'identifiants (séquence déterministe en test)
+    pub fn with_id_provider(mut self, ids: Arc<dyn IdProvider>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Accepter les précisions expérimentales (2/3 bits)
+    pub fn with_experimental_quantization(mut self, enabled: bool) -> Self {
+        self.experimental_quantization = enabled;
+        self
+    }
+
+    /// Débiter les crédits des jobs avant leur enregistrement
+    pub fn with_billing(mut self, billing: Arc<BillingService>) -> Self {
+        self.billing = Some(billing);
+        self
+    }
+
     /// Créer un nouveau job de quantification
     pub async fn create_job(
         &self,
@@ -50,7 +130,18 @@ impl JobService {
         name: String,
         quantization_method: QuantizationMethod,
         output_format: ModelFormat,
+        mut config: QuantizationConfig,
     ) -> Result<Job> {
+        config.validate_for(&quantization_method).map_err(AppError::Validation)?;
+        self.check_experimental_bits(&config)?;
+        self.quantizer.check_method_available(&quantization_method)?;
+        self.check_job_name_available(user_id, &name).await?;
+
+        // Plafonner la calibration et restreindre les formats selon le plan
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        self.check_output_format_allowed(&subscription.plan, &output_format)?;
+        config.clamp_calibration_samples(subscription.plan.max_calibration_samples());
+
         // Récupérer les métadonnées du fichier
         let file_metadata = self.storage.get_file_metadata(input_file_id).await?;
         
@@ -59,10 +150,30 @@ impl JobService {
             return Err(AppError::Unauthorized);
         }
 
+        // Vérifier que le fichier source est lisible avant tout débit de crédits
+        let input_file = self.db.get_file(input_file_id).await?;
+        self.storage.check_input_integrity(&input_file).await?;
+
+        // Re-quantifier un modèle déjà quantifié ne produit rien d'utilisable
+        if !config.allow_quantized_input {
+            match self.storage.read_model_header(&input_file).await {
+                Ok(header) => {
+                    if let Some(existing) = detect_existing_quantization(&input_file.format, &header) {
+                        return Err(AppError::AlreadyQuantized(existing.to_string()));
+                    }
+                }
+                Err(e) => log::warn!("En-tête du fichier {} illisible: {}", input_file.id, e),
+            }
+        }
+
         // Vérifier la compatibilité format/méthode
         if !self.is_compatible(&file_metadata.format, &quantization_method, &output_format) {
             return Err(AppError::InvalidCombination);
         }
+        self.check_gguf_export(&subscription.plan, &file_metadata.format, &config)?;
+
+        // Limiter les jobs en cours par utilisateur
+        self.check_active_job_limit(user_id).await?;
 
         // Calculer le coût en crédits
         let credits_cost = self.calculate_job_cost(
@@ -78,23 +189,222 @@ impl JobService {
             quantization_method,
             file_metadata.format,
             output_format,
-            input_file_id,
+            Some(input_file_id),
             credits_cost,
-        );
-
-        let job = self.db.create_job(&job).await?;
+        )
+        .with_id(self.ids.next_id())
+        .with_quantization_config(config);
 
         // Ajouter à la queue avec priorité selon le plan
+        self.submit_job(job, subscription.plan.queue_priority()).await
+    }
+
+    /// Créer un job à partir d'un dépôt Hugging Face
+    ///
+    /// Le dépôt doit exister et tenir dans `max_size_bytes` (limite du plan);
+    /// ces vérifications ont lieu avant toute consommation de crédits.
+    pub async fn create_job_from_hub(
+        &self,
+        user_id: Uuid,
+        name: String,
+        quantization_method: QuantizationMethod,
+        output_format: ModelFormat,
+        repo_id: String,
+        revision: String,
+        max_size_bytes: u64,
+        mut config: QuantizationConfig,
+    ) -> Result<Job> {
+        config.validate_for(&quantization_method).map_err(AppError::Validation)?;
+        self.check_experimental_bits(&config)?;
+        self.quantizer.check_method_available(&quantization_method)?;
+        self.check_job_name_available(user_id, &name).await?;
+        self.check_active_job_limit(user_id).await?;
+
         let subscription = self.db.get_user_subscription(user_id).await?;
-        let priority = subscription.plan.queue_priority();
-        
-        self.queue.enqueue(job.id, priority).await?;
+        self.check_output_format_allowed(&subscription.plan, &output_format)?;
+        config.clamp_calibration_samples(subscription.plan.max_calibration_samples());
+
+        let info = self.hub_client.model_info(&repo_id, &revision).await?;
+
+        let total_size = info.total_size();
+        if total_size > max_size_bytes {
+            return Err(AppError::FileTooLarge);
+        }
+
+        let input_format = info.weights_format();
+        if !self.is_compatible(&input_format, &quantization_method, &output_format) {
+            return Err(AppError::InvalidCombination);
+        }
+        self.check_gguf_export(&subscription.plan, &input_format, &config)?;
+
+        let file_metadata = FileMetadata {
+            id: Uuid::nil(),
+            filename: repo_id.clone(),
+            file_size: total_size as i64,
+            format: input_format.clone(),
+            model_type: None,
+            architecture: None,
+            parameter_count: None,
+            created_at: Utc::now(),
+        };
+        let credits_cost = self.calculate_job_cost(
+            user_id,
+            &quantization_method,
+            &file_metadata,
+        ).await?;
+
+        let job = Job::new(
+            user_id,
+            name,
+            quantization_method,
+            input_format,
+            output_format,
+            None,
+            credits_cost,
+        )
+        .with_id(self.ids.next_id())
+        .with_source_repo(repo_id, revision)
+        .with_quantization_config(config);
+
+        self.submit_job(job, subscription.plan.queue_priority()).await
+    }
+
+    /// Débiter, enregistrer puis mettre en queue un nouveau job
+    ///
+    /// Aucun job n'existe sans débit préalable: un débit refusé (solde
+    /// insuffisant, conflit) est propagé, un enregistrement raté rembourse.
+    async fn submit_job(&self, job: Job, priority: i32) -> Result<Job> {
+        if let Some(billing) = &self.billing {
+            billing.debit_job_credits(&job).await?;
+        }
+
+        let job = match self.db.create_job(&job).await {
+            Ok(created) => created,
+            Err(e) => {
+                self.refund_job_credits(&job, "enregistrement impossible").await;
+                return Err(e);
+            }
+        };
+        self.record_event(JobEvent::new(job.id, JobEventType::Created)).await;
+
+        self.enqueue_job(job.id, priority).await?;
 
         Ok(job)
     }
 
+    /// Refuser un format de sortie réservé à un plan supérieur
+    fn check_output_format_allowed(&self, plan: &SubscriptionPlan, output_format: &ModelFormat) -> Result<()> {
+        if self.output_formats.allows(plan, output_format) {
+            Ok(())
+        } else {
+            Err(AppError::OutputFormatNotInPlan(output_format.as_str().to_string()))
+        }
+    }
+
+    /// Les précisions 2/3 bits ne sont acceptées que si le serveur les active
+    fn check_experimental_bits(&self, config: &QuantizationConfig) -> Result<()> {
+        match config.experimental_bits() {
+            Some(bits) if !self.experimental_quantization => Err(AppError::Validation(format!(
+                "Précision expérimentale de {} bits désactivée sur ce serveur",
+                bits
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// L'export GGUF supplémentaire exige une source PyTorch/safetensors et un plan incluant GGUF
+    fn check_gguf_export(
+        &self,
+        plan: &SubscriptionPlan,
+        input_format: &ModelFormat,
+        config: &QuantizationConfig,
+    ) -> Result<()> {
+        if config.gguf_export.is_none() {
+            return Ok(());
+        }
+        if !matches!(input_format, ModelFormat::PyTorch | ModelFormat::Safetensors) {
+            return Err(AppError::InvalidCombination);
+        }
+        self.check_output_format_allowed(plan, &ModelFormat::Gguf)
+    }
+
+    /// Refuser un nom déjà porté par un job actif, si l'utilisateur l'a demandé
+    ///
+    /// L'erreur propose le premier nom libre (`nom (2)`, `nom (3)`, ...).
+    async fn check_job_name_available(&self, user_id: Uuid, name: &str) -> Result<()> {
+        let preferences = self.db.get_quantization_preferences(user_id).await?;
+        if !preferences.unique_job_names {
+            return Ok(());
+        }
+
+        let taken = self.db.list_active_job_names_with_prefix(user_id, name).await?;
+        if !taken.iter().any(|existing| existing == name) {
+            return Ok(());
+        }
+
+        let suggestion = (2..)
+            .map(|suffix| format!("{} ({})", name, suffix))
+            .find(|candidate| !taken.contains(candidate))
+            .unwrap_or_else(|| name.to_string());
+
+        Err(AppError::DuplicateJobName(suggestion))
+    }
+
+    /// Refuser un nouveau job si l'utilisateur a atteint sa limite de jobs en cours
+    async fn check_active_job_limit(&self, user_id: Uuid) -> Result<()> {
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        let limit = match self.active_job_limits.limit_for(&subscription.plan) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        if self.db.count_user_active_jobs(user_id).await? >= limit {
+            return Err(AppError::TooManyActiveJobs(limit));
+        }
+
+        Ok(())
+    }
+
+    /// Ajouter un job à la queue Redis
+    ///
+    /// Si Redis est indisponible, le job reste en attente en base sans
+    /// `enqueued_at`; `requeue_unqueued_jobs` le rattrape au retour de Redis.
+    async fn enqueue_job(&self, job_id: Uuid, priority: i32) -> Result<()> {
+        if let Err(e) = self.queue.enqueue(job_id, priority).await {
+            log::warn!("Queue indisponible, job {} conservé en base pour reprise: {}", job_id, e);
+            return Ok(());
+        }
+
+        self.db.mark_job_enqueued(job_id).await?;
+        self.record_event(JobEvent::new(job_id, JobEventType::Queued)).await;
+        Ok(())
+    }
+
+    /// Remettre en queue les jobs créés pendant une indisponibilité de Redis
+    pub async fn requeue_unqueued_jobs(&self) -> Result<u64> {
+        // Délai de grâce pour ne pas doubler un enqueue en cours
+        let jobs = self.db.list_unqueued_jobs(30, 100).await?;
+        let mut requeued = 0;
+
+        for job in jobs {
+            let subscription = self.db.get_user_subscription(job.user_id).await?;
+            // Redis toujours indisponible: on réessaiera au prochain passage
+            self.queue.enqueue(job.id, subscription.plan.queue_priority()).await?;
+            self.db.mark_job_enqueued(job.id).await?;
+            self.record_event(JobEvent::new(job.id, JobEventType::Queued).with_message("Remis en queue")).await;
+            requeued += 1;
+        }
+
+        Ok(requeued)
+    }
+
     /// Traiter un job depuis la queue
     pub async fn process_next_job(&self) -> Result<()> {
+        // En pause: les jobs en cours continuent, aucun nouveau n'est retiré de la queue
+        if self.is_worker_paused() {
+            return Ok(());
+        }
+
         // Vérifier le nombre maximum de jobs simultanés
         let active_count = self.active_jobs.read().await.len();
         if active_count >= self.max_concurrent_jobs {
@@ -109,204 +419,2447 @@ impl JobService {
 
         // Marquer comme actif
         self.active_jobs.write().await.push(job_id);
+        let marker_ttl = self.quantizer.timeout_seconds() + PROCESSING_MARKER_GRACE_SECONDS;
+        if let Err(e) = self.queue.mark_processing(job_id, &self.worker_id, marker_ttl).await {
+            log::warn!("Marqueur de traitement du job {} non posé: {}", job_id, e);
+        }
 
         // Traiter le job en arrière-plan
         let self_clone = self.clone();
         tokio::spawn(async move {
             if let Err(e) = self_clone.process_job(job_id).await {
                 eprintln!("Erreur lors du traitement du job {}: {}", job_id, e);
+                let event = JobEvent::new(job_id, JobEventType::Failed)
+                    .with_worker(&self_clone.worker_id)
+                    .with_message(&e.to_string());
+                self_clone.record_event(event).await;
             }
             
             // Retirer du tableau des jobs actifs
             self_clone.active_jobs.write().await.retain(|&id| id != job_id);
+            if let Err(e) = self_clone.queue.clear_processing(job_id).await {
+                log::warn!("Marqueur de traitement du job {} non retiré: {}", job_id, e);
+            }
         });
 
         Ok(())
     }
 
     /// Traiter un job spécifique
-    async fn process_job(&self, job_id: Uuid) -> Result<()> {
+    #[tracing::instrument(name = "process_job", skip(self), fields(job_id = %job_id, user_id = tracing::field::Empty))]
+    pub(crate) async fn process_job(&self, job_id: Uuid) -> Result<()> {
         // Récupérer le job
         let mut job = self.db.get_job(job_id).await?;
+        tracing::Span::current().record("user_id", tracing::field::display(job.user_id));
 
         // Mettre à jour le statut
         job.start();
         self.db.update_job_status(job.id, &job.status, job.progress).await?;
+        self.record_event(JobEvent::new(job.id, JobEventType::PickedUp).with_worker(&self.worker_id)).await;
+        self.record_log(job.id, "info", "Traitement démarré", None).await;
 
-        // Récupérer le fichier source
-        let input_file = self.storage.get_file_metadata(job.input_file_id).await?;
-        
-        // Télécharger le fichier source
-        let input_path = self.storage.download_file(job.input_file_id).await?;
+        // Répertoire de travail unique, nettoyé quelle que soit l'issue du job
+        let workspace = self.quantizer.create_workspace(job.id)?;
+        let mut timings = StageTimings::default();
+
+        // Récupérer le modèle source (upload ou dépôt Hugging Face)
+        self.report_stage(&mut job, ProgressStage::Download, "Récupération du modèle source").await;
+        let started = Instant::now();
+        let (input_path, original_size, input_checksum) = match self.fetch_input(&job, &workspace).await {
+            Ok(input) => input,
+            Err(e) => {
+                self.record_log(job.id, "error", "Échec de la récupération du modèle source", Some(&e.to_string())).await;
+                job.fail(e.to_string());
+                self.db.fail_job(&job).await?;
+                return Err(e);
+            }
+        };
+        StageTimings::add(&mut timings.download, started);
+        self.record_log(
+            job.id,
+            "info",
+            &format!("Modèle source récupéré ({})", crate::utils::helpers::format_file_size(original_size.max(0) as u64)),
+            None,
+        ).await;
+
+        let quantization_config = job.quantization_config
+            .as_ref()
+            .map(|config| config.0.clone())
+            .unwrap_or_default();
+
+        // Refuser les combinaisons méthode / architecture incompatibles
+        // (analyse best effort: sans résultat, on laisse la quantification décider)
+        self.report_stage(&mut job, ProgressStage::Analyze, "Analyse du modèle").await;
+        let mut model_architecture = QuantizationReport::UNKNOWN_ARCHITECTURE.to_string();
+        let started = Instant::now();
+        let analysis = self.analyze_input(&input_path, input_checksum.as_deref()).await;
+        StageTimings::add(&mut timings.analyze, started);
+        match analysis {
+            Ok(analysis) => {
+                let compatibility = crate::core::analysis::check_method_compatibility(&job.quantization_method, &analysis)
+                    .and_then(|_| crate::core::analysis::check_group_size_compatibility(
+                        quantization_config.effective_group_size(&job.quantization_method),
+                        &analysis,
+                    ));
+                // Refus avant quantification: les crédits débités à la création sont rendus
+                if let Err(e) = compatibility {
+                    self.record_log(job.id, "error", &e.to_string(), None).await;
+                    job.fail(e.to_string());
+                    self.db.fail_job(&job).await?;
+                    self.refund_job_credits(&job, &e.to_string()).await;
+                    return Err(e);
+                }
+
+                // Quantification existante non visible dans l'en-tête (ex: PyTorch)
+                let existing_bits = analysis.quantization_bits.filter(|bits| *bits < 16);
+                if let (Some(bits), false) = (existing_bits, quantization_config.allow_quantized_input) {
+                    let e = AppError::AlreadyQuantized(format!("INT{}", bits));
+                    self.record_log(job.id, "error", &e.to_string(), None).await;
+                    job.fail(e.to_string());
+                    self.db.fail_job(&job).await?;
+                    self.refund_job_credits(&job, &e.to_string()).await;
+                    return Err(e);
+                }
+
+                model_architecture = crate::core::analysis::reported_architecture(&analysis);
+            }
+            Err(e) => {
+                log::warn!("Analyse indisponible pour le job {}: {}", job.id, e);
+                self.record_log(job.id, "warn", "Analyse du modèle indisponible", Some(&e.to_string())).await;
+            }
+        }
+
+        // Modèle source tel que récupéré (entrée de l'export GGUF supplémentaire)
+        let source_path = input_path.clone();
+
+        // Exporter en ONNX si la méthode l'exige (ex: PyTorch → INT8)
+        let mut converted_from = None;
+        let input_path = if QuantizationService::needs_onnx_conversion(&job.quantization_method, &job.input_format) {
+            self.record_log(job.id, "info", &format!("Conversion {:?} → ONNX", job.input_format), None).await;
+            self.report_stage(&mut job, ProgressStage::Export, "Conversion en ONNX").await;
+            let started = Instant::now();
+            let converted = self.quantizer.convert_to_onnx(&input_path, &workspace).await;
+            StageTimings::add(&mut timings.export, started);
+            match converted {
+                Ok(onnx_path) => {
+                    converted_from = Some(job.input_format.clone());
+                    onnx_path
+                }
+                Err(e) => {
+                    self.record_log(job.id, "error", &e.to_string(), None).await;
+                    job.fail(e.to_string());
+                    self.db.fail_job(&job).await?;
+                    return Err(e);
+                }
+            }
+        } else {
+            input_path
+        };
 
         // Quantifier le modèle
-        let output_path = match self.quantizer.quantize(
+        self.record_log(job.id, "info", &format!("Quantification {:?} en cours", job.quantization_method), None).await;
+        self.report_stage(&mut job, ProgressStage::Quantize, "Quantification en cours").await;
+        let memory_limit = match self.db.get_user_subscription(job.user_id).await {
+            Ok(subscription) => self.memory_limits.limit_bytes_for(&subscription.plan),
+            Err(_) => self.memory_limits.limit_bytes_for(&SubscriptionPlan::Free),
+        };
+        let started = Instant::now();
+        let quantized = self.quantizer.quantize(
             &input_path,
             &job.quantization_method,
             &job.output_format,
-            job.id,
-        ).await {
-            Ok(path) => path,
+            &quantization_config,
+            &workspace,
+            memory_limit,
+        ).await;
+        StageTimings::add(&mut timings.quantize, started);
+        let quantized = match quantized {
+            Ok(quantized) => quantized,
             Err(e) => {
+                self.record_log(job.id, "error", "Échec de la quantification", Some(&e.to_string())).await;
                 job.fail(e.to_string());
-                self.db.update_job_status(job.id, &job.status, job.progress).await?;
+                self.db.fail_job(&job).await?;
                 return Err(e);
             }
         };
+        let output_path = quantized.output_path.clone();
+
+        // Sortie ONNX demandée pour une méthode qui n'en produit pas (ex: PyTorch → GPTQ → ONNX)
+        let delivered_path = if QuantizationService::needs_onnx_export(&job.quantization_method, &job.output_format) {
+            self.record_log(job.id, "info", "Export ONNX du modèle quantifié", None).await;
+            self.report_stage(&mut job, ProgressStage::Export, "Export en ONNX").await;
+            let started = Instant::now();
+            let exported = self.quantizer.export_quantized_to_onnx(&output_path, &workspace).await;
+            StageTimings::add(&mut timings.export, started);
+            match exported {
+                Ok(onnx_path) => onnx_path,
+                Err(e) => {
+                    self.record_log(job.id, "error", "Échec de l'export ONNX", Some(&e.to_string())).await;
+                    job.fail(e.to_string());
+                    self.db.fail_job(&job).await?;
+                    return Err(e);
+                }
+            }
+        } else {
+            output_path.clone()
+        };
+
+        // Le résultat doit se charger: une sortie corrompue fait échouer le job
+        let started = Instant::now();
+        let verified = self.quantizer.verify_output(&delivered_path, &job.output_format).await;
+        StageTimings::add(&mut timings.validate, started);
+        if let Err(e) = verified {
+            self.record_log(job.id, "error", "Le modèle quantifié ne se charge pas", Some(&e.to_string())).await;
+            job.fail(e.to_string());
+            self.db.fail_job(&job).await?;
+            return Err(e);
+        }
+
+        // Export GGUF supplémentaire: en parallèle de la mesure de qualité, joint avant l'envoi
+        let gguf_export = match quantization_config.gguf_export {
+            Some(export) => {
+                self.record_log(job.id, "info", &format!("Export GGUF {} lancé", export.as_str()), None).await;
+                match self.quantizer.spawn_gguf_export(&source_path, export, &workspace, memory_limit) {
+                    Ok(task) => Some(task),
+                    Err(e) => {
+                        self.record_log(job.id, "error", "Échec du lancement de l'export GGUF", Some(&e.to_string())).await;
+                        job.fail(e.to_string());
+                        self.db.fail_job(&job).await?;
+                        return Err(e);
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let overridden_layers = self.quantizer.overridden_layers(&workspace).await;
+        if !overridden_layers.is_empty() {
+            self.record_log(
+                job.id,
+                "info",
+                &format!("Précision surchargée pour {} couche(s)", overridden_layers.len()),
+                None,
+            ).await;
+        }
+
+        // Mesurer la qualité (best effort: le job reste valide sans métriques)
+        self.report_stage(&mut job, ProgressStage::Validate, "Mesure de la qualité").await;
+        let started = Instant::now();
+        let metrics = self.quantizer.validate_quality(&input_path, &output_path).await
+            .unwrap_or_else(|e| {
+                log::warn!("Validation qualité indisponible pour le job {}: {}", job.id, e);
+                Default::default()
+            });
+        StageTimings::add(&mut timings.validate, started);
+
+        let file_size = std::fs::metadata(&delivered_path)
+            .map(|m| m.len() as i64)
+            .unwrap_or(0);
+        
+        let report = QuantizationReport::new(
+            original_size,
+            file_size,
+            metrics.perplexity_before,
+            metrics.perplexity_after,
+            metrics.latency_before_ms,
+            metrics.latency_after_ms,
+        )
+        .with_conversion(converted_from)
+        .with_overridden_layers(overridden_layers)
+        .with_calibration_samples(quantization_config.effective_calibration_samples(&job.quantization_method))
+        .with_peak_memory(quantized.peak_memory_bytes)
+        .with_architecture(model_architecture)
+        .with_experimental_bits(quantization_config.experimental_bits());
+
+        // Seuil de qualité demandé: pas de résultat publié au-delà, crédits rendus
+        if let Err(reason) = quantization_config.check_quality_loss(report.perplexity_change_percent()) {
+            self.record_log(job.id, "error", &reason, None).await;
+            job.fail(reason.clone());
+            self.db.fail_job(&job).await?;
+            self.refund_job_credits(&job, &reason).await;
+            return Err(AppError::QualityThresholdExceeded(reason));
+        }
+
+        // Attendre l'export GGUF: son échec fait échouer le job
+        let started = Instant::now();
+        let gguf_output = match gguf_export {
+            Some(task) => match task.join().await {
+                Ok(exported) => match self.quantizer.verify_output(&exported.output_path, &ModelFormat::Gguf).await {
+                    Ok(()) => Some(exported),
+                    Err(e) => {
+                        self.record_log(job.id, "error", "L'export GGUF ne se charge pas", Some(&e.to_string())).await;
+                        job.fail(e.to_string());
+                        self.db.fail_job(&job).await?;
+                        return Err(e);
+                    }
+                },
+                Err(e) => {
+                    self.record_log(job.id, "error", "Échec de l'export GGUF", Some(&e.to_string())).await;
+                    job.fail(e.to_string());
+                    self.db.fail_job(&job).await?;
+                    return Err(e);
+                }
+            },
+            None => None,
+        };
+        if gguf_output.is_some() {
+            StageTimings::add(&mut timings.export, started);
+        }
 
         // Uploader le résultat
-        let output_filename = format!("{}_{}.bin", job.name, job.id);
+        self.report_stage(&mut job, ProgressStage::Upload, "Envoi du résultat").await;
+        let started = Instant::now();
+        let output_filename = format!(
+            "{}_{}.bin",
+            crate::utils::helpers::sanitize_filename(&job.name),
+            job.id
+        );
         let output_file_id = self.storage.upload_result(
             job.user_id,
             &output_filename,
-            &output_path,
+            &delivered_path,
             job.output_format.clone(),
         ).await?;
 
+        // L'export GGUF accompagne le résultat (archive des artefacts du job)
+        if let Some(exported) = gguf_output {
+            let export_filename = format!(
+                "{}_{}.gguf",
+                crate::utils::helpers::sanitize_filename(&job.name),
+                job.id
+            );
+            let export_file = self.storage.upload_job_artifact(
+                output_file_id,
+                job.user_id,
+                &export_filename,
+                &exported.output_path,
+                ModelFormat::Gguf,
+            ).await?;
+            self.db.create_file(&export_file).await?;
+        }
+        StageTimings::add(&mut timings.upload, started);
+
         // Mettre à jour le job avec succès
-        let file_size = std::fs::metadata(&output_path)
-            .map(|m| m.len() as i64)
-            .unwrap_or(0);
-        
+        job.original_size = Some(original_size);
+        job.report = Some(sqlx::types::Json(report.with_stage_timings(timings)));
+
         job.complete(output_file_id, file_size);
         self.db.update_job_completion(job.id, &job).await?;
-
-        // Nettoyer les fichiers temporaires
-        let _ = std::fs::remove_file(&input_path);
-        let _ = std::fs::remove_file(&output_path);
+        self.record_event(JobEvent::new(job.id, JobEventType::Completed).with_worker(&self.worker_id)).await;
+        self.record_log(job.id, "info", "Job terminé", None).await;
 
         Ok(())
     }
 
-    /// Obtenir un job par ID
-    pub async fn get_job(&self, job_id: Uuid) -> Result<Job> {
-        self.db.get_job(job_id).await
-    }
+    /// Relancer la mesure de qualité d'un job terminé, sans requantifier
+    ///
+    /// Le modèle source et le résultat sont récupérés du stockage (ou du hub);
+    /// seules les métriques du rapport sont remplacées.
+    pub async fn revalidate_quality(&self, user_id: Uuid, job_id: Uuid) -> Result<QuantizationReport> {
+        let (job, output) = self.get_job_output(user_id, job_id).await?;
+        let mut report = job.report
+            .as_ref()
+            .map(|report| report.0.clone())
+            .ok_or_else(|| AppError::Validation("Rapport non disponible pour ce job".to_string()))?;
 
-    /// Lister les jobs d'un utilisateur
-    pub async fn list_user_jobs(
-        &self,
-        user_id: Uuid,
-        status_filter: Option<&str>,
-        page: i64,
-        per_page: i64,
-    ) -> Result<Vec<Job>> {
-        self.db.list_user_jobs(user_id, status_filter, page, per_page).await
-    }
+        self.record_log(job.id, "info", "Nouvelle mesure de la qualité demandée", None).await;
 
-    /// Annuler un job
-    pub async fn cancel_job(&self, job_id: Uuid) -> Result<()> {
-        let mut job = self.db.get_job(job_id).await?;
-        
-        if !job.can_be_cancelled() {
-            return Err(AppError::JobCannotBeCancelled);
-        }
+        // Deux répertoires: source et résultat peuvent porter le même nom d'origine
+        let source_workspace = self.quantizer.create_workspace(job.id)?;
+        let output_workspace = self.quantizer.create_workspace(job.id)?;
 
-        job.cancel();
-        self.db.update_job_status(job.id, &job.status, job.progress).await?;
+        let (source_path, _, _) = self.fetch_input(&job, &source_workspace).await?;
 
-        // TODO: Si le job est en cours d'exécution, l'annuler
+        // Même référence qu'au traitement: l'export ONNX quand l'entrée a été convertie
+        let source_path = if report.converted_from.is_some() {
+            self.quantizer.convert_to_onnx(&source_path, &source_workspace).await?
+        } else {
+            source_path
+        };
 
-        Ok(())
-    }
+        self.quantizer.check_disk_space(output.file_size.max(0) as u64)?;
+        let output_path = output_workspace.join(&output.original_filename)?;
+        self.storage.download_to(&output.storage_path, &output_path).await?;
 
-    /// Vérifier la compatibilité format/méthode
-    fn is_compatible(
-        &self,
-        input_format: &ModelFormat,
-        quantization_method: &QuantizationMethod,
-        output_format: &ModelFormat,
-    ) -> bool {
-        match quantization_method {
-            QuantizationMethod::Int8 => {
-                matches!(input_format, ModelFormat::Onnx) &&
-                matches!(output_format, ModelFormat::Onnx)
-            }
-            QuantizationMethod::Gptq | QuantizationMethod::Awq => {
-                matches!(input_format, ModelFormat::PyTorch | ModelFormat::Safetensors) &&
-                matches!(output_format, ModelFormat::PyTorch | ModelFormat::Safetensors)
-            }
-            QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0 => {
-                matches!(input_format, ModelFormat::PyTorch | ModelFormat::Safetensors) &&
-                matches!(output_format, ModelFormat::Gguf)
+        let started = Instant::now();
+        let metrics = match self.quantizer.validate_quality(&source_path, &output_path.to_string_lossy()).await {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                self.record_log(job.id, "error", "Échec de la mesure de la qualité", Some(&e.to_string())).await;
+                return Err(e);
             }
+        };
+
+        report.perplexity_before = metrics.perplexity_before;
+        report.perplexity_after = metrics.perplexity_after;
+        report.latency_before_ms = metrics.latency_before_ms;
+        report.latency_after_ms = metrics.latency_after_ms;
+        report.stage_timings.validate = None;
+        StageTimings::add(&mut report.stage_timings.validate, started);
+
+        self.db.update_job_report(job.id, &report).await?;
+        self.record_log(job.id, "info", "Rapport de qualité mis à jour", None).await;
+
+        Ok(report)
+    }
+
+    /// Rendre les crédits d'un job qui n'a pas livré de résultat (best effort)
+    ///
+    /// Sans service de facturation, rien n'a été débité: rien n'est rendu.
+    async fn refund_job_credits(&self, job: &Job, reason: &str) {
+        if job.credits_used <= 0 || self.billing.is_none() {
+            return;
+        }
+
+        let description = format!("Remboursement du job {}: {}", job.name, reason);
+        if let Err(e) = self.db.create_credit_transaction(job.user_id, "refund", job.credits_used, &description).await {
+            log::warn!("Impossible de rembourser le job {}: {}", job.id, e);
         }
     }
 
-    /// Calculer le coût en crédits d'un job
-    async fn calculate_job_cost(
-        &self,
-        user_id: Uuid,
-        method: &QuantizationMethod,
-        file_metadata: &FileMetadata,
-    ) -> Result<i32> {
-        // Obtenir l'abonnement de l'utilisateur
-        let subscription = self.db.get_user_subscription(user_id).await?;
-        
-        let base_cost = match method {
-            QuantizationMethod::Int8 => 1,
-            QuantizationMethod::Gptq => 2,
-            QuantizationMethod::Awq => 2,
-            QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0 => 1,
-        };
+    /// Signaler le début d'une étape: progression en base et événement pub-sub (best effort)
+    async fn report_stage(&self, job: &mut Job, stage: ProgressStage, message: &str) {
+        job.update_progress(stage.percent());
 
-        // Ajuster selon la taille du modèle
-        let size_factor = match file_metadata.parameter_count {
-            Some(params) if params > 70.0 => 3, // Modèles très larges
-            Some(params) if params > 13.0 => 2, // Modèles larges
-            _ => 1, // Modèles standards
-        };
+        if let Err(e) = self.db.update_job_progress(job.id, job.progress).await {
+            log::warn!("Impossible d'enregistrer la progression du job {}: {}", job.id, e);
+        }
 
-        let total_cost = base_cost * size_factor;
+        let timeline_event = JobEvent::new(job.id, JobEventType::StageStarted)
+            .with_stage(stage.as_str())
+            .with_worker(&self.worker_id)
+            .with_message(message);
+        self.record_event(timeline_event).await;
 
-        // Vérifier les crédits disponibles
-        let credits = self.db.get_user_credits(user_id).await?;
-        if credits < total_cost {
-            return Err(AppError::InsufficientCredits);
+        let event = ProgressEvent::new(job.id, stage, message);
+        if let Err(e) = self.queue.publish_progress(&event).await {
+            log::warn!("Impossible de publier la progression du job {}: {}", job.id, e);
         }
+    }
 
-        Ok(total_cost)
+    /// Ajouter un événement à la chronologie du job (best effort)
+    async fn record_event(&self, event: JobEvent) {
+        if let Err(e) = self.db.append_job_event(&event).await {
+            log::warn!("Impossible d'enregistrer l'événement du job {}: {}", event.job_id, e);
+        }
     }
 
-    /// Obtenir les statistiques des jobs
-    pub async fn get_job_stats(&self, user_id: Option<Uuid>) -> Result<JobStats> {
-        self.db.get_job_stats(user_id).await
+    /// Chronologie du cycle de vie d'un job
+    pub async fn get_job_timeline(&self, job_id: Uuid) -> Result<Vec<JobEvent>> {
+        self.db.list_job_events(job_id).await
     }
 
-    /// Démarrer le worker de traitement des jobs
-    pub async fn start_worker(&self, interval_seconds: u64) {
-        let interval = tokio::time::Duration::from_secs(interval_seconds);
-        
-        loop {
-            if let Err(e) = self.process_next_job().await {
-                eprintln!("Erreur dans le worker: {}", e);
-            }
-            
-            tokio::time::sleep(interval).await;
+    /// Ajouter une ligne au journal du job (best effort)
+    async fn record_log(&self, job_id: Uuid, level: &str, message: &str, stderr: Option<&str>) {
+        let entry = JobLog::new(job_id, level, message, stderr);
+        if let Err(e) = self.db.append_job_log(&entry, self.job_log_max_lines).await {
+            log::warn!("Impossible d'écrire le journal du job {}: {}", job_id, e);
         }
     }
-}
 
-impl Clone for JobService {
-    fn clone(&self) -> Self {
-        Self {
-            db: self.db.clone(),
-            queue: self.queue.clone(),
-            storage: self.storage.clone(),
-            quantizer: self.quantizer.clone(),
-            max_concurrent_jobs: self.max_concurrent_jobs,
-            active_jobs: RwLock::new(Vec::new()),
+    /// Obtenir le journal d'un job (sortie d'erreur brute réservée aux admins)
+    pub async fn get_job_logs(&self, job_id: Uuid, include_stderr: bool) -> Result<Vec<JobLog>> {
+        let logs = self.db.list_job_logs(job_id).await?;
+
+        if include_stderr {
+            Ok(logs)
+        } else {
+            Ok(logs.into_iter().map(JobLog::without_stderr).collect())
         }
     }
-}
 
-/// Statistiques des jobs
-pub struct JobStats {
-    pub total: i64,
-    pub pending: i64,
-    pub processing: i64,
-    pub completed: i64,
-    pub failed: i64,
-    pub cancelled: i64,
-    pub average_duration_seconds: f64,
-}
\ No newline at end of file
+    /// Supprimer les journaux de jobs au-delà de la durée de conservation
+    pub async fn purge_job_logs(&self, retention_days: i64) -> Result<u64> {
+        self.db.delete_old_job_logs(retention_days).await
+    }
+
+    /// Récupérer le modèle source d'un job, après vérification de l'espace disque
+    ///
+    /// Retourne aussi le SHA-256 du contenu quand il est connu (fichiers uploadés).
+    async fn fetch_input(&self, job: &Job, workspace: &TempWorkspace) -> Result<(String, i64, Option<String>)> {
+        if let Some(repo_id) = &job.source_repo_id {
+            let revision = job.source_revision.as_deref().unwrap_or("main");
+            let info = self.hub_client.model_info(repo_id, revision).await?;
+            self.quantizer.check_disk_space(info.total_size())?;
+
+            let source_dir = workspace.path().join("source");
+            let downloaded = self.hub_client.download_model(&info, revision, &source_dir).await?;
+
+            return Ok((source_dir.to_string_lossy().to_string(), downloaded as i64, None));
+        }
+
+        // Le job référence le fichier; la clé interne de stockage sert au téléchargement
+        let input_file_id = job.input_file_id.ok_or(AppError::FileNotFound)?;
+        let input_file = self.db.get_file(input_file_id).await?;
+        let external_data = self.db.list_external_data_files(input_file.id).await?;
+        let total_size = input_file.file_size
+            + external_data.iter().map(|file| file.file_size).sum::<i64>();
+        self.quantizer.check_disk_space(total_size.max(0) as u64)?;
+
+        let input_path = workspace.join(&input_file.original_filename)?;
+        self.storage.download_to(&input_file.storage_path, &input_path).await?;
+
+        // Reconstituer le groupe ONNX: les poids externes à côté du .onnx, sous leur nom d'origine
+        for data_file in &external_data {
+            let data_path = workspace.join(&data_file.original_filename)?;
+            self.storage.download_to(&data_file.storage_path, &data_path).await?;
+        }
+
+        Ok((input_path.to_string_lossy().to_string(), total_size, Some(input_file.checksum_sha256)))
+    }
+
+    /// Enregistrer un fichier déposé par upload direct, une fois vérifié
+    pub async fn register_uploaded_file(&self, file: ModelFile) -> Result<FileMetadata> {
+        Ok(self.db.create_file(&file).await?.to_metadata())
+    }
+
+    /// Enregistrer un modèle uploadé et ses données externes ONNX, tout ou rien
+    ///
+    /// Le modèle n'est jamais visible sans ses poids: tout le groupe est
+    /// inséré dans une même transaction.
+    pub async fn register_model_group(&self, model: ModelFile, external_data: Vec<ModelFile>) -> Result<UploadedModel> {
+        if !external_data.is_empty() && !matches!(model.format, ModelFormat::Onnx) {
+            return Err(AppError::Validation(
+                "Les données externes ne concernent que les modèles ONNX".to_string()
+            ));
+        }
+
+        let children: Vec<ModelFile> = external_data
+            .into_iter()
+            .map(|file| file.with_parent(model.id))
+            .collect();
+        let (model, children) = self.db.create_file_group(&model, &children).await?;
+
+        Ok(UploadedModel {
+            file: model.to_metadata(),
+            external_data: children.iter().map(ModelFile::to_metadata).collect(),
+        })
+    }
+
+    /// Analyse détaillée d'un fichier uploadé (statistiques de poids et méthode conseillée)
+    pub async fn analyze_file(
+        &self,
+        user_id: Uuid,
+        file_id: Uuid,
+    ) -> Result<(ModelAnalysis, crate::core::analysis::MethodRecommendation)> {
+        let file = self.db.get_file(file_id).await?;
+        if file.user_id != user_id {
+            return Err(AppError::FileNotFound);
+        }
+
+        let cache_key = format!("{}:weights", file.checksum_sha256);
+        let analysis = match self.cache.get_analysis(&cache_key).await {
+            Ok(Some(analysis)) => analysis,
+            _ => {
+                self.quantizer.check_disk_space(file.file_size.max(0) as u64)?;
+                let workspace = self.quantizer.create_workspace(Uuid::new_v4())?;
+                let input_path = workspace.join(&file.original_filename)?;
+                self.storage.download_to(&file.storage_path, &input_path).await?;
+
+                let analysis = self.quantizer
+                    .analyze_model_detailed(&input_path.to_string_lossy())
+                    .await?;
+                self.cache.set_analysis(&cache_key, &analysis, self.analysis_cache_ttl).await.ok();
+                analysis
+            }
+        };
+
+        let recommendation = crate::core::analysis::recommend_method(&analysis);
+        Ok((analysis, recommendation))
+    }
+
+    /// Demander l'analyse détaillée d'un fichier
+    ///
+    /// Jusqu'à `sync_max_bytes` l'analyse est faite immédiatement; au-delà elle
+    /// part en tâche de fond et son état se suit via `get_file_analysis`.
+    pub async fn request_file_analysis(
+        &self,
+        user_id: Uuid,
+        file_id: Uuid,
+        sync_max_bytes: u64,
+    ) -> Result<AnalysisOutcome> {
+        let file = self.db.get_file(file_id).await?;
+        if file.user_id != user_id {
+            return Err(AppError::FileNotFound);
+        }
+
+        if file.file_size.max(0) as u64 <= sync_max_bytes {
+            self.run_file_analysis(user_id, file_id).await?;
+            return Ok(AnalysisOutcome::Completed(self.db.get_file_analysis(file_id).await?));
+        }
+
+        // Une seule analyse en cours par fichier
+        let current = self.db.get_file_analysis(file_id).await?;
+        if current.is_in_progress() {
+            return Ok(AnalysisOutcome::Accepted(current));
+        }
+
+        self.db.update_file_analysis(file_id, &AnalysisStatus::Pending, None, None).await?;
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.run_file_analysis(user_id, file_id).await {
+                log::warn!("Analyse du fichier {} en échec: {}", file_id, e);
+            }
+        });
+
+        Ok(AnalysisOutcome::Accepted(self.db.get_file_analysis(file_id).await?))
+    }
+
+    /// État (et résultat) de l'analyse d'un fichier de l'utilisateur
+    pub async fn get_file_analysis(&self, user_id: Uuid, file_id: Uuid) -> Result<FileAnalysis> {
+        let file = self.db.get_file(file_id).await?;
+        if file.user_id != user_id {
+            return Err(AppError::FileNotFound);
+        }
+
+        self.db.get_file_analysis(file_id).await
+    }
+
+    /// Exécuter l'analyse et en conserver l'état sur le fichier
+    async fn run_file_analysis(&self, user_id: Uuid, file_id: Uuid) -> Result<()> {
+        self.db.update_file_analysis(file_id, &AnalysisStatus::Running, None, None).await?;
+
+        match self.analyze_file(user_id, file_id).await {
+            Ok((analysis, recommendation)) => {
+                let result = serde_json::json!({
+                    "analysis": analysis,
+                    "recommendation": recommendation,
+                });
+                self.db.update_file_analysis(file_id, &AnalysisStatus::Completed, Some(&result), None).await
+            }
+            Err(e) => {
+                self.db.update_file_analysis(file_id, &AnalysisStatus::Failed, None, Some(&e.to_string())).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Analyser le modèle source, en réutilisant l'analyse d'un contenu identique
+    async fn analyze_input(&self, input_path: &str, checksum: Option<&str>) -> Result<ModelAnalysis> {
+        let checksum = match checksum {
+            Some(checksum) => checksum,
+            None => return self.quantizer.analyze_model(input_path).await,
+        };
+
+        // Le cache est une optimisation: une erreur Redis ne bloque pas le job
+        match self.cache.get_analysis(checksum).await {
+            Ok(Some(analysis)) => return Ok(analysis),
+            Ok(None) => {}
+            Err(e) => log::warn!("Cache d'analyse indisponible: {}", e),
+        }
+
+        let analysis = self.quantizer.analyze_model(input_path).await?;
+        if let Err(e) = self.cache.set_analysis(checksum, &analysis, self.analysis_cache_ttl).await {
+            log::warn!("Impossible de mettre en cache l'analyse {}: {}", checksum, e);
+        }
+
+        Ok(analysis)
+    }
+
+    /// Obtenir un job par ID
+    pub async fn get_job(&self, job_id: Uuid) -> Result<Job> {
+        self.db.get_job(job_id).await
+    }
+
+    /// Fichier produit par un job terminé de l'utilisateur
+    pub async fn get_job_output(&self, user_id: Uuid, job_id: Uuid) -> Result<(Job, ModelFile)> {
+        let job = self.db.get_job(job_id).await?;
+        if job.user_id != user_id {
+            return Err(AppError::Unauthorized);
+        }
+
+        if !job.is_completed() {
+            return Err(AppError::Validation("Le job n'est pas encore terminé".to_string()));
+        }
+
+        let output_file_id = job.output_file_id.ok_or(AppError::FileNotFound)?;
+        let file = self.db.get_file(output_file_id).await?;
+
+        Ok((job, file))
+    }
+
+    /// Fichiers produits par un job terminé: le modèle et ses données externes éventuelles
+    pub async fn get_job_artifacts(&self, user_id: Uuid, job_id: Uuid) -> Result<(Job, Vec<ModelFile>)> {
+        let (job, output) = self.get_job_output(user_id, job_id).await?;
+
+        let mut artifacts = vec![output];
+        artifacts.extend(self.db.list_external_data_files(artifacts[0].id).await?);
+
+        Ok((job, artifacts))
+    }
+
+    /// Créer un lien de partage public pour le résultat d'un job terminé
+    ///
+    /// Retourne le lien et son token en clair; seule l'empreinte est stockée.
+    pub async fn create_share_link(
+        &self,
+        user_id: Uuid,
+        job_id: Uuid,
+        request: &NewJobShareLink,
+        max_expiry_hours: i64,
+    ) -> Result<(JobShareLink, String)> {
+        // Même contrôle que le téléchargement: propriétaire et job terminé
+        self.get_job_output(user_id, job_id).await?;
+
+        let hours = request.expires_in_hours.unwrap_or(DEFAULT_SHARE_LINK_HOURS.min(max_expiry_hours));
+        if hours < 1 || hours > max_expiry_hours {
+            return Err(AppError::Validation(format!(
+                "expires_in_hours doit être compris entre 1 et {}",
+                max_expiry_hours
+            )));
+        }
+        if matches!(request.max_downloads, Some(max) if max < 1) {
+            return Err(AppError::Validation("max_downloads doit être au moins 1".to_string()));
+        }
+
+        let token = crate::utils::security::generate_random_string(48);
+        let link = self.db.create_share_link(
+            job_id,
+            user_id,
+            &crate::utils::security::sha256_hash(token.as_bytes()),
+            Utc::now() + chrono::Duration::hours(hours),
+            request.max_downloads,
+        ).await?;
+
+        Ok((link, token))
+    }
+
+    /// Lister les liens de partage d'un job de l'utilisateur
+    pub async fn list_share_links(&self, user_id: Uuid, job_id: Uuid) -> Result<Vec<JobShareLink>> {
+        let job = self.db.get_job(job_id).await?;
+        if job.user_id != user_id {
+            return Err(AppError::Unauthorized);
+        }
+
+        self.db.list_job_share_links(job_id).await
+    }
+
+    /// Révoquer un lien de partage
+    pub async fn revoke_share_link(&self, user_id: Uuid, job_id: Uuid, link_id: Uuid) -> Result<()> {
+        if !self.db.revoke_share_link(user_id, job_id, link_id).await? {
+            return Err(AppError::NotFound("Lien de partage non trouvé".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Résoudre un token de partage vers le résultat qu'il donne à télécharger
+    ///
+    /// Les octets que servira la réponse (`Range` interprété comme le fera
+    /// celle-ci) sont réservés sur le budget du lien: `max_downloads` fois la
+    /// taille du fichier. Une reprise paie donc ce qu'elle sert, et les octets
+    /// non envoyés sont rendus par `release_share_link_bytes`. Une réponse
+    /// servie depuis le premier octet compte en plus un téléchargement.
+    pub async fn resolve_share_link(&self, token: &str, range: Option<&str>) -> Result<SharedDownload> {
+        let token_hash = crate::utils::security::sha256_hash(token.as_bytes());
+        let link = self.db.get_share_link_by_token(&token_hash).await?
+            .ok_or(AppError::InvalidToken)?;
+
+        if !link.is_active() {
+            return Err(AppError::TokenExpired);
+        }
+
+        let output = self.get_job_output(link.user_id, link.job_id).await?;
+
+        let (job, file) = output;
+        let size = file.file_size.max(0) as u64;
+        let reserved_bytes = match crate::utils::helpers::parse_byte_range(range, size) {
+            crate::utils::helpers::ByteRange::Full => size,
+            crate::utils::helpers::ByteRange::Partial(start, end) => end - start + 1,
+            crate::utils::helpers::ByteRange::Unsatisfiable => 0,
+        };
+        let counts_download = crate::utils::helpers::serves_from_start(range, size);
+        let claimed = self.db
+            .claim_share_link_bytes(link.id, reserved_bytes as i64, size as i64, counts_download)
+            .await?;
+        if !claimed {
+            return Err(AppError::TokenExpired);
+        }
+
+        Ok(SharedDownload { job, file, link_id: link.id, reserved_bytes })
+    }
+
+    /// Rendre au budget d'un lien de partage les octets réservés non envoyés
+    pub async fn release_share_link_bytes(&self, link_id: Uuid, bytes: u64) -> Result<()> {
+        self.db.release_share_link_bytes(link_id, bytes as i64).await
+    }
+
+    /// Lister les jobs d'un utilisateur
+    pub async fn list_user_jobs(
+        &self,
+        user_id: Uuid,
+        status_filter: Option<&str>,
+        pagination: Pagination,
+    ) -> Result<Vec<Job>> {
+        self.db.list_user_jobs(user_id, status_filter, pagination).await
+    }
+
+    /// Obtenir le statut de plusieurs jobs d'un utilisateur, indexé par ID
+    pub async fn get_jobs_status(
+        &self,
+        user_id: Uuid,
+        job_ids: &[Uuid],
+    ) -> Result<std::collections::HashMap<Uuid, JobStatusSummary>> {
+        let rows = self.db.get_user_jobs_status(user_id, job_ids).await?;
+        Ok(rows.into_iter().map(|row| (row.id, row)).collect())
+    }
+
+    /// Comparer deux variantes quantifiées d'un même utilisateur
+    pub async fn compare_jobs(&self, user_id: Uuid, job_a_id: Uuid, job_b_id: Uuid) -> Result<JobComparison> {
+        let job_a = self.db.get_job(job_a_id).await?;
+        let job_b = self.db.get_job(job_b_id).await?;
+
+        if job_a.user_id != user_id || job_b.user_id != user_id {
+            return Err(AppError::Unauthorized);
+        }
+
+        if !job_a.is_completed() || !job_b.is_completed() {
+            return Err(AppError::Validation("Les deux jobs doivent être terminés".to_string()));
+        }
+
+        Ok(JobComparison::new(
+            ComparedVariant::from_job(&job_a),
+            ComparedVariant::from_job(&job_b),
+        ))
+    }
+
+    /// Annuler un job, en notant qui l'annule et pourquoi
+    pub async fn cancel_job(
+        &self,
+        job_id: Uuid,
+        cancelled_by: CancelledBy,
+        reason: Option<String>,
+    ) -> Result<Job> {
+        let mut job = self.db.get_job(job_id).await?;
+        
+        if !job.can_be_cancelled() {
+            return Err(AppError::JobCannotBeCancelled);
+        }
+        // Le système n'annule que des jobs jamais démarrés
+        let previous_status = job.status.clone();
+        if cancelled_by == CancelledBy::System && previous_status != JobStatus::Pending {
+            return Err(AppError::JobCannotBeCancelled);
+        }
+        let system = cancelled_by == CancelledBy::System;
+
+        let by = match cancelled_by {
+            CancelledBy::User => "utilisateur",
+            CancelledBy::Admin => "admin",
+            CancelledBy::System => "système",
+        };
+        let message = match &reason {
+            Some(reason) => format!("Annulé par {}: {}", by, reason),
+            None => format!("Annulé par {}", by),
+        };
+
+        job.cancel(cancelled_by, reason);
+        // Démarré ou terminé depuis la lecture: rien n'est écrasé
+        if !self.db.cancel_job(&job, &previous_status).await? {
+            return Err(AppError::JobCannotBeCancelled);
+        }
+        self.record_event(JobEvent::new(job.id, JobEventType::Cancelled).with_message(&message)).await;
+
+        // Expiration par la plateforme: les crédits débités à la création sont rendus
+        if system {
+            self.refund_job_credits(&job, &message).await;
+        }
+
+        // TODO: Si le job est en cours d'exécution, l'annuler
+
+        Ok(job)
+    }
+
+    /// Annuler les jobs restés en attente au-delà du délai (annulation système)
+    pub async fn cancel_stale_pending_jobs(&self, max_pending_hours: u64) -> Result<u64> {
+        let jobs = self.db.list_stale_pending_jobs((max_pending_hours * 3600) as i64, 100).await?;
+        let reason = format!("En attente depuis plus de {} h", max_pending_hours);
+        let mut cancelled = 0;
+
+        for job in jobs {
+            match self.cancel_job(job.id, CancelledBy::System, Some(reason.clone())).await {
+                Ok(_) => cancelled += 1,
+                // Démarré entre-temps: plus rien à annuler
+                Err(AppError::JobCannotBeCancelled) => {}
+                Err(e) => log::warn!("Annulation du job {} impossible: {}", job.id, e),
+            }
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Vérifier la compatibilité format/méthode
+    fn is_compatible(
+        &self,
+        input_format: &ModelFormat,
+        quantization_method: &QuantizationMethod,
+        output_format: &ModelFormat,
+    ) -> bool {
+        match quantization_method {
+            QuantizationMethod::Int8 => {
+                matches!(input_format, ModelFormat::Onnx) &&
+                matches!(output_format, ModelFormat::Onnx)
+            }
+            QuantizationMethod::Gptq | QuantizationMethod::Awq => {
+                // ONNX: export du modèle quantifié en fin de pipeline
+                matches!(input_format, ModelFormat::PyTorch | ModelFormat::Safetensors) &&
+                matches!(output_format, ModelFormat::PyTorch | ModelFormat::Safetensors | ModelFormat::Onnx)
+            }
+            QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0 => {
+                matches!(input_format, ModelFormat::PyTorch | ModelFormat::Safetensors) &&
+                matches!(output_format, ModelFormat::Gguf)
+            }
+        }
+    }
+
+    /// Calculer le coût en crédits d'un job
+    async fn calculate_job_cost(
+        &self,
+        user_id: Uuid,
+        method: &QuantizationMethod,
+        file_metadata: &FileMetadata,
+    ) -> Result<i32> {
+        let cost = self.estimate_cost(
+            user_id,
+            method,
+            file_metadata.file_size,
+            file_metadata.parameter_count,
+        ).await?;
+
+        // Solde vérifié au débit (`submit_job`), sous verrou optimiste
+        Ok(cost.credits)
+    }
+
+    /// Réglages de quantification par défaut d'un utilisateur
+    pub async fn get_quantization_preferences(&self, user_id: Uuid) -> Result<QuantizationPreferences> {
+        self.db.get_quantization_preferences(user_id).await
+    }
+
+    /// Remplacer les réglages de quantification par défaut d'un utilisateur
+    pub async fn update_quantization_preferences(
+        &self,
+        user_id: Uuid,
+        update: UpdateQuantizationPreferences,
+    ) -> Result<QuantizationPreferences> {
+        let preferences = QuantizationPreferences::from_update(user_id, update)
+            .map_err(AppError::Validation)?;
+        self.db.upsert_quantization_preferences(&preferences).await?;
+        Ok(preferences)
+    }
+
+    /// Compléter une demande de job: champs fournis, puis préréglage, puis
+    /// réglages de l'utilisateur, puis système
+    ///
+    /// Un réglage par défaut incompatible avec la méthode retenue est ignoré
+    /// (ex: format GGUF par défaut pour un job INT8 explicite).
+    pub async fn resolve_job_settings(&self, user_id: Uuid, new_job: &NewJob) -> Result<JobSettings> {
+        let preferences = self.db.get_quantization_preferences(user_id).await?;
+
+        let quantization_method = new_job.method()
+            .or_else(|| new_job.preset.map(|preset| preset.method()))
+            .or(preferences.default_method)
+            .unwrap_or_default();
+
+        let output_format = new_job.output_format.clone()
+            .or_else(|| preferences.default_output_format
+                .filter(|format| quantization_method.supports_output(format)))
+            .unwrap_or_else(|| quantization_method.default_output_format());
+
+        let mut config = new_job.config.clone();
+        if let Some(preset) = new_job.preset {
+            // Les options du préréglage ne valent que pour sa méthode
+            if std::mem::discriminant(&preset.method()) == std::mem::discriminant(&quantization_method) {
+                config = config.with_defaults_from(preset.config());
+            }
+        }
+        if quantization_method.is_tunable() {
+            if config.bits.is_none() {
+                config.bits = preferences.default_bits.map(|bits| bits as u8);
+            }
+            if config.group_size.is_none() {
+                config.group_size = preferences.default_group_size.map(|size| size as u32);
+            }
+        }
+
+        Ok(JobSettings { quantization_method, output_format, config })
+    }
+
+    /// Estimer le coût d'un job avant sa création (même calcul qu'à la création)
+    pub async fn estimate_cost(
+        &self,
+        user_id: Uuid,
+        method: &QuantizationMethod,
+        size_bytes: i64,
+        parameter_count: Option<f64>,
+    ) -> Result<JobCost> {
+        let subscription = self.db.get_user_subscription(user_id).await?;
+        Ok(subscription.plan.job_cost(&self.credit_costs, method, size_bytes, parameter_count))
+    }
+
+    /// Lister les modèles stockés d'un utilisateur
+    pub async fn list_models(
+        &self,
+        user_id: Uuid,
+        format_filter: Option<&str>,
+        pagination: Pagination,
+    ) -> Result<(Vec<FileMetadata>, i64)> {
+        let (files, total) = self.db.list_user_models(user_id, format_filter, pagination).await?;
+        Ok((files.iter().map(ModelFile::to_metadata).collect(), total))
+    }
+
+    /// Supprimer un modèle stocké
+    ///
+    /// Refusé si un job actif l'utilise. L'objet de stockage n'est supprimé
+    /// que si aucun autre fichier ne le référence.
+    pub async fn delete_model(&self, user_id: Uuid, file_id: Uuid) -> Result<()> {
+        let file = self.db.get_file(file_id).await?;
+
+        if file.user_id != user_id {
+            return Err(AppError::Unauthorized);
+        }
+
+        if self.db.count_active_jobs_for_file(file_id).await? > 0 {
+            return Err(AppError::FileInUse);
+        }
+
+        self.db.delete_file(file_id).await?;
+
+        if self.db.count_storage_references(&file.storage_path, file_id).await? == 0 {
+            self.storage.delete_file(&file).await?;
+        }
+        self.db.mark_file_purged(file_id).await?;
+
+        Ok(())
+    }
+
+    /// Récupérer un fichier appartenant à l'utilisateur (non expiré)
+    pub async fn get_user_file(&self, user_id: Uuid, file_id: Uuid) -> Result<ModelFile> {
+        let file = self.db.get_file(file_id).await?;
+
+        if file.user_id != user_id {
+            return Err(AppError::Unauthorized);
+        }
+
+        if file.expires_at.map_or(false, |expires_at| expires_at <= Utc::now()) {
+            return Err(AppError::FileExpired);
+        }
+
+        Ok(file)
+    }
+
+    /// Récupérer le modèle original d'un fichier uploadé (déchiffré)
+    ///
+    /// Réservé au propriétaire, sauf pour un admin. Un fichier dont la
+    /// rétention est échue est considéré comme purgé.
+    pub async fn download_original_model(
+        &self,
+        user_id: Uuid,
+        file_id: Uuid,
+        is_admin: bool,
+    ) -> Result<(ModelFile, Vec<u8>)> {
+        let file = self.db.get_file(file_id).await?;
+
+        if file.user_id != user_id && !is_admin {
+            return Err(AppError::Unauthorized);
+        }
+
+        if file.expires_at.map_or(false, |expires_at| expires_at <= Utc::now()) {
+            return Err(AppError::FileExpired);
+        }
+
+        let data = self.storage.download_file(&file).await?;
+        Ok((file, data))
+    }
+
+    /// Purger le contenu des fichiers dont la rétention est échue
+    pub async fn purge_expired_files(&self) -> Result<u64> {
+        let mut purged = 0;
+
+        for file in self.db.list_expired_files(500).await? {
+            if let Err(e) = self.storage.delete_file(&file).await {
+                log::warn!("Impossible de supprimer le fichier {}: {}", file.id, e);
+                continue;
+            }
+            self.db.mark_file_purged(file.id).await?;
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+
+    /// Supprimer les objets du stockage qu'aucun fichier ne référence
+    ///
+    /// Conservateur: seuls les objets nommés par le service et plus anciens que
+    /// `grace_hours` sont candidats (un upload direct en cours n'a pas encore
+    /// de ligne en base). En `dry_run`, les suppressions sont seulement journalisées.
+    pub async fn cleanup_orphaned_objects(&self, grace_hours: i64, dry_run: bool) -> Result<u64> {
+        let older_than = Utc::now() - chrono::Duration::hours(grace_hours.max(1));
+        let candidates: Vec<_> = self.storage.list_managed_objects().await?
+            .into_iter()
+            .filter(|object| object.last_modified < older_than)
+            .collect();
+
+        let mut removed = 0;
+        for chunk in candidates.chunks(ORPHAN_LOOKUP_BATCH) {
+            let keys: Vec<String> = chunk.iter().map(|object| object.key.clone()).collect();
+            let referenced = self.db.referenced_storage_paths(&keys).await?;
+
+            for object in crate::services::storage::select_orphaned_objects(chunk.to_vec(), &referenced, older_than) {
+                if dry_run {
+                    log::info!(
+                        "Objet orphelin {} ({}, modifié le {}) serait supprimé",
+                        object.key,
+                        crate::utils::helpers::format_file_size(object.size.max(0) as u64),
+                        object.last_modified
+                    );
+                    removed += 1;
+                    continue;
+                }
+
+                match self.storage.delete_by_key(&object.key).await {
+                    Ok(()) => {
+                        log::info!("Objet orphelin {} supprimé", object.key);
+                        removed += 1;
+                    }
+                    Err(e) => log::warn!("Impossible de supprimer l'objet orphelin {}: {}", object.key, e),
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Prévenir les propriétaires des fichiers expirant dans `warning_days` jours (une seule fois par fichier)
+    pub async fn notify_expiring_files(
+        &self,
+        notifications: &NotificationService,
+        warning_days: i64,
+    ) -> Result<u64> {
+        let mut notified = 0;
+
+        for file in self.db.list_files_expiring_within(warning_days, 500).await? {
+            match notifications.send_file_expiring(&file).await {
+                Ok(sent) => {
+                    // Marqué même si désactivé, pour ne pas réévaluer le fichier à chaque passage
+                    self.db.mark_expiry_notified(file.id).await?;
+                    if sent {
+                        notified += 1;
+                    }
+                }
+                Err(e) => log::warn!("Avertissement d'expiration non envoyé pour {}: {}", file.id, e),
+            }
+        }
+
+        Ok(notified)
+    }
+
+    /// Méthodes de quantification disponibles sur ce serveur
+    pub fn get_capabilities(&self) -> Option<Capabilities> {
+        self.quantizer.capabilities()
+    }
+
+    /// Méthodes supportées, avec coût et disponibilité
+    pub fn list_quantization_methods(&self) -> Vec<QuantizationMethodInfo> {
+        let capabilities = self.quantizer.capabilities();
+
+        QuantizationMethod::ALL
+            .iter()
+            .map(|method| QuantizationMethodInfo::new(method, &self.credit_costs, capabilities.as_ref()))
+            .collect()
+    }
+
+    /// Lister les préréglages de quantification
+    pub fn list_quantization_presets(&self) -> Vec<QuantizationPresetInfo> {
+        QuantizationPreset::ALL
+            .iter()
+            .map(|preset| QuantizationPresetInfo::from(*preset))
+            .collect()
+    }
+
+    /// Obtenir les statistiques des jobs
+    pub async fn get_job_stats(&self, user_id: Option<Uuid>) -> Result<JobStats> {
+        self.db.get_job_stats(user_id).await
+    }
+
+    /// Calculer le signal d'autoscaling à partir de la queue et de l'historique des jobs
+    ///
+    /// Les jobs en statut `processing` tiennent lieu de workers actifs.
+    pub async fn get_scaling_signal(
+        &self,
+        target_drain_seconds: u64,
+        min_workers: u32,
+        max_workers: u32,
+    ) -> Result<ScalingSignal> {
+        let queue_depth = self.queue.queue_size(None).await?;
+        let stats = self.db.get_job_stats(None).await?;
+
+        Ok(ScalingSignal::new(
+            queue_depth,
+            stats.average_duration_seconds,
+            stats.processing,
+            target_drain_seconds,
+            min_workers,
+            max_workers,
+        ))
+    }
+
+    /// Suspendre la prise de nouveaux jobs (retourne false si déjà en pause)
+    pub fn pause_worker(&self) -> bool {
+        !self.paused.swap(true, Ordering::SeqCst)
+    }
+
+    /// Reprendre la prise de jobs (retourne false si les workers tournaient déjà)
+    pub fn resume_worker(&self) -> bool {
+        self.paused.swap(false, Ordering::SeqCst)
+    }
+
+    /// Les workers sont-ils en pause ?
+    pub fn is_worker_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// État des workers pour l'administration
+    pub async fn get_worker_status(&self) -> Result<WorkerStatus> {
+        let queue_depth = self.queue.queue_size(None).await?;
+        let stats = self.db.get_job_stats(None).await?;
+
+        Ok(WorkerStatus {
+            timestamp: Utc::now(),
+            paused: self.is_worker_paused(),
+            queue_depth,
+            processing_jobs: stats.processing,
+        })
+    }
+
+    /// Prochains jobs de chaque queue et jobs en traitement, sans rien retirer (admin)
+    pub async fn inspect_queue(&self, limit: usize) -> Result<crate::services::queue::QueueSnapshot> {
+        self.queue.inspect(limit).await
+    }
+
+    /// Position d'un job dans la queue et délais estimés selon le débit actuel
+    pub async fn get_queue_estimate(&self, job: &Job) -> Result<JobQueueEstimate> {
+        let position = if job.status == JobStatus::Pending {
+            Some(self.db.get_job_queue_position(job).await?)
+        } else {
+            None
+        };
+        let stats = self.db.get_job_stats(None).await?;
+
+        Ok(JobQueueEstimate::new(
+            job,
+            position,
+            stats.average_duration_seconds,
+            self.max_concurrent_jobs,
+        ))
+    }
+
+    /// Démarrer le worker de traitement des jobs
+    pub async fn start_worker(&self, interval_seconds: u64) {
+        let interval = tokio::time::Duration::from_secs(interval_seconds);
+        
+        loop {
+            if let Err(e) = self.process_next_job().await {
+                eprintln!("Erreur dans le worker: {}", e);
+            }
+            
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// Téléchargement autorisé par un lien de partage
+pub struct SharedDownload {
+    pub job: Job,
+    pub file: ModelFile,
+    pub link_id: Uuid,
+    /// Octets réservés sur le budget du lien pour cette réponse
+    pub reserved_bytes: u64,
+}
+
+/// Issue d'une demande d'analyse de fichier
+pub enum AnalysisOutcome {
+    /// Analyse faite immédiatement (petit modèle)
+    Completed(FileAnalysis),
+    /// Analyse lancée en tâche de fond
+    Accepted(FileAnalysis),
+}
+
+impl Clone for JobService {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            queue: self.queue.clone(),
+            storage: self.storage.clone(),
+            quantizer: self.quantizer.clone(),
+            hub_client: self.hub_client.clone(),
+            cache: self.cache.clone(),
+            max_concurrent_jobs: self.max_concurrent_jobs,
+            job_log_max_lines: self.job_log_max_lines,
+            analysis_cache_ttl: self.analysis_cache_ttl,
+            active_job_limits: self.active_job_limits.clone(),
+            credit_costs: self.credit_costs.clone(),
+            output_formats: self.output_formats.clone(),
+            memory_limits: self.memory_limits.clone(),
+            active_jobs: RwLock::new(Vec::new()),
+            paused: self.paused.clone(),
+            experimental_quantization: self.experimental_quantization,
+            ids: self.ids.clone(),
+            worker_id: self.worker_id.clone(),
+            billing: self.billing.clone(),
+        }
+    }
+}
+
+/// Statistiques des jobs
+pub struct JobStats {
+    pub total: i64,
+    pub pending: i64,
+    pub processing: i64,
+    pub completed: i64,
+    pub failed: i64,
+    pub cancelled: i64,
+    pub average_duration_seconds: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn compare_jobs_reports_both_variants_and_recommends_the_best_tradeoff() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // INT8: -50 % pour +1 % de perplexité ; GPTQ: -75 % pour +8 %
+        let int8 = testing::create_completed_job(
+            &db, user.id, "llama-int8", QuantizationMethod::Int8,
+            QuantizationReport::new(1000, 500, Some(10.0), Some(10.1), Some(100.0), Some(60.0)),
+        ).await;
+        let gptq = testing::create_completed_job(
+            &db, user.id, "llama-gptq", QuantizationMethod::Gptq,
+            QuantizationReport::new(1000, 250, Some(10.0), Some(10.8), Some(100.0), Some(40.0)),
+        ).await;
+
+        let comparison = service.compare_jobs(user.id, int8.id, gptq.id).await.unwrap();
+
+        assert_eq!(comparison.job_a.job_id, int8.id);
+        assert_eq!(comparison.job_a.quantized_size, 500);
+        assert!((comparison.job_a.size_reduction_percent - 50.0).abs() < 1e-9);
+        assert!((comparison.job_a.perplexity_change_percent.unwrap() - 1.0).abs() < 1e-9);
+        assert!((comparison.job_a.latency_improvement_percent.unwrap() - 40.0).abs() < 1e-9);
+
+        assert_eq!(comparison.job_b.job_id, gptq.id);
+        assert_eq!(comparison.job_b.quantized_size, 250);
+        assert!((comparison.job_b.size_reduction_percent - 75.0).abs() < 1e-9);
+        assert!((comparison.job_b.perplexity_change_percent.unwrap() - 8.0).abs() < 1e-9);
+        assert!((comparison.job_b.latency_improvement_percent.unwrap() - 60.0).abs() < 1e-9);
+
+        // Le gain de taille de GPTQ ne compense pas sa perte de qualité
+        assert_eq!(comparison.recommended, int8.id);
+        assert!(comparison.reason.contains("llama-int8"));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn compare_jobs_rejects_foreign_and_unfinished_jobs() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let owner = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let other = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let report = || QuantizationReport::new(1000, 500, None, None, None, None);
+        let mine = testing::create_completed_job(&db, owner.id, "a", QuantizationMethod::Int8, report()).await;
+        let theirs = testing::create_completed_job(&db, other.id, "b", QuantizationMethod::Int8, report()).await;
+
+        let pending = Job::new(
+            owner.id, "c".to_string(), QuantizationMethod::Int8,
+            ModelFormat::Safetensors, ModelFormat::Onnx, mine.input_file_id, 1,
+        );
+        let pending = db.create_job(&pending).await.unwrap();
+
+        assert!(matches!(
+            service.compare_jobs(owner.id, mine.id, theirs.id).await,
+            Err(AppError::Unauthorized)
+        ));
+        assert!(matches!(
+            service.compare_jobs(owner.id, mine.id, pending.id).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn list_models_filters_by_format() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let other = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let onnx = testing::create_file(&db, user.id, ModelFormat::Onnx, 1024).await;
+        testing::create_file(&db, user.id, ModelFormat::Safetensors, 1024).await;
+        testing::create_file(&db, other.id, ModelFormat::Onnx, 1024).await;
+
+        let pagination = Pagination::from_params(None, None, 100).unwrap();
+        let (models, total) = service.list_models(user.id, Some("ONNX"), pagination).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, onnx.id);
+
+        let (_, total) = service.list_models(user.id, None, pagination).await.unwrap();
+        assert_eq!(total, 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn delete_model_is_refused_while_a_job_uses_it() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let file = testing::create_file(&db, user.id, ModelFormat::Safetensors, 1024).await;
+
+        let job = Job::new(
+            user.id,
+            "llama".to_string(),
+            QuantizationMethod::Int8,
+            ModelFormat::Safetensors,
+            ModelFormat::Safetensors,
+            Some(file.id),
+            1,
+        );
+        db.create_job(&job).await.unwrap();
+
+        assert!(matches!(
+            service.delete_model(user.id, file.id).await,
+            Err(AppError::FileInUse)
+        ));
+        // Le fichier reste listé
+        assert!(db.get_file(file.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn expiring_file_is_announced_exactly_once() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let emails = Arc::new(testing::RecordingEmailProvider::default());
+        let notifications = testing::notification_service(db.clone(), emails.clone());
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+
+        let soon = format!("soon-{}.bin", Uuid::new_v4());
+        let later = format!("later-{}.bin", Uuid::new_v4());
+        for (name, days) in [(&soon, 2), (&later, 20)] {
+            let file = ModelFile::new(
+                user.id,
+                name.clone(),
+                1024,
+                "0".repeat(64),
+                ModelFormat::Safetensors,
+                "test".to_string(),
+                format!("models/{}/{}", Uuid::new_v4(), name),
+            )
+            .with_expiry(Utc::now() + chrono::Duration::days(days));
+            db.create_file(&file).await.unwrap();
+        }
+
+        // Deux passages du worker: le fichier n'est signalé qu'une fois
+        service.notify_expiring_files(&notifications, 3).await.unwrap();
+        service.notify_expiring_files(&notifications, 3).await.unwrap();
+
+        let subjects = emails.subjects();
+        assert_eq!(subjects.iter().filter(|subject| subject.contains(&soon)).count(), 1);
+        assert_eq!(subjects.iter().filter(|subject| subject.contains(&later)).count(), 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn creation_charges_the_estimated_cost() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        testing::grant_credits(&db, user.id, 10).await;
+
+        let file = testing::create_file(&db, user.id, ModelFormat::Safetensors, 14_000_000_000).await;
+        let metadata = file.to_metadata();
+
+        let estimate = service
+            .estimate_cost(user.id, &QuantizationMethod::Gptq, metadata.file_size, metadata.parameter_count)
+            .await
+            .unwrap();
+        let charged = service.calculate_job_cost(user.id, &QuantizationMethod::Gptq, &metadata).await.unwrap();
+
+        assert_eq!(estimate.credits, 2);
+        assert_eq!(charged, estimate.credits);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn identical_content_reuses_the_cached_analysis() {
+        // Analyseur factice: compte ses appels dans un fichier voisin
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(&[(
+            "analyze_model.py",
+            "import json, os\n\
+             open(os.path.join(os.path.dirname(os.path.abspath(__file__)), 'calls'), 'a').write('x')\n\
+             print(json.dumps({'model_type': 'llama', 'architecture': 'LlamaForCausalLM', 'parameter_count': 7.0,\n\
+             'quantization_bits': None, 'layers': 32, 'vocab_size': 32000, 'context_length': 4096,\n\
+             'file_size_bytes': 1024, 'supported_quantizations': ['int8', 'gptq']}))\n",
+        )]);
+        let calls = std::path::Path::new(&config.quantization_python_path).join("calls");
+        let db = testing::database().await;
+        let service = testing::job_service(db, &config).await;
+        let checksum = format!("{:064x}", Uuid::new_v4().as_u128());
+
+        let first = service.analyze_input("model.bin", Some(&checksum)).await.unwrap();
+        let second = service.analyze_input("autre-copie.bin", Some(&checksum)).await.unwrap();
+
+        assert_eq!(first.architecture, second.architecture);
+        assert_eq!(std::fs::read_to_string(&calls).unwrap(), "x");
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn failed_enqueue_leaves_a_recoverable_job() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let queue = Arc::new(JobQueue::unreachable());
+        let service = testing::job_service_with_queue(db.clone(), &config, queue).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+
+        // Redis injoignable: la création n'échoue pas
+        service.enqueue_job(job.id, 0).await.unwrap();
+
+        let stored = db.get_job(job.id).await.unwrap();
+        assert_eq!(stored.status, JobStatus::Pending);
+        let unqueued = db.list_unqueued_jobs(0, 1000).await.unwrap();
+        assert!(unqueued.iter().any(|candidate| candidate.id == job.id));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn free_user_at_the_cap_is_rejected_until_a_job_completes() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let limit = config.active_job_policy().limit_for(&SubscriptionPlan::Free).expect("limite Free");
+
+        let mut jobs = Vec::new();
+        for i in 0..limit {
+            jobs.push(testing::create_job(&db, user.id, &format!("job-{}", i), QuantizationMethod::Int8).await);
+        }
+
+        match service.check_active_job_limit(user.id).await {
+            Err(AppError::TooManyActiveJobs(cap)) => assert_eq!(cap, limit),
+            other => panic!("limite non appliquée: {:?}", other),
+        }
+
+        db.update_job_status(jobs[0].id, &JobStatus::Completed, 100).await.unwrap();
+        service.check_active_job_limit(user.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn configured_gptq_cost_applies_to_the_check_and_the_consumption() {
+        let db = testing::database().await;
+        let mut config = testing::config();
+        config.credit_cost_gptq = 5;
+        let service = testing::job_service(db.clone(), &config).await;
+        let billing = testing::billing_service(db.clone(), &config);
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        testing::grant_credits(&db, user.id, 10).await;
+
+        let file = testing::create_file(&db, user.id, ModelFormat::Safetensors, 1024).await;
+        let charged = service.calculate_job_cost(user.id, &QuantizationMethod::Gptq, &file.to_metadata()).await.unwrap();
+        assert_eq!(charged, 5);
+
+        let job = Job::new(
+            user.id,
+            "llama".to_string(),
+            QuantizationMethod::Gptq,
+            ModelFormat::Safetensors,
+            QuantizationMethod::Gptq.default_output_format(),
+            Some(file.id),
+            charged,
+        );
+        let job = db.create_job(&job).await.unwrap();
+
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+        billing.consume_job_credits(user.id, job.id).await.unwrap();
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before - 5);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn middle_of_three_queued_jobs_is_second_in_line() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let mut positions = Vec::new();
+        let mut jobs = Vec::new();
+        for name in ["premier", "deuxieme", "troisieme"] {
+            jobs.push(testing::create_job(&db, user.id, name, QuantizationMethod::Int8).await);
+        }
+        for job in &jobs {
+            let estimate = service.get_queue_estimate(job).await.unwrap();
+            positions.push(estimate.queue_position.expect("job en attente"));
+        }
+
+        // La base de test est partagée: des jobs plus anciens peuvent précéder les trois
+        let ahead = positions[0] - 1;
+        assert_eq!(positions, vec![ahead + 1, ahead + 2, ahead + 3]);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn zero_byte_input_is_rejected_without_charging() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        testing::grant_credits(&db, user.id, 10).await;
+        let empty = testing::create_stored_file(&db, &service.storage, user.id, 0).await;
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+
+        let err = service
+            .create_job(
+                user.id,
+                empty.id,
+                "vide".to_string(),
+                QuantizationMethod::Int8,
+                ModelFormat::Onnx,
+                QuantizationConfig::default(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::CorruptInput(_)), "{:?}", err);
+        assert_eq!(actix_web::ResponseError::error_response(&err).status(), actix_web::http::StatusCode::BAD_REQUEST);
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before);
+        let jobs = db.list_user_jobs(user.id, None, Pagination::from_params(None, None, 100).unwrap()).await.unwrap();
+        assert!(jobs.is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn job_is_only_created_once_its_credits_are_debited() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let input = testing::store_model(&db, &service.storage, user.id, &int4_safetensors()).await;
+        let forced = || QuantizationConfig { allow_quantized_input: true, ..Default::default() };
+
+        let err = service
+            .create_job(user.id, input.id, "llama".to_string(), QuantizationMethod::Gptq, ModelFormat::Safetensors, forced())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InsufficientCredits), "{:?}", err);
+        let jobs = db.list_user_jobs(user.id, None, Pagination::from_params(None, None, 100).unwrap()).await.unwrap();
+        assert!(jobs.is_empty());
+
+        testing::grant_credits(&db, user.id, 10).await;
+        let job = service
+            .create_job(user.id, input.id, "llama".to_string(), QuantizationMethod::Gptq, ModelFormat::Safetensors, forced())
+            .await
+            .unwrap();
+        assert!(job.credits_used > 0);
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), 10 - job.credits_used);
+    }
+
+    /// Configuration dont les scripts Python sont ceux d'un pipeline factice
+    fn pipeline_config(extra_scripts: &[(&str, &str)]) -> crate::utils::config::Config {
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(&[testing::PIPELINE_SCRIPTS, extra_scripts].concat());
+        config.quantization_gpu_enabled = true;
+        config
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn processing_publishes_progress_in_stage_order() {
+        let db = testing::database().await;
+        let config = pipeline_config(&[]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = testing::create_stored_job(&db, &service.storage, user.id, "llama", QuantizationMethod::Gptq).await;
+
+        let mut events = service.queue.subscribe_progress(job.id).await.unwrap();
+        service.process_job(job.id).await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv()).await {
+            let done = event.stage == ProgressStage::Upload;
+            received.push(event);
+            if done {
+                break;
+            }
+        }
+
+        let stages: Vec<_> = received.iter().map(|event| event.stage).collect();
+        assert_eq!(
+            stages,
+            vec![ProgressStage::Download, ProgressStage::Analyze, ProgressStage::Quantize, ProgressStage::Validate, ProgressStage::Upload]
+        );
+        assert!(received.windows(2).all(|pair| pair[0].percent < pair[1].percent));
+        assert!(received.iter().all(|event| event.job_id == job.id));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn paused_worker_leaves_queued_jobs_until_resumed() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+        service.enqueue_job(job.id, SubscriptionPlan::Pro.queue_priority()).await.unwrap();
+
+        assert!(service.pause_worker());
+        assert!(!service.pause_worker());
+        service.process_next_job().await.unwrap();
+        assert_eq!(service.queue.queue_size(None).await.unwrap(), 1);
+        assert!(service.active_jobs.read().await.is_empty());
+
+        assert!(service.resume_worker());
+        service.process_next_job().await.unwrap();
+        assert_eq!(service.queue.queue_size(None).await.unwrap(), 0);
+        assert!(service.active_jobs.read().await.contains(&job.id));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn onnx_external_data_is_reassembled_next_to_the_model() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let source = testing::scratch_dir("upload").join("model.onnx");
+        std::fs::write(&source, b"graphe onnx").unwrap();
+        let model = service.storage
+            .upload_job_artifact(Uuid::new_v4(), user.id, "model.onnx", &source.to_string_lossy(), ModelFormat::Onnx)
+            .await
+            .unwrap();
+
+        let weights = b"poids externes".to_vec();
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(1);
+        let data = service.storage
+            .upload_external_data(&model, user.id, "model.onnx.data", &weights, expires_at)
+            .await
+            .unwrap();
+        let uploaded = service.register_model_group(model.clone(), vec![data]).await.unwrap();
+        assert_eq!(uploaded.external_data.len(), 1);
+
+        let job = Job::new(user.id, "onnx".to_string(), QuantizationMethod::Int8, ModelFormat::Onnx, ModelFormat::Onnx, Some(model.id), 1);
+        let job = db.create_job(&job).await.unwrap();
+        let workspace = service.quantizer.create_workspace(job.id).unwrap();
+
+        let (input_path, total_size, _) = service.fetch_input(&job, &workspace).await.unwrap();
+
+        let input_path = std::path::Path::new(&input_path);
+        assert_eq!(input_path.file_name().unwrap(), "model.onnx");
+        assert_eq!(std::fs::read(input_path).unwrap(), b"graphe onnx");
+        assert_eq!(std::fs::read(input_path.with_file_name("model.onnx.data")).unwrap(), weights);
+        assert_eq!(total_size, (b"graphe onnx".len() + weights.len()) as i64);
+    }
+
+    /// Job GPTQ en attente sur un fichier stocké, avec ses options de quantification
+    async fn stored_job_with_config(service: &JobService, user_id: Uuid, config: QuantizationConfig) -> Job {
+        let input = testing::create_stored_file(&service.db, &service.storage, user_id, 1024).await;
+        let job = Job::new(
+            user_id,
+            format!("llama-{}", Uuid::new_v4().simple()),
+            QuantizationMethod::Gptq,
+            ModelFormat::Safetensors,
+            ModelFormat::Safetensors,
+            Some(input.id),
+            1,
+        )
+        .with_quantization_config(config);
+        service.db.create_job(&job).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn quality_loss_above_the_threshold_fails_and_refunds() {
+        // Perplexité 10.0 -> 12.0: +20 %
+        let db = testing::database().await;
+        let config = pipeline_config(&[(
+            "validate_quality.py",
+            "import json\nprint(json.dumps({'perplexity_before': 10.0, 'perplexity_after': 12.0}))\n",
+        )]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+
+        let strict = stored_job_with_config(
+            &service,
+            user.id,
+            QuantizationConfig { max_quality_loss_percent: Some(5.0), ..Default::default() },
+        ).await;
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+        let err = service.process_job(strict.id).await.unwrap_err();
+        assert!(matches!(err, AppError::QualityThresholdExceeded(_)), "{:?}", err);
+
+        let strict = db.get_job(strict.id).await.unwrap();
+        assert_eq!(strict.status, JobStatus::Failed);
+        assert!(strict.output_file_id.is_none());
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before + strict.credits_used);
+
+        let lenient = stored_job_with_config(
+            &service,
+            user.id,
+            QuantizationConfig { max_quality_loss_percent: Some(50.0), ..Default::default() },
+        ).await;
+        service.process_job(lenient.id).await.unwrap();
+        let lenient = db.get_job(lenient.id).await.unwrap();
+        assert_eq!(lenient.status, JobStatus::Completed);
+        assert!(lenient.output_file_id.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn omitted_method_falls_back_to_the_saved_default() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        service.update_quantization_preferences(user.id, UpdateQuantizationPreferences {
+            default_method: Some(QuantizationMethod::Awq),
+            default_bits: None,
+            default_group_size: Some(64),
+            default_output_format: None,
+            unique_job_names: false,
+        }).await.unwrap();
+
+        let new_job: NewJob = serde_json::from_value(serde_json::json!({ "name": "llama" })).unwrap();
+        let settings = service.resolve_job_settings(user.id, &new_job).await.unwrap();
+        assert!(matches!(settings.quantization_method, QuantizationMethod::Awq));
+        assert_eq!(settings.config.group_size, Some(64));
+
+        // Un champ explicite l'emporte sur le réglage enregistré
+        let explicit: NewJob = serde_json::from_value(serde_json::json!({
+            "name": "llama", "quantization_method": "int8"
+        })).unwrap();
+        let settings = service.resolve_job_settings(user.id, &explicit).await.unwrap();
+        assert!(matches!(settings.quantization_method, QuantizationMethod::Int8));
+    }
+
+    /// Safetensors minimal dont les poids sont empaquetés en 4 bits (bitsandbytes)
+    fn int4_safetensors() -> Vec<u8> {
+        let header = serde_json::json!({
+            "model.layers.0.mlp.weight": { "dtype": "U8", "shape": [8, 1], "data_offsets": [0, 8] },
+            "model.layers.0.mlp.weight.absmax": { "dtype": "F32", "shape": [1], "data_offsets": [8, 12] },
+        }).to_string();
+        let mut data = (header.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(header.as_bytes());
+        data.extend_from_slice(&[0u8; 12]);
+        data
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn already_quantized_input_is_rejected_unless_allowed() {
+        let db = testing::database().await;
+        let config = pipeline_config(&[]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        testing::grant_credits(&db, user.id, 10).await;
+        let input = testing::store_model(&db, &service.storage, user.id, &int4_safetensors()).await;
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+
+        let err = service
+            .create_job(
+                user.id,
+                input.id,
+                "deja-int4".to_string(),
+                QuantizationMethod::Gptq,
+                ModelFormat::Safetensors,
+                QuantizationConfig::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, AppError::AlreadyQuantized(detected) if detected == "INT4"), "{:?}", err);
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before);
+
+        let forced = QuantizationConfig { allow_quantized_input: true, ..Default::default() };
+        let job = service
+            .create_job(
+                user.id,
+                input.id,
+                "deja-int4-force".to_string(),
+                QuantizationMethod::Gptq,
+                ModelFormat::Safetensors,
+                forced,
+            )
+            .await
+            .unwrap();
+        assert!(job.quantization_config.unwrap().allow_quantized_input);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn each_failure_is_kept_in_the_error_history() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // Fichier d'entrée sans objet stocké: chaque tentative échoue
+        let job = testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+        assert!(service.process_job(job.id).await.is_err());
+        assert!(service.process_job(job.id).await.is_err());
+
+        let result = service.get_job(job.id).await.unwrap().to_result();
+        assert_eq!(result.retry_count, 2);
+        assert_eq!(result.error_history.len(), 2);
+        assert!(result.error_history[0].timestamp <= result.error_history[1].timestamp);
+        assert_eq!(result.error_message.as_deref(), Some(result.error_history[1].message.as_str()));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn method_without_backend_is_rejected_at_creation() {
+        let db = testing::database().await;
+        let mut config = testing::config();
+        config.quantization_gpu_enabled = true;
+        let quantizer = testing::quantizer_with_python(&config, &testing::python_with_modules(&["auto_gptq"]));
+        quantizer.probe_capabilities().await;
+        let service = testing::job_service_with_quantizer(db.clone(), &config, quantizer).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        testing::grant_credits(&db, user.id, 10).await;
+        let input = testing::create_file(&db, user.id, ModelFormat::Safetensors, 1024).await;
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+
+        let err = service
+            .create_job(
+                user.id,
+                input.id,
+                "awq".to_string(),
+                QuantizationMethod::Awq,
+                ModelFormat::Safetensors,
+                QuantizationConfig::default(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::MethodUnavailable(_)), "{:?}", err);
+        assert_eq!(actix_web::ResponseError::error_response(&err).status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before);
+        assert!(service.get_capabilities().is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn gguf_output_requires_a_paid_plan() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+
+        let mut outcomes = Vec::new();
+        for plan in [SubscriptionPlan::Free, SubscriptionPlan::Pro] {
+            let user = testing::create_user(&db, plan).await;
+            testing::grant_credits(&db, user.id, 10).await;
+            let input = testing::create_file(&db, user.id, ModelFormat::Safetensors, 1024).await;
+            outcomes.push(
+                service
+                    .create_job(
+                        user.id,
+                        input.id,
+                        "llama-gguf".to_string(),
+                        QuantizationMethod::GgufQ4_0,
+                        ModelFormat::Gguf,
+                        QuantizationConfig::default(),
+                    )
+                    .await,
+            );
+        }
+
+        let err = outcomes.remove(0).unwrap_err();
+        assert!(matches!(err, AppError::OutputFormatNotInPlan(_)), "{:?}", err);
+        assert_eq!(actix_web::ResponseError::error_response(&err).status(), actix_web::http::StatusCode::PAYMENT_REQUIRED);
+
+        let job = outcomes.remove(0).unwrap();
+        assert!(matches!(job.output_format, ModelFormat::Gguf));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn timeout_cancellation_is_tagged_system_and_user_cancel_user() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // En attente depuis trois heures: annulé par la plateforme
+        let stale = testing::create_job(&db, user.id, "ancien", QuantizationMethod::Int8).await;
+        sqlx::query("UPDATE jobs SET created_at = NOW() - INTERVAL '3 hours' WHERE id = $1")
+            .bind(stale.id)
+            .execute(db.write_pool())
+            .await
+            .unwrap();
+
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+        assert!(service.cancel_stale_pending_jobs(1).await.unwrap() >= 1);
+
+        let stale = service.get_job(stale.id).await.unwrap().to_result();
+        assert!(matches!(stale.status, JobStatus::Cancelled));
+        assert_eq!(stale.cancelled_by, Some(CancelledBy::System));
+        assert!(stale.cancellation_reason.unwrap().contains("1 h"));
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before + 1);
+
+        let recent = testing::create_job(&db, user.id, "recent", QuantizationMethod::Int8).await;
+        service.cancel_job(recent.id, CancelledBy::User, Some("plus utile".to_string())).await.unwrap();
+
+        let recent = service.get_job(recent.id).await.unwrap().to_result();
+        assert_eq!(recent.cancelled_by, Some(CancelledBy::User));
+        assert_eq!(recent.cancellation_reason.as_deref(), Some("plus utile"));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn cancellation_never_overwrites_a_job_started_since_it_was_read() {
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let mut job = testing::create_job(&db, user.id, "pris", QuantizationMethod::Int8).await;
+
+        // Un worker prend le job entre la lecture et l'annulation
+        sqlx::query("UPDATE jobs SET status = 'processing' WHERE id = $1")
+            .bind(job.id)
+            .execute(db.write_pool())
+            .await
+            .unwrap();
+
+        job.cancel(CancelledBy::System, Some("En attente depuis plus de 1 h".to_string()));
+        assert!(!db.cancel_job(&job, &JobStatus::Pending).await.unwrap());
+        assert_eq!(db.get_job(job.id).await.unwrap().status, JobStatus::Processing);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn presets_expand_and_explicit_fields_win() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let resolve = |value: serde_json::Value| {
+            let new_job: NewJob = serde_json::from_value(value).unwrap();
+            let service = &service;
+            async move { service.resolve_job_settings(user.id, &new_job).await.unwrap() }
+        };
+
+        let compressed = resolve(serde_json::json!({ "name": "llama", "preset": "max-compression" })).await;
+        assert!(matches!(compressed.quantization_method, QuantizationMethod::Awq));
+        assert_eq!(compressed.config.bits, Some(4));
+
+        // La taille de groupe explicite remplace celle du préréglage, le reste est gardé
+        let tuned = resolve(serde_json::json!({
+            "name": "llama", "preset": "max-compression", "config": { "group_size": 64 }
+        })).await;
+        assert_eq!(tuned.config.group_size, Some(64));
+        assert_eq!(tuned.config.bits, Some(4));
+
+        // Une méthode explicite écarte les options propres à la méthode du préréglage
+        let int8 = resolve(serde_json::json!({
+            "name": "llama", "preset": "max-compression", "quantization_method": "int8"
+        })).await;
+        assert!(matches!(int8.quantization_method, QuantizationMethod::Int8));
+        assert_eq!(int8.config.bits, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn duplicate_active_name_is_rejected_when_uniqueness_is_enabled() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let service = testing::job_service(db.clone(), &config).await;
+        let strict = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let lenient = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        service.update_quantization_preferences(strict.id, UpdateQuantizationPreferences {
+            default_method: None,
+            default_bits: None,
+            default_group_size: None,
+            default_output_format: None,
+            unique_job_names: true,
+        }).await.unwrap();
+
+        for user in [&strict, &lenient] {
+            testing::create_job(&db, user.id, "llama", QuantizationMethod::Int8).await;
+        }
+
+        // Réglage par défaut: les doublons restent permis
+        assert!(service.check_job_name_available(lenient.id, "llama").await.is_ok());
+
+        let input = testing::create_file(&db, strict.id, ModelFormat::Onnx, 1024).await;
+        let err = service
+            .create_job(
+                strict.id,
+                input.id,
+                "llama".to_string(),
+                QuantizationMethod::Int8,
+                ModelFormat::Onnx,
+                QuantizationConfig::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, AppError::DuplicateJobName(suggestion) if suggestion == "llama (2)"), "{:?}", err);
+        assert_eq!(actix_web::ResponseError::error_response(&err).status(), actix_web::http::StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn job_above_the_plan_memory_cap_fails_out_of_memory() {
+        let db = testing::database().await;
+        let mut config = pipeline_config(&[(
+            "quantize_gptq.py",
+            "import time\ndata = b'x' * (200 * 1024 * 1024)\ntime.sleep(5)\n",
+        )]);
+        config.starter_user_max_job_memory_mb = 64;
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let job = stored_job_with_config(&service, user.id, QuantizationConfig::default()).await;
+
+        let err = service.process_job(job.id).await.unwrap_err();
+        assert!(matches!(err, AppError::OutOfMemory(_)), "{:?}", err);
+
+        let job = service.get_job(job.id).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Failed));
+        assert_eq!(job.error_message, Some(err.to_string()));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn successful_run_records_the_ordered_timeline() {
+        let db = testing::database().await;
+        let config = pipeline_config(&[]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        testing::grant_credits(&db, user.id, 10).await;
+        let input = testing::create_stored_file(&db, &service.storage, user.id, 1024).await;
+
+        let job = service
+            .create_job(
+                user.id,
+                input.id,
+                "chronologie".to_string(),
+                QuantizationMethod::Gptq,
+                ModelFormat::Safetensors,
+                QuantizationConfig::default(),
+            )
+            .await
+            .unwrap();
+        service.process_job(job.id).await.unwrap();
+
+        let timeline = service.get_job_timeline(job.id).await.unwrap();
+        let sequence: Vec<_> = timeline.iter().map(|event| (event.event, event.stage.as_deref())).collect();
+        assert_eq!(
+            sequence,
+            vec![
+                (JobEventType::Created, None),
+                (JobEventType::Queued, None),
+                (JobEventType::PickedUp, None),
+                (JobEventType::StageStarted, Some("download")),
+                (JobEventType::StageStarted, Some("analyze")),
+                (JobEventType::StageStarted, Some("quantize")),
+                (JobEventType::StageStarted, Some("validate")),
+                (JobEventType::StageStarted, Some("upload")),
+                (JobEventType::Completed, None),
+            ]
+        );
+        assert!(timeline.windows(2).all(|pair| pair[0].created_at <= pair[1].created_at));
+        // Les transitions du worker portent son identifiant
+        assert!(timeline[2..].iter().all(|event| event.worker_id.as_deref() == Some(service.worker_id.as_str())));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn gguf_export_runs_alongside_quality_validation() {
+        // Export et mesure de qualité durent 3 s chacun: en série, plus de 6 s
+        let db = testing::database().await;
+        let config = pipeline_config(&[
+            (
+                "convert_gguf.py",
+                "import sys, time\ntime.sleep(3)\nargs = sys.argv[1:]\nopen(args[args.index('--output') + 1], 'wb').write(b'GGUF export')\n",
+            ),
+            (
+                "validate_quality.py",
+                "import json, time\ntime.sleep(3)\nprint(json.dumps({'perplexity_before': 10.0, 'perplexity_after': 10.2}))\n",
+            ),
+        ]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = stored_job_with_config(
+            &service,
+            user.id,
+            QuantizationConfig { gguf_export: Some(crate::models::GgufExport::Q4_0), ..Default::default() },
+        )
+        .await;
+
+        let started = Instant::now();
+        service.process_job(job.id).await.unwrap();
+        let elapsed = started.elapsed();
+
+        assert!(elapsed < std::time::Duration::from_millis(5500), "{:?}", elapsed);
+        let job = service.get_job(job.id).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Completed));
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn failing_gguf_export_fails_the_job() {
+        let db = testing::database().await;
+        let config = pipeline_config(&[("convert_gguf.py", "import sys\nsys.stderr.write('llama.cpp absent')\nsys.exit(1)\n")]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = stored_job_with_config(
+            &service,
+            user.id,
+            QuantizationConfig { gguf_export: Some(crate::models::GgufExport::Q4_0), ..Default::default() },
+        )
+        .await;
+
+        let err = service.process_job(job.id).await.unwrap_err();
+        assert!(matches!(err, AppError::ConversionFailed(_)), "{:?}", err);
+
+        let job = service.get_job(job.id).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Failed));
+        assert!(job.output_file_id.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn report_carries_the_detected_architecture_or_unknown() {
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let detected = pipeline_config(&[]);
+        let service = testing::job_service(db.clone(), &detected).await;
+        let job = stored_job_with_config(&service, user.id, QuantizationConfig::default()).await;
+        service.process_job(job.id).await.unwrap();
+        let report = service.get_job(job.id).await.unwrap().report.unwrap().0;
+        assert_eq!(report.model_architecture, "llamaforcausallm");
+
+        let unrecognized = pipeline_config(&[(
+            "analyze_model.py",
+            "import json\nprint(json.dumps({'model_type': 'unknown', 'architecture': '', 'parameter_count': 1.0,\n\
+             'quantization_bits': None, 'layers': 4, 'vocab_size': None, 'context_length': None, 'hidden_size': None,\n\
+             'file_size_bytes': 1024, 'supported_quantizations': []}))\n",
+        )]);
+        let service = testing::job_service(db.clone(), &unrecognized).await;
+        let job = stored_job_with_config(&service, user.id, QuantizationConfig::default()).await;
+        service.process_job(job.id).await.unwrap();
+        let report = service.get_job(job.id).await.unwrap().report.unwrap().0;
+        assert_eq!(report.model_architecture, QuantizationReport::UNKNOWN_ARCHITECTURE);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn report_times_each_executed_stage() {
+        let db = testing::database().await;
+        let config = pipeline_config(&[]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = stored_job_with_config(&service, user.id, QuantizationConfig::default()).await;
+
+        service.process_job(job.id).await.unwrap();
+
+        let timings = service.get_job(job.id).await.unwrap().report.unwrap().0.stage_timings;
+        for (stage, seconds) in [
+            ("download", timings.download),
+            ("analyze", timings.analyze),
+            ("quantize", timings.quantize),
+            ("validate", timings.validate),
+            ("upload", timings.upload),
+        ] {
+            assert!(seconds.is_some_and(|seconds| seconds > 0.0), "{}: {:?}", stage, seconds);
+        }
+        // Ni conversion ni export GGUF pour ce job
+        assert_eq!(timings.export, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn revalidation_updates_the_completed_report() {
+        let db = testing::database().await;
+        let config = pipeline_config(&[]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = stored_job_with_config(&service, user.id, QuantizationConfig::default()).await;
+        service.process_job(job.id).await.unwrap();
+
+        // Nouvelle mesure: le script renvoie désormais d'autres perplexités
+        std::fs::write(
+            std::path::Path::new(&config.quantization_python_path).join("validate_quality.py"),
+            "import json\nprint(json.dumps({'perplexity_before': 11.0, 'perplexity_after': 11.5}))\n",
+        )
+        .unwrap();
+        let report = service.revalidate_quality(user.id, job.id).await.unwrap();
+        assert_eq!(report.perplexity_before, Some(11.0));
+        assert_eq!(report.perplexity_after, Some(11.5));
+
+        let stored = service.get_job(job.id).await.unwrap().report.unwrap().0;
+        assert_eq!(stored.perplexity_after, Some(11.5));
+        assert_eq!(stored.quantized_size, report.quantized_size);
+
+        let stranger = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        assert!(service.revalidate_quality(stranger.id, job.id).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn three_bit_quantization_requires_the_experimental_flag() {
+        let db = testing::database().await;
+        let received = testing::scratch_dir("args").join("args.json");
+        let script = format!(
+            "import json, os, sys\nargs = sys.argv[1:]\nopen({:?}, 'w').write(json.dumps(args))\n\
+             path = os.path.join(args[args.index('--output-dir') + 1], 'quantized.bin')\n\
+             open(path, 'wb').write(b'poids quantifies')\nsys.stdout.write(path)\n",
+            received.to_string_lossy()
+        );
+        let mut config = pipeline_config(&[("quantize_gptq.py", script.as_str())]);
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        testing::grant_credits(&db, user.id, 10).await;
+        let three_bits = || QuantizationConfig { bits: Some(3), ..Default::default() };
+
+        config.enable_experimental_quantization = false;
+        let service = testing::job_service(db.clone(), &config).await;
+        let input = testing::create_stored_file(&db, &service.storage, user.id, 1024).await;
+        let before = db.get_user_credit_balance(user.id).await.unwrap();
+        let err = service
+            .create_job(user.id, input.id, "int3".to_string(), QuantizationMethod::Gptq, ModelFormat::Safetensors, three_bits())
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, AppError::Validation(message) if message.contains("3 bits")), "{:?}", err);
+        assert_eq!(db.get_user_credit_balance(user.id).await.unwrap(), before);
+
+        config.enable_experimental_quantization = true;
+        let service = testing::job_service(db.clone(), &config).await;
+        let input = testing::create_stored_file(&db, &service.storage, user.id, 1024).await;
+        let job = service
+            .create_job(user.id, input.id, "int3".to_string(), QuantizationMethod::Gptq, ModelFormat::Safetensors, three_bits())
+            .await
+            .unwrap();
+        service.process_job(job.id).await.unwrap();
+
+        let args: Vec<String> = serde_json::from_str(&std::fs::read_to_string(&received).unwrap()).unwrap();
+        let bits = args.iter().position(|arg| arg == "--bits").unwrap();
+        assert_eq!(args[bits + 1], "3");
+        let report = service.get_job(job.id).await.unwrap().report.unwrap().0;
+        assert!(report.experimental);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn pytorch_input_with_onnx_output_exports_after_quantization() {
+        let db = testing::database().await;
+        let calls = testing::scratch_dir("onnx").join("calls.log");
+        let script = format!(
+            "import sys\nargs = sys.argv[1:]\nopen({:?}, 'a').write(' '.join(args) + '\\n')\n\
+             open(args[args.index('--output') + 1], 'wb').write(b'modele onnx')\n",
+            calls.to_string_lossy()
+        );
+        let config = pipeline_config(&[("convert_onnx.py", script.as_str())]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        // Une sortie ONNX est atteignable depuis PyTorch en GPTQ, pas en GGUF
+        assert!(service.is_compatible(&ModelFormat::PyTorch, &QuantizationMethod::Gptq, &ModelFormat::Onnx));
+        assert!(!service.is_compatible(&ModelFormat::PyTorch, &QuantizationMethod::GgufQ4_0, &ModelFormat::Onnx));
+
+        let input = testing::create_stored_file(&db, &service.storage, user.id, 1024).await;
+        let job = Job::new(
+            user.id,
+            "llama-onnx".to_string(),
+            QuantizationMethod::Gptq,
+            ModelFormat::PyTorch,
+            ModelFormat::Onnx,
+            Some(input.id),
+            1,
+        );
+        let job = db.create_job(&job).await.unwrap();
+        service.process_job(job.id).await.unwrap();
+
+        // Un seul export, celui du modèle quantifié
+        let calls = std::fs::read_to_string(&calls).unwrap();
+        assert_eq!(calls.lines().count(), 1, "{}", calls);
+        assert!(calls.contains("--quantized"));
+        assert!(calls.contains("model_quantized.onnx"));
+
+        let job = service.get_job(job.id).await.unwrap();
+        let output = db.get_file(job.output_file_id.unwrap()).await.unwrap();
+        assert!(matches!(output.format, ModelFormat::Onnx));
+        assert!(job.report.unwrap().0.stage_timings.export.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn orphan_cleanup_deletes_only_unreferenced_objects_past_the_grace_window() {
+        let db = testing::database().await;
+        let config = testing::config();
+        let dir = testing::scratch_dir("storage");
+        let storage = testing::storage_in(&dir);
+        let service = testing::job_service_with_storage(db.clone(), &config, storage.clone()).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+
+        let age = |path: &std::path::Path| {
+            let two_days_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(48 * 3600);
+            std::fs::File::options().write(true).open(path).unwrap().set_modified(two_days_ago).unwrap();
+        };
+        let referenced = testing::store_model(&db, &storage, user.id, b"poids").await;
+        age(std::path::Path::new(&referenced.storage_path));
+        let orphan = dir.join(format!("{}_orphelin.bin", Uuid::new_v4()));
+        std::fs::write(&orphan, b"reste d'un upload").unwrap();
+        age(&orphan);
+        // Orphelin récent: peut-être un upload dont la ligne n'est pas encore écrite
+        let recent = dir.join(format!("{}_recent.bin", Uuid::new_v4()));
+        std::fs::write(&recent, b"upload en cours").unwrap();
+
+        assert_eq!(service.cleanup_orphaned_objects(24, true).await.unwrap(), 1);
+        assert!(orphan.exists());
+
+        assert_eq!(service.cleanup_orphaned_objects(24, false).await.unwrap(), 1);
+        assert!(!orphan.exists());
+        assert!(recent.exists());
+        assert!(std::path::Path::new(&referenced.storage_path).exists());
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn truncated_output_fails_the_smoke_test_and_the_job() {
+        let db = testing::database().await;
+        // En-tête safetensors annonçant 4096 octets, fichier coupé après 16
+        let truncated_quantize = "import os, struct, sys\n\
+            args = sys.argv[1:]\n\
+            path = os.path.join(args[args.index('--output-dir') + 1], 'quantized.safetensors')\n\
+            open(path, 'wb').write(struct.pack('<Q', 4096) + b'{\"weight\":')\n\
+            sys.stdout.write(path)\n";
+        let loader = "import struct, sys\n\
+            args = sys.argv[1:]\n\
+            data = open(args[args.index('--input') + 1], 'rb').read()\n\
+            (size,) = struct.unpack('<Q', data[:8])\n\
+            if len(data) < 8 + size:\n    sys.stderr.write('fichier tronque')\n    sys.exit(1)\n";
+        let config = pipeline_config(&[("quantize_gptq.py", truncated_quantize), ("verify_output.py", loader)]);
+        let service = testing::job_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Pro).await;
+        let job = stored_job_with_config(&service, user.id, QuantizationConfig::default()).await;
+
+        let err = service.process_job(job.id).await.unwrap_err();
+        assert!(matches!(&err, AppError::CorruptOutput(message) if message.contains("tronque")), "{:?}", err);
+
+        let job = service.get_job(job.id).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Failed));
+        assert!(job.output_file_id.is_none());
+    }
+}
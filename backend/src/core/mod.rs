@@ -4,6 +4,8 @@ pub mod job_service;
 pub mod quantization_service;
 pub mod billing_service;
 pub mod notification_service;
+pub mod analysis;
+pub mod preflight;
 
 // Ré-exports pour faciliter l'import
 pub use user_service::UserService;
@@ -1,5 +1,9 @@
 // core/notification_service.rs
-use crate::models::{Job, SubscriptionPlan};
+use crate::models::{
+    Job, ModelFile, SubscriptionPlan, NotificationPreferences, UpdateNotificationPreferences,
+    BroadcastRequest, BroadcastSummary, NotificationInbox, Pagination,
+};
+use crate::services::database::Database;
 use crate::utils::error::{AppError, Result};
 use std::sync::Arc;
 use tokio::sync::broadcast;
@@ -7,6 +11,7 @@ use uuid::Uuid;
 use serde_json::json;
 
 pub struct NotificationService {
+    db: Arc<Database>,
     email_provider: Arc<dyn EmailProvider + Send + Sync>,
     sms_provider: Option<Arc<dyn SmsProvider + Send + Sync>>,
     websocket_broadcaster: broadcast::Sender<WebSocketMessage>,
@@ -15,6 +20,7 @@ pub struct NotificationService {
 
 impl NotificationService {
     pub fn new(
+        db: Arc<Database>,
         email_provider: Arc<dyn EmailProvider + Send + Sync>,
         sms_provider: Option<Arc<dyn SmsProvider + Send + Sync>>,
         frontend_url: String,
@@ -22,6 +28,7 @@ impl NotificationService {
         let (tx, _) = broadcast::channel(100);
         
         Self {
+            db,
             email_provider,
             sms_provider,
             websocket_broadcaster: tx,
@@ -29,8 +36,97 @@ impl NotificationService {
         }
     }
 
+    /// Obtenir les préférences de notification d'un utilisateur
+    pub async fn get_preferences(&self, user_id: Uuid) -> Result<NotificationPreferences> {
+        self.db.get_notification_preferences(user_id).await
+    }
+
+    /// Mettre à jour les préférences de notification d'un utilisateur
+    pub async fn update_preferences(
+        &self,
+        user_id: Uuid,
+        update: &UpdateNotificationPreferences,
+    ) -> Result<NotificationPreferences> {
+        let mut preferences = self.db.get_notification_preferences(user_id).await?;
+        preferences.apply(update);
+        self.db.upsert_notification_preferences(&preferences).await?;
+        Ok(preferences)
+    }
+
+    /// Lister la boîte de réception in-app d'un utilisateur
+    pub async fn list_inbox(&self, user_id: Uuid, pagination: Pagination) -> Result<NotificationInbox> {
+        let (notifications, total, unread_count) = self.db.list_notifications(user_id, pagination).await?;
+
+        Ok(NotificationInbox {
+            page: pagination.response(notifications, total),
+            unread_count,
+        })
+    }
+
+    /// Marquer une notification in-app comme lue
+    pub async fn mark_read(&self, user_id: Uuid, notification_id: Uuid) -> Result<()> {
+        if !self.db.mark_notification_read(user_id, notification_id).await? {
+            return Err(AppError::NotFound("Notification not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Écrire la notification dans la boîte de réception in-app
+    ///
+    /// Un échec est journalisé sans bloquer l'email qui l'accompagne.
+    async fn record_in_app(&self, user_id: Uuid, notification_type: &str, title: &str, body: &str) -> bool {
+        match self.db.insert_notification(user_id, notification_type, title, body).await {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("Notification in-app non enregistrée pour {}: {}", user_id, e);
+                false
+            }
+        }
+    }
+
     /// Envoyer une notification de job terminé
     pub async fn send_job_completed(&self, user_id: Uuid, job: &Job) -> Result<()> {
+        let preferences = self.get_preferences(user_id).await?;
+        
+        self.record_in_app(
+            user_id,
+            "job.completed",
+            &format!("Votre job '{}' est terminé", job.name),
+            "Votre modèle quantifié est prêt à être téléchargé.",
+        ).await;
+        
+        if preferences.job_complete_email {
+            self.send_job_completed_email(user_id, job).await?;
+        }
+
+        if preferences.job_complete_sms {
+            if let (Some(sms_provider), Some(phone_number)) =
+                (&self.sms_provider, self.get_user_phone(user_id).await?)
+            {
+                let message = format!("Votre job '{}' est terminé.", job.name);
+                sms_provider.send_sms(&phone_number, &message).await?;
+            }
+        }
+
+        // Envoyer une notification WebSocket
+        let ws_message = WebSocketMessage {
+            user_id,
+            event_type: "job.completed".to_string(),
+            data: json!({
+                "job_id": job.id,
+                "job_name": job.name,
+                "status": "completed",
+                "download_url": format!("{}/jobs/{}/download", self.frontend_url, job.id),
+            }),
+        };
+
+        let _ = self.websocket_broadcaster.send(ws_message);
+
+        Ok(())
+    }
+
+    /// Envoyer l'email de job terminé
+    async fn send_job_completed_email(&self, user_id: Uuid, job: &Job) -> Result<()> {
         let user_email = self.get_user_email(user_id).await?;
         
         let email_subject = format!("Votre job '{}' est terminé", job.name);
@@ -66,17 +162,33 @@ L'équipe Quantization Platform"#,
             &user_email,
             &email_subject,
             &email_body,
-        ).await?;
+        ).await
+    }
 
-        // Envoyer une notification WebSocket
+    /// Envoyer une notification de job échoué
+    pub async fn send_job_failed(&self, user_id: Uuid, job: &Job, error: &str) -> Result<()> {
+        let preferences = self.get_preferences(user_id).await?;
+        
+        self.record_in_app(
+            user_id,
+            "job.failed",
+            &format!("Votre job '{}' a échoué", job.name),
+            error,
+        ).await;
+        
+        if preferences.job_failed_email {
+            self.send_job_failed_email(user_id, job, error).await?;
+        }
+
+        // Notification WebSocket
         let ws_message = WebSocketMessage {
             user_id,
-            event_type: "job.completed".to_string(),
+            event_type: "job.failed".to_string(),
             data: json!({
                 "job_id": job.id,
                 "job_name": job.name,
-                "status": "completed",
-                "download_url": format!("{}/jobs/{}/download", self.frontend_url, job.id),
+                "status": "failed",
+                "error": error,
             }),
         };
 
@@ -85,8 +197,8 @@ L'équipe Quantization Platform"#,
         Ok(())
     }
 
-    /// Envoyer une notification de job échoué
-    pub async fn send_job_failed(&self, user_id: Uuid, job: &Job, error: &str) -> Result<()> {
+    /// Envoyer l'email de job échoué
+    async fn send_job_failed_email(&self, user_id: Uuid, job: &Job, error: &str) -> Result<()> {
         let user_email = self.get_user_email(user_id).await?;
         
         let email_subject = format!("Votre job '{}' a échoué", job.name);
@@ -114,23 +226,127 @@ L'équipe Quantization Platform"#,
             &user_email,
             &email_subject,
             &email_body,
-        ).await?;
+        ).await
+    }
 
-        // Notification WebSocket
-        let ws_message = WebSocketMessage {
-            user_id,
-            event_type: "job.failed".to_string(),
-            data: json!({
-                "job_id": job.id,
-                "job_name": job.name,
-                "status": "failed",
-                "error": error,
-            }),
+    /// Prévenir qu'un fichier va bientôt être supprimé (retourne false si l'utilisateur l'a désactivé)
+    pub async fn send_file_expiring(&self, file: &ModelFile) -> Result<bool> {
+        let preferences = self.get_preferences(file.user_id).await?;
+        if !preferences.file_expiry_email {
+            return Ok(false);
+        }
+
+        let expires_at = match file.expires_at {
+            Some(expires_at) => expires_at,
+            None => return Ok(false),
         };
 
-        let _ = self.websocket_broadcaster.send(ws_message);
+        let user_email = self.get_user_email(file.user_id).await?;
+        
+        let subject = format!("Votre fichier '{}' expire bientôt", file.original_filename);
+        let body = format!(
+            r#"Bonjour,
 
-        Ok(())
+Votre fichier "{}" sera supprimé le {}.
+
+Pensez à le télécharger avant cette date:
+{}/files/{}
+
+Passez à un plan supérieur pour conserver vos fichiers plus longtemps: {}/billing
+
+Cordialement,
+L'équipe Quantization Platform"#,
+            file.original_filename,
+            expires_at.format("%d/%m/%Y à %H:%M UTC"),
+            self.frontend_url,
+            file.id,
+            self.frontend_url
+        );
+
+        self.email_provider.send(&user_email, &subject, &body).await?;
+
+        self.record_in_app(
+            file.user_id,
+            "file.expiring",
+            &subject,
+            &format!("Ce fichier sera supprimé le {}.", expires_at.format("%d/%m/%Y à %H:%M UTC")),
+        ).await;
+
+        Ok(true)
+    }
+
+    /// Envoyer une annonce admin par lots de `batch_size` utilisateurs
+    ///
+    /// Les emails non critiques ne partent qu'aux utilisateurs ayant accepté
+    /// les messages `marketing`; un message critique ignore cette préférence.
+    /// Une pause de `batch_delay` sépare deux lots pour ménager le fournisseur d'email.
+    pub async fn send_broadcast(
+        &self,
+        request: &BroadcastRequest,
+        batch_size: usize,
+        batch_delay: std::time::Duration,
+    ) -> Result<BroadcastSummary> {
+        let mut summary = BroadcastSummary::default();
+        let mut after = None;
+        let body = format!(
+            r#"Bonjour,
+
+{}
+
+Cordialement,
+L'équipe Quantization Platform
+
+Gérer vos notifications: {}/settings/notifications"#,
+            request.message,
+            self.frontend_url
+        );
+
+        loop {
+            let recipients = self.db
+                .list_broadcast_recipients(&request.plans, after, batch_size.max(1) as i64)
+                .await?;
+            let Some(last) = recipients.last() else { break };
+            after = Some(last.id);
+            summary.recipients += recipients.len();
+
+            for recipient in &recipients {
+                if request.in_app {
+                    let data = json!({
+                        "subject": request.subject,
+                        "message": request.message,
+                        "critical": request.critical,
+                    });
+                    // Aucun abonné WebSocket n'est pas une erreur d'envoi
+                    let _ = self.send_websocket_notification(recipient.id, "broadcast", data);
+                    if self.record_in_app(recipient.id, "broadcast", &request.subject, &request.message).await {
+                        summary.in_app_sent += 1;
+                    } else {
+                        summary.failed += 1;
+                    }
+                }
+
+                if request.email {
+                    if !request.critical && !recipient.marketing {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    match self.email_provider.send(&recipient.email, &request.subject, &body).await {
+                        Ok(()) => summary.emails_sent += 1,
+                        Err(e) => {
+                            summary.failed += 1;
+                            log::warn!("Annonce non envoyée à {}: {}", recipient.id, e);
+                        }
+                    }
+                }
+            }
+
+            if recipients.len() < batch_size.max(1) {
+                break;
+            }
+            tokio::time::sleep(batch_delay).await;
+        }
+
+        Ok(summary)
     }
 
     /// Envoyer un email de bienvenue
@@ -211,6 +427,13 @@ L'équipe Quantization Platform"#,
             self.frontend_url
         );
 
+        self.record_in_app(
+            user_id,
+            "credits.exhausted",
+            subject,
+            "Passez à un plan supérieur ou attendez la réinitialisation mensuelle pour continuer.",
+        ).await;
+
         self.email_provider.send(&user_email, subject, &body).await
     }
 
@@ -252,6 +475,13 @@ L'équipe Quantization Platform"#,
             }
         );
 
+        self.record_in_app(
+            user_id,
+            "subscription.changed",
+            subject,
+            &format!("Votre abonnement est passé de {:?} à {:?}.", old_plan, new_plan),
+        ).await;
+
         self.email_provider.send(&user_email, subject, &body).await
     }
 
@@ -280,6 +510,11 @@ L'équipe Quantization Platform"#,
         // Pour le MVP, on simule
         Ok(format!("user_{}@example.com", user_id))
     }
+
+    /// Obtenir le numéro de téléphone de l'utilisateur (non collecté pour le MVP)
+    async fn get_user_phone(&self, _user_id: Uuid) -> Result<Option<String>> {
+        Ok(None)
+    }
 }
 
 // Traits pour les fournisseurs de notification
@@ -312,4 +547,125 @@ pub struct WebSocketMessage {
     pub user_id: Uuid,
     pub event_type: String,
     pub data: serde_json::Value,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ModelFormat, QuantizationMethod};
+    use crate::utils::testing::{self, RecordingEmailProvider};
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn disabled_completion_email_is_not_sent() {
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let emails = Arc::new(RecordingEmailProvider::default());
+        let notifications = testing::notification_service(db.clone(), emails.clone());
+
+        notifications.update_preferences(user.id, &UpdateNotificationPreferences {
+            job_complete_email: Some(false),
+            job_failed_email: None,
+            job_complete_sms: None,
+            file_expiry_email: None,
+            marketing: None,
+        }).await.unwrap();
+
+        let job = Job::new(
+            user.id,
+            "llama".to_string(),
+            QuantizationMethod::Int8,
+            ModelFormat::Safetensors,
+            ModelFormat::Safetensors,
+            None,
+            1,
+        );
+        notifications.send_job_completed(user.id, &job).await.unwrap();
+        assert!(emails.subjects().is_empty(), "{:?}", emails.subjects());
+
+        // Les échecs restent notifiés: seule la préférence désactivée est respectée
+        notifications.send_job_failed(user.id, &job, "erreur").await.unwrap();
+        assert_eq!(emails.subjects().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn critical_broadcast_ignores_the_marketing_opt_out() {
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+        let emails = Arc::new(RecordingEmailProvider::default());
+        let notifications = testing::notification_service(db.clone(), emails.clone());
+
+        notifications.update_preferences(user.id, &UpdateNotificationPreferences {
+            job_complete_email: None,
+            job_failed_email: None,
+            job_complete_sms: None,
+            file_expiry_email: None,
+            marketing: Some(false),
+        }).await.unwrap();
+
+        let mut request = BroadcastRequest {
+            subject: "Nouveautés".to_string(),
+            message: "Découvrez les nouvelles méthodes".to_string(),
+            plans: vec![SubscriptionPlan::Starter],
+            email: true,
+            in_app: false,
+            critical: false,
+        };
+        let summary = notifications
+            .send_broadcast(&request, 500, std::time::Duration::ZERO)
+            .await
+            .unwrap();
+        assert!(emails.subjects_to(&user.email).is_empty());
+        assert!(summary.skipped >= 1, "{:?}", summary);
+
+        request.subject = "Incident de sécurité".to_string();
+        request.critical = true;
+        notifications
+            .send_broadcast(&request, 500, std::time::Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(emails.subjects_to(&user.email), vec!["Incident de sécurité".to_string()]);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn completed_job_lands_unread_in_the_inbox_until_marked_read() {
+        let db = testing::database().await;
+        let user = testing::create_user(&db, SubscriptionPlan::Free).await;
+        let emails = Arc::new(RecordingEmailProvider::default());
+        let notifications = testing::notification_service(db.clone(), emails.clone());
+
+        let job = Job::new(
+            user.id,
+            "llama".to_string(),
+            QuantizationMethod::Int8,
+            ModelFormat::Safetensors,
+            ModelFormat::Safetensors,
+            None,
+            1,
+        );
+        notifications.send_job_completed(user.id, &job).await.unwrap();
+
+        let pagination = Pagination { page: 1, per_page: Pagination::DEFAULT_PER_PAGE };
+        let inbox = notifications.list_inbox(user.id, pagination).await.unwrap();
+        assert_eq!(inbox.unread_count, 1);
+        assert_eq!(inbox.page.items.len(), 1);
+        let notification = &inbox.page.items[0];
+        assert_eq!(notification.notification_type, "job.completed");
+        assert!(!notification.read);
+
+        notifications.mark_read(user.id, notification.id).await.unwrap();
+
+        let inbox = notifications.list_inbox(user.id, pagination).await.unwrap();
+        assert_eq!(inbox.unread_count, 0);
+        assert!(inbox.page.items[0].read);
+
+        // La notification d'un autre utilisateur reste introuvable
+        let other = testing::create_user(&db, SubscriptionPlan::Free).await;
+        assert!(matches!(
+            notifications.mark_read(other.id, notification.id).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,239 @@
+// core/preflight.rs
+//! Vérification préalable au démarrage (`--check`)
+//!
+//! Valide la configuration et joint chaque dépendance (base, Redis, stockage,
+//! Python) sans démarrer le serveur. Destinée aux pipelines de déploiement:
+//! le code de sortie est non nul dès qu'une vérification échoue.
+
+use crate::core::QuantizationService;
+use crate::services::{Cache, Database, FileStorage, PoolSettings, PythonClient};
+use crate::utils::config::Config;
+use crate::utils::workspace::WorkDirs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Longueur minimale de la clé de chiffrement du stockage (AES-256)
+const MIN_ENCRYPTION_KEY_BYTES: usize = 32;
+
+/// Résultat d'une vérification
+#[derive(Debug, Clone)]
+pub struct PreflightCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Rapport complet de la vérification préalable
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    fn record(&mut self, name: &'static str, result: std::result::Result<String, String>) -> bool {
+        let passed = result.is_ok();
+        let detail = result.unwrap_or_else(|e| e);
+        self.checks.push(PreflightCheck { name, passed, detail });
+        passed
+    }
+
+    /// Toutes les vérifications ont-elles réussi ?
+    pub fn passed(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|check| check.passed)
+    }
+
+}
+
+/// Rapport lisible: une ligne par vérification, puis le bilan
+impl std::fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed { "OK  " } else { "FAIL" };
+            writeln!(f, "[{}] {:<14} {}", status, check.name, check.detail)?;
+        }
+
+        let failed = self.checks.iter().filter(|check| !check.passed).count();
+        if failed == 0 {
+            writeln!(f, "Vérification réussie ({} contrôles)", self.checks.len())
+        } else {
+            writeln!(f, "Vérification échouée: {} contrôle(s) sur {} en échec", failed, self.checks.len())
+        }
+    }
+}
+
+/// Lancer toutes les vérifications à partir de l'environnement
+pub async fn run() -> PreflightReport {
+    run_with(Config::from_env()).await
+}
+
+/// Lancer les vérifications sur une configuration déjà chargée (ou son erreur)
+async fn run_with(config: crate::utils::error::Result<Config>) -> PreflightReport {
+    let mut report = PreflightReport::default();
+
+    // Sans configuration valide, les autres vérifications n'ont pas de sens
+    let config = match config {
+        Ok(config) => {
+            report.record("configuration", Ok(format!("mode {}", config.run_mode)));
+            config
+        }
+        Err(e) => {
+            report.record("configuration", Err(e.to_string()));
+            return report;
+        }
+    };
+
+    report.record("encryption_key", check_encryption_key(&config.storage_encryption_key));
+
+    let database = Database::new(
+        &config.database_url,
+        config.database_read_url.as_deref(),
+        &PoolSettings::from_config(&config),
+    ).await;
+    report.record("database", match database {
+        Ok(db) if db.has_read_replica() => Ok("primaire et réplique joignables".to_string()),
+        Ok(_) => Ok("joignable".to_string()),
+        Err(e) => Err(e.to_string()),
+    });
+
+    let redis = match Cache::new(&config.redis_url, Some(&config.redis_cache_prefix()), config.redis_cache_ttl_seconds).await {
+        Ok(cache) => cache.health_check().await.map(|_| "joignable".to_string()),
+        Err(e) => Err(e),
+    };
+    report.record("redis", redis.map_err(|e| e.to_string()));
+
+    let storage = FileStorage::new(
+        config.minio_endpoint.as_deref(),
+        config.minio_access_key.as_deref(),
+        config.minio_secret_key.as_deref(),
+        &config.minio_bucket,
+        Some(Path::new("./storage")),
+        None,
+        config.max_file_size_mb,
+        config.download_url_expiry_hours,
+        config.storage_retry_policy(),
+    );
+    report.record("storage", storage.health_check().await
+        .map(|_| format!("{} ({})", config.storage_type, config.minio_bucket))
+        .map_err(|e| e.to_string()));
+
+    let work_dirs = match WorkDirs::prepare(Path::new(&config.work_dir)) {
+        Ok(work_dirs) => {
+            report.record("work_dir", Ok(config.work_dir.clone()));
+            work_dirs
+        }
+        Err(e) => {
+            report.record("work_dir", Err(e.to_string()));
+            return report;
+        }
+    };
+
+    let python_client = Arc::new(PythonClient::new(
+        &config.quantization_python_path,
+        Some("python3"),
+        config.quantization_timeout_seconds,
+    ));
+    let quant_service = QuantizationService::new(
+        python_client,
+        config.quantization_gpu_enabled,
+        config.quantization_timeout_seconds,
+        config.quantization_max_retries,
+        work_dirs,
+        false,
+        1,
+        1,
+        1,
+    );
+    report.record("python", check_python(&quant_service).await);
+
+    report
+}
+
+/// Clé de chiffrement du stockage: absente (chiffrement désactivé) ou assez longue
+fn check_encryption_key(key: &str) -> std::result::Result<String, String> {
+    if key.is_empty() {
+        Ok("chiffrement désactivé".to_string())
+    } else if key.len() < MIN_ENCRYPTION_KEY_BYTES {
+        Err(format!(
+            "STORAGE_ENCRYPTION_KEY trop courte ({} octets, {} requis)",
+            key.len(),
+            MIN_ENCRYPTION_KEY_BYTES
+        ))
+    } else {
+        Ok("AES-256".to_string())
+    }
+}
+
+/// Python et ses dépendances, puis sonde des backends de quantification
+///
+/// Une méthode indisponible n'est pas bloquante (elle sera refusée à la
+/// création des jobs); aucune méthode disponible l'est.
+async fn check_python(quant_service: &QuantizationService) -> std::result::Result<String, String> {
+    quant_service.health_check().await.map_err(|e| e.to_string())?;
+
+    let capabilities = quant_service.probe_capabilities().await;
+    let unavailable: Vec<String> = capabilities.methods
+        .iter()
+        .filter(|capability| !capability.available)
+        .map(|capability| format!(
+            "{} ({})",
+            capability.method,
+            capability.reason.as_deref().unwrap_or("raison inconnue")
+        ))
+        .collect();
+
+    if unavailable.len() == capabilities.methods.len() {
+        return Err("aucune méthode de quantification disponible".to_string());
+    }
+    if unavailable.is_empty() {
+        Ok(format!("{} méthodes disponibles", capabilities.methods.len()))
+    } else {
+        Ok(format!("indisponibles: {}", unavailable.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_each_check_then_the_outcome() {
+        let mut report = PreflightReport::default();
+        report.record("database", Ok("PostgreSQL joignable".to_string()));
+        report.record("redis", Err("connexion refusée".to_string()));
+
+        let text = report.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("[OK  ] database"));
+        assert!(lines[1].starts_with("[FAIL] redis"));
+        assert!(lines[1].ends_with("connexion refusée"));
+        assert_eq!(lines[2], "Vérification échouée: 1 contrôle(s) sur 2 en échec");
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn empty_report_does_not_pass() {
+        assert!(!PreflightReport::default().passed());
+    }
+
+    #[tokio::test]
+    async fn missing_required_config_fails_the_check() {
+        let missing = Err(crate::utils::error::AppError::Validation(
+            "Variable d'environnement requise manquante: DATABASE_URL".to_string(),
+        ));
+
+        let report = run_with(missing).await;
+
+        assert!(!report.passed());
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.checks[0].name, "configuration");
+        assert!(report.checks[0].detail.contains("DATABASE_URL"));
+    }
+
+    #[test]
+    fn short_encryption_key_is_rejected() {
+        assert!(check_encryption_key("").is_ok());
+        assert!(check_encryption_key("trop-courte").is_err());
+        assert!(check_encryption_key(&"k".repeat(MIN_ENCRYPTION_KEY_BYTES)).is_ok());
+    }
+}
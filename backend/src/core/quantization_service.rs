@@ -1,6 +1,7 @@
 // core/quantization_service.rs
-use crate::models::{QuantizationMethod, ModelFormat};
+use crate::models::{QuantizationMethod, ModelFormat, QuantizationConfig, Capabilities, MethodCapability, GgufExport};
 use crate::utils::error::{AppError, Result};
+use crate::utils::workspace::{TempWorkspace, WorkDirs};
 use crate::services::python::PythonClient;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
@@ -8,13 +9,69 @@ use std::sync::Arc;
 use tokio::process::Command;
 use tokio::sync::Semaphore;
 
+/// Marqueur émis par `convert_onnx.py` quand le graphe ne peut pas être tracé
+/// (flot de contrôle dynamique dépendant des données)
+const UNTRACEABLE_MARKER: &str = "UNTRACEABLE_MODEL";
+
+/// Marge appliquée à la taille du modèle pour estimer l'espace disque nécessaire
+/// (copie d'entrée + artefacts intermédiaires + sortie)
+const DISK_SAFETY_FACTOR: f64 = 3.0;
+
+/// Configuration de précision par couche passée aux scripts (`--layer-config`)
+const LAYER_CONFIG_FILE: &str = "layer_config.json";
+
+/// Liste JSON des couches effectivement surchargées, écrite par les scripts
+const OVERRIDDEN_LAYERS_FILE: &str = "overridden_layers.json";
+
+/// Sous-répertoire du job recevant l'export GGUF supplémentaire
+const GGUF_EXPORT_DIR: &str = "gguf_export";
+
+/// Résultat d'une quantification
+#[derive(Debug, Clone)]
+pub struct QuantizationOutput {
+    pub output_path: String,
+    /// Pic de mémoire du script (None si non mesurable)
+    pub peak_memory_bytes: Option<u64>,
+}
+
+/// Export GGUF lancé en tâche de fond
+///
+/// Abandonné (job en échec avant la jointure), il est interrompu et son
+/// script tué (`kill_on_drop`): aucune tâche ne survit au job.
+pub struct ExportTask {
+    handle: tokio::task::JoinHandle<Result<QuantizationOutput>>,
+}
+
+impl ExportTask {
+    /// Attendre la fin de l'export
+    pub async fn join(mut self) -> Result<QuantizationOutput> {
+        match (&mut self.handle).await {
+            Ok(result) => result,
+            Err(e) => Err(AppError::ConversionFailed(format!("export GGUF interrompu: {}", e))),
+        }
+    }
+}
+
+impl Drop for ExportTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
 pub struct QuantizationService {
     python_client: Arc<PythonClient>,
     gpu_enabled: bool,
     timeout_seconds: u64,
     max_retries: u32,
-    work_dir: PathBuf,
+    work_dirs: WorkDirs,
+    keep_workspaces: bool,
     semaphore: Arc<Semaphore>,
+    /// Exports GGUF simultanés, indépendants des permis de quantification
+    export_semaphore: Arc<Semaphore>,
+    /// Validations qualité simultanées (deux modèles chargés en mémoire chacune)
+    validation_semaphore: Arc<Semaphore>,
+    /// Méthodes disponibles, sondées au démarrage (None avant la sonde)
+    capabilities: std::sync::RwLock<Option<Capabilities>>,
 }
 
 impl QuantizationService {
@@ -23,77 +80,259 @@ impl QuantizationService {
         gpu_enabled: bool,
         timeout_seconds: u64,
         max_retries: u32,
-        work_dir: PathBuf,
+        work_dirs: WorkDirs,
+        keep_workspaces: bool,
         max_concurrent: usize,
+        max_concurrent_exports: usize,
+        max_concurrent_validations: usize,
     ) -> Self {
         Self {
             python_client,
             gpu_enabled,
             timeout_seconds,
             max_retries,
-            work_dir,
+            work_dirs,
+            keep_workspaces,
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            export_semaphore: Arc::new(Semaphore::new(max_concurrent_exports.max(1))),
+            validation_semaphore: Arc::new(Semaphore::new(max_concurrent_validations.max(1))),
+            capabilities: std::sync::RwLock::new(None),
+        }
+    }
+
+    /// Durée maximale d'une quantification (secondes)
+    pub fn timeout_seconds(&self) -> u64 {
+        self.timeout_seconds
+    }
+
+    /// Sonder les backends Python installés et retenir les méthodes disponibles
+    pub async fn probe_capabilities(&self) -> Capabilities {
+        let gptq_installed = self.python_client.test_gptq_connection().await;
+        let awq_installed = self.python_client.test_awq_connection().await;
+
+        let methods = QuantizationMethod::ALL
+            .iter()
+            .map(|method| {
+                let reason = match method {
+                    QuantizationMethod::Gptq if !gptq_installed => Some("auto_gptq non installé"),
+                    QuantizationMethod::Awq if !awq_installed => Some("autoawq non installé"),
+                    QuantizationMethod::Gptq | QuantizationMethod::Awq if !self.gpu_enabled => Some("GPU désactivé"),
+                    _ => None,
+                };
+                MethodCapability {
+                    method: method.clone(),
+                    available: reason.is_none(),
+                    reason: reason.map(str::to_string),
+                }
+            })
+            .collect();
+
+        let capabilities = Capabilities {
+            gpu_enabled: self.gpu_enabled,
+            methods,
+            checked_at: chrono::Utc::now(),
+        };
+
+        if let Ok(mut guard) = self.capabilities.write() {
+            *guard = Some(capabilities.clone());
+        }
+
+        capabilities
+    }
+
+    /// Capacités sondées au démarrage
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        self.capabilities.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// Refuser une méthode dont le backend n'est pas installé
+    /// (sans sonde préalable, toutes les méthodes sont acceptées)
+    pub fn check_method_available(&self, method: &QuantizationMethod) -> Result<()> {
+        let Some(capabilities) = self.capabilities() else {
+            return Ok(());
+        };
+
+        match capabilities.unavailable_reason(method) {
+            Some(reason) => Err(AppError::MethodUnavailable(format!("{} ({})", method, reason))),
+            None => Ok(()),
+        }
+    }
+
+    /// Vérifier qu'il reste assez d'espace disque pour traiter un modèle
+    pub fn check_disk_space(&self, model_size: u64) -> Result<()> {
+        let required = (model_size as f64 * DISK_SAFETY_FACTOR) as u64;
+        let available = crate::utils::helpers::available_disk_space(&self.work_dirs.worker)?;
+
+        if available < required {
+            return Err(AppError::InsufficientDiskSpace(format!(
+                "{} requis, {} disponibles dans {}",
+                crate::utils::helpers::format_file_size(required),
+                crate::utils::helpers::format_file_size(available),
+                self.work_dirs.worker.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Créer le répertoire de travail d'un job (supprimé au drop)
+    pub fn create_workspace(&self, job_id: Uuid) -> Result<TempWorkspace> {
+        Ok(TempWorkspace::create(&self.work_dirs.worker, job_id)?.keep(self.keep_workspaces))
+    }
+
+    /// Indique si l'entrée doit être exportée en ONNX avant quantification
+    /// (INT8 ne traite que des modèles ONNX)
+    pub fn needs_onnx_conversion(method: &QuantizationMethod, input_format: &ModelFormat) -> bool {
+        matches!(method, QuantizationMethod::Int8)
+            && matches!(input_format, ModelFormat::PyTorch | ModelFormat::Safetensors)
+    }
+
+    /// Indique si le modèle quantifié doit être exporté en ONNX après coup
+    /// (sortie ONNX demandée à une méthode qui produit des poids PyTorch/safetensors)
+    pub fn needs_onnx_export(method: &QuantizationMethod, output_format: &ModelFormat) -> bool {
+        matches!(output_format, ModelFormat::Onnx)
+            && !matches!(method, QuantizationMethod::Int8)
+    }
+
+    /// Exporter un modèle PyTorch/safetensors en ONNX (torch.onnx.export)
+    pub async fn convert_to_onnx(&self, input_path: &str, workspace: &TempWorkspace) -> Result<String> {
+        self.run_onnx_export(input_path, workspace, "model.onnx", &[]).await
+    }
+
+    /// Exporter en ONNX le modèle quantifié (GPTQ/AWQ), poids quantifiés conservés
+    pub async fn export_quantized_to_onnx(&self, quantized_path: &str, workspace: &TempWorkspace) -> Result<String> {
+        self.run_onnx_export(quantized_path, workspace, "model_quantized.onnx", &["--quantized"]).await
+    }
+
+    async fn run_onnx_export(
+        &self,
+        input_path: &str,
+        workspace: &TempWorkspace,
+        output_name: &str,
+        extra_args: &[&str],
+    ) -> Result<String> {
+        let output_path = workspace.join(output_name)?;
+        let output_path_str = output_path.to_string_lossy().to_string();
+
+        let mut args = vec!["--input", input_path, "--output", &output_path_str];
+        args.extend_from_slice(extra_args);
+
+        match self.python_client.call_script("convert_onnx.py", &args).await {
+            Ok(_) => Ok(output_path_str),
+            Err(AppError::ExternalService(message)) if message.contains(UNTRACEABLE_MARKER) => {
+                Err(AppError::ConversionFailed(
+                    "le modèle contient un flot de contrôle dynamique qui ne peut pas être tracé pour l'export ONNX".to_string(),
+                ))
+            }
+            Err(e) => Err(AppError::ConversionFailed(e.to_string())),
+        }
+    }
+
+    /// Vérifier que le modèle produit se charge (parsing et tenseurs attendus)
+    ///
+    /// Détecte une sortie tronquée ou corrompue avant que le job ne soit terminé.
+    pub async fn verify_output(&self, output_path: &str, format: &ModelFormat) -> Result<()> {
+        match tokio::fs::metadata(output_path).await {
+            Ok(metadata) if metadata.len() > 0 => {}
+            Ok(_) => return Err(AppError::CorruptOutput("fichier vide".to_string())),
+            Err(e) => return Err(AppError::CorruptOutput(format!("fichier introuvable: {}", e))),
+        }
+
+        match self.python_client.call_script(
+            "verify_output.py",
+            &["--input", output_path, "--format", format.as_str()],
+        ).await {
+            Ok(_) => Ok(()),
+            Err(AppError::ExternalService(message)) => Err(AppError::CorruptOutput(message)),
+            Err(e) => Err(e),
         }
     }
 
-    /// Quantifier un modèle
+    /// Quantifier un modèle dans le répertoire de travail du job
+    ///
+    /// Le script est tué s'il dépasse `memory_limit_bytes` (`OutOfMemory`).
     pub async fn quantize(
         &self,
         input_path: &str,
         method: &QuantizationMethod,
         output_format: &ModelFormat,
-        job_id: Uuid,
-    ) -> Result<String> {
+        config: &QuantizationConfig,
+        workspace: &TempWorkspace,
+        memory_limit_bytes: Option<u64>,
+    ) -> Result<QuantizationOutput> {
         // Acquérir un permis pour limiter la concurrence
         let _permit = self.semaphore.acquire().await
             .map_err(|_| AppError::ResourceBusy)?;
 
-        // Créer un répertoire de travail pour ce job
-        let job_dir = self.work_dir.join(job_id.to_string());
-        tokio::fs::create_dir_all(&job_dir).await?;
-
         // Copier le fichier d'entrée dans le répertoire de travail
-        let input_filename = Path::new(input_path)
-            .file_name()
-            .ok_or(AppError::InvalidPath)?
-            .to_string_lossy()
-            .to_string();
-        
-        let job_input_path = job_dir.join(&input_filename);
-        tokio::fs::copy(input_path, &job_input_path).await?;
+        // (sauf s'il y a déjà été téléchargé, ex: dépôt Hugging Face)
+        let job_input_path = if Path::new(input_path).starts_with(workspace.path()) {
+            PathBuf::from(input_path)
+        } else {
+            let job_input_path = workspace.join(input_path)?;
+            tokio::fs::copy(input_path, &job_input_path).await?;
+            job_input_path
+        };
+
+        // Précision (globale ou par couche): configuration écrite dans le répertoire du job
+        let layer_config = if !config.has_script_options() {
+            None
+        } else {
+            let path = workspace.join(LAYER_CONFIG_FILE)?;
+            let content = serde_json::to_vec_pretty(&config.script_config(method))
+                .map_err(|e| AppError::SerializeError(e.to_string()))?;
+            tokio::fs::write(&path, content).await?;
+            Some(path.to_string_lossy().to_string())
+        };
 
         // Exécuter la quantification
-        let output_path = self.execute_quantization(
+        let (output_path, peak_memory_bytes) = self.execute_quantization(
             &job_input_path,
             method,
             output_format,
-            &job_dir,
+            workspace.path(),
+            layer_config.as_deref(),
+            config,
+            memory_limit_bytes,
         ).await?;
 
-        Ok(output_path)
+        Ok(QuantizationOutput { output_path, peak_memory_bytes })
     }
 
-    /// Exécuter la quantification selon la méthode
+    /// Exécuter la quantification selon la méthode (sortie et pic de mémoire)
     async fn execute_quantization(
         &self,
         input_path: &Path,
         method: &QuantizationMethod,
         output_format: &ModelFormat,
         output_dir: &Path,
-    ) -> Result<String> {
+        layer_config: Option<&str>,
+        config: &QuantizationConfig,
+        memory_limit_bytes: Option<u64>,
+    ) -> Result<(String, Option<u64>)> {
         let input_path_str = input_path.to_string_lossy();
         let output_dir_str = output_dir.to_string_lossy();
+        let nsamples = config.effective_calibration_samples(method)
+            .unwrap_or(crate::models::DEFAULT_CALIBRATION_SAMPLES)
+            .to_string();
+        let group_size = config.effective_group_size(method)
+            .unwrap_or(crate::models::DEFAULT_GROUP_SIZE)
+            .to_string();
+        // GPTQ/AWQ: 4 bits par défaut, 2/3 bits si activés (vérifié à la création)
+        let bits = config.bits.unwrap_or_else(|| method.default_bits()).to_string();
 
         match method {
             QuantizationMethod::Int8 => {
                 // Quantification INT8 pour ONNX
-                self.python_client.call_script(
+                self.call_quantize_script(
                     "quantize_int8.py",
-                    &[
+                    vec![
                         "--input", &input_path_str,
                         "--output-dir", &output_dir_str,
                         "--bits", "8",
                     ],
+                    layer_config,
+                    memory_limit_bytes,
                 ).await
             }
             QuantizationMethod::Gptq => {
@@ -101,17 +340,20 @@ impl QuantizationService {
                     return Err(AppError::GpuRequired);
                 }
                 
-                // Quantification GPTQ 4-bit
-                self.python_client.call_script(
+                // Quantification GPTQ (4 bits par défaut)
+                self.call_quantize_script(
                     "quantize_gptq.py",
-                    &[
+                    vec![
                         "--input", &input_path_str,
                         "--output-dir", &output_dir_str,
-                        "--bits", "4",
-                        "--group-size", "128",
+                        "--bits", &bits,
+                        "--group-size", &group_size,
                         "--damp-percent", "0.1",
+                        "--nsamples", &nsamples,
                         "--act-order",
                     ],
+                    layer_config,
+                    memory_limit_bytes,
                 ).await
             }
             QuantizationMethod::Awq => {
@@ -119,50 +361,116 @@ impl QuantizationService {
                     return Err(AppError::GpuRequired);
                 }
                 
-                // Quantification AWQ 4-bit
-                self.python_client.call_script(
+                // Quantification AWQ (4 bits par défaut)
+                self.call_quantize_script(
                     "quantize_awq.py",
-                    &[
+                    vec![
                         "--input", &input_path_str,
                         "--output-dir", &output_dir_str,
-                        "--bits", "4",
-                        "--group-size", "128",
+                        "--bits", &bits,
+                        "--group-size", &group_size,
+                        "--nsamples", &nsamples,
                         "--zero-point",
                     ],
+                    layer_config,
+                    memory_limit_bytes,
                 ).await
             }
             QuantizationMethod::GgufQ4_0 => {
                 // Conversion en GGUF Q4_0
-                self.convert_to_gguf(&input_path_str, output_dir, "q4_0").await
+                self.convert_to_gguf(&input_path_str, output_dir, "q4_0", memory_limit_bytes).await
             }
             QuantizationMethod::GgufQ5_0 => {
                 // Conversion en GGUF Q5_0
-                self.convert_to_gguf(&input_path_str, output_dir, "q5_0").await
+                self.convert_to_gguf(&input_path_str, output_dir, "q5_0", memory_limit_bytes).await
             }
         }
     }
 
+    /// Lancer l'export GGUF du modèle source en parallèle du reste du pipeline
+    ///
+    /// L'export attend un permis dans la tâche elle-même: l'appelant poursuit
+    /// (mesure de qualité, rapport) et joint la tâche avant de terminer le job.
+    pub fn spawn_gguf_export(
+        self: &Arc<Self>,
+        input_path: &str,
+        export: GgufExport,
+        workspace: &TempWorkspace,
+        memory_limit_bytes: Option<u64>,
+    ) -> Result<ExportTask> {
+        let export_dir = workspace.join(GGUF_EXPORT_DIR)?;
+        let input_path = input_path.to_string();
+        let service = Arc::clone(self);
+
+        let handle = tokio::spawn(async move {
+            let _permit = service.export_semaphore.acquire().await
+                .map_err(|_| AppError::ResourceBusy)?;
+
+            tokio::fs::create_dir_all(&export_dir).await?;
+            let (output_path, peak_memory_bytes) = service
+                .convert_to_gguf(&input_path, &export_dir, export.as_str(), memory_limit_bytes)
+                .await
+                .map_err(|e| AppError::ConversionFailed(format!("export GGUF: {}", e)))?;
+
+            Ok(QuantizationOutput { output_path, peak_memory_bytes })
+        });
+
+        Ok(ExportTask { handle })
+    }
+
+    /// Lancer un script de quantification, avec la configuration par couche si présente
+    async fn call_quantize_script(
+        &self,
+        script_name: &str,
+        mut args: Vec<&str>,
+        layer_config: Option<&str>,
+        memory_limit_bytes: Option<u64>,
+    ) -> Result<(String, Option<u64>)> {
+        if let Some(path) = layer_config {
+            args.extend(["--layer-config", path]);
+        }
+
+        let output = self.python_client
+            .call_script_with_memory_limit(script_name, &args, memory_limit_bytes)
+            .await?;
+        Ok((output.stdout, output.peak_memory_bytes))
+    }
+
+    /// Couches dont la précision a été surchargée, d'après le rapport du script (best effort)
+    pub async fn overridden_layers(&self, workspace: &TempWorkspace) -> Vec<String> {
+        let Ok(path) = workspace.join(OVERRIDDEN_LAYERS_FILE) else {
+            return Vec::new();
+        };
+
+        match tokio::fs::read(&path).await {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+
     /// Convertir en format GGUF
     async fn convert_to_gguf(
         &self,
         input_path: &str,
         output_dir: &Path,
         quantization: &str,
-    ) -> Result<String> {
+        memory_limit_bytes: Option<u64>,
+    ) -> Result<(String, Option<u64>)> {
         let output_path = output_dir.join("model.gguf");
         let output_path_str = output_path.to_string_lossy();
 
         // Utiliser llama.cpp ou un script Python
-        self.python_client.call_script(
+        let output = self.python_client.call_script_with_memory_limit(
             "convert_gguf.py",
             &[
                 "--input", input_path,
                 "--output", &output_path_str,
                 "--quantization", quantization,
             ],
+            memory_limit_bytes,
         ).await?;
 
-        Ok(output_path_str.to_string())
+        Ok((output_path_str.to_string(), output.peak_memory_bytes))
     }
 
     /// Analyser un modèle pour extraire des métadonnées
@@ -179,6 +487,38 @@ impl QuantizationService {
         Ok(analysis)
     }
 
+    /// Analyser un modèle avec les statistiques de poids par couche (lecture seule, plus coûteux)
+    pub async fn analyze_model_detailed(&self, model_path: &str) -> Result<ModelAnalysis> {
+        let result = self.python_client.call_script(
+            "analyze_model.py",
+            &["--model", model_path, "--weight-stats"],
+        ).await?;
+
+        let analysis: ModelAnalysis = serde_json::from_str(&result)
+            .map_err(|e| AppError::ParseError(e.to_string()))?;
+
+        Ok(analysis)
+    }
+
+    /// Mesurer la qualité du modèle quantifié face à l'original
+    ///
+    /// Attend un permis de validation: indépendant des permis de quantification,
+    /// il borne la mémoire quand plusieurs jobs terminent en même temps.
+    pub async fn validate_quality(&self, original_path: &str, quantized_path: &str) -> Result<QualityMetrics> {
+        let _permit = self.validation_semaphore.acquire().await
+            .map_err(|_| AppError::ResourceBusy)?;
+
+        let result = self.python_client.call_script(
+            "validate_quality.py",
+            &["--original", original_path, "--quantized", quantized_path],
+        ).await?;
+
+        let metrics: QualityMetrics = serde_json::from_str(&result)
+            .map_err(|e| AppError::ParseError(e.to_string()))?;
+
+        Ok(metrics)
+    }
+
     /// Vérifier la santé du service Python
     pub async fn health_check(&self) -> Result<()> {
         // Vérifier que Python est accessible
@@ -208,6 +548,7 @@ impl QuantizationService {
             "quantize_gptq.py",
             "quantize_awq.py",
             "convert_gguf.py",
+            "convert_onnx.py",
             "analyze_model.py",
         ];
 
@@ -222,16 +563,20 @@ impl QuantizationService {
         Ok(true)
     }
 
-    /// Nettoyer les fichiers temporaires
+    /// Nettoyer les fichiers temporaires (répertoires worker et results)
     pub async fn cleanup_old_files(&self, max_age_days: i64) -> Result<u64> {
         let mut deleted = 0;
         
-        if let Ok(entries) = tokio::fs::read_dir(&self.work_dir).await {
+        for dir in [&self.work_dirs.worker, &self.work_dirs.results] {
+            let Ok(entries) = tokio::fs::read_dir(dir).await else {
+                continue;
+            };
             let mut entries = tokio_stream::wrappers::ReadDirStream::new(entries);
             
             while let Some(entry) = entries.next().await {
                 if let Ok(entry) = entry {
                     let metadata = entry.metadata().await.ok();
+                    let is_dir = metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false);
                     let is_old = metadata
                         .and_then(|m| m.modified().ok())
                         .map(|modified| {
@@ -243,7 +588,11 @@ impl QuantizationService {
                         .unwrap_or(false);
 
                     if is_old {
-                        let _ = tokio::fs::remove_dir_all(entry.path()).await;
+                        let _ = if is_dir {
+                            tokio::fs::remove_dir_all(entry.path()).await
+                        } else {
+                            tokio::fs::remove_file(entry.path()).await
+                        };
                         deleted += 1;
                     }
                 }
@@ -261,7 +610,8 @@ impl Clone for QuantizationService {
             gpu_enabled: self.gpu_enabled,
             timeout_seconds: self.timeout_seconds,
             max_retries: self.max_retries,
-            work_dir: self.work_dir.clone(),
+            work_dirs: self.work_dirs.clone(),
+            keep_workspaces: self.keep_workspaces,
             semaphore: self.semaphore.clone(),
         }
     }
@@ -277,6 +627,284 @@ pub struct ModelAnalysis {
     pub layers: i32,
     pub vocab_size: Option<i32>,
     pub context_length: Option<i32>,
+    /// Dimension cachée (taille des couches linéaires)
+    #[serde(default)]
+    pub hidden_size: Option<i32>,
     pub file_size_bytes: u64,
     pub supported_quantizations: Vec<String>,
-}
\ No newline at end of file
+    /// Statistiques des poids (uniquement pour l'analyse détaillée)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight_stats: Option<WeightStats>,
+}
+
+/// Statistiques des poids d'un modèle, pour expliquer une mauvaise quantification
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WeightStats {
+    pub layers: Vec<LayerWeightStats>,
+    /// Proportion globale de poids aberrants (|w - mean| > 6 std)
+    pub outlier_ratio: f64,
+}
+
+/// Statistiques des poids d'une couche
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LayerWeightStats {
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std: f64,
+    pub outlier_ratio: f64,
+}
+
+/// Métriques de qualité avant/après quantification
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct QualityMetrics {
+    pub perplexity_before: Option<f64>,
+    pub perplexity_after: Option<f64>,
+    pub latency_before_ms: Option<f64>,
+    pub latency_after_ms: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+
+    #[test]
+    fn disk_precheck_rejects_models_larger_than_free_space() {
+        let config = testing::config();
+        let quantizer = testing::quantizer(&config);
+        let available = crate::utils::helpers::available_disk_space(Path::new(&config.work_dir)).unwrap();
+
+        // Modèle de la taille de l'espace libre: la marge de sécurité ne tient pas
+        let err = quantizer.check_disk_space(available.max(1)).unwrap_err();
+        assert!(matches!(err, AppError::InsufficientDiskSpace(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn disk_precheck_accepts_small_models() {
+        let config = testing::config();
+        let quantizer = testing::quantizer(&config);
+
+        assert!(quantizer.check_disk_space(1024).is_ok());
+    }
+
+    #[test]
+    fn enospc_maps_to_insufficient_disk_space() {
+        let err = AppError::from(std::io::Error::from_raw_os_error(28));
+        assert!(matches!(err, AppError::InsufficientDiskSpace(_)), "{:?}", err);
+
+        let err = AppError::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(matches!(err, AppError::StorageError(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn pytorch_input_is_converted_to_onnx_before_int8() {
+        assert!(QuantizationService::needs_onnx_conversion(&QuantizationMethod::Int8, &ModelFormat::PyTorch));
+        assert!(QuantizationService::needs_onnx_conversion(&QuantizationMethod::Int8, &ModelFormat::Safetensors));
+
+        // Déjà en ONNX, ou méthode qui lit directement les poids PyTorch
+        assert!(!QuantizationService::needs_onnx_conversion(&QuantizationMethod::Int8, &ModelFormat::Onnx));
+        assert!(!QuantizationService::needs_onnx_conversion(&QuantizationMethod::Gptq, &ModelFormat::PyTorch));
+    }
+
+    #[tokio::test]
+    async fn untraceable_model_fails_the_conversion_with_a_clear_message() {
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(&[(
+            "convert_onnx.py",
+            "import sys\nsys.stderr.write('UNTRACEABLE_MODEL: data-dependent control flow')\nsys.exit(1)\n",
+        )]);
+        let quantizer = testing::quantizer(&config);
+        let workspace = quantizer.create_workspace(Uuid::new_v4()).unwrap();
+
+        let err = quantizer.convert_to_onnx("model.bin", &workspace).await.unwrap_err();
+        match err {
+            AppError::ConversionFailed(message) => assert!(message.contains("flot de contrôle dynamique"), "{}", message),
+            other => panic!("erreur inattendue: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn detailed_analysis_reports_weight_stats_that_steer_to_awq() {
+        // Petit modèle factice: deux couches, la seconde chargée en poids aberrants
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(&[(
+            "analyze_model.py",
+            "import json, sys\n\
+             analysis = {'model_type': 'llama', 'architecture': 'LlamaForCausalLM', 'parameter_count': 0.1,\n\
+             'quantization_bits': None, 'layers': 2, 'vocab_size': 32000, 'context_length': 2048,\n\
+             'file_size_bytes': 1024, 'supported_quantizations': ['int8', 'gptq', 'awq']}\n\
+             if '--weight-stats' in sys.argv:\n\
+             \x20   analysis['weight_stats'] = {'outlier_ratio': 0.004, 'layers': [\n\
+             \x20       {'name': 'layers.0.mlp', 'min': -0.2, 'max': 0.2, 'mean': 0.0, 'std': 0.05, 'outlier_ratio': 0.0},\n\
+             \x20       {'name': 'layers.1.mlp', 'min': -9.5, 'max': 11.0, 'mean': 0.01, 'std': 0.06, 'outlier_ratio': 0.008}]}\n\
+             print(json.dumps(analysis))\n",
+        )]);
+        let quantizer = testing::quantizer(&config);
+
+        let quick = quantizer.analyze_model("model.safetensors").await.unwrap();
+        assert!(quick.weight_stats.is_none());
+
+        let detailed = quantizer.analyze_model_detailed("model.safetensors").await.unwrap();
+        let stats = detailed.weight_stats.as_ref().expect("statistiques des poids");
+        assert_eq!(stats.layers.len(), 2);
+        assert_eq!(stats.layers[1].name, "layers.1.mlp");
+        assert!(stats.layers[1].max > stats.layers[0].max);
+
+        let recommendation = crate::core::analysis::recommend_method(&detailed);
+        assert!(matches!(recommendation.method, QuantizationMethod::Awq), "{:?}", recommendation);
+    }
+
+    #[tokio::test]
+    async fn skipped_layers_reach_the_quantizer_config() {
+        // Script factice: le "modèle quantifié" est la configuration reçue
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(&[(
+            "quantize_int8.py",
+            "import os, shutil, sys\n\
+             args = sys.argv[1:]\n\
+             path = os.path.join(args[args.index('--output-dir') + 1], 'config.json')\n\
+             shutil.copy(args[args.index('--layer-config') + 1], path)\n\
+             sys.stdout.write(path)\n",
+        )]);
+        let quantizer = testing::quantizer(&config);
+        let workspace = quantizer.create_workspace(Uuid::new_v4()).unwrap();
+        let input = workspace.join("model.onnx").unwrap();
+        std::fs::write(&input, b"onnx").unwrap();
+
+        use crate::models::LayerOverride;
+        let job_config = QuantizationConfig {
+            layer_overrides: vec![
+                LayerOverride { pattern: "lm_head".to_string(), bits: None },
+                LayerOverride { pattern: "model.layers.*.mlp.down_proj".to_string(), bits: Some(8) },
+            ],
+            ..Default::default()
+        };
+        let output = quantizer
+            .quantize(&input.to_string_lossy(), &QuantizationMethod::Int8, &ModelFormat::Onnx, &job_config, &workspace, None)
+            .await
+            .unwrap();
+
+        let sent: serde_json::Value = serde_json::from_slice(&std::fs::read(&output.output_path).unwrap()).unwrap();
+        assert_eq!(sent["skip"], serde_json::json!(["lm_head"]));
+        assert_eq!(sent["overrides"][0]["pattern"], "model.layers.*.mlp.down_proj");
+        assert!(!sent["overrides"].as_array().unwrap().iter().any(|o| o["pattern"] == "lm_head"));
+
+        assert!(job_config.layer_overrides[1].matches("model.layers.12.mlp.down_proj"));
+        assert!(!job_config.layer_overrides[0].matches("model.layers.0.self_attn.q_proj"));
+    }
+
+    #[tokio::test]
+    async fn calibration_samples_are_clamped_and_forwarded() {
+        // Script factice: le "modèle quantifié" est la ligne de commande reçue
+        let mut config = testing::config();
+        config.quantization_gpu_enabled = true;
+        config.quantization_python_path = testing::python_scripts(&[(
+            "quantize_gptq.py",
+            "import json, os, sys\n\
+             args = sys.argv[1:]\n\
+             path = os.path.join(args[args.index('--output-dir') + 1], 'args.json')\n\
+             open(path, 'w').write(json.dumps(args))\n\
+             sys.stdout.write(path)\n",
+        )]);
+        let quantizer = testing::quantizer(&config);
+        let workspace = quantizer.create_workspace(Uuid::new_v4()).unwrap();
+        let input = workspace.join("model.safetensors").unwrap();
+        std::fs::write(&input, b"poids").unwrap();
+
+        // 1000 demandés, 512 au plus pour un abonnement Starter
+        let mut job_config = QuantizationConfig { calibration_samples: Some(1000), ..Default::default() };
+        job_config.clamp_calibration_samples(crate::models::SubscriptionPlan::Starter.max_calibration_samples());
+        let output = quantizer
+            .quantize(&input.to_string_lossy(), &QuantizationMethod::Gptq, &ModelFormat::Safetensors, &job_config, &workspace, None)
+            .await
+            .unwrap();
+
+        let args: Vec<String> = serde_json::from_slice(&std::fs::read(&output.output_path).unwrap()).unwrap();
+        let nsamples = args.iter().position(|arg| arg == "--nsamples").unwrap();
+        assert_eq!(args[nsamples + 1], "512");
+    }
+
+    #[tokio::test]
+    async fn probe_marks_methods_without_backend_unavailable() {
+        let mut config = testing::config();
+        config.quantization_gpu_enabled = true;
+        let quantizer = testing::quantizer_with_python(&config, &testing::python_with_modules(&["auto_gptq"]));
+
+        // Avant la sonde, rien n'est refusé
+        assert!(quantizer.check_method_available(&QuantizationMethod::Awq).is_ok());
+
+        let capabilities = quantizer.probe_capabilities().await;
+        assert!(capabilities.unavailable_reason(&QuantizationMethod::Gptq).is_none());
+        assert!(capabilities.unavailable_reason(&QuantizationMethod::Int8).is_none());
+
+        let err = quantizer.check_method_available(&QuantizationMethod::Awq).unwrap_err();
+        assert!(matches!(err, AppError::MethodUnavailable(_)), "{:?}", err);
+        assert!(err.to_string().contains("autoawq"));
+    }
+
+    #[tokio::test]
+    async fn group_size_is_forwarded_to_the_script() {
+        let mut config = testing::config();
+        config.quantization_gpu_enabled = true;
+        config.quantization_python_path = testing::python_scripts(&[(
+            "quantize_awq.py",
+            "import json, os, sys\n\
+             args = sys.argv[1:]\n\
+             path = os.path.join(args[args.index('--output-dir') + 1], 'args.json')\n\
+             open(path, 'w').write(json.dumps(args))\n\
+             sys.stdout.write(path)\n",
+        )]);
+        let quantizer = testing::quantizer(&config);
+        let workspace = quantizer.create_workspace(Uuid::new_v4()).unwrap();
+        let input = workspace.join("model.safetensors").unwrap();
+        std::fs::write(&input, b"poids").unwrap();
+
+        let job_config = QuantizationConfig { group_size: Some(64), ..Default::default() };
+        let output = quantizer
+            .quantize(&input.to_string_lossy(), &QuantizationMethod::Awq, &ModelFormat::Safetensors, &job_config, &workspace, None)
+            .await
+            .unwrap();
+
+        let args: Vec<String> = serde_json::from_slice(&std::fs::read(&output.output_path).unwrap()).unwrap();
+        let group_size = args.iter().position(|arg| arg == "--group-size").unwrap();
+        assert_eq!(args[group_size + 1], "64");
+    }
+
+    #[tokio::test]
+    async fn validations_serialize_with_a_single_permit() {
+        // Chaque validation note son intervalle d'exécution
+        let intervals = testing::scratch_dir("validations").join("intervals.log");
+        let script = format!(
+            "import json, time\nstart = time.time()\ntime.sleep(0.5)\n\
+             open({:?}, 'a').write('%f %f\\n' % (start, time.time()))\n\
+             print(json.dumps({{'perplexity_before': 10.0, 'perplexity_after': 10.1}}))\n",
+            intervals.to_string_lossy()
+        );
+        let mut config = testing::config();
+        config.quantization_python_path = testing::python_scripts(&[("validate_quality.py", script.as_str())]);
+        config.quality_validation_max_concurrent = 1;
+        config.quantization_max_concurrent_jobs = 3;
+        let quantizer = testing::quantizer(&config);
+
+        let (a, b, c) = tokio::join!(
+            quantizer.validate_quality("a.bin", "a-q.bin"),
+            quantizer.validate_quality("b.bin", "b-q.bin"),
+            quantizer.validate_quality("c.bin", "c-q.bin"),
+        );
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+
+        let mut runs: Vec<(f64, f64)> = std::fs::read_to_string(&intervals)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let (start, end) = line.split_once(' ').unwrap();
+                (start.parse().unwrap(), end.parse().unwrap())
+            })
+            .collect();
+        runs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(runs.len(), 3);
+        assert!(runs.windows(2).all(|pair| pair[0].1 <= pair[1].0), "{:?}", runs);
+    }
+}
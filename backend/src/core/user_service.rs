@@ -1,7 +1,8 @@
 // core/user_service.rs
 use crate::models::{
-    User, NewUser, UserProfile, AuthToken, 
-    Subscription, SubscriptionPlan
+    User, NewUser, UserProfile, AuthToken, ImpersonationToken, ApiKey, IssuedApiKey,
+    Subscription, SubscriptionPlan, AdminUserSummary, UserSearchFilter, Pagination,
+    UserWebhook, NewUserWebhook, IssuedUserWebhook, SUBSCRIPTION_WEBHOOK_EVENTS
 };
 use crate::services::database::Database;
 use crate::services::cache::Cache;
@@ -12,6 +13,9 @@ use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Nombre maximum de webhooks par utilisateur
+const MAX_WEBHOOKS_PER_USER: usize = 10;
+
 pub struct UserService {
     db: Arc<Database>,
     cache: Arc<Cache>,
@@ -134,6 +138,36 @@ impl UserService {
         }
     }
 
+    /// Générer un token d'impersonation pour qu'un admin voie le compte d'un utilisateur
+    pub async fn generate_impersonation_token(
+        &self,
+        admin_id: Uuid,
+        target_user_id: Uuid,
+        ttl_minutes: i64,
+    ) -> Result<ImpersonationToken> {
+        if admin_id == target_user_id {
+            return Err(AppError::Validation("Impossible de s'impersonner soi-même".to_string()));
+        }
+
+        let target = self.db.get_user_by_id(target_user_id).await?;
+
+        let access_token = crate::utils::security::generate_impersonation_token(
+            target.id,
+            &target.email,
+            admin_id,
+            &self.jwt_secret,
+            ttl_minutes,
+        );
+
+        Ok(ImpersonationToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: ttl_minutes * 60,
+            user_id: target.id,
+            impersonator_id: admin_id,
+        })
+    }
+
     /// Rafraîchir un token
     pub async fn refresh_auth_token(&self, refresh_token: &str) -> Result<AuthToken> {
         let claims = jwt::verify_refresh_token(refresh_token, &self.jwt_secret)?;
@@ -168,23 +202,96 @@ impl UserService {
         Ok(user.email == self.admin_email)
     }
 
-    /// Créer une clé API
-    pub async fn create_api_key(&self, user_id: Uuid, name: &str, permissions: &[String]) -> Result<String> {
-        let api_key = password::generate_api_key();
+    /// Créer une clé API (le secret n'est retourné qu'ici)
+    pub async fn create_api_key(&self, user_id: Uuid, name: &str, permissions: &[String]) -> Result<IssuedApiKey> {
+        let secret = password::generate_api_key();
         
-        self.db.create_api_key(
+        let key = self.db.create_api_key(
             user_id,
-            &api_key,
+            &crate::utils::security::hash_api_key(&secret),
+            &api_key_prefix(&secret),
             name,
             permissions,
         ).await?;
 
-        Ok(api_key)
+        Ok(IssuedApiKey { key, secret })
+    }
+
+    /// Lister les clés API d'un utilisateur (sans secret)
+    pub async fn get_user_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        self.db.list_user_api_keys(user_id).await
+    }
+
+    /// Révoquer une clé API
+    pub async fn delete_api_key(&self, user_id: Uuid, key_id: Uuid) -> Result<()> {
+        if !self.db.revoke_api_key(user_id, key_id).await? {
+            return Err(AppError::NotFound("Clé API non trouvée".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Enregistrer un webhook (le secret de signature n'est renvoyé qu'ici)
+    pub async fn register_webhook(&self, user_id: Uuid, req: &NewUserWebhook) -> Result<IssuedUserWebhook> {
+        if let Some(unknown) = req.event_types.iter().find(|e| !SUBSCRIPTION_WEBHOOK_EVENTS.contains(&e.as_str())) {
+            return Err(AppError::Validation(format!(
+                "Type d'événement inconnu: {} (attendus: {})",
+                unknown,
+                SUBSCRIPTION_WEBHOOK_EVENTS.join(", ")
+            )));
+        }
+        
+        // HTTPS vers une adresse publique uniquement (pas de requête vers le réseau interne)
+        crate::services::webhook::check_webhook_destination(&req.url).await?;
+        
+        let existing = self.db.list_user_webhooks(user_id).await?;
+        if existing.len() >= MAX_WEBHOOKS_PER_USER {
+            return Err(AppError::Validation(format!(
+                "Nombre maximum de webhooks atteint ({})",
+                MAX_WEBHOOKS_PER_USER
+            )));
+        }
+        
+        let mut event_types = req.event_types.clone();
+        event_types.sort();
+        event_types.dedup();
+        
+        let secret = format!("whsec_{}", crate::utils::security::generate_random_string(32));
+        let webhook = self.db.create_user_webhook(user_id, &req.url, &secret, &event_types).await?;
+        
+        Ok(IssuedUserWebhook { webhook, secret })
+    }
+    
+    /// Lister les webhooks de l'utilisateur
+    pub async fn list_webhooks(&self, user_id: Uuid) -> Result<Vec<UserWebhook>> {
+        self.db.list_user_webhooks(user_id).await
+    }
+    
+    /// Supprimer un webhook
+    pub async fn delete_webhook(&self, user_id: Uuid, webhook_id: Uuid) -> Result<()> {
+        if !self.db.delete_user_webhook(user_id, webhook_id).await? {
+            return Err(AppError::NotFound("Webhook non trouvé".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Émettre un nouveau secret pour une clé API existante (mêmes nom et permissions)
+    pub async fn rotate_api_key(&self, user_id: Uuid, key_id: Uuid) -> Result<IssuedApiKey> {
+        let secret = password::generate_api_key();
+        
+        let key = self.db.rotate_api_key(
+            user_id,
+            key_id,
+            &crate::utils::security::hash_api_key(&secret),
+            &api_key_prefix(&secret),
+        ).await?
+        .ok_or_else(|| AppError::NotFound("Clé API non trouvée".to_string()))?;
+
+        Ok(IssuedApiKey { key, secret })
     }
 
     /// Vérifier une clé API
     pub async fn verify_api_key(&self, api_key: &str) -> Result<(Uuid, Vec<String>)> {
-        self.db.get_api_key_permissions(api_key).await
+        self.db.get_api_key_permissions(&crate::utils::security::hash_api_key(api_key)).await
     }
 
     /// Initialiser la réinitialisation de mot de passe
@@ -207,7 +314,9 @@ impl UserService {
     }
 
     /// Réinitialiser le mot de passe avec un token
-    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<()> {
+    ///
+    /// Retourne l'ID de l'utilisateur concerné.
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<Uuid> {
         let key = format!("password_reset:{}", token);
         
         // Récupérer l'user ID depuis le cache
@@ -224,7 +333,7 @@ impl UserService {
         // Supprimer le token du cache
         self.cache.delete(&key).await?;
         
-        Ok(())
+        Ok(user_id)
     }
 
     /// Changer le mot de passe (avec vérification)
@@ -259,4 +368,52 @@ impl UserService {
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Restaurer un compte supprimé (admin)
+    pub async fn restore_user_account(&self, user_id: Uuid) -> Result<()> {
+        self.db.restore_user(user_id).await
+    }
+
+    /// Rechercher des utilisateurs (admin), avec plan courant et consommation de crédits
+    pub async fn search_users(
+        &self,
+        filter: &UserSearchFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<AdminUserSummary>, i64)> {
+        self.db.search_users(filter, pagination).await
+    }
+}
+
+/// Préfixe affiché d'une clé API (`qnt_` + 4 caractères)
+fn api_key_prefix(secret: &str) -> String {
+    secret.chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::SubscriptionPlan;
+    use crate::utils::testing;
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL et Redis (TEST_DATABASE_URL, TEST_REDIS_URL)"]
+    async fn rotated_key_old_value_stops_authenticating() {
+        let config = testing::config();
+        let db = testing::database().await;
+        let service = testing::user_service(db.clone(), &config).await;
+        let user = testing::create_user(&db, SubscriptionPlan::Starter).await;
+
+        let issued = service.create_api_key(user.id, "ci", &["jobs:read".to_string()]).await.unwrap();
+        let (owner, permissions) = service.verify_api_key(&issued.secret).await.unwrap();
+        assert_eq!(owner, user.id);
+        assert_eq!(permissions, vec!["jobs:read".to_string()]);
+
+        let rotated = service.rotate_api_key(user.id, issued.key.id).await.unwrap();
+        assert_ne!(rotated.secret, issued.secret);
+
+        assert!(service.verify_api_key(&issued.secret).await.is_err());
+        let (owner, permissions) = service.verify_api_key(&rotated.secret).await.unwrap();
+        assert_eq!(owner, user.id);
+        // Les scopes choisis à la création survivent à la rotation
+        assert_eq!(permissions, vec!["jobs:read".to_string()]);
+    }
+}
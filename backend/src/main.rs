@@ -7,9 +7,10 @@ mod utils;
 
 use crate::utils::config::Config;
 use crate::utils::error::Result;
+use crate::utils::workspace::WorkDirs;
 use crate::services::{
-    Database, Cache, JobQueue, FileStorage, 
-    GoogleAuthClient, SendGridClient, PythonClient
+    Database, PoolSettings, Cache, JobQueue, FileStorage, AuditRepository,
+    GoogleAuthClient, SendGridClient, PythonClient, HuggingFaceClient, WebhookNotifier,
 };
 use crate::core::{
     UserService, JobService, QuantizationService,
@@ -18,15 +19,21 @@ use crate::core::{
 use actix_web::{web, App, HttpServer};
 use std::sync::Arc;
 use std::path::Path;
-use tracing_subscriber::{fmt, EnvFilter};
 
 #[actix_web::main]
 async fn main() -> Result<()> {
+    // Vérification préalable (`--check`): rapport puis sortie, sans démarrer le serveur
+    if std::env::args().skip(1).any(|arg| arg == "--check") {
+        let report = crate::core::preflight::run().await;
+        print!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+    
     // 1. Charger la configuration
     let config = Config::from_env()?;
     
     // 2. Initialiser le logging
-    init_logging(&config)?;
+    crate::utils::logging::init(&config)?;
     
     // 3. Initialiser les services d'infrastructure
     let (db, cache, queue, storage) = init_infrastructure(&config).await?;
@@ -34,11 +41,14 @@ async fn main() -> Result<()> {
     // 4. Initialiser les services externes
     let (google_client, email_provider, python_client) = init_external_services(&config);
     
+    // Journal d'audit (partage le pool de la base)
+    let audit = Arc::new(AuditRepository::new(&db));
+    
     // 5. Initialiser les services métier
     let (user_service, job_service, quant_service, billing_service, notification_service) = 
         init_business_services(
             &config, 
-            db, cache, queue.clone(), storage.clone(), 
+            db, cache.clone(), queue.clone(), storage.clone(), 
             google_client, email_provider, python_client
         ).await?;
     
@@ -46,6 +56,9 @@ async fn main() -> Result<()> {
     start_background_workers(
         job_service.clone(), 
         quant_service.clone(), 
+        billing_service.clone(),
+        notification_service.clone(),
+        audit.clone(),
         &config
     );
     
@@ -53,29 +66,12 @@ async fn main() -> Result<()> {
     start_http_server(
         config, 
         user_service, job_service, billing_service, notification_service,
-        queue, storage,
+        queue, storage, cache, audit,
     ).await?;
     
-    Ok(())
-}
-
-/// Initialiser le système de logging
-fn init_logging(config: &Config) -> Result<()> {
-    let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(&config.log_level));
-    
-    if config.logging_format == "json" {
-        tracing_subscriber::fmt()
-            .json()
-            .with_env_filter(filter)
-            .init();
-    } else {
-        tracing_subscriber::fmt()
-            .with_env_filter(filter)
-            .init();
-    }
+    // Exporter les derniers spans avant l'arrêt
+    opentelemetry::global::shutdown_tracer_provider();
     
-    log::info!("Logging initialisé avec niveau: {}", config.log_level);
     Ok(())
 }
 
@@ -91,8 +87,15 @@ async fn init_infrastructure(
     log::info!("Initialisation de l'infrastructure...");
     
     // Base de données
-    let db = Arc::new(Database::new(&config.database_url).await?);
+    let db = Arc::new(Database::new(
+        &config.database_url,
+        config.database_read_url.as_deref(),
+        &PoolSettings::from_config(config),
+    ).await?);
     log::info!("✅ Base de données connectée");
+    if db.has_read_replica() {
+        log::info!("✅ Réplique en lecture connectée");
+    }
     
     // Exécuter les migrations
     db.run_migrations().await?;
@@ -102,7 +105,7 @@ async fn init_infrastructure(
     let cache = Arc::new(
         Cache::new(
             &config.redis_url,
-            Some(&config.redis_queue_prefix),
+            Some(&config.redis_cache_prefix()),
             config.redis_cache_ttl_seconds,
         ).await?
     );
@@ -112,7 +115,8 @@ async fn init_infrastructure(
     let queue = Arc::new(
         JobQueue::new(
             &config.redis_url,
-            Some(&config.redis_queue_prefix),
+            Some(&config.redis_namespace()),
+            config.queue_fair_ratio,
         ).await?
     );
     log::info!("✅ Queue Redis initialisée");
@@ -130,7 +134,9 @@ async fn init_infrastructure(
             Some(&config.storage_encryption_key)
         },
         config.max_file_size_mb,
-    ));
+        config.download_url_expiry_hours,
+        config.storage_retry_policy(),
+    ).with_compression(config.storage_compression_level));
     log::info!("✅ Stockage initialisé (type: {})", config.storage_type);
     
     Ok((db, cache, queue, storage))
@@ -180,6 +186,8 @@ fn init_external_services(
                 log::warn!("SendGrid configuré mais SENDGRID_API_KEY manquant, utilisation du logger");
                 Arc::new(LogEmailProvider)
             }
+        } else if config.enable_email_notifications && config.email_provider == "smtp" {
+            init_smtp_provider(config)
         } else {
             log::info!("📧 Emails en mode log (développement)");
             Arc::new(LogEmailProvider)
@@ -196,6 +204,44 @@ fn init_external_services(
     (google_client, email_provider, python_client)
 }
 
+/// Construire le fournisseur SMTP, ou retomber sur le logger si la configuration est incomplète
+#[cfg(feature = "email")]
+fn init_smtp_provider(config: &Config) -> Arc<dyn crate::core::notification_service::EmailProvider + Send + Sync> {
+    let host = match &config.smtp_host {
+        Some(host) => host,
+        None => {
+            log::warn!("SMTP configuré mais SMTP_HOST manquant, utilisation du logger");
+            return Arc::new(LogEmailProvider);
+        }
+    };
+    let port = config.smtp_port.unwrap_or(if config.smtp_tls { 587 } else { 25 });
+    
+    match crate::services::SmtpEmailProvider::new(
+        host,
+        port,
+        config.smtp_username.clone(),
+        config.smtp_password.clone(),
+        config.smtp_tls,
+        &config.email_from,
+        &config.email_from_name,
+    ) {
+        Ok(provider) => {
+            log::info!("📧 Emails via SMTP ({}:{})", host, port);
+            Arc::new(provider)
+        }
+        Err(e) => {
+            log::warn!("Configuration SMTP invalide ({}), utilisation du logger", e);
+            Arc::new(LogEmailProvider)
+        }
+    }
+}
+
+#[cfg(not(feature = "email"))]
+fn init_smtp_provider(_config: &Config) -> Arc<dyn crate::core::notification_service::EmailProvider + Send + Sync> {
+    log::warn!("SMTP configuré mais la feature 'email' est désactivée, utilisation du logger");
+    Arc::new(LogEmailProvider)
+}
+
 /// Initialiser les services métier
 async fn init_business_services(
     config: &Config,
@@ -225,42 +271,80 @@ async fn init_business_services(
     ));
     log::info!("✅ Service utilisateur initialisé");
     
-    // Service de quantification
-    let work_dir = Path::new("./work").to_path_buf();
-    std::fs::create_dir_all(&work_dir).ok();
+    // Service de quantification (répertoires de travail validés au démarrage)
+    let work_dirs = WorkDirs::prepare(Path::new(&config.work_dir)).map_err(|e| {
+        log::error!("❌ Répertoire de travail inutilisable ({}): {}", config.work_dir, e);
+        e
+    })?;
+    log::info!("✅ Répertoire de travail: {}", config.work_dir);
     
     let quant_service = Arc::new(QuantizationService::new(
         python_client.clone(),
         config.quantization_gpu_enabled,
         config.quantization_timeout_seconds,
         config.quantization_max_retries,
-        work_dir,
+        work_dirs,
+        config.keep_temp_workspaces,
         config.quantization_max_concurrent_jobs,
+        config.gguf_export_max_concurrent,
+        config.quality_validation_max_concurrent,
     ));
     log::info!("✅ Service de quantification initialisé");
     
-    // Service de jobs
-    let job_service = Arc::new(JobService::new(
-        db.clone(),
-        queue.clone(),
-        storage.clone(),
-        quant_service.clone(),
-        config.quantization_max_concurrent_jobs,
+    // Backends Python réellement installés (les méthodes absentes sont refusées à la création)
+    let capabilities = quant_service.probe_capabilities().await;
+    for capability in capabilities.methods.iter().filter(|c| !c.available) {
+        log::warn!(
+            "⚠️ Méthode {} indisponible: {}",
+            capability.method,
+            capability.reason.as_deref().unwrap_or("raison inconnue")
+        );
+    }
+    
+    // Client du hub Hugging Face (modèles source par référence)
+    let hub_client = Arc::new(HuggingFaceClient::new(
+        config.huggingface_token.clone(),
+        config.max_file_size_mb * 1024 * 1024,
     ));
-    log::info!("✅ Service de jobs initialisé");
     
     // Service de facturation
+    // Webhooks utilisateur (événements d'abonnement)
+    let webhook_notifier = Arc::new(WebhookNotifier::new(db.clone(), config.webhook_timeout_seconds));
+    
     let billing_service = Arc::new(BillingService::new(
         db.clone(),
         config.stripe_secret_key.clone().unwrap_or_default(),
         config.stripe_webhook_secret.clone().unwrap_or_default(),
         config.stripe_currency.clone(),
         config.stripe_trial_period_days,
-    ));
+        config.retention_policy(),
+        config.storage_quota_policy(),
+    ).with_webhooks(webhook_notifier));
     log::info!("✅ Service de facturation initialisé");
     
+    // Service de jobs (débite les crédits à la création)
+    let job_service = Arc::new(JobService::new(
+        db.clone(),
+        queue.clone(),
+        storage.clone(),
+        quant_service.clone(),
+        hub_client,
+        cache.clone(),
+        config.quantization_max_concurrent_jobs,
+        config.job_log_max_lines,
+        config.analysis_cache_ttl_seconds as usize,
+        config.active_job_policy(),
+        config.credit_cost_policy(),
+        config.output_format_policy(),
+        config.job_memory_policy(),
+    )
+    .with_experimental_quantization(config.enable_experimental_quantization)
+    .with_billing(billing_service.clone()));
+    log::info!("✅ Service de jobs initialisé");
+    
     // Service de notifications
     let notification_service = Arc::new(NotificationService::new(
+        db.clone(),
         email_provider,
         None, // Pas de SMS pour le MVP
         config.frontend_url.clone(),
@@ -295,15 +379,59 @@ async fn init_admin_user(user_service: &UserService, config: &Config) -> Result<
 fn start_background_workers(
     job_service: Arc<JobService>,
     quant_service: Arc<QuantizationService>,
+    billing_service: Arc<BillingService>,
+    notification_service: Arc<NotificationService>,
+    audit: Arc<AuditRepository>,
     config: &Config,
 ) {
-    // Worker de traitement des jobs
+    // Workers de traitement des jobs (consommateurs de la queue)
+    for consumer in 0..config.queue_consumers.max(1) {
+        let job_service_clone = job_service.clone();
+        tokio::spawn(async move {
+            log::info!("🚀 Démarrage du worker de jobs #{}...", consumer);
+            job_service_clone.start_worker(5).await; // Vérifie toutes les 5 secondes
+        });
+    }
+    
+    // Reprise des jobs jamais arrivés dans Redis (queue indisponible à la création)
     let job_service_clone = job_service.clone();
     tokio::spawn(async move {
-        log::info!("🚀 Démarrage du worker de jobs...");
-        job_service_clone.start_worker(5).await; // Vérifie toutes les 5 secondes
+        let interval = tokio::time::Duration::from_secs(60);
+        
+        loop {
+            tokio::time::sleep(interval).await;
+            
+            match job_service_clone.requeue_unqueued_jobs().await {
+                Ok(requeued) if requeued > 0 => {
+                    log::info!("🔁 {} jobs remis en queue après indisponibilité de Redis", requeued);
+                }
+                Err(e) => log::warn!("Reprise des jobs hors queue impossible: {}", e),
+                _ => {}
+            }
+        }
     });
     
+    // Annulation des jobs bloqués en attente (annulation système)
+    if config.job_pending_timeout_hours > 0 {
+        let job_service_clone = job_service.clone();
+        let max_pending_hours = config.job_pending_timeout_hours;
+        tokio::spawn(async move {
+            let interval = tokio::time::Duration::from_secs(600);
+            
+            loop {
+                tokio::time::sleep(interval).await;
+                
+                match job_service_clone.cancel_stale_pending_jobs(max_pending_hours).await {
+                    Ok(cancelled) if cancelled > 0 => {
+                        log::info!("⏱️ {} jobs annulés après {} h d'attente", cancelled, max_pending_hours);
+                    }
+                    Err(e) => log::warn!("Annulation des jobs en attente impossible: {}", e),
+                    _ => {}
+                }
+            }
+        });
+    }
+    
     // Worker de nettoyage des fichiers temporaires
     let quant_service_clone = quant_service.clone();
     tokio::spawn(async move {
@@ -321,6 +449,93 @@ fn start_background_workers(
         }
     });
     
+    // Worker de purge des fichiers expirés (rétention par plan)
+    let job_service_clone = job_service.clone();
+    let cleanup_interval_hours = config.cleanup_interval_hours.max(1);
+    let job_log_retention_days = config.job_log_retention_days;
+    let orphan_cleanup = config.orphan_cleanup_enabled
+        .then_some((config.orphan_cleanup_grace_hours, config.orphan_cleanup_dry_run));
+    let file_expiry_warning_days = config.file_expiry_warning_days;
+    tokio::spawn(async move {
+        let interval = tokio::time::Duration::from_secs(cleanup_interval_hours * 3600);
+        
+        loop {
+            tokio::time::sleep(interval).await;
+            
+            // Prévenir avant suppression, puis purger ce qui a expiré
+            match job_service_clone.notify_expiring_files(&notification_service, file_expiry_warning_days).await {
+                Ok(notified) if notified > 0 => {
+                    log::info!("📧 {} avertissements d'expiration envoyés", notified);
+                }
+                Err(e) => log::warn!("Erreur lors des avertissements d'expiration: {}", e),
+                _ => {}
+            }
+            
+            match job_service_clone.purge_expired_files().await {
+                Ok(purged) if purged > 0 => {
+                    log::info!("🗑️ {} fichiers expirés purgés", purged);
+                }
+                Err(e) => log::warn!("Erreur lors de la purge des fichiers: {}", e),
+                _ => {}
+            }
+            
+            match job_service_clone.purge_job_logs(job_log_retention_days).await {
+                Ok(deleted) if deleted > 0 => {
+                    log::info!("🗑️ {} lignes de journal de jobs supprimées", deleted);
+                }
+                Err(e) => log::warn!("Erreur lors de la purge des journaux de jobs: {}", e),
+                _ => {}
+            }
+            
+            if let Some((grace_hours, dry_run)) = orphan_cleanup {
+                match job_service_clone.cleanup_orphaned_objects(grace_hours, dry_run).await {
+                    Ok(removed) if removed > 0 && dry_run => {
+                        log::info!("🧹 {} objets orphelins détectés (simulation, rien supprimé)", removed);
+                    }
+                    Ok(removed) if removed > 0 => {
+                        log::info!("🧹 {} objets orphelins supprimés du stockage", removed);
+                    }
+                    Err(e) => log::warn!("Erreur lors du nettoyage des objets orphelins: {}", e),
+                    _ => {}
+                }
+            }
+        }
+    });
+    
+    // Worker de réconciliation des abonnements Stripe (webhooks manqués)
+    tokio::spawn(async move {
+        let interval = tokio::time::Duration::from_secs(3600); // Toutes les heures
+        
+        loop {
+            tokio::time::sleep(interval).await;
+            
+            // Avant la réconciliation, qui avance la fin de période au renouvellement
+            match billing_service.apply_pending_plan_changes(&audit).await {
+                Ok(applied) if applied > 0 => {
+                    log::info!("💳 {} rétrogradations programmées appliquées", applied);
+                }
+                Err(e) => log::warn!("Erreur lors de l'application des rétrogradations: {}", e),
+                _ => {}
+            }
+            
+            match billing_service.reconcile_subscriptions(&audit).await {
+                Ok(corrected) if corrected > 0 => {
+                    log::info!("💳 {} abonnements réalignés sur Stripe", corrected);
+                }
+                Err(e) => log::warn!("Erreur lors de la réconciliation Stripe: {}", e),
+                _ => {}
+            }
+            
+            match billing_service.expire_stale_checkout_sessions().await {
+                Ok(expired) if expired > 0 => {
+                    log::info!("💳 {} sessions de checkout abandonnées expirées", expired);
+                }
+                Err(e) => log::warn!("Erreur lors de l'expiration des sessions de checkout: {}", e),
+                _ => {}
+            }
+        }
+    });
+    
     log::info!("✅ Workers background démarrés");
 }
 
@@ -333,6 +548,8 @@ async fn start_http_server(
     notification_service: Arc<NotificationService>,
     queue: Arc<JobQueue>,
     storage: Arc<FileStorage>,
+    cache: Arc<Cache>,
+    audit: Arc<AuditRepository>,
 ) -> Result<()> {
     let host = config.server_host.clone();
     let port = config.server_port;
@@ -341,23 +558,46 @@ async fn start_http_server(
     log::info!("📊 Mode: {}", config.run_mode);
     log::info!("👷 Workers: {}", config.workers);
     
+    // Mode maintenance, modifiable à chaud par un admin
+    let maintenance = Arc::new(api::maintenance::MaintenanceMode::new(
+        config.maintenance_mode,
+        config.maintenance_retry_after_seconds,
+    ));
+    if config.maintenance_mode {
+        log::warn!("🚧 Mode maintenance actif: trafic non admin refusé");
+    }
+    
+    // Corps limités hors upload (flux multipart, plafonné par MAX_UPLOAD_SIZE_MB)
+    let json_limit = config.max_json_payload_kb * 1024;
+    
     HttpServer::new(move || {
         App::new()
             // Données de configuration
             .app_data(web::Data::new(config.clone()))
             
-            // Services métier
-            .app_data(web::Data::new(user_service.clone()))
-            .app_data(web::Data::new(job_service.clone()))
-            .app_data(web::Data::new(billing_service.clone()))
-            .app_data(web::Data::new(notification_service.clone()))
+            // Taille des corps: 413 au-delà
+            .app_data(api::json_config(json_limit))
+            .app_data(web::PayloadConfig::new(json_limit))
+            
+            // Services métier (Data<T> partageant l'Arc, extrait en web::Data<T>)
+            .app_data(web::Data::from(user_service.clone()))
+            .app_data(web::Data::from(job_service.clone()))
+            .app_data(web::Data::from(billing_service.clone()))
+            .app_data(web::Data::from(notification_service.clone()))
             
             // Services d'infrastructure
-            .app_data(web::Data::new(queue.clone()))
-            .app_data(web::Data::new(storage.clone()))
+            .app_data(web::Data::from(queue.clone()))
+            .app_data(web::Data::from(storage.clone()))
+            .app_data(web::Data::from(cache.clone()))
+            .app_data(web::Data::from(audit.clone()))
+            .app_data(web::Data::from(maintenance.clone()))
             
             // Middleware
+            // Maintenance: 503 pour le trafic non admin (après CORS et normalisation)
+            .wrap(actix_web::middleware::from_fn(api::maintenance::reject_during_maintenance))
             .wrap(actix_web::middleware::Logger::default())
+            // Un span par requête (exporté en OTLP si configuré)
+            .wrap(tracing_actix_web::TracingLogger::default())
             .wrap(actix_cors::Cors::default()
                 .allow_any_origin()
                 .allow_any_method()
@@ -372,6 +612,8 @@ async fn start_http_server(
             // Health check
             .route("/health", web::get().to(health_check))
             .route("/ready", web::get().to(ready_check))
+            // Métriques Prometheus (autoscaling)
+            .route("/metrics", web::get().to(metrics))
     })
     .workers(config.workers)
     .bind((host, port))?
@@ -398,4 +640,24 @@ async fn ready_check() -> actix_web::HttpResponse {
         "status": "ready",
         "timestamp": chrono::Utc::now().to_rfc3339(),
     }))
-}
\ No newline at end of file
+}
+
+/// Métriques Prometheus pour l'autoscaling des workers
+async fn metrics(
+    job_service: web::Data<JobService>,
+    config: web::Data<Config>,
+) -> actix_web::HttpResponse {
+    match job_service.get_scaling_signal(
+        config.scaling_target_drain_seconds,
+        config.scaling_min_workers,
+        config.scaling_max_workers,
+    ).await {
+        Ok(signal) => actix_web::HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(signal.to_prometheus()),
+        Err(e) => {
+            log::warn!("Métriques indisponibles: {}", e);
+            actix_web::HttpResponse::ServiceUnavailable().finish()
+        }
+    }
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 /// Plan d'abonnement
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "subscription_plan", rename_all = "snake_case")]
 pub enum SubscriptionPlan {
     Free,      // Gratuit
@@ -13,7 +13,7 @@ pub enum SubscriptionPlan {
 }
 
 /// État d'un abonnement
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "subscription_status", rename_all = "snake_case")]
 pub enum SubscriptionStatus {
     Active,       // Actif
@@ -52,11 +52,182 @@ pub struct Subscription {
     /// Date d'annulation
     pub cancelled_at: Option<DateTime<Utc>>,
     
+    /// Fin de la période d'essai (None hors essai ou une fois l'essai converti)
+    pub trial_ends_at: Option<DateTime<Utc>>,
+    
     /// Date de création
     pub created_at: DateTime<Utc>,
     
     /// Date de mise à jour
     pub updated_at: DateTime<Utc>,
+    
+    /// Version, incrémentée à chaque écriture (verrou optimiste des crédits)
+    #[serde(skip)]
+    pub version: i64,
+    
+    /// Plan inférieur programmé pour la fin de la période en cours
+    pub pending_plan: Option<SubscriptionPlan>,
+}
+
+/// État d'un abonnement tel que connu par Stripe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeSubscriptionState {
+    pub status: SubscriptionStatus,
+    pub current_period_end: DateTime<Utc>,
+    /// Plan déduit du prix Stripe (None si prix inconnu)
+    pub plan: Option<SubscriptionPlan>,
+    pub price_id: Option<String>,
+    /// Fin de l'essai côté Stripe
+    pub trial_end: Option<DateTime<Utc>>,
+}
+
+/// État d'une session de checkout Stripe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "checkout_session_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CheckoutSessionStatus {
+    Pending,    // En attente de paiement
+    Completed,  // Paiement finalisé
+    Expired,    // Abandonnée ou expirée
+}
+
+/// Session de checkout Stripe persistée localement
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CheckoutSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub stripe_session_id: String,
+    pub plan: SubscriptionPlan,
+    pub status: CheckoutSessionStatus,
+    /// Expiration côté Stripe
+    pub expires_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CheckoutSession {
+    /// Crée une session en attente
+    pub fn new_pending(
+        user_id: Uuid,
+        stripe_session_id: String,
+        plan: SubscriptionPlan,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        let now = Utc::now();
+        
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            stripe_session_id,
+            plan,
+            status: CheckoutSessionStatus::Pending,
+            expires_at,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+    
+    /// Session encore en attente alors que Stripe l'a expirée
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.status == CheckoutSessionStatus::Pending && now >= self.expires_at
+    }
+    
+    /// Marque la session comme finalisée
+    pub fn complete(&mut self) {
+        let now = Utc::now();
+        self.status = CheckoutSessionStatus::Completed;
+        self.completed_at = Some(now);
+        self.updated_at = now;
+    }
+    
+    /// Marque la session comme expirée
+    pub fn expire(&mut self) {
+        self.status = CheckoutSessionStatus::Expired;
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Prix indicatif d'un crédit (plan Starter: 19€ pour 10 crédits)
+pub const EUR_PER_CREDIT: f64 = 1.9;
+
+/// Coût d'un job, calculé avant création et débité à l'identique
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobCost {
+    pub credits: i32,
+    pub estimated_eur: f64,
+}
+
+/// Coût de base en crédits par méthode (configurable par l'opérateur)
+#[derive(Debug, Clone)]
+pub struct CreditCostPolicy {
+    pub int8: i32,
+    pub gptq: i32,
+    pub awq: i32,
+    pub gguf: i32,
+}
+
+impl Default for CreditCostPolicy {
+    fn default() -> Self {
+        Self {
+            int8: 1,
+            gptq: 2,
+            awq: 2,
+            gguf: 1,
+        }
+    }
+}
+
+impl CreditCostPolicy {
+    /// Coût de base d'une méthode, avant facteur de taille
+    pub fn credit_cost(&self, method: &crate::models::QuantizationMethod) -> i32 {
+        use crate::models::QuantizationMethod;
+        
+        match method {
+            QuantizationMethod::Int8 => self.int8,
+            QuantizationMethod::Gptq => self.gptq,
+            QuantizationMethod::Awq => self.awq,
+            QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0 => self.gguf,
+        }
+    }
+}
+
+impl JobCost {
+    /// Coût selon la méthode et la taille du modèle
+    ///
+    /// Base selon `CreditCostPolicy`, multipliée par 2 au-delà de 13B
+    /// paramètres et par 3 au-delà de 70B. Le plan Pro est illimité.
+    pub fn estimate(
+        costs: &CreditCostPolicy,
+        plan: &SubscriptionPlan,
+        method: &crate::models::QuantizationMethod,
+        size_bytes: i64,
+        parameter_count: Option<f64>,
+    ) -> Self {
+        if *plan == SubscriptionPlan::Pro {
+            return Self { credits: 0, estimated_eur: 0.0 };
+        }
+        
+        let base_cost = costs.credit_cost(method);
+        
+        // Nombre de paramètres (milliards), sinon estimé depuis la taille en fp16
+        let params = parameter_count.unwrap_or(size_bytes.max(0) as f64 / 2e9);
+        let size_factor = if params > 70.0 {
+            3 // Modèles très larges
+        } else if params > 13.0 {
+            2 // Modèles larges
+        } else {
+            1 // Modèles standards
+        };
+        
+        let credits = base_cost * size_factor;
+        
+        Self {
+            credits,
+            estimated_eur: credits as f64 * EUR_PER_CREDIT,
+        }
+    }
 }
 
 /// Informations de crédits
@@ -64,10 +235,92 @@ pub struct Subscription {
 pub struct CreditInfo {
     pub total_credits: i32,
     pub used_credits: i32,
-    pub remaining_credits: i32,
+    /// Solde disponible (None = illimité)
+    pub remaining_credits: Option<i32>,
+    /// Crédits illimités (plan Pro): aucun solde n'est décompté
+    pub unlimited: bool,
     pub reset_date: Option<DateTime<Utc>>,
 }
 
+impl CreditInfo {
+    /// Le solde couvre-t-il `amount` crédits ?
+    pub fn covers(&self, amount: i32) -> bool {
+        self.remaining_credits.map_or(true, |remaining| remaining >= amount)
+    }
+}
+
+/// Fenêtre d'historique utilisée pour projeter la consommation (jours)
+pub const USAGE_PROJECTION_WINDOW_DAYS: i64 = 30;
+
+/// Projection de la consommation jusqu'à la fin de la période
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageProjection {
+    pub plan: SubscriptionPlan,
+    pub window_days: i64,
+    /// Moyennes journalières sur la fenêtre
+    pub credits_per_day: f64,
+    pub jobs_per_day: f64,
+    /// Solde disponible (None = illimité)
+    pub remaining_credits: Option<i32>,
+    pub period_end: DateTime<Utc>,
+    /// Crédits consommés d'ici la fin de la période au rythme actuel
+    pub projected_credits: f64,
+    /// Crédits épuisés avant la fin de la période ?
+    pub will_exhaust: bool,
+    pub exhausted_at: Option<DateTime<Utc>>,
+    /// Plan couvrant le rythme actuel, si un changement s'impose
+    pub suggested_plan: Option<SubscriptionPlan>,
+}
+
+impl UsageProjection {
+    /// Extrapoler la consommation de la fenêtre jusqu'à `period_end`
+    ///
+    /// Les crédits illimités (Pro) ne s'épuisent jamais.
+    pub fn new(
+        plan: SubscriptionPlan,
+        remaining_credits: Option<i32>,
+        credits_consumed: i64,
+        jobs: i64,
+        period_end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let window_days = USAGE_PROJECTION_WINDOW_DAYS;
+        let credits_per_day = credits_consumed as f64 / window_days as f64;
+        let jobs_per_day = jobs as f64 / window_days as f64;
+        
+        let days_left = ((period_end - now).num_seconds() as f64 / 86_400.0).max(0.0);
+        let projected_credits = credits_per_day * days_left;
+        
+        let remaining = remaining_credits.filter(|_| !plan.has_unlimited_credits());
+        let will_exhaust = credits_per_day > 0.0
+            && remaining.map_or(false, |remaining| projected_credits > remaining.max(0) as f64);
+        
+        let exhausted_at = will_exhaust.then(|| {
+            let days = remaining.unwrap_or(0).max(0) as f64 / credits_per_day;
+            now + chrono::Duration::seconds((days * 86_400.0) as i64)
+        });
+        
+        let suggested_plan = if will_exhaust {
+            plan.upgrade_for(credits_per_day * 30.0)
+        } else {
+            None
+        };
+        
+        Self {
+            plan,
+            window_days,
+            credits_per_day,
+            jobs_per_day,
+            remaining_credits,
+            period_end,
+            projected_credits,
+            will_exhaust,
+            exhausted_at,
+            suggested_plan,
+        }
+    }
+}
+
 /// Transaction de crédits
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CreditTransaction {
@@ -106,7 +359,165 @@ pub struct PlanInfo {
     pub features: Vec<String>,
 }
 
+/// Durée de conservation des fichiers par plan (en jours)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub free_days: i32,
+    pub starter_days: i32,
+    pub pro_days: i32,
+}
+
+impl RetentionPolicy {
+    /// Jours de conservation pour un plan
+    pub fn days_for(&self, plan: &SubscriptionPlan) -> i32 {
+        match plan {
+            SubscriptionPlan::Free => self.free_days,
+            SubscriptionPlan::Starter => self.starter_days,
+            SubscriptionPlan::Pro => self.pro_days,
+        }
+    }
+    
+    /// Date d'expiration d'un fichier créé à `created_at`
+    pub fn expires_at(&self, plan: &SubscriptionPlan, created_at: DateTime<Utc>) -> DateTime<Utc> {
+        created_at + chrono::Duration::days(self.days_for(plan) as i64)
+    }
+}
+
+/// Quota d'espace de stockage par plan (en Mo, 0 = illimité)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StorageQuotaPolicy {
+    pub free_mb: u64,
+    pub starter_mb: u64,
+    pub pro_mb: u64,
+}
+
+impl StorageQuotaPolicy {
+    /// Quota en octets pour un plan (None si illimité)
+    pub fn bytes_for(&self, plan: &SubscriptionPlan) -> Option<i64> {
+        let mb = match plan {
+            SubscriptionPlan::Free => self.free_mb,
+            SubscriptionPlan::Starter => self.starter_mb,
+            SubscriptionPlan::Pro => self.pro_mb,
+        };
+        
+        if mb == 0 {
+            None
+        } else {
+            Some((mb * 1024 * 1024) as i64)
+        }
+    }
+}
+
+/// Formats de sortie autorisés par plan (liste vide = tous)
+#[derive(Debug, Clone, Default)]
+pub struct OutputFormatPolicy {
+    pub free: Vec<crate::models::ModelFormat>,
+    pub starter: Vec<crate::models::ModelFormat>,
+    pub pro: Vec<crate::models::ModelFormat>,
+}
+
+impl OutputFormatPolicy {
+    /// Le plan permet-il d'obtenir ce format ?
+    pub fn allows(&self, plan: &SubscriptionPlan, format: &crate::models::ModelFormat) -> bool {
+        let formats = match plan {
+            SubscriptionPlan::Free => &self.free,
+            SubscriptionPlan::Starter => &self.starter,
+            SubscriptionPlan::Pro => &self.pro,
+        };
+        
+        formats.is_empty()
+            || formats.iter().any(|allowed| std::mem::discriminant(allowed) == std::mem::discriminant(format))
+    }
+}
+
+/// Mémoire maximale (Mo) d'un script de quantification par plan (0 = illimitée)
+#[derive(Debug, Clone)]
+pub struct JobMemoryPolicy {
+    pub free_mb: u64,
+    pub starter_mb: u64,
+    pub pro_mb: u64,
+}
+
+impl JobMemoryPolicy {
+    /// Plafond en octets pour un plan (None si illimité)
+    pub fn limit_bytes_for(&self, plan: &SubscriptionPlan) -> Option<u64> {
+        let limit_mb = match plan {
+            SubscriptionPlan::Free => self.free_mb,
+            SubscriptionPlan::Starter => self.starter_mb,
+            SubscriptionPlan::Pro => self.pro_mb,
+        };
+        
+        if limit_mb == 0 {
+            None
+        } else {
+            Some(limit_mb * 1024 * 1024)
+        }
+    }
+}
+
+/// Nombre maximal de jobs en cours (en attente + en traitement) par plan
+#[derive(Debug, Clone)]
+pub struct ActiveJobPolicy {
+    pub free: i64,
+    pub starter: i64,
+    pub pro: i64,
+}
+
+impl ActiveJobPolicy {
+    /// Limite pour un plan (None si illimité)
+    pub fn limit_for(&self, plan: &SubscriptionPlan) -> Option<i64> {
+        let limit = match plan {
+            SubscriptionPlan::Free => self.free,
+            SubscriptionPlan::Starter => self.starter,
+            SubscriptionPlan::Pro => self.pro,
+        };
+        
+        if limit <= 0 {
+            None
+        } else {
+            Some(limit)
+        }
+    }
+}
+
 impl SubscriptionPlan {
+    /// Rang du plan (Free < Starter < Pro)
+    fn rank(&self) -> u8 {
+        match self {
+            SubscriptionPlan::Free => 0,
+            SubscriptionPlan::Starter => 1,
+            SubscriptionPlan::Pro => 2,
+        }
+    }
+    
+    /// Crédits illimités (aucun solde décompté)
+    pub fn has_unlimited_credits(&self) -> bool {
+        self.info().credits_per_month < 0
+    }
+    
+    /// Le plan est-il inférieur à `other` ?
+    pub fn is_lower_than(&self, other: &SubscriptionPlan) -> bool {
+        self.rank() < other.rank()
+    }
+    
+    /// Plan supérieur le moins cher couvrant une consommation mensuelle
+    /// (None si déjà au plan le plus élevé)
+    pub fn upgrade_for(&self, monthly_credits: f64) -> Option<SubscriptionPlan> {
+        let higher: &[SubscriptionPlan] = match self {
+            SubscriptionPlan::Free => &[SubscriptionPlan::Starter, SubscriptionPlan::Pro],
+            SubscriptionPlan::Starter => &[SubscriptionPlan::Pro],
+            SubscriptionPlan::Pro => &[],
+        };
+        
+        higher
+            .iter()
+            .find(|plan| {
+                plan.has_unlimited_credits() || plan.info().credits_per_month as f64 >= monthly_credits
+            })
+            .or(higher.last())
+            .cloned()
+    }
+    
     /// Retourne les informations du plan
     pub fn info(&self) -> PlanInfo {
         match self {
@@ -149,18 +560,23 @@ impl SubscriptionPlan {
         }
     }
     
-    /// Coût en crédits pour un job
-    pub fn job_cost(&self, job_type: &str) -> i32 {
+    /// Coût d'un job pour ce plan (voir `JobCost::estimate`)
+    pub fn job_cost(
+        &self,
+        costs: &CreditCostPolicy,
+        method: &crate::models::QuantizationMethod,
+        size_bytes: i64,
+        parameter_count: Option<f64>,
+    ) -> JobCost {
+        JobCost::estimate(costs, self, method, size_bytes, parameter_count)
+    }
+    
+    /// Échantillons de calibration GPTQ/AWQ autorisés au plus
+    pub fn max_calibration_samples(&self) -> usize {
         match self {
-            SubscriptionPlan::Free => 1, // 1 crédit = 1 job
-            SubscriptionPlan::Starter => match job_type {
-                "int8" => 1,
-                "gptq" => 2,
-                "awq" => 2,
-                "gguf" => 1,
-                _ => 1,
-            },
-            SubscriptionPlan::Pro => 0, // Gratuit pour Pro
+            SubscriptionPlan::Free => 128,
+            SubscriptionPlan::Starter => 512,
+            SubscriptionPlan::Pro => crate::models::MAX_CALIBRATION_SAMPLES,
         }
     }
     
@@ -189,14 +605,34 @@ impl Subscription {
             stripe_subscription_id: None,
             stripe_price_id: None,
             cancelled_at: None,
+            trial_ends_at: None,
             created_at: now,
             updated_at: now,
+            version: 0,
+            pending_plan: None,
         }
     }
     
-    /// Vérifie si l'abonnement est actif
+    /// Vérifie si l'abonnement est actif (période d'essai comprise)
     pub fn is_active(&self) -> bool {
-        self.status == SubscriptionStatus::Active && Utc::now() < self.current_period_end
+        matches!(self.status, SubscriptionStatus::Active | SubscriptionStatus::Trialing)
+            && Utc::now() < self.current_period_end
+    }
+    
+    /// Vérifie si l'abonnement est en période d'essai
+    pub fn is_trialing(&self) -> bool {
+        self.status == SubscriptionStatus::Trialing
+            && self.trial_ends_at.map_or(false, |end| Utc::now() < end)
+    }
+    
+    /// Démarre une période d'essai du plan courant
+    pub fn start_trial(&mut self, trial_days: i64) {
+        let trial_ends_at = self.current_period_start + chrono::Duration::days(trial_days);
+        
+        self.status = SubscriptionStatus::Trialing;
+        self.trial_ends_at = Some(trial_ends_at);
+        self.current_period_end = trial_ends_at;
+        self.updated_at = Utc::now();
     }
     
     /// Met à jour le plan
@@ -210,6 +646,33 @@ impl Subscription {
         self.stripe_subscription_id = stripe_subscription_id;
         self.updated_at = now;
         self.cancelled_at = None;
+        self.trial_ends_at = None;
+        self.pending_plan = None;
+    }
+    
+    /// Programme un plan inférieur pour la fin de la période (le plan courant reste acquis)
+    pub fn schedule_downgrade(&mut self, plan: SubscriptionPlan) {
+        self.pending_plan = Some(plan);
+        self.updated_at = Utc::now();
+    }
+    
+    /// Le plan programmé doit-il s'appliquer à cette date ?
+    pub fn pending_plan_due(&self, now: DateTime<Utc>) -> bool {
+        self.pending_plan.is_some() && now >= self.current_period_end
+    }
+    
+    /// Applique le plan programmé (retourne le plan appliqué)
+    pub fn apply_pending_plan(&mut self) -> Option<SubscriptionPlan> {
+        let plan = self.pending_plan.take()?;
+        
+        if plan == SubscriptionPlan::Free {
+            self.downgrade_to_free();
+        } else {
+            self.plan = plan.clone();
+            self.updated_at = Utc::now();
+        }
+        
+        Some(plan)
     }
     
     /// Annule l'abonnement
@@ -218,4 +681,215 @@ impl Subscription {
         self.cancelled_at = Some(Utc::now());
         self.updated_at = Utc::now();
     }
-}
\ No newline at end of file
+    
+    /// Rétrograde vers Free, comme une annulation locale
+    pub fn downgrade_to_free(&mut self) {
+        self.cancel();
+        self.plan = SubscriptionPlan::Free;
+        self.stripe_subscription_id = None;
+        self.stripe_price_id = None;
+        self.trial_ends_at = None;
+        self.pending_plan = None;
+    }
+    
+    /// Essai terminé sans paiement réussi
+    pub fn trial_payment_failed(&self, remote: &StripeSubscriptionState) -> bool {
+        self.trial_ends_at.is_some() && remote.status == SubscriptionStatus::PastDue
+    }
+    
+    /// Aligne l'abonnement local sur l'état Stripe (retourne true si corrigé)
+    pub fn reconcile_with(&mut self, remote: &StripeSubscriptionState) -> bool {
+        if remote.status == SubscriptionStatus::Cancelled {
+            if self.status == SubscriptionStatus::Cancelled && self.plan == SubscriptionPlan::Free {
+                return false;
+            }
+            self.downgrade_to_free();
+            return true;
+        }
+        
+        // Le premier paiement après l'essai a échoué: pas de période de grâce
+        if self.trial_payment_failed(remote) {
+            self.downgrade_to_free();
+            return true;
+        }
+        
+        let mut changed = false;
+        
+        if self.status != remote.status {
+            self.status = remote.status.clone();
+            self.cancelled_at = None;
+            changed = true;
+        }
+        
+        // Essai en cours: suivre la date de fin Stripe; essai converti: l'oublier
+        let trial_ends_at = if remote.status == SubscriptionStatus::Trialing {
+            remote.trial_end.or(self.trial_ends_at)
+        } else {
+            None
+        };
+        if self.trial_ends_at != trial_ends_at {
+            self.trial_ends_at = trial_ends_at;
+            changed = true;
+        }
+        
+        if self.current_period_end != remote.current_period_end {
+            self.current_period_end = remote.current_period_end;
+            changed = true;
+        }
+        
+        if let Some(plan) = &remote.plan {
+            if &self.plan != plan {
+                self.plan = plan.clone();
+                self.stripe_price_id = remote.price_id.clone();
+                changed = true;
+            }
+        }
+        
+        if changed {
+            self.updated_at = Utc::now();
+        }
+        
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_retention_follows_the_plan() {
+        let retention = RetentionPolicy { free_days: 7, starter_days: 30, pro_days: 90 };
+        let created_at = Utc::now();
+
+        assert_eq!(retention.expires_at(&SubscriptionPlan::Free, created_at), created_at + chrono::Duration::days(7));
+        assert_eq!(retention.expires_at(&SubscriptionPlan::Pro, created_at), created_at + chrono::Duration::days(90));
+    }
+
+    /// Abonnement Starter payé, lié à l'abonnement Stripe `sub_123`
+    fn paid_subscription() -> Subscription {
+        let mut subscription = Subscription::new_free(Uuid::new_v4());
+        subscription.upgrade(SubscriptionPlan::Starter, Some("sub_123".to_string()));
+        subscription
+    }
+
+    fn remote(status: SubscriptionStatus, subscription: &Subscription) -> StripeSubscriptionState {
+        StripeSubscriptionState {
+            status,
+            current_period_end: subscription.current_period_end,
+            plan: Some(subscription.plan.clone()),
+            price_id: None,
+            trial_end: None,
+        }
+    }
+
+    #[test]
+    fn subscription_cancelled_on_stripe_is_deactivated_locally() {
+        let mut subscription = paid_subscription();
+        let cancelled = remote(SubscriptionStatus::Cancelled, &subscription);
+
+        assert!(subscription.reconcile_with(&cancelled));
+        assert!(!subscription.is_active());
+        assert_eq!(subscription.plan, SubscriptionPlan::Free);
+        assert_eq!(subscription.stripe_subscription_id, None);
+
+        // Déjà aligné: aucune nouvelle correction
+        assert!(!subscription.reconcile_with(&cancelled));
+    }
+
+    #[test]
+    fn subscription_in_sync_with_stripe_is_left_alone() {
+        let mut subscription = paid_subscription();
+        let active = remote(SubscriptionStatus::Active, &subscription);
+
+        assert!(!subscription.reconcile_with(&active));
+        assert_eq!(subscription.plan, SubscriptionPlan::Starter);
+    }
+
+    #[test]
+    fn renewed_period_end_is_copied_from_stripe() {
+        let mut subscription = paid_subscription();
+        let mut renewed = remote(SubscriptionStatus::Active, &subscription);
+        renewed.current_period_end = subscription.current_period_end + chrono::Duration::days(30);
+
+        assert!(subscription.reconcile_with(&renewed));
+        assert_eq!(subscription.current_period_end, renewed.current_period_end);
+    }
+
+    #[test]
+    fn four_bit_methods_cost_two_credits_and_scale_with_size() {
+        use crate::models::QuantizationMethod;
+        let costs = CreditCostPolicy::default();
+        let plan = SubscriptionPlan::Starter;
+
+        assert_eq!(JobCost::estimate(&costs, &plan, &QuantizationMethod::Gptq, 0, Some(7.0)).credits, 2);
+        assert_eq!(JobCost::estimate(&costs, &plan, &QuantizationMethod::Awq, 0, Some(7.0)).credits, 2);
+        assert_eq!(JobCost::estimate(&costs, &plan, &QuantizationMethod::Int8, 0, Some(7.0)).credits, 1);
+
+        assert_eq!(JobCost::estimate(&costs, &plan, &QuantizationMethod::Gptq, 0, Some(30.0)).credits, 4);
+        assert_eq!(JobCost::estimate(&costs, &plan, &QuantizationMethod::Gptq, 0, Some(80.0)).credits, 6);
+        // Sans nombre de paramètres: estimé depuis la taille en fp16 (28 Go -> 14B)
+        assert_eq!(JobCost::estimate(&costs, &plan, &QuantizationMethod::Gptq, 28_000_000_000, None).credits, 4);
+
+        let pro = JobCost::estimate(&costs, &SubscriptionPlan::Pro, &QuantizationMethod::Gptq, 0, Some(80.0));
+        assert_eq!(pro.credits, 0);
+    }
+
+    #[test]
+    fn trialing_starter_has_plan_credits_and_reverts_when_payment_fails() {
+        let mut subscription = paid_subscription();
+        subscription.start_trial(14);
+
+        assert!(subscription.is_trialing());
+        assert_eq!(subscription.plan, SubscriptionPlan::Starter);
+        assert_eq!(subscription.plan.info().credits_per_month, 10);
+        assert_eq!(subscription.current_period_end, subscription.trial_ends_at.unwrap());
+
+        // Stripe n'a pas pu prélever à la fin de l'essai
+        let past_due = remote(SubscriptionStatus::PastDue, &subscription);
+        assert!(subscription.trial_payment_failed(&past_due));
+        assert!(subscription.reconcile_with(&past_due));
+        assert_eq!(subscription.plan, SubscriptionPlan::Free);
+        assert!(!subscription.is_trialing());
+    }
+
+    #[test]
+    fn output_formats_are_restricted_per_plan() {
+        use crate::models::ModelFormat;
+
+        let policy = OutputFormatPolicy {
+            free: vec![ModelFormat::Onnx],
+            starter: vec![ModelFormat::Onnx, ModelFormat::Gguf],
+            pro: Vec::new(),
+        };
+
+        assert!(policy.allows(&SubscriptionPlan::Free, &ModelFormat::Onnx));
+        assert!(!policy.allows(&SubscriptionPlan::Free, &ModelFormat::Gguf));
+        assert!(policy.allows(&SubscriptionPlan::Starter, &ModelFormat::Gguf));
+        assert!(!policy.allows(&SubscriptionPlan::Starter, &ModelFormat::Safetensors));
+        assert!(policy.allows(&SubscriptionPlan::Pro, &ModelFormat::Safetensors));
+    }
+
+    #[test]
+    fn usage_projection_extrapolates_the_window_and_flags_exhaustion() {
+        let now = Utc::now();
+        let period_end = now + chrono::Duration::days(15);
+
+        // 30 crédits sur 30 jours: 1/jour, soit 15 d'ici la fin pour 5 restants
+        let projection = UsageProjection::new(SubscriptionPlan::Starter, Some(5), 30, 60, period_end, now);
+        assert!((projection.credits_per_day - 1.0).abs() < 1e-9);
+        assert!((projection.jobs_per_day - 2.0).abs() < 1e-9);
+        assert!((projection.projected_credits - 15.0).abs() < 1e-6);
+        assert!(projection.will_exhaust);
+        assert_eq!(projection.exhausted_at, Some(now + chrono::Duration::days(5)));
+        assert_eq!(projection.suggested_plan, Some(SubscriptionPlan::Pro));
+
+        let light = UsageProjection::new(SubscriptionPlan::Starter, Some(5), 3, 3, period_end, now);
+        assert!(!light.will_exhaust);
+        assert_eq!(light.exhausted_at, None);
+        assert_eq!(light.suggested_plan, None);
+
+        let unlimited = UsageProjection::new(SubscriptionPlan::Pro, Some(0), 3000, 3000, period_end, now);
+        assert!(!unlimited.will_exhaust);
+    }
+}
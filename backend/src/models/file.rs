@@ -53,6 +53,9 @@ pub struct ModelFile {
     
     /// Date d'expiration (nettoyage automatique)
     pub expires_at: Option<DateTime<Utc>>,
+    
+    /// Modèle ONNX auquel ce fichier de données externes est rattaché
+    pub parent_file_id: Option<Uuid>,
 }
 
 /// Pour uploader un fichier
@@ -64,6 +67,54 @@ pub struct FileUpload {
     pub format: ModelFormat,
 }
 
+/// Demande d'URL pour un upload direct vers le stockage
+#[derive(Debug, Clone, Deserialize)]
+pub struct DirectUploadRequest {
+    pub filename: String,
+    /// Taille annoncée, vérifiée contre le quota avant de signer l'URL
+    pub file_size: i64,
+    /// Empreinte SHA-256 du fichier (hexadécimal), signée dans l'URL
+    pub checksum_sha256: String,
+}
+
+/// URL PUT signée pour un upload direct
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectUploadUrl {
+    /// Identifiant à renvoyer à `upload-complete` (ID du futur fichier)
+    pub upload_id: Uuid,
+    pub upload_url: String,
+    /// En-têtes signés à envoyer tels quels avec le PUT (taille, empreinte)
+    pub headers: std::collections::HashMap<String, String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Upload direct en attente de confirmation (conservé en cache)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUpload {
+    pub user_id: Uuid,
+    pub filename: String,
+    /// Clé de dépôt allouée par le serveur (jamais référencée par un fichier)
+    pub staging_key: String,
+    pub file_size: i64,
+    /// Empreinte annoncée (hexadécimal minuscule)
+    pub checksum_sha256: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Confirmation d'un upload direct terminé
+#[derive(Debug, Clone, Deserialize)]
+pub struct DirectUploadComplete {
+    pub upload_id: Uuid,
+}
+
+/// Modèle uploadé avec ses données externes ONNX (vide pour les autres formats)
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadedModel {
+    #[serde(flatten)]
+    pub file: FileMetadata,
+    pub external_data: Vec<FileMetadata>,
+}
+
 /// Pour télécharger un fichier
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileDownload {
@@ -74,6 +125,63 @@ pub struct FileDownload {
     pub expires_at: DateTime<Utc>,
 }
 
+/// Espace de stockage utilisé par un utilisateur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub used_bytes: i64,
+    /// Quota du plan (None = illimité)
+    pub quota_bytes: Option<i64>,
+    pub remaining_bytes: Option<i64>,
+}
+
+impl StorageUsage {
+    pub fn new(used_bytes: i64, quota_bytes: Option<i64>) -> Self {
+        Self {
+            used_bytes,
+            quota_bytes,
+            remaining_bytes: quota_bytes.map(|quota| (quota - used_bytes).max(0)),
+        }
+    }
+    
+    /// Indique si `additional_bytes` tiennent encore dans le quota
+    pub fn allows(&self, additional_bytes: i64) -> bool {
+        match self.quota_bytes {
+            Some(quota) => self.used_bytes + additional_bytes <= quota,
+            None => true,
+        }
+    }
+}
+
+/// État de l'analyse détaillée d'un fichier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "analysis_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisStatus {
+    Pending,    // En attente d'exécution
+    Running,    // En cours
+    Completed,  // Résultat disponible
+    Failed,     // Échec (voir `error`)
+}
+
+/// Analyse détaillée d'un fichier, telle que stockée avec lui
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FileAnalysis {
+    pub file_id: Uuid,
+    /// None si aucune analyse n'a été demandée
+    pub status: Option<AnalysisStatus>,
+    /// Analyse et méthode conseillée (analyse terminée)
+    pub result: Option<sqlx::types::Json<serde_json::Value>>,
+    pub error: Option<String>,
+    pub analyzed_at: Option<DateTime<Utc>>,
+}
+
+impl FileAnalysis {
+    /// Analyse demandée et pas encore terminée
+    pub fn is_in_progress(&self) -> bool {
+        matches!(self.status, Some(AnalysisStatus::Pending | AnalysisStatus::Running))
+    }
+}
+
 /// Métadonnées d'un fichier
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileMetadata {
@@ -117,9 +225,28 @@ impl ModelFile {
             download_expires_at: None,
             created_at: Utc::now(),
             expires_at: Some(Utc::now() + chrono::Duration::days(30)), // Nettoyage après 30 jours
+            parent_file_id: None,
         }
     }
     
+    /// Remplace l'ID généré (fourni par un `IdProvider`)
+    pub fn with_id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+    
+    /// Rattache ce fichier (données externes) à son modèle ONNX
+    pub fn with_parent(mut self, parent_file_id: Uuid) -> Self {
+        self.parent_file_id = Some(parent_file_id);
+        self
+    }
+    
+    /// Définit la date d'expiration (rétention selon le plan)
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+    
     /// Génère un token de téléchargement temporaire
     pub fn generate_download_token(&mut self, validity_hours: i64) -> String {
         use rand::Rng;
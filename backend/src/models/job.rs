@@ -12,11 +12,22 @@ pub enum JobStatus {
     Processing,   // En cours de traitement
     Completed,    // Terminé avec succès
     Failed,       // Échec
-    Cancelled,    // Annulé par l'utilisateur
+    Cancelled,    // Annulé (voir `CancelledBy`)
+}
+
+/// Auteur d'une annulation de job
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "cancelled_by", rename_all = "snake_case")]
+#[serde(rename_all = "lowercase")]
+pub enum CancelledBy {
+    User,         // Propriétaire du job
+    Admin,        // Administrateur
+    System,       // Plateforme (ex: délai d'attente dépassé)
 }
 
 /// Méthode de quantification
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "quantization_method", rename_all = "snake_case")]
 pub enum QuantizationMethod {
     Int8,        // Quantification 8-bit
@@ -26,6 +37,204 @@ pub enum QuantizationMethod {
     GgufQ5_0,    // GGUF Q5_0
 }
 
+impl QuantizationMethod {
+    /// Toutes les méthodes supportées
+    pub const ALL: [QuantizationMethod; 5] = [
+        QuantizationMethod::Int8,
+        QuantizationMethod::Gptq,
+        QuantizationMethod::Awq,
+        QuantizationMethod::GgufQ4_0,
+        QuantizationMethod::GgufQ5_0,
+    ];
+    
+    /// Identifiant textuel (identique à la valeur SQL et à la sérialisation)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuantizationMethod::Int8 => "int8",
+            QuantizationMethod::Gptq => "gptq",
+            QuantizationMethod::Awq => "awq",
+            QuantizationMethod::GgufQ4_0 => "gguf_q4_0",
+            QuantizationMethod::GgufQ5_0 => "gguf_q5_0",
+        }
+    }
+    
+    /// Précision appliquée par défaut (bits)
+    pub fn default_bits(&self) -> u8 {
+        match self {
+            QuantizationMethod::Int8 => 8,
+            QuantizationMethod::Gptq | QuantizationMethod::Awq => 4,
+            QuantizationMethod::GgufQ4_0 => 4,
+            QuantizationMethod::GgufQ5_0 => 5,
+        }
+    }
+    
+    /// La précision et la taille de groupe sont-elles réglables ?
+    pub fn is_tunable(&self) -> bool {
+        matches!(self, QuantizationMethod::Gptq | QuantizationMethod::Awq)
+    }
+    
+    /// La méthode s'exécute-t-elle sur GPU ?
+    pub fn requires_gpu(&self) -> bool {
+        matches!(self, QuantizationMethod::Gptq | QuantizationMethod::Awq)
+    }
+    
+    /// La méthode a-t-elle besoin d'échantillons de calibration ?
+    pub fn requires_calibration(&self) -> bool {
+        matches!(self, QuantizationMethod::Gptq | QuantizationMethod::Awq)
+    }
+    
+    /// Réduction de taille habituelle en % (source FP16 → FP32)
+    pub fn typical_size_reduction(&self) -> (u8, u8) {
+        match self {
+            QuantizationMethod::Int8 => (50, 75),
+            QuantizationMethod::Gptq | QuantizationMethod::Awq => (70, 87),
+            QuantizationMethod::GgufQ4_0 => (70, 86),
+            QuantizationMethod::GgufQ5_0 => (65, 83),
+        }
+    }
+    
+    /// Formats de sortie produits par la méthode (le premier sert par défaut)
+    ///
+    /// GPTQ/AWQ peuvent aussi livrer de l'ONNX, exporté après quantification.
+    pub fn output_formats(&self) -> &'static [ModelFormat] {
+        match self {
+            QuantizationMethod::Int8 => &[ModelFormat::Onnx],
+            QuantizationMethod::Gptq | QuantizationMethod::Awq => &[ModelFormat::Safetensors, ModelFormat::PyTorch, ModelFormat::Onnx],
+            QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0 => &[ModelFormat::Gguf],
+        }
+    }
+    
+    /// Format de sortie par défaut de la méthode
+    pub fn default_output_format(&self) -> ModelFormat {
+        self.output_formats()[0].clone()
+    }
+    
+    /// La méthode peut-elle produire ce format ?
+    pub fn supports_output(&self, format: &ModelFormat) -> bool {
+        self.output_formats()
+            .iter()
+            .any(|supported| std::mem::discriminant(supported) == std::mem::discriminant(format))
+    }
+}
+
+impl Default for QuantizationMethod {
+    /// Méthode système par défaut, sans réglage de l'utilisateur
+    fn default() -> Self {
+        QuantizationMethod::Int8
+    }
+}
+
+impl std::fmt::Display for QuantizationMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for QuantizationMethod {
+    type Err = UnknownQuantizationMethod;
+    
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.trim().to_lowercase();
+        
+        QuantizationMethod::ALL
+            .iter()
+            .find(|method| method.as_str() == normalized)
+            .cloned()
+            .ok_or_else(|| UnknownQuantizationMethod(value.to_string()))
+    }
+}
+
+/// Méthode de quantification inconnue
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownQuantizationMethod(pub String);
+
+impl std::fmt::Display for UnknownQuantizationMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let valid = QuantizationMethod::ALL
+            .iter()
+            .map(|method| method.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Invalid quantization method '{}'. Must be one of: {}", self.0, valid)
+    }
+}
+
+impl std::error::Error for UnknownQuantizationMethod {}
+
+/// Préréglage de quantification pour les utilisateurs non experts
+///
+/// Se déplie en méthode + options; les champs explicites du job priment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuantizationPreset {
+    Balanced,
+    MaxCompression,
+    MaxQuality,
+}
+
+impl QuantizationPreset {
+    pub const ALL: [QuantizationPreset; 3] = [
+        QuantizationPreset::Balanced,
+        QuantizationPreset::MaxCompression,
+        QuantizationPreset::MaxQuality,
+    ];
+    
+    /// Hausse de perplexité tolérée par `max-quality` (%)
+    pub const MAX_QUALITY_LOSS_PERCENT: f64 = 1.0;
+    
+    pub fn description(&self) -> &'static str {
+        match self {
+            QuantizationPreset::Balanced => "INT8: bon compromis taille/qualité, sans calibration",
+            QuantizationPreset::MaxCompression => "AWQ 4 bits: taille minimale, calibration sur GPU",
+            QuantizationPreset::MaxQuality => "INT8 avec contrôle de la perte de qualité",
+        }
+    }
+    
+    /// Méthode retenue par le préréglage
+    pub fn method(&self) -> QuantizationMethod {
+        match self {
+            QuantizationPreset::Balanced | QuantizationPreset::MaxQuality => QuantizationMethod::Int8,
+            QuantizationPreset::MaxCompression => QuantizationMethod::Awq,
+        }
+    }
+    
+    /// Options de quantification du préréglage
+    pub fn config(&self) -> QuantizationConfig {
+        match self {
+            QuantizationPreset::Balanced => QuantizationConfig::default(),
+            QuantizationPreset::MaxCompression => QuantizationConfig {
+                bits: Some(4),
+                group_size: Some(DEFAULT_GROUP_SIZE),
+                ..QuantizationConfig::default()
+            },
+            QuantizationPreset::MaxQuality => QuantizationConfig {
+                max_quality_loss_percent: Some(Self::MAX_QUALITY_LOSS_PERCENT),
+                ..QuantizationConfig::default()
+            },
+        }
+    }
+}
+
+/// Description d'un préréglage pour l'interface
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizationPresetInfo {
+    pub preset: QuantizationPreset,
+    pub description: String,
+    pub quantization_method: QuantizationMethod,
+    pub config: QuantizationConfig,
+}
+
+impl From<QuantizationPreset> for QuantizationPresetInfo {
+    fn from(preset: QuantizationPreset) -> Self {
+        Self {
+            preset,
+            description: preset.description().to_string(),
+            quantization_method: preset.method(),
+            config: preset.config(),
+        }
+    }
+}
+
 /// Format de modèle
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "model_format", rename_all = "snake_case")]
@@ -36,6 +245,111 @@ pub enum ModelFormat {
     Gguf,
 }
 
+impl ModelFormat {
+    pub const ALL: [ModelFormat; 4] = [
+        ModelFormat::PyTorch,
+        ModelFormat::Onnx,
+        ModelFormat::Safetensors,
+        ModelFormat::Gguf,
+    ];
+    
+    /// Nom du format en minuscules (configuration)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelFormat::PyTorch => "pytorch",
+            ModelFormat::Onnx => "onnx",
+            ModelFormat::Safetensors => "safetensors",
+            ModelFormat::Gguf => "gguf",
+        }
+    }
+    
+    /// Formats qu'une extension de fichier peut désigner (vide si inconnue)
+    ///
+    /// `.bin` est ambigu: checkpoint PyTorch (`pytorch_model.bin`) ou safetensors.
+    pub fn for_extension(extension: &str) -> &'static [ModelFormat] {
+        match extension.to_lowercase().as_str() {
+            "pt" | "pth" => &[ModelFormat::PyTorch],
+            "bin" => &[ModelFormat::Safetensors, ModelFormat::PyTorch],
+            "safetensors" => &[ModelFormat::Safetensors],
+            "onnx" => &[ModelFormat::Onnx],
+            "gguf" => &[ModelFormat::Gguf],
+            _ => &[],
+        }
+    }
+}
+
+impl std::str::FromStr for ModelFormat {
+    type Err = String;
+    
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.trim().to_lowercase();
+        
+        ModelFormat::ALL
+            .iter()
+            .find(|format| format.as_str() == normalized)
+            .cloned()
+            .ok_or_else(|| format!("Invalid model format '{}'", value))
+    }
+}
+
+/// Description d'une méthode de quantification pour l'interface
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizationMethodInfo {
+    pub method: QuantizationMethod,
+    pub bits: u8,
+    /// Coût de base en crédits (avant facteur de taille)
+    pub credit_cost: i32,
+    pub requires_gpu: bool,
+    pub requires_calibration: bool,
+    /// Précision et taille de groupe réglables
+    pub tunable: bool,
+    pub output_formats: Vec<ModelFormat>,
+    /// Réduction de taille habituelle (%)
+    pub size_reduction_min_percent: u8,
+    pub size_reduction_max_percent: u8,
+    /// Backend installé sur ce serveur
+    pub available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unavailable_reason: Option<String>,
+}
+
+impl QuantizationMethodInfo {
+    /// Décrire une méthode (disponibilité selon la sonde, si elle a eu lieu)
+    pub fn new(
+        method: &QuantizationMethod,
+        costs: &crate::models::CreditCostPolicy,
+        capabilities: Option<&crate::models::Capabilities>,
+    ) -> Self {
+        let (size_reduction_min_percent, size_reduction_max_percent) = method.typical_size_reduction();
+        let unavailable_reason = capabilities
+            .and_then(|capabilities| capabilities.unavailable_reason(method))
+            .map(str::to_string);
+        
+        Self {
+            method: method.clone(),
+            bits: method.default_bits(),
+            credit_cost: costs.credit_cost(method),
+            requires_gpu: method.requires_gpu(),
+            requires_calibration: method.requires_calibration(),
+            tunable: method.is_tunable(),
+            output_formats: method.output_formats().to_vec(),
+            size_reduction_min_percent,
+            size_reduction_max_percent,
+            available: unavailable_reason.is_none(),
+            unavailable_reason,
+        }
+    }
+}
+
+/// Origine du modèle source d'un job
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobSource {
+    #[default]
+    Upload,      // Fichier uploadé sur la plateforme
+    Huggingface, // Dépôt public du hub Hugging Face
+}
+
 /// Un job de quantification
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Job {
@@ -63,8 +377,14 @@ pub struct Job {
     /// Format du modèle de sortie
     pub output_format: ModelFormat,
     
-    /// ID du fichier modèle source
-    pub input_file_id: Uuid,
+    /// ID du fichier modèle source (absent pour un dépôt Hugging Face)
+    pub input_file_id: Option<Uuid>,
+    
+    /// Dépôt Hugging Face source (ex: "meta-llama/Llama-2-7b-hf")
+    pub source_repo_id: Option<String>,
+    
+    /// Révision du dépôt (branche, tag ou commit)
+    pub source_revision: Option<String>,
     
     /// ID du fichier modèle quantifié (optionnel)
     pub output_file_id: Option<Uuid>,
@@ -87,11 +407,42 @@ pub struct Job {
     /// Date de création
     pub created_at: DateTime<Utc>,
     
+    /// Date de dernière modification (maintenue par trigger)
+    pub updated_at: DateTime<Utc>,
+    
     /// Date de début de traitement
     pub started_at: Option<DateTime<Utc>>,
     
     /// Date de fin de traitement
     pub completed_at: Option<DateTime<Utc>>,
+    
+    /// Rapport de quantification (rempli à la complétion)
+    pub report: Option<sqlx::types::Json<QuantizationReport>>,
+    
+    /// Options de quantification choisies à la création
+    pub quantization_config: Option<sqlx::types::Json<QuantizationConfig>>,
+    
+    /// Nombre d'échecs du job (chaque relance repart d'un échec)
+    pub retry_count: i32,
+    
+    /// Erreurs successives, de la plus ancienne à la plus récente
+    pub error_history: sqlx::types::Json<Vec<JobError>>,
+    
+    /// Auteur de l'annulation (si annulé)
+    pub cancelled_by: Option<CancelledBy>,
+    
+    /// Motif de l'annulation
+    pub cancellation_reason: Option<String>,
+}
+
+/// Nombre maximal d'erreurs conservées dans l'historique d'un job
+pub const MAX_ERROR_HISTORY: usize = 10;
+
+/// Une erreur passée d'un job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobError {
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
 }
 
 /// Pour créer un nouveau job
@@ -100,8 +451,559 @@ pub struct NewJob {
     #[validate(length(min = 1, max = 100, message = "Le nom doit faire entre 1 et 100 caractères"))]
     pub name: String,
     
+    /// Préréglage (méthode + options), surchargé par les champs explicites
+    pub preset: Option<QuantizationPreset>,
+    
+    /// Méthode (à défaut: préréglage, réglage de l'utilisateur, puis méthode système)
+    ///
+    /// Lue en texte et contrôlée par `validate()`, pour qu'une méthode inconnue
+    /// figure dans `details` avec les autres champs; voir `NewJob::method`.
+    #[validate(custom = "validate_method_name")]
+    pub quantization_method: Option<String>,
+    
+    /// Format de sortie (à défaut: réglage de l'utilisateur, puis format de la méthode)
+    pub output_format: Option<ModelFormat>,
+    
+    /// Origine du modèle (upload par défaut)
+    #[serde(default)]
+    pub source: JobSource,
+    
+    /// Dépôt Hugging Face (requis si source = "huggingface")
+    #[validate(length(min = 3, max = 255, message = "Identifiant de dépôt invalide"))]
+    pub repo_id: Option<String>,
+    
+    /// Révision du dépôt (par défaut "main")
+    pub revision: Option<String>,
+    
+    /// Options de quantification (précision par couche, ...)
+    #[serde(default)]
+    pub config: QuantizationConfig,
+}
+
+impl NewJob {
+    /// Méthode demandée (None si absente ou inconnue, ce que `validate()` refuse)
+    pub fn method(&self) -> Option<QuantizationMethod> {
+        self.quantization_method.as_deref().and_then(|value| value.parse().ok())
+    }
+}
+
+fn validate_method_name(value: &str) -> Result<(), validator::ValidationError> {
+    match value.parse::<QuantizationMethod>() {
+        Ok(_) => Ok(()),
+        Err(unknown) => {
+            let mut error = validator::ValidationError::new("unknown_quantization_method");
+            error.message = Some(unknown.to_string().into());
+            Err(error)
+        }
+    }
+}
+
+/// Réglages effectifs d'un job, une fois les valeurs par défaut appliquées
+#[derive(Debug, Clone)]
+pub struct JobSettings {
     pub quantization_method: QuantizationMethod,
     pub output_format: ModelFormat,
+    pub config: QuantizationConfig,
+}
+
+/// Nombre maximal de surcharges de précision par job
+pub const MAX_LAYER_OVERRIDES: usize = 64;
+
+/// Précisions sous 4 bits: expérimentales, activées par configuration
+pub const EXPERIMENTAL_BITS: &[u8] = &[2, 3];
+
+/// Échantillons de calibration (GPTQ/AWQ) quand le job n'en précise pas
+pub const DEFAULT_CALIBRATION_SAMPLES: usize = 128;
+
+/// Borne absolue des échantillons de calibration (le plan peut imposer moins)
+pub const MAX_CALIBRATION_SAMPLES: usize = 2048;
+
+/// Taille de groupe GPTQ/AWQ quand le job n'en précise pas
+pub const DEFAULT_GROUP_SIZE: u32 = 128;
+
+/// Tailles de groupe acceptées (puissances de deux entre ces bornes)
+pub const MIN_GROUP_SIZE: u32 = 32;
+pub const MAX_GROUP_SIZE: u32 = 256;
+
+/// Vérifier une taille de groupe GPTQ/AWQ
+pub fn validate_group_size(group_size: u32) -> Result<(), String> {
+    if !group_size.is_power_of_two() || !(MIN_GROUP_SIZE..=MAX_GROUP_SIZE).contains(&group_size) {
+        return Err(format!(
+            "Taille de groupe invalide: {} (puissance de deux entre {} et {} attendue)",
+            group_size, MIN_GROUP_SIZE, MAX_GROUP_SIZE
+        ));
+    }
+    Ok(())
+}
+
+/// Précision forcée pour les couches dont le nom correspond à un motif
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerOverride {
+    /// Motif du nom de couche, `*` remplaçant n'importe quelle suite de caractères
+    /// (ex: "lm_head", "model.layers.*.mlp.down_proj")
+    pub pattern: String,
+    
+    /// Bits pour ces couches (None = couche laissée en précision d'origine)
+    #[serde(default)]
+    pub bits: Option<u8>,
+}
+
+impl LayerOverride {
+    /// Le nom de couche correspond-il au motif ?
+    pub fn matches(&self, layer_name: &str) -> bool {
+        let mut parts = self.pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        
+        let mut rest = match layer_name.strip_prefix(first) {
+            Some(rest) => rest,
+            None => return false,
+        };
+        
+        let parts: Vec<&str> = parts.collect();
+        let Some((last, middle)) = parts.split_last() else {
+            // Pas de joker: correspondance exacte
+            return rest.is_empty();
+        };
+        
+        for part in middle {
+            match rest.find(part) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+        
+        rest.ends_with(last)
+    }
+}
+
+/// Quantification d'un export GGUF supplémentaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GgufExport {
+    #[serde(rename = "q4_0")]
+    Q4_0,
+    #[serde(rename = "q5_0")]
+    Q5_0,
+}
+
+impl GgufExport {
+    pub const ALL: [GgufExport; 2] = [GgufExport::Q4_0, GgufExport::Q5_0];
+    
+    /// Valeur passée à `convert_gguf.py --quantization`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GgufExport::Q4_0 => "q4_0",
+            GgufExport::Q5_0 => "q5_0",
+        }
+    }
+}
+
+/// Options de quantification propres à un job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuantizationConfig {
+    /// Précision par couche (mixed precision); les autres couches
+    /// utilisent la précision de la méthode
+    #[serde(default)]
+    pub layer_overrides: Vec<LayerOverride>,
+    
+    /// Précision de toutes les couches (GPTQ/AWQ), à défaut celle de la méthode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bits: Option<u8>,
+    
+    /// Taille de groupe (GPTQ/AWQ, puissance de deux de 32 à 256), 128 par défaut
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_size: Option<u32>,
+    
+    /// Échantillons de calibration (GPTQ/AWQ): plus précis mais plus long
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration_samples: Option<usize>,
+    
+    /// Accepter un modèle source déjà quantifié (refusé par défaut)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_quantized_input: bool,
+    
+    /// Hausse de perplexité maximale tolérée (%), au-delà le job échoue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_quality_loss_percent: Option<f64>,
+    
+    /// Export GGUF du modèle source en plus du résultat (PyTorch/safetensors),
+    /// exécuté en parallèle de la mesure de qualité
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gguf_export: Option<GgufExport>,
+}
+
+impl QuantizationConfig {
+    /// Compléter les options non renseignées avec celles d'un préréglage
+    pub fn with_defaults_from(mut self, defaults: QuantizationConfig) -> Self {
+        if self.layer_overrides.is_empty() {
+            self.layer_overrides = defaults.layer_overrides;
+        }
+        self.bits = self.bits.or(defaults.bits);
+        self.group_size = self.group_size.or(defaults.group_size);
+        self.calibration_samples = self.calibration_samples.or(defaults.calibration_samples);
+        self.allow_quantized_input |= defaults.allow_quantized_input;
+        self.max_quality_loss_percent = self.max_quality_loss_percent.or(defaults.max_quality_loss_percent);
+        self.gguf_export = self.gguf_export.or(defaults.gguf_export);
+        self
+    }
+    
+    /// Vérifier les options pour une méthode donnée
+    pub fn validate_for(&self, method: &QuantizationMethod) -> Result<(), String> {
+        if let Some(threshold) = self.max_quality_loss_percent {
+            if !threshold.is_finite() || threshold <= 0.0 || threshold > 100.0 {
+                return Err("La perte de qualité maximale doit être comprise entre 0 et 100 %".to_string());
+            }
+        }
+        
+        if self.gguf_export.is_some()
+            && matches!(method, QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0)
+        {
+            return Err(format!("Export GGUF supplémentaire inutile pour {}", method));
+        }
+        
+        if (self.bits.is_some() || self.group_size.is_some()) && !method.is_tunable() {
+            return Err(format!("Précision et taille de groupe non réglables pour {}", method));
+        }
+        if let Some(bits) = self.bits {
+            if !matches!(bits, 2 | 3 | 4 | 8) {
+                return Err(format!("Précision non supportée: {} bits", bits));
+            }
+        }
+        if let Some(group_size) = self.group_size {
+            validate_group_size(group_size)?;
+        }
+        if let Some(samples) = self.calibration_samples {
+            if !method.is_tunable() {
+                return Err(format!("Pas de calibration pour {}", method));
+            }
+            if samples == 0 || samples > MAX_CALIBRATION_SAMPLES {
+                return Err(format!(
+                    "Le nombre d'échantillons de calibration doit être compris entre 1 et {}",
+                    MAX_CALIBRATION_SAMPLES
+                ));
+            }
+        }
+        
+        if self.layer_overrides.is_empty() {
+            return Ok(());
+        }
+        
+        if matches!(method, QuantizationMethod::GgufQ4_0 | QuantizationMethod::GgufQ5_0) {
+            return Err("La précision par couche n'est pas supportée pour GGUF".to_string());
+        }
+        
+        if self.layer_overrides.len() > MAX_LAYER_OVERRIDES {
+            return Err(format!("Au plus {} surcharges de précision par job", MAX_LAYER_OVERRIDES));
+        }
+        
+        let mut seen = std::collections::HashSet::new();
+        for layer_override in &self.layer_overrides {
+            let pattern = layer_override.pattern.as_str();
+            
+            if pattern.is_empty() || pattern.len() > 200 {
+                return Err("Motif de couche vide ou trop long".to_string());
+            }
+            if !pattern.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/' | '*')) {
+                return Err(format!("Motif de couche invalide: {}", pattern));
+            }
+            if pattern.chars().all(|c| c == '*') {
+                return Err("Un motif ne peut pas viser toutes les couches".to_string());
+            }
+            if !seen.insert(pattern) {
+                return Err(format!("Motif de couche en double: {}", pattern));
+            }
+            
+            match (method, layer_override.bits) {
+                (_, None) => {}
+                // L'INT8 ONNX ne sait qu'exclure des couches
+                (QuantizationMethod::Int8, Some(_)) => {
+                    return Err("INT8 ne permet que d'exclure des couches (bits absent)".to_string());
+                }
+                (_, Some(2 | 3 | 4 | 8)) => {}
+                (_, Some(bits)) => {
+                    return Err(format!("Précision non supportée pour {}: {} bits", pattern, bits));
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Vérifier la variation de perplexité mesurée face au seuil demandé
+    ///
+    /// Sans seuil tout résultat est accepté; avec un seuil, une mesure absente
+    /// ne permet pas de garantir la qualité et est refusée.
+    pub fn check_quality_loss(&self, perplexity_change_percent: Option<f64>) -> Result<(), String> {
+        let Some(threshold) = self.max_quality_loss_percent else {
+            return Ok(());
+        };
+        
+        match perplexity_change_percent {
+            Some(change) if change <= threshold => Ok(()),
+            Some(change) => Err(format!(
+                "Perte de qualité de {:.2} % supérieure au seuil de {:.2} %", change, threshold
+            )),
+            None => Err("Perte de qualité non mesurable, seuil impossible à garantir".to_string()),
+        }
+    }
+    
+    /// Plus faible précision expérimentale demandée (globale ou par couche)
+    pub fn experimental_bits(&self) -> Option<u8> {
+        self.bits
+            .into_iter()
+            .chain(self.layer_overrides.iter().filter_map(|o| o.bits))
+            .filter(|bits| EXPERIMENTAL_BITS.contains(bits))
+            .min()
+    }
+    
+    /// Des options doivent-elles être transmises au script ?
+    pub fn has_script_options(&self) -> bool {
+        !self.layer_overrides.is_empty() || self.bits.is_some() || self.group_size.is_some()
+    }
+    
+    /// Ramener les échantillons de calibration au maximum du plan
+    pub fn clamp_calibration_samples(&mut self, max: usize) {
+        if let Some(samples) = self.calibration_samples.as_mut() {
+            *samples = (*samples).min(max);
+        }
+    }
+    
+    /// Taille de groupe effectivement utilisée (None hors GPTQ/AWQ)
+    pub fn effective_group_size(&self, method: &QuantizationMethod) -> Option<u32> {
+        method.is_tunable()
+            .then(|| self.group_size.unwrap_or(DEFAULT_GROUP_SIZE))
+    }
+    
+    /// Échantillons de calibration effectivement utilisés (None sans calibration)
+    pub fn effective_calibration_samples(&self, method: &QuantizationMethod) -> Option<usize> {
+        method.is_tunable()
+            .then(|| self.calibration_samples.unwrap_or(DEFAULT_CALIBRATION_SAMPLES))
+    }
+    
+    /// Configuration transmise au script de quantification
+    pub fn script_config(&self, method: &QuantizationMethod) -> serde_json::Value {
+        let skip: Vec<&str> = self.layer_overrides
+            .iter()
+            .filter(|o| o.bits.is_none())
+            .map(|o| o.pattern.as_str())
+            .collect();
+        let overrides: Vec<&LayerOverride> = self.layer_overrides
+            .iter()
+            .filter(|o| o.bits.is_some())
+            .collect();
+        
+        serde_json::json!({
+            "default_bits": self.bits.unwrap_or_else(|| method.default_bits()),
+            "group_size": self.group_size,
+            "skip": skip,
+            "overrides": overrides,
+        })
+    }
+}
+
+/// Rapport produit à la fin d'une quantification
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuantizationReport {
+    pub original_size: i64,
+    pub quantized_size: i64,
+    pub size_reduction_percent: f64,
+    pub perplexity_before: Option<f64>,
+    pub perplexity_after: Option<f64>,
+    pub latency_before_ms: Option<f64>,
+    pub latency_after_ms: Option<f64>,
+    /// Format d'origine si le modèle a été converti avant quantification
+    #[serde(default)]
+    pub converted_from: Option<ModelFormat>,
+    /// Couches quantifiées avec une précision différente (ou non quantifiées)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overridden_layers: Vec<String>,
+    /// Échantillons de calibration utilisés (GPTQ/AWQ)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration_samples: Option<usize>,
+    /// Pic de mémoire résidente du script de quantification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_memory_bytes: Option<u64>,
+    /// Architecture détectée à l'analyse du modèle source (`unknown` si indéterminée)
+    #[serde(default = "unknown_architecture")]
+    pub model_architecture: String,
+    /// Durée de chaque étape du traitement
+    #[serde(default)]
+    pub stage_timings: StageTimings,
+    /// Précision expérimentale (2/3 bits) utilisée: qualité non garantie
+    #[serde(default)]
+    pub experimental: bool,
+    /// Avertissements à afficher avec le résultat
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Durée des étapes d'un traitement, en secondes (None = étape non exécutée)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageTimings {
+    pub download: Option<f64>,
+    pub analyze: Option<f64>,
+    pub quantize: Option<f64>,
+    pub validate: Option<f64>,
+    /// Conversion ONNX et attente de l'export GGUF supplémentaire
+    pub export: Option<f64>,
+    pub upload: Option<f64>,
+}
+
+impl StageTimings {
+    /// Ajoute la durée écoulée depuis `started` à une étape
+    pub fn add(stage: &mut Option<f64>, started: std::time::Instant) {
+        *stage = Some(stage.unwrap_or(0.0) + started.elapsed().as_secs_f64());
+    }
+}
+
+/// Architecture des rapports sans analyse exploitable
+fn unknown_architecture() -> String {
+    QuantizationReport::UNKNOWN_ARCHITECTURE.to_string()
+}
+
+/// Une variante quantifiée dans une comparaison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedVariant {
+    pub job_id: Uuid,
+    pub name: String,
+    pub quantization_method: QuantizationMethod,
+    pub quantized_size: i64,
+    pub size_reduction_percent: f64,
+    pub perplexity_change_percent: Option<f64>,
+    pub latency_improvement_percent: Option<f64>,
+}
+
+/// Comparaison côte à côte de deux variantes quantifiées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobComparison {
+    pub job_a: ComparedVariant,
+    pub job_b: ComparedVariant,
+    pub recommended: Uuid,
+    pub reason: String,
+}
+
+/// Ligne du journal d'exécution d'un job
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobLog {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub level: String, // "info", "warn", "error"
+    pub message: String,
+    /// Sortie d'erreur brute du script (admins uniquement)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Transition du cycle de vie d'un job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "job_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum JobEventType {
+    Created,
+    Queued,
+    PickedUp,
+    StageStarted,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Événement de la chronologie d'un job
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobEvent {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub job_id: Uuid,
+    pub event: JobEventType,
+    /// Étape du pipeline (`stage_started` uniquement)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// Worker ayant produit l'événement (absent pour les actions de l'API)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl JobEvent {
+    pub fn new(job_id: Uuid, event: JobEventType) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            job_id,
+            event,
+            stage: None,
+            worker_id: None,
+            message: None,
+            created_at: Utc::now(),
+        }
+    }
+    
+    pub fn with_stage(mut self, stage: impl Into<String>) -> Self {
+        self.stage = Some(stage.into());
+        self
+    }
+    
+    pub fn with_worker(mut self, worker_id: &str) -> Self {
+        self.worker_id = Some(worker_id.to_string());
+        self
+    }
+    
+    /// Message tronqué à `JobLog::MAX_MESSAGE_LEN`
+    pub fn with_message(mut self, message: &str) -> Self {
+        self.message = Some(truncate_chars(message, JobLog::MAX_MESSAGE_LEN));
+        self
+    }
+}
+
+/// Statut compact d'un job (requêtes groupées des tableaux de bord)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobStatusSummary {
+    #[serde(skip_serializing)]
+    pub id: Uuid,
+    pub status: JobStatus,
+    pub progress: i32,
+}
+
+/// Position d'un job dans la queue et estimation des délais
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobQueueEstimate {
+    pub job_id: Uuid,
+    pub status: JobStatus,
+    /// Rang dans la queue (1 = prochain traité), None si le job n'est plus en attente
+    pub queue_position: Option<i64>,
+    pub estimated_start_seconds: Option<u64>,
+    pub estimated_completion_seconds: Option<u64>,
+}
+
+impl JobQueueEstimate {
+    /// Estimer les délais à partir du rang, de la durée moyenne d'un job et du nombre de workers
+    pub fn new(job: &Job, queue_position: Option<i64>, average_duration_seconds: f64, workers: usize) -> Self {
+        let average = average_duration_seconds.max(0.0);
+        let workers = workers.max(1) as f64;
+        
+        let (start, completion) = match (&job.status, queue_position) {
+            (JobStatus::Pending, Some(position)) => {
+                // Les jobs devant nous sont traités par vagues de `workers`
+                let waves_ahead = ((position.max(1) - 1) as f64 / workers).floor();
+                let start = waves_ahead * average;
+                (Some(start as u64), Some((start + average) as u64))
+            }
+            (JobStatus::Processing, _) => {
+                let remaining = average * (100 - job.progress.clamp(0, 100)) as f64 / 100.0;
+                (Some(0), Some(remaining as u64))
+            }
+            _ => (None, None),
+        };
+        
+        Self {
+            job_id: job.id,
+            status: job.status.clone(),
+            queue_position,
+            estimated_start_seconds: start,
+            estimated_completion_seconds: completion,
+        }
+    }
 }
 
 /// Pour mettre à jour la progression d'un job
@@ -119,14 +1021,61 @@ pub struct JobResult {
     pub status: JobStatus,
     pub progress: i32,
     pub error_message: Option<String>,
+    pub retry_count: i32,
+    pub error_history: Vec<JobError>,
+    pub cancelled_by: Option<CancelledBy>,
+    pub cancellation_reason: Option<String>,
+    pub original_size: Option<i64>,
+    pub quantized_size: Option<i64>,
+    pub compression_ratio: Option<f64>,
+    /// Résultat téléchargeable (URL signée générée à la demande, jamais incluse ici)
+    pub downloadable: bool,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Résumé d'un job pour les listes
+///
+/// Ne contient aucune URL: le téléchargement passe par `/jobs/{id}/download`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub status: JobStatus,
+    pub progress: i32,
+    pub quantization_method: QuantizationMethod,
+    pub input_format: ModelFormat,
+    pub output_format: ModelFormat,
     pub original_size: Option<i64>,
     pub quantized_size: Option<i64>,
     pub compression_ratio: Option<f64>,
-    pub download_url: Option<String>,
+    pub credits_used: i32,
+    pub downloadable: bool,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+impl From<&Job> for JobSummary {
+    fn from(job: &Job) -> Self {
+        Self {
+            id: job.id,
+            name: job.name.clone(),
+            status: job.status.clone(),
+            progress: job.progress,
+            quantization_method: job.quantization_method.clone(),
+            input_format: job.input_format.clone(),
+            output_format: job.output_format.clone(),
+            original_size: job.original_size,
+            quantized_size: job.quantized_size,
+            compression_ratio: job.compression_ratio(),
+            credits_used: job.credits_used,
+            downloadable: job.is_downloadable(),
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
 impl Job {
     /// Crée un nouveau job
     pub fn new(
@@ -135,7 +1084,7 @@ impl Job {
         quantization_method: QuantizationMethod,
         input_format: ModelFormat,
         output_format: ModelFormat,
-        input_file_id: Uuid,
+        input_file_id: Option<Uuid>,
         credits_used: i32,
     ) -> Self {
         Self {
@@ -148,6 +1097,8 @@ impl Job {
             input_format,
             output_format,
             input_file_id,
+            source_repo_id: None,
+            source_revision: None,
             output_file_id: None,
             error_message: None,
             original_size: None,
@@ -155,11 +1106,46 @@ impl Job {
             processing_time: None,
             credits_used,
             created_at: Utc::now(),
+            updated_at: Utc::now(),
             started_at: None,
             completed_at: None,
+            report: None,
+            quantization_config: None,
+            retry_count: 0,
+            error_history: sqlx::types::Json(Vec::new()),
+            cancelled_by: None,
+            cancellation_reason: None,
         }
     }
     
+    /// Remplace l'ID généré (fourni par un `IdProvider`)
+    pub fn with_id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+    
+    /// Définit les options de quantification (non stockées si vides)
+    pub fn with_quantization_config(mut self, config: QuantizationConfig) -> Self {
+        self.quantization_config = if config == QuantizationConfig::default() {
+            None
+        } else {
+            Some(sqlx::types::Json(config))
+        };
+        self
+    }
+    
+    /// Définit un dépôt Hugging Face comme source du job
+    pub fn with_source_repo(mut self, repo_id: String, revision: String) -> Self {
+        self.source_repo_id = Some(repo_id);
+        self.source_revision = Some(revision);
+        self
+    }
+    
+    /// ETag du job, dérivé de sa date de dernière modification
+    pub fn etag(&self) -> String {
+        format!("\"{}-{}\"", self.id.simple(), self.updated_at.timestamp_micros())
+    }
+    
     /// Met à jour la progression
     pub fn update_progress(&mut self, progress: i32) {
         self.progress = progress.clamp(0, 100);
@@ -188,19 +1174,35 @@ impl Job {
         }
     }
     
-    /// Marque comme échoué
+    /// Marque comme échoué, en gardant la trace des erreurs précédentes
     pub fn fail(&mut self, error: String) {
+        let now = Utc::now();
+        
+        let history = &mut self.error_history.0;
+        history.push(JobError { timestamp: now, message: error.clone() });
+        if history.len() > MAX_ERROR_HISTORY {
+            history.drain(..history.len() - MAX_ERROR_HISTORY);
+        }
+        
         self.status = JobStatus::Failed;
         self.error_message = Some(error);
-        self.completed_at = Some(Utc::now());
+        self.retry_count += 1;
+        self.completed_at = Some(now);
     }
     
-    /// Annule le job
-    pub fn cancel(&mut self) {
+    /// Annule le job, en gardant l'auteur et le motif
+    pub fn cancel(&mut self, cancelled_by: CancelledBy, reason: Option<String>) {
         self.status = JobStatus::Cancelled;
+        self.cancelled_by = Some(cancelled_by);
+        self.cancellation_reason = reason;
         self.completed_at = Some(Utc::now());
     }
     
+    /// Vérifie si le job est terminé avec succès
+    pub fn is_completed(&self) -> bool {
+        self.status == JobStatus::Completed
+    }
+    
     /// Calcule le ratio de compression
     pub fn compression_ratio(&self) -> Option<f64> {
         if let (Some(original), Some(quantized)) = (self.original_size, self.quantized_size) {
@@ -214,19 +1216,386 @@ impl Job {
         }
     }
     
+    /// Le résultat peut-il être téléchargé ? (job terminé avec un fichier de sortie)
+    pub fn is_downloadable(&self) -> bool {
+        matches!(self.status, JobStatus::Completed) && self.output_file_id.is_some()
+    }
+    
     /// Convertit en résultat pour l'API
-    pub fn to_result(&self, download_url: Option<String>) -> JobResult {
+    pub fn to_result(&self) -> JobResult {
         JobResult {
             id: self.id,
             status: self.status.clone(),
             progress: self.progress,
             error_message: self.error_message.clone(),
+            retry_count: self.retry_count,
+            error_history: self.error_history.0.clone(),
+            cancelled_by: self.cancelled_by.clone(),
+            cancellation_reason: self.cancellation_reason.clone(),
             original_size: self.original_size,
             quantized_size: self.quantized_size,
             compression_ratio: self.compression_ratio(),
-            download_url,
+            downloadable: self.is_downloadable(),
             created_at: self.created_at,
             completed_at: self.completed_at,
         }
     }
-}
\ No newline at end of file
+}
+
+impl QuantizationReport {
+    /// Architecture non déterminée par l'analyse
+    pub const UNKNOWN_ARCHITECTURE: &'static str = "unknown";
+    
+    /// Crée un rapport à partir des tailles et des métriques de qualité
+    pub fn new(
+        original_size: i64,
+        quantized_size: i64,
+        perplexity_before: Option<f64>,
+        perplexity_after: Option<f64>,
+        latency_before_ms: Option<f64>,
+        latency_after_ms: Option<f64>,
+    ) -> Self {
+        let size_reduction_percent = if original_size > 0 {
+            (1.0 - quantized_size as f64 / original_size as f64) * 100.0
+        } else {
+            0.0
+        };
+        
+        Self {
+            original_size,
+            quantized_size,
+            size_reduction_percent,
+            perplexity_before,
+            perplexity_after,
+            latency_before_ms,
+            latency_after_ms,
+            converted_from: None,
+            overridden_layers: Vec::new(),
+            calibration_samples: None,
+            peak_memory_bytes: None,
+            model_architecture: unknown_architecture(),
+            stage_timings: StageTimings::default(),
+            experimental: false,
+            warnings: Vec::new(),
+        }
+    }
+    
+    /// Indique une conversion de format préalable à la quantification
+    pub fn with_conversion(mut self, converted_from: Option<ModelFormat>) -> Self {
+        self.converted_from = converted_from;
+        self
+    }
+    
+    /// Liste les couches dont la précision a été surchargée
+    pub fn with_overridden_layers(mut self, layers: Vec<String>) -> Self {
+        self.overridden_layers = layers;
+        self
+    }
+    
+    /// Indique le nombre d'échantillons de calibration utilisés
+    pub fn with_calibration_samples(mut self, samples: Option<usize>) -> Self {
+        self.calibration_samples = samples;
+        self
+    }
+    
+    /// Architecture détectée du modèle source
+    pub fn with_architecture(mut self, model_architecture: String) -> Self {
+        self.model_architecture = model_architecture;
+        self
+    }
+    
+    /// Signale une précision expérimentale (qualité non garantie)
+    pub fn with_experimental_bits(mut self, bits: Option<u8>) -> Self {
+        if let Some(bits) = bits {
+            self.experimental = true;
+            self.warnings.push(format!(
+                "Quantification expérimentale {} bits: qualité non garantie",
+                bits
+            ));
+        }
+        self
+    }
+    
+    /// Durées des étapes mesurées jusqu'ici
+    pub fn with_stage_timings(mut self, stage_timings: StageTimings) -> Self {
+        self.stage_timings = stage_timings;
+        self
+    }
+    
+    /// Pic de mémoire mesuré pendant la quantification
+    pub fn with_peak_memory(mut self, peak_memory_bytes: Option<u64>) -> Self {
+        self.peak_memory_bytes = peak_memory_bytes;
+        self
+    }
+    
+    /// Variation de perplexité en pourcentage (positif = dégradation)
+    pub fn perplexity_change_percent(&self) -> Option<f64> {
+        match (self.perplexity_before, self.perplexity_after) {
+            (Some(before), Some(after)) if before > 0.0 => Some((after - before) / before * 100.0),
+            _ => None,
+        }
+    }
+    
+    /// Gain de latence en pourcentage (positif = plus rapide)
+    pub fn latency_improvement_percent(&self) -> Option<f64> {
+        match (self.latency_before_ms, self.latency_after_ms) {
+            (Some(before), Some(after)) if before > 0.0 => Some((before - after) / before * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Poids de la perte de qualité face au gain de taille dans la recommandation
+const QUALITY_WEIGHT: f64 = 5.0;
+
+impl ComparedVariant {
+    /// Construit une variante comparée depuis un job terminé
+    pub fn from_job(job: &Job) -> Self {
+        let report = job.report.as_ref().map(|r| r.0.clone()).unwrap_or_else(|| {
+            QuantizationReport::new(
+                job.original_size.unwrap_or(0),
+                job.quantized_size.unwrap_or(0),
+                None,
+                None,
+                None,
+                None,
+            )
+        });
+        
+        Self {
+            job_id: job.id,
+            name: job.name.clone(),
+            quantization_method: job.quantization_method.clone(),
+            quantized_size: report.quantized_size,
+            size_reduction_percent: report.size_reduction_percent,
+            perplexity_change_percent: report.perplexity_change_percent(),
+            latency_improvement_percent: report.latency_improvement_percent(),
+        }
+    }
+    
+    /// Score compromis qualité/taille (plus haut = meilleur)
+    pub fn tradeoff_score(&self) -> f64 {
+        let quality_penalty = self.perplexity_change_percent.unwrap_or(0.0).max(0.0);
+        self.size_reduction_percent - QUALITY_WEIGHT * quality_penalty
+    }
+}
+
+impl JobLog {
+    /// Taille maximale d'un message ou d'une sortie d'erreur
+    pub const MAX_MESSAGE_LEN: usize = 8_000;
+    
+    /// Crée une ligne de journal (messages tronqués à `MAX_MESSAGE_LEN`)
+    pub fn new(job_id: Uuid, level: &str, message: &str, stderr: Option<&str>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            job_id,
+            level: level.to_string(),
+            message: truncate_chars(message, Self::MAX_MESSAGE_LEN),
+            stderr: stderr.map(|s| truncate_chars(s, Self::MAX_MESSAGE_LEN)),
+            created_at: Utc::now(),
+        }
+    }
+    
+    /// Retire la sortie d'erreur brute (vue propriétaire)
+    pub fn without_stderr(mut self) -> Self {
+        self.stderr = None;
+        self
+    }
+}
+
+fn truncate_chars(value: &str, max: usize) -> String {
+    match value.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &value[..idx]),
+        None => value.to_string(),
+    }
+}
+
+impl JobComparison {
+    /// Compare deux variantes et choisit la recommandée
+    pub fn new(job_a: ComparedVariant, job_b: ComparedVariant) -> Self {
+        let (recommended, reason) = if job_a.tradeoff_score() >= job_b.tradeoff_score() {
+            (job_a.job_id, format!("{} offre le meilleur compromis qualité/taille", job_a.name))
+        } else {
+            (job_b.job_id, format!("{} offre le meilleur compromis qualité/taille", job_b.name))
+        };
+        
+        Self {
+            job_a,
+            job_b,
+            recommended,
+            reason,
+        }
+    }
+}
+/// Durée de validité par défaut d'un lien de partage
+pub const DEFAULT_SHARE_LINK_HOURS: i64 = 24;
+
+/// Lien public vers le résultat d'un job (le token n'est jamais relu)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobShareLink {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    /// Nombre maximal de téléchargements (None = illimité)
+    pub max_downloads: Option<i32>,
+    pub download_count: i32,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl JobShareLink {
+    /// Lien ni révoqué ni expiré (le plafond est vérifié à chaque téléchargement)
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && Utc::now() < self.expires_at
+    }
+}
+
+/// Paramètres d'un nouveau lien de partage
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewJobShareLink {
+    /// Validité en heures (défaut: `DEFAULT_SHARE_LINK_HOURS`)
+    pub expires_in_hours: Option<i64>,
+    pub max_downloads: Option<i32>,
+}
+
+/// Lien de partage émis: le token et l'URL ne sont retournés qu'une seule fois
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedShareLink {
+    #[serde(flatten)]
+    pub link: JobShareLink,
+    pub token: String,
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::error::AppError;
+    use actix_web::ResponseError;
+
+    fn new_job(value: serde_json::Value) -> NewJob {
+        serde_json::from_value(value).expect("NewJob désérialisable")
+    }
+
+    #[tokio::test]
+    async fn validation_details_list_every_invalid_field() {
+        let job = new_job(serde_json::json!({ "name": "", "quantization_method": "int3" }));
+        let errors = job.validate().expect_err("nom vide et méthode inconnue");
+
+        let response = AppError::from(errors).error_response();
+        assert_eq!(response.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+        assert!(body["details"]["name"].is_array());
+        assert!(body["details"]["quantization_method"][0]
+            .as_str()
+            .unwrap()
+            .contains("int3"));
+    }
+
+    #[test]
+    fn method_accepts_only_the_serialized_name() {
+        let job = new_job(serde_json::json!({ "name": "m", "quantization_method": "gguf_q4_0" }));
+        assert!(matches!(job.method(), Some(QuantizationMethod::GgufQ4_0)));
+        assert!(job.validate().is_ok());
+
+        // Nom de variante Rust: inconnu de l'API
+        let job = new_job(serde_json::json!({ "name": "m", "quantization_method": "GgufQ5_0" }));
+        assert!(job.method().is_none());
+        assert!(job.validate().is_err());
+
+        let job = new_job(serde_json::json!({ "name": "m" }));
+        assert!(job.method().is_none());
+        assert!(job.validate().is_ok());
+    }
+
+    #[test]
+    fn every_method_round_trips_through_display() {
+        for method in QuantizationMethod::ALL {
+            let parsed: QuantizationMethod = method.to_string().parse().unwrap();
+            assert_eq!(parsed.as_str(), method.as_str());
+
+            // Casse et espaces ignorés
+            let parsed: QuantizationMethod = format!(" {} ", method.to_string().to_uppercase()).parse().unwrap();
+            assert_eq!(parsed.as_str(), method.as_str());
+
+            // JSON, SQL et Display partagent le même nom
+            assert_eq!(serde_json::to_value(&method).unwrap(), method.as_str());
+            let parsed: QuantizationMethod = serde_json::from_value(method.as_str().into()).unwrap();
+            assert_eq!(parsed.as_str(), method.as_str());
+        }
+    }
+
+    #[test]
+    fn unknown_method_is_an_error() {
+        let err = "int3".parse::<QuantizationMethod>().unwrap_err();
+        assert_eq!(err, UnknownQuantizationMethod("int3".to_string()));
+        assert!(err.to_string().contains("'int3'"));
+        assert!(err.to_string().contains(QuantizationMethod::Gptq.as_str()));
+
+        assert!("".parse::<QuantizationMethod>().is_err());
+    }
+
+    #[test]
+    fn queue_estimate_accounts_for_the_jobs_ahead() {
+        let job = Job::new(
+            Uuid::new_v4(),
+            "llama".to_string(),
+            QuantizationMethod::Int8,
+            ModelFormat::Safetensors,
+            ModelFormat::Onnx,
+            None,
+            1,
+        );
+
+        // Deux workers: les rangs 1 et 2 démarrent tout de suite, le 3 attend une vague
+        let second = JobQueueEstimate::new(&job, Some(2), 600.0, 2);
+        assert_eq!(second.estimated_start_seconds, Some(0));
+        let third = JobQueueEstimate::new(&job, Some(3), 600.0, 2);
+        assert_eq!(third.estimated_start_seconds, Some(600));
+        assert_eq!(third.estimated_completion_seconds, Some(1200));
+    }
+
+    #[test]
+    fn error_history_keeps_the_most_recent_failures() {
+        let mut job = Job::new(
+            Uuid::new_v4(), "llama".to_string(), QuantizationMethod::Int8,
+            ModelFormat::Onnx, ModelFormat::Onnx, None, 1,
+        );
+        for attempt in 0..MAX_ERROR_HISTORY + 2 {
+            job.fail(format!("échec {}", attempt));
+        }
+
+        assert_eq!(job.retry_count as usize, MAX_ERROR_HISTORY + 2);
+        assert_eq!(job.error_history.len(), MAX_ERROR_HISTORY);
+        assert_eq!(job.error_history[0].message, "échec 2");
+        assert_eq!(job.error_message.as_deref(), Some(format!("échec {}", MAX_ERROR_HISTORY + 1).as_str()));
+    }
+
+    #[test]
+    fn calibration_samples_are_bounded_and_reserved_to_gptq_and_awq() {
+        let samples = |count| QuantizationConfig { calibration_samples: Some(count), ..Default::default() };
+
+        assert!(samples(256).validate_for(&QuantizationMethod::Gptq).is_ok());
+        assert!(samples(0).validate_for(&QuantizationMethod::Awq).is_err());
+        assert!(samples(MAX_CALIBRATION_SAMPLES + 1).validate_for(&QuantizationMethod::Gptq).is_err());
+        assert!(samples(256).validate_for(&QuantizationMethod::Int8).is_err());
+
+        assert_eq!(QuantizationConfig::default().effective_calibration_samples(&QuantizationMethod::Awq), Some(DEFAULT_CALIBRATION_SAMPLES));
+        assert_eq!(QuantizationConfig::default().effective_calibration_samples(&QuantizationMethod::Int8), None);
+    }
+
+    #[test]
+    fn group_size_must_be_a_power_of_two_in_range() {
+        let group = |size| QuantizationConfig { group_size: Some(size), ..Default::default() };
+
+        let err = group(96).validate_for(&QuantizationMethod::Gptq).unwrap_err();
+        assert!(err.contains("96"), "{}", err);
+        assert!(group(512).validate_for(&QuantizationMethod::Awq).is_err());
+        assert!(group(64).validate_for(&QuantizationMethod::Gptq).is_ok());
+        assert_eq!(group(64).effective_group_size(&QuantizationMethod::Gptq), Some(64));
+    }
+}
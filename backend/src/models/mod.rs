@@ -2,35 +2,48 @@
 pub mod user;
 pub use user::{
     User, NewUser, UserLogin, GoogleAuth, 
-    AuthToken, UserProfile
+    AuthToken, UserProfile, ImpersonationToken, ApiKey, IssuedApiKey, AdminUserSummary, UserSearchFilter,
+    UserWebhook, NewUserWebhook, IssuedUserWebhook, SUBSCRIPTION_WEBHOOK_EVENTS,
+    NotificationPreferences, UpdateNotificationPreferences,
+    Notification, NotificationInbox, BroadcastRequest, BroadcastRecipient, BroadcastSummary,
+    QuantizationPreferences, UpdateQuantizationPreferences
 };
 
 // Modèle: job.rs
 pub mod job;
 pub use job::{
-    Job, JobStatus, QuantizationMethod, ModelFormat,
-    NewJob, JobProgress, JobResult
+    Job, JobStatus, JobSource, QuantizationMethod, UnknownQuantizationMethod, ModelFormat,
+    NewJob, JobSettings, QuantizationPreset, QuantizationPresetInfo, JobProgress, JobError, CancelledBy, QuantizationMethodInfo, JobResult, JobLog, JobEvent, JobEventType, JobStatusSummary, JobQueueEstimate, JobSummary,
+    QuantizationReport, StageTimings, ComparedVariant, JobComparison,
+    QuantizationConfig, LayerOverride, GgufExport, MAX_LAYER_OVERRIDES,
+    EXPERIMENTAL_BITS, JobShareLink, NewJobShareLink, IssuedShareLink, DEFAULT_SHARE_LINK_HOURS,
+    DEFAULT_CALIBRATION_SAMPLES, MAX_CALIBRATION_SAMPLES,
+    DEFAULT_GROUP_SIZE, MIN_GROUP_SIZE, MAX_GROUP_SIZE, validate_group_size
 };
 
 // Modèle: file.rs
 pub mod file;
 pub use file::{
     ModelFile, FileUpload, FileDownload,
-    FileMetadata, ModelMetadata
+    FileMetadata, ModelMetadata, StorageUsage, AnalysisStatus, FileAnalysis,
+    DirectUploadRequest, DirectUploadUrl, PendingUpload, DirectUploadComplete, UploadedModel
 };
 
 // Modèle: billing.rs
 pub mod billing;
 pub use billing::{
-    Subscription, SubscriptionPlan, SubscriptionStatus,
-    CreditInfo, CreditTransaction, PlanInfo
+    Subscription, SubscriptionPlan, SubscriptionStatus, StripeSubscriptionState,
+    CreditInfo, CreditTransaction, UsageProjection, USAGE_PROJECTION_WINDOW_DAYS, PlanInfo, RetentionPolicy, StorageQuotaPolicy, ActiveJobPolicy,
+    OutputFormatPolicy, JobMemoryPolicy,
+    JobCost, CreditCostPolicy, EUR_PER_CREDIT, CheckoutSession, CheckoutSessionStatus
 };
 
 // Modèle: system.rs
 pub mod system;
 pub use system::{
     AuditLog, HealthStatus, ServiceHealth,
-    SystemMetrics, AppConfig
+    SystemMetrics, ScalingSignal, WorkerStatus, AppConfig,
+    Capabilities, MethodCapability,
 };
 
 // Types communs
@@ -47,6 +60,58 @@ pub struct PaginatedResponse<T> {
     pub total_pages: i64,
 }
 
+/// Pagination normalisée d'une liste (`page` commence à 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub page: i64,
+    pub per_page: i64,
+}
+
+impl Pagination {
+    /// Taille de page quand la requête n'en précise pas
+    pub const DEFAULT_PER_PAGE: i64 = 20;
+    
+    /// Valider les paramètres de requête: zéro ou négatif refusé,
+    /// `per_page` ramené à `max_per_page`
+    pub fn from_params(
+        page: Option<i64>,
+        per_page: Option<i64>,
+        max_per_page: i64,
+    ) -> Result<Self, crate::utils::error::AppError> {
+        let page = page.unwrap_or(1);
+        let per_page = per_page.unwrap_or_else(|| Self::DEFAULT_PER_PAGE.min(max_per_page));
+        
+        if page < 1 {
+            return Err(crate::utils::error::AppError::Validation(
+                "page doit être supérieur ou égal à 1".to_string()
+            ));
+        }
+        if per_page < 1 {
+            return Err(crate::utils::error::AppError::Validation(
+                "per_page doit être supérieur ou égal à 1".to_string()
+            ));
+        }
+        
+        Ok(Self { page, per_page: per_page.min(max_per_page.max(1)) })
+    }
+    
+    /// Décalage SQL (`OFFSET`) de la page
+    pub fn offset(&self) -> i64 {
+        (self.page - 1).saturating_mul(self.per_page)
+    }
+    
+    /// Réponse paginée pour les éléments de cette page
+    pub fn response<T>(&self, items: Vec<T>, total: i64) -> PaginatedResponse<T> {
+        PaginatedResponse {
+            items,
+            total,
+            page: self.page,
+            per_page: self.per_page,
+            total_pages: (total as f64 / self.per_page as f64).ceil() as i64,
+        }
+    }
+}
+
 /// Réponse d'erreur standard
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -81,4 +146,27 @@ impl<T> SuccessResponse<T> {
             message: Some(message.to_string()),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::error::AppError;
+    use actix_web::{http::StatusCode, ResponseError};
+
+    #[test]
+    fn pagination_rejects_zero_and_clamps_to_the_max() {
+        let err = Pagination::from_params(None, Some(0), 100).unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)), "{:?}", err);
+        assert_eq!(err.error_response().status(), StatusCode::BAD_REQUEST);
+        assert!(Pagination::from_params(Some(-1), Some(10), 100).is_err());
+
+        let pagination = Pagination::from_params(Some(3), Some(1000), 100).unwrap();
+        assert_eq!(pagination, Pagination { page: 3, per_page: 100 });
+        assert_eq!(pagination.offset(), 200);
+
+        // Sans paramètres: première page, taille par défaut bornée par le max
+        assert_eq!(Pagination::from_params(None, None, 100).unwrap(), Pagination { page: 1, per_page: 20 });
+        assert_eq!(Pagination::from_params(None, None, 5).unwrap().per_page, 5);
+    }
+}
@@ -75,6 +75,26 @@ pub struct SystemMetrics {
     pub used_storage_gb: f64,
 }
 
+/// Signal d'autoscaling des workers (consommable par KEDA/HPA)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalingSignal {
+    pub timestamp: DateTime<Utc>,
+    pub queue_depth: u64,
+    pub average_job_duration_seconds: f64,
+    pub active_workers: i64,
+    pub recommended_workers: u32,
+}
+
+/// État des workers de traitement (pause de maintenance)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    pub timestamp: DateTime<Utc>,
+    /// Les workers ne prennent plus de nouveaux jobs (les jobs en cours se terminent)
+    pub paused: bool,
+    pub queue_depth: u64,
+    pub processing_jobs: i64,
+}
+
 /// Configuration de l'application
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -135,13 +155,10 @@ impl Default for AppConfig {
                 "onnx".to_string(),
                 "gguf".to_string(),
             ],
-            quantization_methods: vec![
-                "int8".to_string(),
-                "gptq".to_string(),
-                "awq".to_string(),
-                "gguf_q4_0".to_string(),
-                "gguf_q5_0".to_string(),
-            ],
+            quantization_methods: crate::models::QuantizationMethod::ALL
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
             default_expiry_days: 30,
             rate_limit_per_minute: 60,
         }
@@ -196,4 +213,140 @@ impl SystemMetrics {
             used_storage_gb,
         }
     }
-}
\ No newline at end of file
+}
+
+impl ScalingSignal {
+    /// Durée supposée d'un job tant qu'aucun historique n'est disponible
+    const DEFAULT_JOB_DURATION_SECONDS: f64 = 60.0;
+
+    /// Crée un signal à partir des métriques brutes
+    pub fn new(
+        queue_depth: u64,
+        average_job_duration_seconds: f64,
+        active_workers: i64,
+        target_drain_seconds: u64,
+        min_workers: u32,
+        max_workers: u32,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            queue_depth,
+            average_job_duration_seconds,
+            active_workers,
+            recommended_workers: Self::recommend(
+                queue_depth,
+                average_job_duration_seconds,
+                active_workers,
+                target_drain_seconds,
+                min_workers,
+                max_workers,
+            ),
+        }
+    }
+
+    /// Nombre de workers pour vider la queue en `target_drain_seconds`,
+    /// en plus de ceux déjà occupés, borné par [min_workers, max_workers]
+    pub fn recommend(
+        queue_depth: u64,
+        average_job_duration_seconds: f64,
+        active_workers: i64,
+        target_drain_seconds: u64,
+        min_workers: u32,
+        max_workers: u32,
+    ) -> u32 {
+        let duration = if average_job_duration_seconds > 0.0 {
+            average_job_duration_seconds
+        } else {
+            Self::DEFAULT_JOB_DURATION_SECONDS
+        };
+
+        let backlog_seconds = queue_depth as f64 * duration;
+        let for_backlog = (backlog_seconds / target_drain_seconds.max(1) as f64).ceil() as i64;
+        let wanted = for_backlog + active_workers.max(0);
+
+        (wanted.max(0) as u32).clamp(min_workers, max_workers.max(min_workers))
+    }
+
+    /// Exposition au format texte Prometheus
+    pub fn to_prometheus(&self) -> String {
+        format!(
+            "# TYPE quantization_queue_depth gauge\n\
+             quantization_queue_depth {}\n\
+             # TYPE quantization_job_duration_seconds_avg gauge\n\
+             quantization_job_duration_seconds_avg {}\n\
+             # TYPE quantization_active_workers gauge\n\
+             quantization_active_workers {}\n\
+             # TYPE quantization_recommended_workers gauge\n\
+             quantization_recommended_workers {}\n",
+            self.queue_depth,
+            self.average_job_duration_seconds,
+            self.active_workers,
+            self.recommended_workers,
+        )
+    }
+}
+
+/// Disponibilité d'une méthode de quantification sur ce serveur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodCapability {
+    pub method: crate::models::QuantizationMethod,
+    pub available: bool,
+    /// Raison de l'indisponibilité (backend Python absent, pas de GPU)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Capacités détectées au démarrage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub gpu_enabled: bool,
+    pub methods: Vec<MethodCapability>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl Capabilities {
+    /// Raison de l'indisponibilité d'une méthode (None si elle est disponible)
+    pub fn unavailable_reason(&self, method: &crate::models::QuantizationMethod) -> Option<&str> {
+        self.methods
+            .iter()
+            .find(|capability| {
+                std::mem::discriminant(&capability.method) == std::mem::discriminant(method)
+                    && !capability.available
+            })
+            .map(|capability| capability.reason.as_deref().unwrap_or("indisponible"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_queue_keeps_the_busy_workers() {
+        assert_eq!(ScalingSignal::recommend(0, 120.0, 3, 600, 1, 20), 3);
+        assert_eq!(ScalingSignal::recommend(0, 120.0, 0, 600, 1, 20), 1);
+    }
+
+    #[test]
+    fn backlog_is_drained_within_the_target() {
+        // 10 jobs de 2 min à vider en 10 min: 2 workers de plus
+        assert_eq!(ScalingSignal::recommend(10, 120.0, 0, 600, 0, 20), 2);
+        assert_eq!(ScalingSignal::recommend(10, 120.0, 4, 600, 0, 20), 6);
+        // Arrondi au supérieur: 11 jobs demandent un troisième worker
+        assert_eq!(ScalingSignal::recommend(11, 120.0, 0, 600, 0, 20), 3);
+    }
+
+    #[test]
+    fn recommendation_is_bounded() {
+        assert_eq!(ScalingSignal::recommend(1000, 300.0, 2, 600, 1, 8), 8);
+        assert_eq!(ScalingSignal::recommend(0, 300.0, 0, 600, 2, 8), 2);
+        // Borne max incohérente: le minimum l'emporte
+        assert_eq!(ScalingSignal::recommend(0, 300.0, 0, 600, 4, 2), 4);
+    }
+
+    #[test]
+    fn missing_duration_history_uses_the_default() {
+        // 10 jobs × 60 s par défaut à vider en 5 min
+        assert_eq!(ScalingSignal::recommend(10, 0.0, 0, 300, 0, 20), 2);
+    }
+}
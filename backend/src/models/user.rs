@@ -3,6 +3,8 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use crate::models::job::{QuantizationMethod, ModelFormat};
+use crate::models::billing::SubscriptionPlan;
 
 /// Représente un utilisateur du système
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, Validate)]
@@ -59,6 +61,102 @@ pub struct AuthToken {
     pub expires_in: i64,
 }
 
+/// Métadonnées d'une clé API (le secret n'est jamais relu)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// Début de la clé, pour l'identifier dans l'interface
+    pub key_prefix: String,
+    pub permissions: sqlx::types::Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Clé API nouvellement émise: le secret n'est retourné qu'une seule fois
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedApiKey {
+    #[serde(flatten)]
+    pub key: ApiKey,
+    pub secret: String,
+}
+
+/// Événements d'abonnement auxquels un webhook peut s'abonner
+pub const SUBSCRIPTION_WEBHOOK_EVENTS: &[&str] = &[
+    "subscription.upgraded",
+    "subscription.downgrade_scheduled",
+    "subscription.downgraded",
+    "subscription.cancelled",
+    "subscription.payment_failed",
+];
+
+/// Webhook enregistré par un utilisateur
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserWebhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    /// Clé de signature HMAC, retournée uniquement à la création
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub event_types: sqlx::types::Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Enregistrement d'un webhook
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct NewUserWebhook {
+    #[validate(url)]
+    pub url: String,
+    /// Types d'événements (`SUBSCRIPTION_WEBHOOK_EVENTS`)
+    #[validate(length(min = 1))]
+    pub event_types: Vec<String>,
+}
+
+/// Webhook nouvellement enregistré: le secret n'est retourné qu'une seule fois
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedUserWebhook {
+    #[serde(flatten)]
+    pub webhook: UserWebhook,
+    pub secret: String,
+}
+
+/// Token d'impersonation émis pour le support (pas de refresh token)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationToken {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub user_id: Uuid,
+    pub impersonator_id: Uuid,
+}
+
+/// Résumé d'un utilisateur pour la recherche admin (sans hash de mot de passe)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AdminUserSummary {
+    pub id: Uuid,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    /// Compte non supprimé
+    pub active: bool,
+    /// Plan de l'abonnement courant (None si aucun abonnement)
+    pub plan: Option<SubscriptionPlan>,
+    pub used_credits: i64,
+    pub remaining_credits: i64,
+}
+
+/// Filtres de la recherche d'utilisateurs (admin)
+#[derive(Debug, Clone, Default)]
+pub struct UserSearchFilter {
+    /// Fragment d'email, sans distinction de casse
+    pub query: Option<String>,
+    pub plan: Option<SubscriptionPlan>,
+    pub active: Option<bool>,
+}
+
 /// Données du profil utilisateur
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserProfile {
@@ -68,6 +166,220 @@ pub struct UserProfile {
     pub last_login_at: Option<DateTime<Utc>>,
 }
 
+/// Préférences de notification d'un utilisateur, par canal
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationPreferences {
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    
+    /// Email à la fin d'un job
+    pub job_complete_email: bool,
+    
+    /// Email en cas d'échec d'un job
+    pub job_failed_email: bool,
+    
+    /// SMS à la fin d'un job
+    pub job_complete_sms: bool,
+    
+    /// Email avant la suppression d'un fichier arrivant à expiration
+    pub file_expiry_email: bool,
+    
+    /// Communications marketing
+    pub marketing: bool,
+    
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Notification de la boîte de réception in-app
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    /// Type d'événement (`job.completed`, `broadcast`...)
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub notification_type: String,
+    pub title: String,
+    pub body: String,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Page de la boîte de réception, avec le nombre total de non lues
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationInbox {
+    #[serde(flatten)]
+    pub page: crate::models::PaginatedResponse<Notification>,
+    pub unread_count: i64,
+}
+
+/// Annonce envoyée par un admin à tous les utilisateurs actifs (ou à certains plans)
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct BroadcastRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub subject: String,
+    #[validate(length(min = 1, max = 10000))]
+    pub message: String,
+    /// Plans ciblés (tous si absent ou vide)
+    #[serde(default)]
+    pub plans: Vec<SubscriptionPlan>,
+    #[serde(default = "default_true")]
+    pub email: bool,
+    #[serde(default = "default_true")]
+    pub in_app: bool,
+    /// Message critique (incident, sécurité): ignore les préférences marketing
+    #[serde(default)]
+    pub critical: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Destinataire d'une annonce
+#[derive(Debug, Clone, FromRow)]
+pub struct BroadcastRecipient {
+    pub id: Uuid,
+    pub email: String,
+    /// Accepte les messages non critiques (préférence `marketing`)
+    pub marketing: bool,
+}
+
+/// Bilan d'une annonce
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BroadcastSummary {
+    pub recipients: usize,
+    pub emails_sent: usize,
+    pub in_app_sent: usize,
+    /// Emails non envoyés (préférences de l'utilisateur)
+    pub skipped: usize,
+    /// Envois en échec
+    pub failed: usize,
+}
+
+/// Mise à jour partielle des préférences de notification
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateNotificationPreferences {
+    pub job_complete_email: Option<bool>,
+    pub job_failed_email: Option<bool>,
+    pub job_complete_sms: Option<bool>,
+    pub file_expiry_email: Option<bool>,
+    pub marketing: Option<bool>,
+}
+
+impl NotificationPreferences {
+    /// Préférences par défaut (emails transactionnels activés, SMS et marketing désactivés)
+    pub fn default_for(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            job_complete_email: true,
+            job_failed_email: true,
+            job_complete_sms: false,
+            file_expiry_email: true,
+            marketing: false,
+            updated_at: Utc::now(),
+        }
+    }
+    
+    /// Appliquer une mise à jour partielle
+    pub fn apply(&mut self, update: &UpdateNotificationPreferences) {
+        if let Some(value) = update.job_complete_email {
+            self.job_complete_email = value;
+        }
+        if let Some(value) = update.job_failed_email {
+            self.job_failed_email = value;
+        }
+        if let Some(value) = update.job_complete_sms {
+            self.job_complete_sms = value;
+        }
+        if let Some(value) = update.file_expiry_email {
+            self.file_expiry_email = value;
+        }
+        if let Some(value) = update.marketing {
+            self.marketing = value;
+        }
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Réglages de quantification par défaut d'un utilisateur
+///
+/// Appliqués aux champs omis à la création d'un job, avant les valeurs système.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuantizationPreferences {
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    
+    pub default_method: Option<QuantizationMethod>,
+    
+    /// Précision (GPTQ/AWQ)
+    pub default_bits: Option<i16>,
+    
+    /// Taille de groupe (GPTQ/AWQ)
+    pub default_group_size: Option<i32>,
+    
+    pub default_output_format: Option<ModelFormat>,
+    
+    /// Refuser un job portant le nom d'un de ses jobs actifs
+    pub unique_job_names: bool,
+    
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Nouveaux réglages par défaut (remplacent les précédents; champ absent = pas de défaut)
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateQuantizationPreferences {
+    pub default_method: Option<QuantizationMethod>,
+    pub default_bits: Option<u8>,
+    pub default_group_size: Option<u32>,
+    pub default_output_format: Option<ModelFormat>,
+    #[serde(default)]
+    pub unique_job_names: bool,
+}
+
+impl QuantizationPreferences {
+    /// Aucun réglage: les valeurs système s'appliquent
+    pub fn default_for(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            default_method: None,
+            default_bits: None,
+            default_group_size: None,
+            default_output_format: None,
+            unique_job_names: false,
+            updated_at: Utc::now(),
+        }
+    }
+    
+    /// Construire des réglages vérifiés
+    pub fn from_update(user_id: Uuid, update: UpdateQuantizationPreferences) -> Result<Self, String> {
+        if let Some(bits) = update.default_bits {
+            if !matches!(bits, 2 | 3 | 4 | 8) {
+                return Err(format!("Précision non supportée: {} bits", bits));
+            }
+        }
+        if let Some(group_size) = update.default_group_size {
+            crate::models::validate_group_size(group_size)?;
+        }
+        if let (Some(method), Some(format)) = (&update.default_method, &update.default_output_format) {
+            if !method.supports_output(format) {
+                return Err(format!("{} ne produit pas le format {:?}", method, format));
+            }
+        }
+        
+        Ok(Self {
+            user_id,
+            default_method: update.default_method,
+            default_bits: update.default_bits.map(i16::from),
+            default_group_size: update.default_group_size.map(|size| size as i32),
+            default_output_format: update.default_output_format,
+            unique_job_names: update.unique_job_names,
+            updated_at: Utc::now(),
+        })
+    }
+}
+
 impl User {
     /// Crée un nouvel utilisateur avec un mot de passe hashé
     pub fn new(email: String, password: &str) -> Self {
@@ -0,0 +1,131 @@
+// services/audit.rs
+use crate::models::{AuditLog, Pagination};
+use crate::services::database::Database;
+use crate::utils::error::{AppError, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Dépôt des logs d'audit (actions sensibles)
+pub struct AuditRepository {
+    pool: PgPool,
+    read_pool: PgPool,
+}
+
+impl AuditRepository {
+    /// Créer un dépôt partageant les pools de la base
+    pub fn new(db: &Database) -> Self {
+        Self {
+            pool: db.write_pool().clone(),
+            read_pool: db.read_pool().clone(),
+        }
+    }
+
+    /// Enregistrer une entrée d'audit
+    pub async fn record(&self, entry: &AuditLog) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (
+                id, user_id, ip_address, user_agent, action,
+                resource_type, resource_id, old_values, new_values,
+                message, created_at
+            )
+            VALUES ($1, $2, $3::inet, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#
+        )
+        .bind(entry.id)
+        .bind(entry.user_id)
+        .bind(&entry.ip_address)
+        .bind(&entry.user_agent)
+        .bind(&entry.action)
+        .bind(&entry.resource_type)
+        .bind(entry.resource_id)
+        .bind(&entry.old_values)
+        .bind(&entry.new_values)
+        .bind(&entry.message)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Enregistrer sans faire échouer l'action auditée
+    pub async fn log(&self, entry: AuditLog) {
+        if let Err(e) = self.record(&entry).await {
+            log::warn!("Échec d'écriture du log d'audit '{}': {}", entry.action, e);
+        }
+    }
+
+    /// Lister les logs d'audit avec filtres et pagination
+    pub async fn list(
+        &self,
+        filter: &AuditFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<AuditLog>, i64)> {
+        let offset = pagination.offset();
+        let per_page = pagination.per_page;
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, user_id, host(ip_address) AS ip_address, user_agent, action, \
+             resource_type, resource_id, old_values, new_values, message, created_at \
+             FROM audit_logs WHERE 1 = 1"
+        );
+        filter.push_conditions(&mut query);
+        query.push(" ORDER BY created_at DESC LIMIT ");
+        query.push_bind(per_page);
+        query.push(" OFFSET ");
+        query.push_bind(offset);
+
+        let rows = query
+            .build_query_as::<AuditLog>()
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut count_query = QueryBuilder::<Postgres>::new(
+            "SELECT COUNT(*) FROM audit_logs WHERE 1 = 1"
+        );
+        filter.push_conditions(&mut count_query);
+
+        let total: (i64,) = count_query
+            .build_query_as()
+            .fetch_one(&self.read_pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((rows, total.0))
+    }
+}
+
+/// Filtres de recherche des logs d'audit
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub actor: Option<Uuid>,
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+impl AuditFilter {
+    /// Ajouter les conditions WHERE correspondant aux filtres renseignés
+    fn push_conditions(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(actor) = self.actor {
+            query.push(" AND user_id = ").push_bind(actor);
+        }
+        if let Some(action) = &self.action {
+            query.push(" AND action = ").push_bind(action.clone());
+        }
+        if let Some(resource_type) = &self.resource_type {
+            query.push(" AND resource_type = ").push_bind(resource_type.clone());
+        }
+        if let Some(start_date) = self.start_date {
+            query.push(" AND created_at >= ").push_bind(start_date);
+        }
+        if let Some(end_date) = self.end_date {
+            query.push(" AND created_at <= ").push_bind(end_date);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 // services/cache.rs
+use crate::core::quantization_service::ModelAnalysis;
 use crate::utils::error::{AppError, Result};
 use redis::{AsyncCommands, Client};
 use std::sync::Arc;
@@ -54,13 +55,24 @@ impl Cache {
     }
 
     /// Récupérer une valeur
+    ///
+    /// Une erreur Redis est traitée comme une absence: l'appelant retombe
+    /// sur la source de vérité au lieu d'échouer.
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
-        let mut conn = self.client.get_async_connection().await
-            .map_err(|e| AppError::RedisError(e.to_string()))?;
-
         let full_key = self.key(key);
-        let value: Option<String> = conn.get(&full_key).await
-            .map_err(|e| AppError::RedisError(e.to_string()))?;
+        let value: Option<String> = match self.client.get_async_connection().await {
+            Ok(mut conn) => match conn.get(&full_key).await {
+                Ok(value) => value,
+                Err(e) => {
+                    log::warn!("Cache indisponible pour {}: {}", full_key, e);
+                    None
+                }
+            },
+            Err(e) => {
+                log::warn!("Cache indisponible pour {}: {}", full_key, e);
+                None
+            }
+        };
 
         match value {
             Some(json) => {
@@ -108,6 +120,23 @@ impl Cache {
         Ok(value)
     }
 
+    /// Incrémenter une valeur et (re)poser son TTL dans une même transaction
+    pub async fn incr_with_ttl(&self, key: &str, by: i64, ttl_seconds: usize) -> Result<i64> {
+        let mut conn = self.client.get_async_connection().await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        let full_key = self.key(key);
+        let (value,): (i64,) = redis::pipe()
+            .atomic()
+            .incr(&full_key, by)
+            .expire(&full_key, ttl_seconds).ignore()
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        Ok(value)
+    }
+
     /// Décrémenter une valeur
     pub async fn decr(&self, key: &str, by: i64) -> Result<i64> {
         let mut conn = self.client.get_async_connection().await
@@ -120,6 +149,49 @@ impl Cache {
         Ok(value)
     }
 
+    /// Réserver un créneau d'upload pour un utilisateur
+    ///
+    /// Le créneau est libéré au drop du guard retourné, y compris si l'upload
+    /// échoue en cours de route. Le TTL protège contre un compteur bloqué
+    /// après un crash du processus.
+    pub async fn acquire_upload_slot(&self, user_id: uuid::Uuid, limit: usize) -> Result<UploadSlot> {
+        let key = format!("uploads:in_flight:{}", user_id);
+        let in_flight = self.incr_with_ttl(&key, 1, 3600).await?;
+
+        let slot = UploadSlot {
+            cache: self.clone(),
+            key,
+        };
+
+        if in_flight > limit as i64 {
+            // Le drop du guard annule l'incrément
+            return Err(AppError::ResourceBusy);
+        }
+
+        Ok(slot)
+    }
+
+    /// Compter une requête dans une fenêtre fixe (retourne false si la limite est dépassée)
+    pub async fn check_rate_limit(&self, key: &str, limit: i64, window_seconds: usize) -> Result<bool> {
+        let key = format!("ratelimit:{}", key);
+        let count = self.incr(&key, 1).await?;
+        if count == 1 {
+            self.expire(&key, window_seconds).await?;
+        }
+
+        Ok(count <= limit)
+    }
+
+    /// Récupérer l'analyse d'un modèle par le SHA-256 de son contenu
+    pub async fn get_analysis(&self, checksum_sha256: &str) -> Result<Option<ModelAnalysis>> {
+        self.get(&format!("analysis:{}", checksum_sha256)).await
+    }
+
+    /// Mémoriser l'analyse d'un modèle (contenu identique => analyse identique)
+    pub async fn set_analysis(&self, checksum_sha256: &str, analysis: &ModelAnalysis, ttl_seconds: usize) -> Result<()> {
+        self.set_ex(&format!("analysis:{}", checksum_sha256), analysis, ttl_seconds).await
+    }
+
     /// Obtenir le TTL restant
     pub async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
         let mut conn = self.client.get_async_connection().await
@@ -309,6 +381,24 @@ impl Clone for Cache {
     }
 }
 
+/// Créneau d'upload réservé, libéré au drop
+pub struct UploadSlot {
+    cache: Cache,
+    key: String,
+}
+
+impl Drop for UploadSlot {
+    fn drop(&mut self) {
+        let cache = self.cache.clone();
+        let key = std::mem::take(&mut self.key);
+        tokio::spawn(async move {
+            if let Err(e) = cache.decr(&key, 1).await {
+                log::warn!("Impossible de libérer le créneau d'upload {}: {}", key, e);
+            }
+        });
+    }
+}
+
 /// Statistiques du cache
 #[derive(Debug, Default, Serialize)]
 pub struct CacheStats {
@@ -318,4 +408,58 @@ pub struct CacheStats {
     pub hits: u64,
     pub misses: u64,
     pub hit_rate: f64,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+    use uuid::Uuid;
+
+    /// Attendre qu'un créneau se libère (la libération au drop est asynchrone)
+    async fn wait_for_slot(cache: &Cache, user_id: Uuid, limit: usize) -> UploadSlot {
+        for _ in 0..50 {
+            if let Ok(slot) = cache.acquire_upload_slot(user_id, limit).await {
+                return slot;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("aucun créneau d'upload libéré");
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite Redis (TEST_REDIS_URL)"]
+    async fn uploads_beyond_the_limit_wait_for_a_freed_slot() {
+        let cache = testing::cache().await;
+        let user_id = Uuid::new_v4();
+        let limit = 3;
+
+        let mut slots = Vec::new();
+        for _ in 0..limit {
+            slots.push(cache.acquire_upload_slot(user_id, limit).await.unwrap());
+        }
+
+        let rejected = cache.acquire_upload_slot(user_id, limit).await;
+        assert!(matches!(rejected, Err(AppError::ResourceBusy)));
+
+        // Les autres utilisateurs ne sont pas concernés
+        assert!(cache.acquire_upload_slot(Uuid::new_v4(), limit).await.is_ok());
+
+        // Un upload terminé (ou en échec) rend son créneau
+        drop(slots.pop());
+        let _slot = wait_for_slot(&cache, user_id, limit).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite Redis (TEST_REDIS_URL)"]
+    async fn upload_slot_counter_always_carries_a_ttl() {
+        let cache = testing::cache().await;
+        let user_id = Uuid::new_v4();
+
+        let _slot = cache.acquire_upload_slot(user_id, 1).await.unwrap();
+
+        // Incrément et TTL posés ensemble: jamais de compteur sans expiration
+        let ttl = cache.ttl(&format!("uploads:in_flight:{}", user_id)).await.unwrap();
+        assert!(ttl.is_some_and(|ttl| ttl <= Duration::from_secs(3600)));
+    }
+}
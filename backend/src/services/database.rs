@@ -1,32 +1,172 @@
 // services/database.rs
 use crate::models::{
-    User, Job, ModelFile, Subscription, CreditTransaction,
+    User, ApiKey, Job, JobLog, JobEvent, JobStatusSummary, ModelFile, Subscription, CreditTransaction,
     JobStatus, QuantizationMethod, ModelFormat,
-    SubscriptionPlan, SubscriptionStatus,
+    SubscriptionPlan, SubscriptionStatus, NotificationPreferences, QuantizationPreferences, CheckoutSession,
+    AnalysisStatus, FileAnalysis, AdminUserSummary, UserSearchFilter, BroadcastRecipient, Notification, UserWebhook, JobShareLink, QuantizationReport, Pagination,
 };
 use crate::utils::error::{AppError, Result};
-use sqlx::{PgPool, postgres::PgPoolOptions, Row, FromRow};
+use crate::utils::ids::{IdProvider, default_id_provider};
+use sqlx::{PgPool, postgres::{PgConnectOptions, PgPoolOptions}, ConnectOptions, Executor, Row, FromRow};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
 pub struct Database {
     pool: PgPool,
+    /// Réplique en lecture seule (None: lectures sur le primaire)
+    read_pool: Option<PgPool>,
+    ids: Arc<dyn IdProvider>,
+}
+
+/// Paramètres du pool de connexions
+#[derive(Debug, Clone)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// Délai maximal pour établir la connexion initiale (secondes)
+    pub connect_timeout_seconds: u64,
+    /// Délai maximal d'attente d'une connexion libre (secondes)
+    pub acquire_timeout_seconds: u64,
+    /// Durée avant fermeture d'une connexion inactive (secondes)
+    pub idle_timeout_seconds: u64,
+    /// `statement_timeout` de chaque connexion (ms, 0 = illimité)
+    pub statement_timeout_ms: u64,
+    /// Seuil de journalisation des requêtes lentes (ms)
+    pub slow_query_ms: u64,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 20,
+            min_connections: 5,
+            connect_timeout_seconds: 30,
+            acquire_timeout_seconds: 30,
+            idle_timeout_seconds: 600,
+            statement_timeout_ms: 30_000,
+            slow_query_ms: 1_000,
+        }
+    }
+}
+
+impl PoolSettings {
+    /// Lire les paramètres depuis la configuration
+    pub fn from_config(config: &crate::utils::config::Config) -> Self {
+        Self {
+            max_connections: config.database_max_connections,
+            min_connections: config.database_min_connections,
+            connect_timeout_seconds: config.database_connection_timeout as u64,
+            acquire_timeout_seconds: config.database_acquire_timeout,
+            idle_timeout_seconds: config.database_idle_timeout,
+            statement_timeout_ms: config.database_statement_timeout_ms,
+            slow_query_ms: config.database_slow_query_ms,
+        }
+    }
+
+    /// Construire les options du pool (erreur si la configuration est incohérente)
+    pub fn pool_options(&self) -> Result<PgPoolOptions> {
+        if self.max_connections == 0 {
+            return Err(AppError::Validation("DATABASE_MAX_CONNECTIONS must be greater than 0".to_string()));
+        }
+        if self.min_connections > self.max_connections {
+            return Err(AppError::Validation(format!(
+                "DATABASE_MIN_CONNECTIONS ({}) must not exceed DATABASE_MAX_CONNECTIONS ({})",
+                self.min_connections, self.max_connections
+            )));
+        }
+
+        // Posé à l'ouverture de chaque connexion: vaut pour toute requête qui l'emprunte
+        let statement_timeout_ms = self.statement_timeout_ms;
+
+        Ok(PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(Duration::from_secs(self.acquire_timeout_seconds))
+            .idle_timeout(Duration::from_secs(self.idle_timeout_seconds))
+            .after_connect(move |conn, _meta| {
+                Box::pin(async move {
+                    conn.execute(format!("SET statement_timeout = {}", statement_timeout_ms).as_str())
+                        .await?;
+                    Ok(())
+                })
+            }))
+    }
+
+    /// Options de connexion: requêtes au-delà du seuil journalisées en warning
+    /// (cible `sqlx::query`, avec leur durée)
+    pub fn connect_options(&self, database_url: &str) -> Result<PgConnectOptions> {
+        let options: PgConnectOptions = database_url
+            .parse()
+            .map_err(|e: sqlx::Error| AppError::Validation(format!("DATABASE_URL invalide: {}", e)))?;
+
+        Ok(options
+            .log_statements(log::LevelFilter::Debug)
+            .log_slow_statements(log::LevelFilter::Warn, Duration::from_millis(self.slow_query_ms)))
+    }
 }
 
 impl Database {
     /// Créer une nouvelle instance de base de données
-    pub async fn new(database_url: &str) -> Result<Self> {
-        let pool = PgPoolOptions::new()
-            .max_connections(20)
-            .min_connections(5)
-            .connect_timeout(Duration::from_secs(30))
-            .connect(database_url)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+    ///
+    /// Avec `read_url`, les lectures (`get_*`, `list_*`...) passent par la
+    /// réplique; les écritures, les transactions et les lectures qui
+    /// conditionnent une écriture (crédits, limites, références de stockage,
+    /// job repris par le worker) restent sur le primaire.
+    pub async fn new(database_url: &str, read_url: Option<&str>, settings: &PoolSettings) -> Result<Self> {
+        let pool = Self::connect(database_url, settings).await?;
+        let read_pool = match read_url {
+            Some(read_url) => Some(Self::connect(read_url, settings).await?),
+            None => None,
+        };
+
+        Ok(Self {
+            pool,
+            read_pool,
+            ids: default_id_provider(),
+        })
+    }
+
+    /// Ouvrir un pool de connexions
+    async fn connect(database_url: &str, settings: &PoolSettings) -> Result<PgPool> {
+        let options = settings.pool_options()?;
+        let connect_options = settings.connect_options(database_url)?;
+
+        tokio::time::timeout(
+            Duration::from_secs(settings.connect_timeout_seconds),
+            options.connect_with(connect_options),
+        )
+        .await
+        .map_err(|_| AppError::Database("Timeout de connexion à la base de données".to_string()))?
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Remplacer la source d'identifiants (séquence déterministe en test)
+    pub fn with_id_provider(mut self, ids: Arc<dyn IdProvider>) -> Self {
+        self.ids = ids;
+        self
+    }
 
-        Ok(Self { pool })
+    /// Accès au pool pour les dépôts spécialisés
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Pool des requêtes en lecture seule (réplique, à défaut le primaire)
+    pub fn read_pool(&self) -> &PgPool {
+        self.read_pool.as_ref().unwrap_or(&self.pool)
+    }
+
+    /// Pool des écritures (toujours le primaire)
+    pub fn write_pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Une réplique en lecture est-elle configurée ?
+    pub fn has_read_replica(&self) -> bool {
+        self.read_pool.is_some()
     }
 
     /// Exécuter les migrations
@@ -81,20 +221,128 @@ impl Database {
             "SELECT * FROM users WHERE email = $1 AND deleted_at IS NULL"
         )
         .bind(email)
-        .fetch_one(&self.pool)
+        .fetch_one(self.read_pool())
         .await
         .map_err(|_| AppError::UserNotFound)?;
 
         Ok(row)
     }
 
+    /// Rechercher des utilisateurs (admin): fragment d'email, plan courant, compte actif
+    pub async fn search_users(
+        &self,
+        filter: &UserSearchFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<AdminUserSummary>, i64)> {
+        let offset = pagination.offset();
+        let per_page = pagination.per_page;
+
+        let push_from = |query: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>| {
+            query.push(
+                r#"
+                FROM users u
+                LEFT JOIN LATERAL (
+                    SELECT plan FROM subscriptions
+                    WHERE user_id = u.id
+                    ORDER BY created_at DESC LIMIT 1
+                ) s ON TRUE
+                WHERE TRUE
+                "#
+            );
+            if let Some(fragment) = filter.query.as_deref() {
+                query.push(" AND u.email ILIKE ").push_bind(like_pattern(fragment));
+            }
+            if let Some(plan) = &filter.plan {
+                query.push(" AND s.plan = ").push_bind(plan.clone());
+            }
+            match filter.active {
+                Some(true) => { query.push(" AND u.deleted_at IS NULL"); }
+                Some(false) => { query.push(" AND u.deleted_at IS NOT NULL"); }
+                None => {}
+            }
+        };
+
+        let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new(
+            r#"
+            SELECT u.id, u.email, u.created_at, u.last_login_at,
+                   u.deleted_at IS NULL AS active, s.plan,
+                   COALESCE((SELECT SUM(ABS(amount)) FROM credit_transactions
+                             WHERE user_id = u.id AND amount < 0), 0)::BIGINT AS used_credits,
+                   COALESCE((SELECT SUM(amount) FROM credit_transactions
+                             WHERE user_id = u.id), 0)::BIGINT AS remaining_credits
+            "#
+        );
+        push_from(&mut query);
+        query.push(" ORDER BY u.created_at DESC LIMIT ").push_bind(per_page);
+        query.push(" OFFSET ").push_bind(offset);
+
+        let rows = query
+            .build_query_as::<AdminUserSummary>()
+            .fetch_all(self.read_pool())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut count_query = sqlx::QueryBuilder::<sqlx::Postgres>::new("SELECT COUNT(*)");
+        push_from(&mut count_query);
+
+        let total: (i64,) = count_query
+            .build_query_as()
+            .fetch_one(self.read_pool())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((rows, total.0))
+    }
+
+    /// Lister une page de destinataires d'annonce (utilisateurs actifs), par ID croissant
+    ///
+    /// Un utilisateur sans abonnement compte comme `free`; `plans` vide = tous les plans.
+    pub async fn list_broadcast_recipients(
+        &self,
+        plans: &[SubscriptionPlan],
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<BroadcastRecipient>> {
+        let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new(
+            r#"
+            SELECT u.id, u.email, COALESCE(np.marketing, FALSE) AS marketing
+            FROM users u
+            LEFT JOIN notification_preferences np ON np.user_id = u.id
+            LEFT JOIN LATERAL (
+                SELECT plan FROM subscriptions
+                WHERE user_id = u.id
+                ORDER BY created_at DESC LIMIT 1
+            ) s ON TRUE
+            WHERE u.deleted_at IS NULL
+            "#
+        );
+        if !plans.is_empty() {
+            query.push(" AND COALESCE(s.plan, 'free'::subscription_plan) IN (");
+            let mut separated = query.separated(", ");
+            for plan in plans {
+                separated.push_bind(plan.clone());
+            }
+            separated.push_unseparated(")");
+        }
+        if let Some(after) = after {
+            query.push(" AND u.id > ").push_bind(after);
+        }
+        query.push(" ORDER BY u.id LIMIT ").push_bind(limit);
+
+        query
+            .build_query_as::<BroadcastRecipient>()
+            .fetch_all(self.read_pool())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
     /// Récupérer un utilisateur par ID
     pub async fn get_user_by_id(&self, id: Uuid) -> Result<User> {
         let row = sqlx::query_as::<_, User>(
             "SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL"
         )
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(self.read_pool())
         .await
         .map_err(|_| AppError::UserNotFound)?;
 
@@ -144,13 +392,30 @@ impl Database {
         Ok(())
     }
 
+    /// Restaurer un utilisateur supprimé (soft delete)
+    pub async fn restore_user(&self, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE users SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL"
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
     /// Obtenir l'ID Stripe d'un utilisateur
     pub async fn get_user_stripe_id(&self, user_id: Uuid) -> Result<Option<String>> {
         let row: Option<(Option<String>,)> = sqlx::query_as(
             "SELECT stripe_customer_id FROM users WHERE id = $1"
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.read_pool())
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -180,9 +445,10 @@ impl Database {
             INSERT INTO jobs (
                 id, user_id, name, status, progress,
                 quantization_method, input_format, output_format,
-                input_file_id, credits_used, created_at
+                input_file_id, source_repo_id, source_revision,
+                credits_used, created_at, quantization_config
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING *
             "#
         )
@@ -195,8 +461,11 @@ impl Database {
         .bind(&job.input_format)
         .bind(&job.output_format)
         .bind(job.input_file_id)
+        .bind(&job.source_repo_id)
+        .bind(&job.source_revision)
         .bind(job.credits_used)
         .bind(job.created_at)
+        .bind(&job.quantization_config)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -217,6 +486,92 @@ impl Database {
         Ok(row)
     }
 
+    /// Compter les jobs non terminés d'un utilisateur (en attente + en traitement)
+    pub async fn count_user_active_jobs(&self, user_id: Uuid) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM jobs WHERE user_id = $1 AND status IN ('pending', 'processing')"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row.0)
+    }
+
+    /// Noms des jobs actifs d'un utilisateur commençant par `name`
+    pub async fn list_active_job_names_with_prefix(&self, user_id: Uuid, name: &str) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT name FROM jobs
+            WHERE user_id = $1 AND status IN ('pending', 'processing')
+              AND left(name, char_length($2)) = $2
+            "#
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(name,)| name).collect())
+    }
+
+    /// Rang d'un job en attente: jobs en attente de priorité égale ou supérieure créés avant lui, plus un
+    pub async fn get_job_queue_position(&self, job: &Job) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            r#"
+            WITH priorities AS (
+                SELECT j.id, j.created_at,
+                       CASE s.plan WHEN 'pro' THEN 3 WHEN 'starter' THEN 2 ELSE 1 END AS priority
+                FROM jobs j
+                LEFT JOIN subscriptions s ON s.user_id = j.user_id
+                WHERE j.status = 'pending'
+            )
+            SELECT COUNT(*) + 1 FROM priorities p, priorities me
+            WHERE me.id = $1 AND p.id <> me.id
+              AND (p.priority > me.priority OR (p.priority = me.priority AND p.created_at < me.created_at))
+            "#
+        )
+        .bind(job.id)
+        .fetch_one(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row.0)
+    }
+
+    /// Marquer un job comme présent dans la queue Redis
+    pub async fn mark_job_enqueued(&self, job_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE jobs SET enqueued_at = NOW() WHERE id = $1")
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Lister les jobs en attente jamais arrivés dans Redis (créés pendant une panne)
+    pub async fn list_unqueued_jobs(&self, older_than_seconds: i64, limit: i64) -> Result<Vec<Job>> {
+        let rows = sqlx::query_as::<_, Job>(
+            r#"
+            SELECT * FROM jobs
+            WHERE status = 'pending' AND enqueued_at IS NULL
+              AND created_at < NOW() - ($1 || ' seconds')::INTERVAL
+            ORDER BY created_at
+            LIMIT $2
+            "#
+        )
+        .bind(older_than_seconds.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
     /// Mettre à jour le statut d'un job
     pub async fn update_job_status(
         &self,
@@ -251,6 +606,92 @@ impl Database {
         Ok(())
     }
 
+    /// Enregistrer l'annulation d'un job (auteur et motif)
+    ///
+    /// Sans effet (false) si le job n'est plus dans l'état `expected_status`,
+    /// par exemple pris par un worker depuis sa lecture.
+    pub async fn cancel_job(&self, job: &Job, expected_status: &JobStatus) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = $1, cancelled_by = $2, cancellation_reason = $3,
+                completed_at = $4, updated_at = $5
+            WHERE id = $6 AND status = $7
+            "#
+        )
+        .bind(&job.status)
+        .bind(&job.cancelled_by)
+        .bind(&job.cancellation_reason)
+        .bind(job.completed_at)
+        .bind(Utc::now())
+        .bind(job.id)
+        .bind(expected_status)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Lister les jobs en attente depuis plus de `older_than_seconds`
+    pub async fn list_stale_pending_jobs(&self, older_than_seconds: i64, limit: i64) -> Result<Vec<Job>> {
+        let rows = sqlx::query_as::<_, Job>(
+            r#"
+            SELECT * FROM jobs
+            WHERE status = 'pending'
+              AND created_at < NOW() - ($1 || ' seconds')::INTERVAL
+            ORDER BY created_at
+            LIMIT $2
+            "#
+        )
+        .bind(older_than_seconds.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Enregistrer l'échec d'un job (message, historique des erreurs, compteur)
+    pub async fn fail_job(&self, job: &Job) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = $1, progress = $2, error_message = $3,
+                error_history = $4, retry_count = $5,
+                completed_at = $6, updated_at = $7
+            WHERE id = $8
+            "#
+        )
+        .bind(&job.status)
+        .bind(job.progress)
+        .bind(&job.error_message)
+        .bind(&job.error_history)
+        .bind(job.retry_count)
+        .bind(job.completed_at)
+        .bind(Utc::now())
+        .bind(job.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Mettre à jour la progression d'un job en cours (sans toucher au statut)
+    pub async fn update_job_progress(&self, job_id: Uuid, progress: i32) -> Result<()> {
+        sqlx::query("UPDATE jobs SET progress = $1, updated_at = $2 WHERE id = $3")
+            .bind(progress)
+            .bind(Utc::now())
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Mettre à jour la complétion d'un job
     pub async fn update_job_completion(&self, job_id: Uuid, job: &Job) -> Result<()> {
         sqlx::query(
@@ -258,8 +699,9 @@ impl Database {
             UPDATE jobs 
             SET status = $1, progress = $2, output_file_id = $3,
                 quantized_size = $4, processing_time = $5,
-                completed_at = $6, updated_at = $7
-            WHERE id = $8
+                completed_at = $6, updated_at = $7,
+                original_size = $8, report = $9
+            WHERE id = $10
             "#
         )
         .bind(&job.status)
@@ -269,6 +711,8 @@ impl Database {
         .bind(job.processing_time)
         .bind(job.completed_at)
         .bind(Utc::now())
+        .bind(job.original_size)
+        .bind(&job.report)
         .bind(job_id)
         .execute(&self.pool)
         .await
@@ -277,15 +721,28 @@ impl Database {
         Ok(())
     }
 
+    /// Remplacer le rapport de quantification d'un job
+    pub async fn update_job_report(&self, job_id: Uuid, report: &QuantizationReport) -> Result<()> {
+        sqlx::query("UPDATE jobs SET report = $1, updated_at = $2 WHERE id = $3")
+            .bind(sqlx::types::Json(report))
+            .bind(Utc::now())
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Lister les jobs d'un utilisateur
     pub async fn list_user_jobs(
         &self,
         user_id: Uuid,
         status_filter: Option<&str>,
-        page: i64,
-        per_page: i64,
+        pagination: Pagination,
     ) -> Result<Vec<Job>> {
-        let offset = (page - 1) * per_page;
+        let offset = pagination.offset();
+        let per_page = pagination.per_page;
         
         let mut query = "SELECT * FROM jobs WHERE user_id = $1".to_string();
         let mut params: Vec<Box<dyn sqlx::Encode<sqlx::Postgres> + Send + Sync + '_>> = vec![
@@ -306,13 +763,27 @@ impl Database {
         let rows = sqlx::query_as::<_, Job>(&query)
             .bind(user_id)
             .bind_all(params)
-            .fetch_all(&self.pool)
+            .fetch_all(self.read_pool())
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(rows)
     }
 
+    /// Obtenir le statut de plusieurs jobs d'un utilisateur (les IDs non possédés sont ignorés)
+    pub async fn get_user_jobs_status(&self, user_id: Uuid, job_ids: &[Uuid]) -> Result<Vec<JobStatusSummary>> {
+        let rows = sqlx::query_as::<_, JobStatusSummary>(
+            "SELECT id, status, progress FROM jobs WHERE id = ANY($1) AND user_id = $2"
+        )
+        .bind(job_ids)
+        .bind(user_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
     /// Obtenir les statistiques des jobs
     pub async fn get_job_stats(&self, user_id: Option<Uuid>) -> Result<JobStats> {
         let mut query = "
@@ -333,7 +804,7 @@ impl Database {
 
         let row = sqlx::query(&query)
             .bind(user_id)
-            .fetch_one(&self.pool)
+            .fetch_one(self.read_pool())
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -350,6 +821,107 @@ impl Database {
         Ok(stats)
     }
 
+    /// Ajouter une ligne au journal d'un job (les plus anciennes au-delà de `max_lines` sont supprimées)
+    pub async fn append_job_log(&self, entry: &JobLog, max_lines: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO job_logs (id, job_id, level, message, stderr, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(entry.id)
+        .bind(entry.job_id)
+        .bind(&entry.level)
+        .bind(&entry.message)
+        .bind(&entry.stderr)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM job_logs
+            WHERE job_id = $1 AND id NOT IN (
+                SELECT id FROM job_logs WHERE job_id = $1
+                ORDER BY created_at DESC LIMIT $2
+            )
+            "#
+        )
+        .bind(entry.job_id)
+        .bind(max_lines)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Lister le journal d'un job (ordre chronologique)
+    pub async fn list_job_logs(&self, job_id: Uuid) -> Result<Vec<JobLog>> {
+        let rows = sqlx::query_as::<_, JobLog>(
+            "SELECT * FROM job_logs WHERE job_id = $1 ORDER BY created_at ASC"
+        )
+        .bind(job_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Ajouter un événement à la chronologie d'un job
+    pub async fn append_job_event(&self, event: &JobEvent) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO job_events (id, job_id, event, stage, worker_id, message, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(event.id)
+        .bind(event.job_id)
+        .bind(event.event)
+        .bind(&event.stage)
+        .bind(&event.worker_id)
+        .bind(&event.message)
+        .bind(event.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Chronologie d'un job, dans l'ordre d'écriture
+    pub async fn list_job_events(&self, job_id: Uuid) -> Result<Vec<JobEvent>> {
+        let rows = sqlx::query_as::<_, JobEvent>(
+            r#"
+            SELECT id, job_id, event, stage, worker_id, message, created_at
+            FROM job_events WHERE job_id = $1
+            ORDER BY sequence ASC
+            "#
+        )
+        .bind(job_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Supprimer les journaux de jobs plus anciens que `days` jours
+    pub async fn delete_old_job_logs(&self, days: i64) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM job_logs WHERE created_at < NOW() - ($1 * INTERVAL '1 day')"
+        )
+        .bind(days)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
     // === FICHIERS ===
 
     /// Créer une entrée de fichier
@@ -360,9 +932,9 @@ impl Database {
                 id, user_id, original_filename, storage_filename,
                 file_size, checksum_sha256, format, model_type,
                 architecture, parameter_count, storage_bucket,
-                storage_path, created_at, expires_at
+                storage_path, created_at, expires_at, parent_file_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
             RETURNING *
             "#
         )
@@ -380,6 +952,7 @@ impl Database {
         .bind(&file.storage_path)
         .bind(file.created_at)
         .bind(file.expires_at)
+        .bind(file.parent_file_id)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -387,13 +960,122 @@ impl Database {
         Ok(row)
     }
 
-    /// Récupérer un fichier par ID
-    pub async fn get_file(&self, file_id: Uuid) -> Result<ModelFile> {
-        let row = sqlx::query_as::<_, ModelFile>(
+    /// Enregistrer un modèle et ses fichiers enfants dans une même transaction
+    pub async fn create_file_group(&self, model: &ModelFile, children: &[ModelFile]) -> Result<(ModelFile, Vec<ModelFile>)> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut rows = Vec::with_capacity(children.len() + 1);
+        for file in std::iter::once(model).chain(children) {
+            let row = sqlx::query_as::<_, ModelFile>(
+                r#"
+                INSERT INTO model_files (
+                    id, user_id, original_filename, storage_filename,
+                    file_size, checksum_sha256, format, model_type,
+                    architecture, parameter_count, storage_bucket,
+                    storage_path, created_at, expires_at, parent_file_id
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                RETURNING *
+                "#
+            )
+            .bind(file.id)
+            .bind(file.user_id)
+            .bind(&file.original_filename)
+            .bind(&file.storage_filename)
+            .bind(file.file_size)
+            .bind(&file.checksum_sha256)
+            .bind(&file.format)
+            .bind(&file.model_type)
+            .bind(&file.architecture)
+            .bind(file.parameter_count)
+            .bind(&file.storage_bucket)
+            .bind(&file.storage_path)
+            .bind(file.created_at)
+            .bind(file.expires_at)
+            .bind(file.parent_file_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.push(row);
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let model = rows.remove(0);
+        Ok((model, rows))
+    }
+
+    /// Lister les fichiers de données externes d'un modèle ONNX
+    pub async fn list_external_data_files(&self, parent_file_id: Uuid) -> Result<Vec<ModelFile>> {
+        let rows = sqlx::query_as::<_, ModelFile>(
+            "SELECT * FROM model_files WHERE parent_file_id = $1 ORDER BY original_filename"
+        )
+        .bind(parent_file_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Enregistrer l'état (et le résultat) de l'analyse d'un fichier
+    pub async fn update_file_analysis(
+        &self,
+        file_id: Uuid,
+        status: &AnalysisStatus,
+        result: Option<&serde_json::Value>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let analyzed_at = match status {
+            AnalysisStatus::Completed | AnalysisStatus::Failed => Some(Utc::now()),
+            _ => None,
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE model_files
+            SET analysis_status = $1, analysis_result = $2, analysis_error = $3, analyzed_at = $4
+            WHERE id = $5
+            "#
+        )
+        .bind(status)
+        .bind(result.map(sqlx::types::Json))
+        .bind(error)
+        .bind(analyzed_at)
+        .bind(file_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Récupérer l'état de l'analyse d'un fichier
+    pub async fn get_file_analysis(&self, file_id: Uuid) -> Result<FileAnalysis> {
+        let row = sqlx::query_as::<_, FileAnalysis>(
+            r#"
+            SELECT id AS file_id, analysis_status AS status, analysis_result AS result,
+                   analysis_error AS error, analyzed_at
+            FROM model_files WHERE id = $1
+            "#
+        )
+        .bind(file_id)
+        .fetch_one(self.read_pool())
+        .await
+        .map_err(|_| AppError::FileNotFound)?;
+
+        Ok(row)
+    }
+
+    /// Récupérer un fichier par ID
+    pub async fn get_file(&self, file_id: Uuid) -> Result<ModelFile> {
+        let row = sqlx::query_as::<_, ModelFile>(
             "SELECT * FROM model_files WHERE id = $1"
         )
         .bind(file_id)
-        .fetch_one(&self.pool)
+        .fetch_one(self.read_pool())
         .await
         .map_err(|_| AppError::FileNotFound)?;
 
@@ -420,6 +1102,141 @@ impl Database {
         Ok(())
     }
 
+    /// Créer un lien de partage public pour le résultat d'un job
+    pub async fn create_share_link(
+        &self,
+        job_id: Uuid,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        max_downloads: Option<i32>,
+    ) -> Result<JobShareLink> {
+        let row = sqlx::query_as::<_, JobShareLink>(
+            r#"
+            INSERT INTO job_share_links (id, job_id, user_id, token_hash, expires_at, max_downloads, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, job_id, user_id, expires_at, max_downloads, download_count, revoked_at, created_at
+            "#
+        )
+        .bind(self.ids.next_id())
+        .bind(job_id)
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .bind(max_downloads)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row)
+    }
+
+    /// Lister les liens de partage d'un job (plus récents d'abord)
+    pub async fn list_job_share_links(&self, job_id: Uuid) -> Result<Vec<JobShareLink>> {
+        let rows = sqlx::query_as::<_, JobShareLink>(
+            r#"
+            SELECT id, job_id, user_id, expires_at, max_downloads, download_count, revoked_at, created_at
+            FROM job_share_links
+            WHERE job_id = $1
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(job_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Retrouver un lien de partage à partir de l'empreinte de son token
+    pub async fn get_share_link_by_token(&self, token_hash: &str) -> Result<Option<JobShareLink>> {
+        let row = sqlx::query_as::<_, JobShareLink>(
+            r#"
+            SELECT id, job_id, user_id, expires_at, max_downloads, download_count, revoked_at, created_at
+            FROM job_share_links
+            WHERE token_hash = $1
+            "#
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row)
+    }
+
+    /// Réserver `bytes` octets sur le budget du lien s'il est encore utilisable
+    ///
+    /// Le budget vaut `max_downloads` fois `file_size`: une reprise par `Range`
+    /// paie les octets qu'elle sert. `counts_download` incrémente en plus le
+    /// compteur affiché (réponse servie depuis le premier octet). Vérification
+    /// et réservation dans la même requête: des requêtes simultanées ne
+    /// peuvent pas dépasser le plafond.
+    pub async fn claim_share_link_bytes(
+        &self,
+        link_id: Uuid,
+        bytes: i64,
+        file_size: i64,
+        counts_download: bool,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE job_share_links
+            SET download_count = download_count + CASE WHEN $4 THEN 1 ELSE 0 END,
+                bytes_served = bytes_served + $2
+            WHERE id = $1
+              AND revoked_at IS NULL
+              AND expires_at > NOW()
+              AND (
+                max_downloads IS NULL
+                OR ((NOT $4 OR download_count < max_downloads)
+                    AND bytes_served + $2 <= max_downloads::BIGINT * $3)
+              )
+            "#
+        )
+        .bind(link_id)
+        .bind(bytes)
+        .bind(file_size)
+        .bind(counts_download)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Rendre au budget du lien les octets réservés mais jamais envoyés
+    /// (téléchargement interrompu, qui pourra reprendre)
+    pub async fn release_share_link_bytes(&self, link_id: Uuid, bytes: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE job_share_links SET bytes_served = GREATEST(bytes_served - $2, 0) WHERE id = $1"
+        )
+        .bind(link_id)
+        .bind(bytes)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Révoquer un lien de partage (effet immédiat)
+    pub async fn revoke_share_link(&self, user_id: Uuid, job_id: Uuid, link_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE job_share_links SET revoked_at = NOW() WHERE id = $1 AND job_id = $2 AND user_id = $3 AND revoked_at IS NULL"
+        )
+        .bind(link_id)
+        .bind(job_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Lister les fichiers d'un utilisateur
     pub async fn list_user_files(
         &self,
@@ -430,7 +1247,8 @@ impl Database {
     ) -> Result<Vec<ModelFile>> {
         let offset = (page - 1) * per_page;
         
-        let mut query = "SELECT * FROM model_files WHERE user_id = $1".to_string();
+        // Les données externes sont listées avec leur modèle, pas séparément
+        let mut query = "SELECT * FROM model_files WHERE user_id = $1 AND parent_file_id IS NULL".to_string();
         let mut params: Vec<Box<dyn sqlx::Encode<sqlx::Postgres> + Send + Sync + '_>> = vec![
             Box::new(user_id)
         ];
@@ -448,7 +1266,7 @@ impl Database {
 
         let rows = sqlx::query_as::<_, ModelFile>(&query)
             .bind_all(params)
-            .fetch_all(&self.pool)
+            .fetch_all(self.read_pool())
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -458,7 +1276,206 @@ impl Database {
     /// Supprimer un fichier (soft delete)
     pub async fn delete_file(&self, file_id: Uuid) -> Result<()> {
         sqlx::query(
-            "UPDATE model_files SET expires_at = $1 WHERE id = $2"
+            "UPDATE model_files SET expires_at = $1 WHERE id = $2 OR parent_file_id = $2"
+        )
+        .bind(Utc::now())
+        .bind(file_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Lister les modèles stockés d'un utilisateur (hors fichiers supprimés ou expirés)
+    pub async fn list_user_models(
+        &self,
+        user_id: Uuid,
+        format_filter: Option<&str>,
+        pagination: Pagination,
+    ) -> Result<(Vec<ModelFile>, i64)> {
+        let offset = pagination.offset();
+        let per_page = pagination.per_page;
+
+        let push_conditions = |query: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>| {
+            query.push(" WHERE user_id = ").push_bind(user_id);
+            query.push(" AND purged_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())");
+            if let Some(format) = format_filter {
+                query.push(" AND format::text = ").push_bind(format.to_lowercase());
+            }
+        };
+
+        let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new("SELECT * FROM model_files");
+        push_conditions(&mut query);
+        query.push(" ORDER BY created_at DESC LIMIT ").push_bind(per_page);
+        query.push(" OFFSET ").push_bind(offset);
+
+        let rows = query
+            .build_query_as::<ModelFile>()
+            .fetch_all(self.read_pool())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut count_query = sqlx::QueryBuilder::<sqlx::Postgres>::new("SELECT COUNT(*) FROM model_files");
+        push_conditions(&mut count_query);
+
+        let total: (i64,) = count_query
+            .build_query_as()
+            .fetch_one(self.read_pool())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((rows, total.0))
+    }
+
+    /// Compter les jobs actifs (en attente ou en cours) utilisant un fichier
+    pub async fn count_active_jobs_for_file(&self, file_id: Uuid) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM jobs
+            WHERE (input_file_id = $1 OR output_file_id = $1)
+              AND status IN ('pending', 'processing')
+            "#
+        )
+        .bind(file_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(count.0)
+    }
+
+    /// Compter les autres fichiers actifs partageant le même objet de stockage (dédoublonnage)
+    pub async fn count_storage_references(&self, storage_path: &str, exclude_file_id: Uuid) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM model_files
+            WHERE storage_path = $1 AND id <> $2 AND purged_at IS NULL
+            "#
+        )
+        .bind(storage_path)
+        .bind(exclude_file_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(count.0)
+    }
+
+    /// Prolonger l'expiration des fichiers actifs d'un utilisateur (changement de plan)
+    pub async fn extend_user_files_expiry(&self, user_id: Uuid, retention_days: i32) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE model_files
+            SET expires_at = created_at + make_interval(days => $2),
+                expiry_notified = FALSE
+            WHERE user_id = $1
+              AND purged_at IS NULL
+              AND expires_at > NOW()
+              AND expires_at < created_at + make_interval(days => $2)
+            "#
+        )
+        .bind(user_id)
+        .bind(retention_days)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Espace utilisé par un utilisateur (compteur maintenu par trigger)
+    pub async fn get_user_storage_used(&self, user_id: Uuid) -> Result<i64> {
+        let used: i64 = sqlx::query_scalar(
+            "SELECT storage_used_bytes FROM users WHERE id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::UserNotFound)?;
+
+        Ok(used)
+    }
+
+    /// Recalculer le compteur d'espace utilisé à partir des fichiers (correction de dérive)
+    pub async fn recalculate_user_storage_used(&self, user_id: Uuid) -> Result<i64> {
+        let used: i64 = sqlx::query_scalar(
+            r#"
+            UPDATE users
+            SET storage_used_bytes = COALESCE((
+                SELECT SUM(file_size) FROM model_files
+                WHERE user_id = $1 AND purged_at IS NULL
+            ), 0)
+            WHERE id = $1
+            RETURNING storage_used_bytes
+            "#
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(used)
+    }
+
+    /// Lister les fichiers expirés dont le contenu n'a pas encore été purgé
+    pub async fn list_expired_files(&self, limit: i64) -> Result<Vec<ModelFile>> {
+        let rows = sqlx::query_as::<_, ModelFile>(
+            r#"
+            SELECT * FROM model_files
+            WHERE expires_at <= NOW() AND purged_at IS NULL
+            ORDER BY expires_at ASC
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Lister les fichiers expirant dans les `days` prochains jours, non encore signalés
+    pub async fn list_files_expiring_within(&self, days: i64, limit: i64) -> Result<Vec<ModelFile>> {
+        let rows = sqlx::query_as::<_, ModelFile>(
+            r#"
+            SELECT * FROM model_files
+            WHERE expires_at > NOW()
+              AND expires_at <= NOW() + make_interval(days => $1::int)
+              AND purged_at IS NULL
+              AND expiry_notified = FALSE
+            ORDER BY expires_at ASC
+            LIMIT $2
+            "#
+        )
+        .bind(days)
+        .bind(limit)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Marquer un fichier comme signalé avant expiration
+    pub async fn mark_expiry_notified(&self, file_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE model_files SET expiry_notified = TRUE WHERE id = $1"
+        )
+        .bind(file_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Marquer le contenu d'un fichier comme purgé
+    pub async fn mark_file_purged(&self, file_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE model_files SET purged_at = $1 WHERE id = $2"
         )
         .bind(Utc::now())
         .bind(file_id)
@@ -469,6 +1486,21 @@ impl Database {
         Ok(())
     }
 
+    /// Clés de stockage parmi `storage_paths` encore référencées par un fichier
+    ///
+    /// Lu sur le primaire: le résultat décide de suppressions.
+    pub async fn referenced_storage_paths(&self, storage_paths: &[String]) -> Result<HashSet<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT storage_path FROM model_files WHERE storage_path = ANY($1)"
+        )
+        .bind(storage_paths)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(path,)| path).collect())
+    }
+
     // === ABONNEMENTS ===
 
     /// Créer un abonnement
@@ -479,9 +1511,9 @@ impl Database {
                 id, user_id, plan, status,
                 current_period_start, current_period_end,
                 stripe_subscription_id, stripe_price_id,
-                created_at, updated_at
+                trial_ends_at, created_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             RETURNING *
             "#
         )
@@ -493,6 +1525,7 @@ impl Database {
         .bind(subscription.current_period_end)
         .bind(&subscription.stripe_subscription_id)
         .bind(&subscription.stripe_price_id)
+        .bind(subscription.trial_ends_at)
         .bind(subscription.created_at)
         .bind(subscription.updated_at)
         .fetch_one(&self.pool)
@@ -508,7 +1541,7 @@ impl Database {
             "SELECT * FROM subscriptions WHERE user_id = $1 ORDER BY created_at DESC LIMIT 1"
         )
         .bind(user_id)
-        .fetch_one(&self.pool)
+        .fetch_one(self.read_pool())
         .await
         .map_err(|_| AppError::NotFound("Abonnement non trouvé".to_string()))?;
 
@@ -522,8 +1555,9 @@ impl Database {
             UPDATE subscriptions 
             SET plan = $1, status = $2, current_period_start = $3,
                 current_period_end = $4, stripe_subscription_id = $5,
-                stripe_price_id = $6, cancelled_at = $7, updated_at = $8
-            WHERE id = $9
+                stripe_price_id = $6, cancelled_at = $7, updated_at = $8,
+                trial_ends_at = $9, pending_plan = $10, version = version + 1
+            WHERE id = $11
             "#
         )
         .bind(&subscription.plan)
@@ -534,6 +1568,8 @@ impl Database {
         .bind(&subscription.stripe_price_id)
         .bind(subscription.cancelled_at)
         .bind(subscription.updated_at)
+        .bind(subscription.trial_ends_at)
+        .bind(&subscription.pending_plan)
         .bind(subscription.id)
         .execute(&self.pool)
         .await
@@ -542,56 +1578,184 @@ impl Database {
         Ok(())
     }
 
-    // === CRÉDITS ===
-
-    /// Obtenir le total des crédits d'un utilisateur
-    pub async fn get_user_total_credits(&self, user_id: Uuid) -> Result<i32> {
-        let row: (i32,) = sqlx::query_as(
-            "SELECT COALESCE(SUM(amount), 0) FROM credit_transactions WHERE user_id = $1"
+    /// Lister les abonnements dont le plan programmé arrive à échéance
+    pub async fn list_due_pending_plans(&self, now: DateTime<Utc>) -> Result<Vec<Subscription>> {
+        let rows = sqlx::query_as::<_, Subscription>(
+            "SELECT * FROM subscriptions WHERE pending_plan IS NOT NULL AND current_period_end <= $1 ORDER BY current_period_end"
         )
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(now)
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(row.0)
+        Ok(rows)
     }
 
-    /// Obtenir les crédits utilisés
-    pub async fn get_user_used_credits(&self, user_id: Uuid) -> Result<i32> {
-        let row: (i32,) = sqlx::query_as(
-            "SELECT COALESCE(SUM(ABS(amount)), 0) FROM credit_transactions 
-             WHERE user_id = $1 AND amount < 0"
+    /// Lister les abonnements liés à Stripe (pour la réconciliation)
+    pub async fn list_stripe_subscriptions(&self) -> Result<Vec<Subscription>> {
+        let rows = sqlx::query_as::<_, Subscription>(
+            "SELECT * FROM subscriptions WHERE stripe_subscription_id IS NOT NULL ORDER BY updated_at"
         )
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .fetch_all(self.read_pool())
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(row.0)
+        Ok(rows)
     }
 
-    /// Créer une transaction de crédits
-    pub async fn create_credit_transaction(
-        &self,
-        user_id: Uuid,
-        transaction_type: &str,
-        amount: i32,
-        description: &str,
-    ) -> Result<()> {
-        let total_credits = self.get_user_total_credits(user_id).await?;
-        let balance_after = total_credits + amount;
+    // === SESSIONS DE CHECKOUT ===
 
+    /// Enregistrer une session de checkout Stripe
+    pub async fn create_checkout_session(&self, session: &CheckoutSession) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO credit_transactions (
-                id, user_id, transaction_type, amount,
+            INSERT INTO checkout_sessions 
+            (id, user_id, stripe_session_id, plan, status, expires_at, completed_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#
+        )
+        .bind(session.id)
+        .bind(session.user_id)
+        .bind(&session.stripe_session_id)
+        .bind(&session.plan)
+        .bind(&session.status)
+        .bind(session.expires_at)
+        .bind(session.completed_at)
+        .bind(session.created_at)
+        .bind(session.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Obtenir une session de checkout par son ID Stripe
+    pub async fn get_checkout_session(&self, stripe_session_id: &str) -> Result<CheckoutSession> {
+        sqlx::query_as::<_, CheckoutSession>(
+            "SELECT * FROM checkout_sessions WHERE stripe_session_id = $1"
+        )
+        .bind(stripe_session_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound("Session de checkout non trouvée".to_string()))
+    }
+
+    /// Mettre à jour l'état d'une session de checkout
+    pub async fn update_checkout_session(&self, session: &CheckoutSession) -> Result<()> {
+        sqlx::query(
+            "UPDATE checkout_sessions SET status = $1, completed_at = $2, updated_at = $3 WHERE id = $4"
+        )
+        .bind(&session.status)
+        .bind(session.completed_at)
+        .bind(session.updated_at)
+        .bind(session.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Expirer les sessions restées en attente au-delà de leur expiration Stripe
+    pub async fn expire_stale_checkout_sessions(&self, now: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE checkout_sessions 
+            SET status = 'expired', updated_at = $1
+            WHERE status = 'pending' AND expires_at <= $1
+            "#
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    // === CRÉDITS ===
+
+    /// Obtenir le total des crédits reçus (mouvements positifs uniquement)
+    pub async fn get_user_total_credits(&self, user_id: Uuid) -> Result<i32> {
+        let row: (i32,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(amount), 0)::INT FROM credit_transactions 
+             WHERE user_id = $1 AND amount > 0"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row.0)
+    }
+
+    /// Obtenir les crédits utilisés
+    pub async fn get_user_used_credits(&self, user_id: Uuid) -> Result<i32> {
+        let row: (i32,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(ABS(amount)), 0)::INT FROM credit_transactions 
+             WHERE user_id = $1 AND amount < 0"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row.0)
+    }
+
+    /// Solde de crédits (somme de tous les mouvements)
+    pub async fn get_user_credit_balance(&self, user_id: Uuid) -> Result<i32> {
+        let row: (i32,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(amount), 0)::INT FROM credit_transactions WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row.0)
+    }
+
+    /// Crédits consommés et nombre de jobs débités depuis une date
+    pub async fn get_credit_consumption_since(&self, user_id: Uuid, since: DateTime<Utc>) -> Result<(i64, i64)> {
+        let row: (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(-amount), 0)::BIGINT, COUNT(*)
+            FROM credit_transactions
+            WHERE user_id = $1 AND transaction_type = 'consumption' AND created_at >= $2
+            "#
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_one(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row)
+    }
+
+    /// Créer une transaction de crédits
+    pub async fn create_credit_transaction(
+        &self,
+        user_id: Uuid,
+        transaction_type: &str,
+        amount: i32,
+        description: &str,
+    ) -> Result<()> {
+        let balance_after = self.get_user_credit_balance(user_id).await? + amount;
+
+        sqlx::query(
+            r#"
+            INSERT INTO credit_transactions (
+                id, user_id, transaction_type, amount,
                 balance_after, description, created_at
             )
             VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#
         )
-        .bind(Uuid::new_v4())
+        .bind(self.ids.next_id())
         .bind(user_id)
         .bind(transaction_type)
         .bind(amount)
@@ -605,14 +1769,95 @@ impl Database {
         Ok(())
     }
 
+    /// Version courante de l'abonnement (lue sur le primaire)
+    pub async fn get_subscription_version(&self, user_id: Uuid) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            "SELECT version FROM subscriptions WHERE user_id = $1 ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| AppError::NoSubscription)?;
+
+        Ok(row.0)
+    }
+
+    /// Créer une transaction de crédits si l'abonnement est toujours à la
+    /// version lue (false si un autre mouvement est passé entre-temps)
+    pub async fn create_credit_transaction_if_version(
+        &self,
+        user_id: Uuid,
+        expected_version: i64,
+        transaction_type: &str,
+        amount: i32,
+        description: &str,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET version = version + 1, updated_at = $1
+            WHERE version = $3 AND id = (
+                SELECT id FROM subscriptions WHERE user_id = $2
+                ORDER BY created_at DESC LIMIT 1
+            )
+            "#
+        )
+        .bind(Utc::now())
+        .bind(user_id)
+        .bind(expected_version)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let total: (i32,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(amount), 0)::INT FROM credit_transactions WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO credit_transactions (
+                id, user_id, transaction_type, amount,
+                balance_after, description, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(self.ids.next_id())
+        .bind(user_id)
+        .bind(transaction_type)
+        .bind(amount)
+        .bind(total.0 + amount)
+        .bind(description)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(true)
+    }
+
     /// Obtenir l'historique des transactions de crédits
     pub async fn get_user_credit_transactions(
         &self,
         user_id: Uuid,
-        page: i64,
-        per_page: i64,
+        pagination: Pagination,
     ) -> Result<Vec<CreditTransaction>> {
-        let offset = (page - 1) * per_page;
+        let offset = pagination.offset();
+        let per_page = pagination.per_page;
         
         let rows = sqlx::query_as::<_, CreditTransaction>(
             r#"
@@ -625,7 +1870,7 @@ impl Database {
         .bind(user_id)
         .bind(per_page)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.read_pool())
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -675,48 +1920,383 @@ impl Database {
 
     // === CLÉS API ===
 
-    /// Créer une clé API
+    /// Créer une clé API (seule l'empreinte est stockée)
     pub async fn create_api_key(
         &self,
         user_id: Uuid,
-        api_key: &str,
+        key_hash: &str,
+        key_prefix: &str,
         name: &str,
         permissions: &[String],
-    ) -> Result<()> {
-        sqlx::query(
+    ) -> Result<ApiKey> {
+        let row = sqlx::query_as::<_, ApiKey>(
             r#"
-            INSERT INTO api_keys (id, user_id, key, name, permissions, created_at, expires_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO api_keys (id, user_id, key_hash, key_prefix, name, permissions, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, name, key_prefix, permissions, created_at, expires_at, revoked_at
             "#
         )
-        .bind(Uuid::new_v4())
+        .bind(self.ids.next_id())
         .bind(user_id)
-        .bind(api_key)
+        .bind(key_hash)
+        .bind(key_prefix)
         .bind(name)
-        .bind(permissions)
+        .bind(sqlx::types::Json(permissions))
         .bind(Utc::now())
         .bind(Utc::now() + chrono::Duration::days(90))
-        .execute(&self.pool)
+        .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(())
+        Ok(row)
     }
 
-    /// Récupérer les permissions d'une clé API
+    /// Récupérer les permissions d'une clé API à partir de son empreinte
     pub async fn get_api_key_permissions(
         &self,
-        api_key: &str,
+        key_hash: &str,
     ) -> Result<(Uuid, Vec<String>)> {
-        let row: Option<(Uuid, Vec<String>)> = sqlx::query_as(
-            "SELECT user_id, permissions FROM api_keys WHERE key = $1 AND expires_at > NOW()"
+        let row: Option<(Uuid, sqlx::types::Json<Vec<String>>)> = sqlx::query_as(
+            r#"
+            SELECT user_id, permissions FROM api_keys
+            WHERE key_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            "#
         )
-        .bind(api_key)
+        .bind(key_hash)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        row.map(|(user_id, permissions)| (user_id, permissions.0))
+            .ok_or(AppError::Unauthorized)
+    }
+
+    /// Lister les clés API actives d'un utilisateur
+    pub async fn list_user_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        let rows = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, name, key_prefix, permissions, created_at, expires_at, revoked_at
+            FROM api_keys
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Révoquer une clé API (retourne false si introuvable)
+    pub async fn revoke_api_key(&self, user_id: Uuid, key_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL"
+        )
+        .bind(key_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enregistrer un webhook utilisateur
+    pub async fn create_user_webhook(
+        &self,
+        user_id: Uuid,
+        url: &str,
+        secret: &str,
+        event_types: &[String],
+    ) -> Result<UserWebhook> {
+        let row = sqlx::query_as::<_, UserWebhook>(
+            r#"
+            INSERT INTO user_webhooks (id, user_id, url, secret, event_types, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, url, secret, event_types, created_at
+            "#
+        )
+        .bind(self.ids.next_id())
+        .bind(user_id)
+        .bind(url)
+        .bind(secret)
+        .bind(sqlx::types::Json(event_types))
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row)
+    }
+
+    /// Lister les webhooks d'un utilisateur
+    pub async fn list_user_webhooks(&self, user_id: Uuid) -> Result<Vec<UserWebhook>> {
+        let rows = sqlx::query_as::<_, UserWebhook>(
+            r#"
+            SELECT id, user_id, url, secret, event_types, created_at
+            FROM user_webhooks
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Webhooks d'un utilisateur abonnés à un type d'événement
+    pub async fn list_webhooks_for_event(&self, user_id: Uuid, event_type: &str) -> Result<Vec<UserWebhook>> {
+        let rows = sqlx::query_as::<_, UserWebhook>(
+            r#"
+            SELECT id, user_id, url, secret, event_types, created_at
+            FROM user_webhooks
+            WHERE user_id = $1 AND event_types ? $2
+            "#
+        )
+        .bind(user_id)
+        .bind(event_type)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    /// Supprimer un webhook utilisateur
+    pub async fn delete_user_webhook(&self, user_id: Uuid, webhook_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM user_webhooks WHERE id = $1 AND user_id = $2")
+            .bind(webhook_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Ajouter une notification à la boîte de réception d'un utilisateur
+    pub async fn insert_notification(
+        &self,
+        user_id: Uuid,
+        notification_type: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<Notification> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (id, user_id, type, title, body)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, type, title, body, read, created_at
+            "#
+        )
+        .bind(self.ids.next_id())
+        .bind(user_id)
+        .bind(notification_type)
+        .bind(title)
+        .bind(body)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Lister les notifications d'un utilisateur (plus récentes d'abord)
+    ///
+    /// Retourne la page, le total et le nombre de notifications non lues.
+    pub async fn list_notifications(
+        &self,
+        user_id: Uuid,
+        pagination: Pagination,
+    ) -> Result<(Vec<Notification>, i64, i64)> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, user_id, type, title, body, read, created_at
+            FROM notifications
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#
+        )
+        .bind(user_id)
+        .bind(pagination.per_page)
+        .bind(pagination.offset())
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let (total, unread): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE NOT read)
+            FROM notifications
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_one(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((notifications, total, unread))
+    }
+
+    /// Marquer une notification comme lue (false si elle n'appartient pas à l'utilisateur)
+    pub async fn mark_notification_read(&self, user_id: Uuid, notification_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("UPDATE notifications SET read = TRUE WHERE id = $1 AND user_id = $2")
+            .bind(notification_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Retrouver l'utilisateur d'un client Stripe
+    pub async fn get_user_id_by_stripe_customer(&self, customer_id: &str) -> Result<Option<Uuid>> {
+        let row: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM users WHERE stripe_customer_id = $1"
+        )
+        .bind(customer_id)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(row.map(|(id,)| id))
+    }
+
+    /// Remplacer le secret d'une clé API (l'ancien cesse immédiatement de fonctionner)
+    pub async fn rotate_api_key(
+        &self,
+        user_id: Uuid,
+        key_id: Uuid,
+        key_hash: &str,
+        key_prefix: &str,
+    ) -> Result<Option<ApiKey>> {
+        let row = sqlx::query_as::<_, ApiKey>(
+            r#"
+            UPDATE api_keys
+            SET key_hash = $1, key_prefix = $2, created_at = NOW(), expires_at = NOW() + INTERVAL '90 days'
+            WHERE id = $3 AND user_id = $4 AND revoked_at IS NULL
+            RETURNING id, user_id, name, key_prefix, permissions, created_at, expires_at, revoked_at
+            "#
+        )
+        .bind(key_hash)
+        .bind(key_prefix)
+        .bind(key_id)
+        .bind(user_id)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        row.ok_or(AppError::Unauthorized)
+        Ok(row)
+    }
+
+    // === PRÉFÉRENCES DE NOTIFICATION ===
+
+    /// Récupérer les préférences de notification (valeurs par défaut si absentes)
+    pub async fn get_notification_preferences(&self, user_id: Uuid) -> Result<NotificationPreferences> {
+        let preferences = sqlx::query_as::<_, NotificationPreferences>(
+            r#"
+            SELECT user_id, job_complete_email, job_failed_email, job_complete_sms,
+                   file_expiry_email, marketing, updated_at
+            FROM notification_preferences
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(preferences.unwrap_or_else(|| NotificationPreferences::default_for(user_id)))
+    }
+
+    /// Enregistrer les préférences de notification
+    pub async fn upsert_notification_preferences(&self, preferences: &NotificationPreferences) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_preferences (
+                user_id, job_complete_email, job_failed_email, job_complete_sms,
+                file_expiry_email, marketing, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_id) DO UPDATE SET
+                job_complete_email = EXCLUDED.job_complete_email,
+                job_failed_email = EXCLUDED.job_failed_email,
+                job_complete_sms = EXCLUDED.job_complete_sms,
+                file_expiry_email = EXCLUDED.file_expiry_email,
+                marketing = EXCLUDED.marketing,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(preferences.user_id)
+        .bind(preferences.job_complete_email)
+        .bind(preferences.job_failed_email)
+        .bind(preferences.job_complete_sms)
+        .bind(preferences.file_expiry_email)
+        .bind(preferences.marketing)
+        .bind(preferences.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // === RÉGLAGES DE QUANTIFICATION ===
+
+    /// Récupérer les réglages de quantification par défaut (aucun si absents)
+    pub async fn get_quantization_preferences(&self, user_id: Uuid) -> Result<QuantizationPreferences> {
+        let preferences = sqlx::query_as::<_, QuantizationPreferences>(
+            r#"
+            SELECT user_id, default_method, default_bits, default_group_size,
+                   default_output_format, unique_job_names, updated_at
+            FROM user_preferences
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(preferences.unwrap_or_else(|| QuantizationPreferences::default_for(user_id)))
+    }
+
+    /// Enregistrer les réglages de quantification par défaut
+    pub async fn upsert_quantization_preferences(&self, preferences: &QuantizationPreferences) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_preferences (
+                user_id, default_method, default_bits, default_group_size,
+                default_output_format, unique_job_names, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_id) DO UPDATE SET
+                default_method = EXCLUDED.default_method,
+                default_bits = EXCLUDED.default_bits,
+                default_group_size = EXCLUDED.default_group_size,
+                default_output_format = EXCLUDED.default_output_format,
+                unique_job_names = EXCLUDED.unique_job_names,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(preferences.user_id)
+        .bind(&preferences.default_method)
+        .bind(preferences.default_bits)
+        .bind(preferences.default_group_size)
+        .bind(&preferences.default_output_format)
+        .bind(preferences.unique_job_names)
+        .bind(preferences.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
     }
 }
 
@@ -724,6 +2304,7 @@ impl Clone for Database {
     fn clone(&self) -> Self {
         Self {
             pool: self.pool.clone(),
+            ids: self.ids.clone(),
         }
     }
 }
@@ -738,4 +2319,128 @@ pub struct JobStats {
     pub failed: i64,
     pub cancelled: i64,
     pub average_duration_seconds: f64,
-}
\ No newline at end of file
+}
+
+/// Motif `ILIKE` de recherche partielle (`%` et `_` saisis pris littéralement)
+fn like_pattern(fragment: &str) -> String {
+    let escaped = fragment
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+
+    #[test]
+    fn pool_options_apply_the_configured_settings() {
+        let mut config = testing::config();
+        config.database_max_connections = 12;
+        config.database_min_connections = 3;
+        config.database_acquire_timeout = 7;
+        config.database_idle_timeout = 120;
+
+        let options = PoolSettings::from_config(&config).pool_options().unwrap();
+        assert_eq!(options.get_max_connections(), 12);
+        assert_eq!(options.get_min_connections(), 3);
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(7));
+        assert_eq!(options.get_idle_timeout(), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn min_connections_above_max_is_rejected() {
+        let settings = PoolSettings {
+            max_connections: 2,
+            min_connections: 5,
+            ..PoolSettings::default()
+        };
+        assert!(matches!(settings.pool_options(), Err(AppError::Validation(_))));
+
+        let settings = PoolSettings {
+            max_connections: 0,
+            min_connections: 0,
+            ..PoolSettings::default()
+        };
+        assert!(matches!(settings.pool_options(), Err(AppError::Validation(_))));
+    }
+
+    /// Couche de tracing qui retient les requêtes signalées comme lentes
+    #[derive(Clone, Default)]
+    struct SlowQueries(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SlowQueries {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let metadata = event.metadata();
+            if metadata.target() != "sqlx::query" || *metadata.level() != tracing::Level::WARN {
+                return;
+            }
+            let mut fields = String::new();
+            event.record(&mut |field: &tracing::field::Field, value: &dyn std::fmt::Debug| {
+                fields.push_str(&format!("{}={:?} ", field.name(), value));
+            });
+            self.0.lock().unwrap().push(fields);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn slow_query_is_logged_with_its_duration() {
+        use tracing_subscriber::prelude::*;
+
+        let slow = SlowQueries::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(slow.clone()));
+
+        let settings = PoolSettings { slow_query_ms: 50, ..PoolSettings::default() };
+        let db = Database::new(&testing::database_url(), None, &settings).await.unwrap();
+
+        sqlx::query("SELECT 1").execute(&db.pool).await.unwrap();
+        assert!(slow.0.lock().unwrap().is_empty());
+
+        sqlx::query("SELECT pg_sleep(0.2)").execute(&db.pool).await.unwrap();
+        let logged = slow.0.lock().unwrap().clone();
+        assert_eq!(logged.len(), 1, "{:?}", logged);
+        assert!(logged[0].contains("pg_sleep"), "{}", logged[0]);
+        assert!(logged[0].contains("elapsed="), "{}", logged[0]);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn statement_timeout_cancels_a_runaway_query() {
+        let settings = PoolSettings { statement_timeout_ms: 100, ..PoolSettings::default() };
+        let db = Database::new(&testing::database_url(), None, &settings).await.unwrap();
+
+        let err = sqlx::query("SELECT pg_sleep(1)").execute(&db.pool).await.unwrap_err();
+        assert!(err.to_string().contains("statement timeout"), "{}", err);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite PostgreSQL (TEST_DATABASE_URL)"]
+    async fn reads_use_the_replica_and_writes_the_primary() {
+        let url = testing::database_url();
+        let settings = PoolSettings::default();
+
+        let primary_only = Database::new(&url, None, &settings).await.unwrap();
+        assert!(!primary_only.has_read_replica());
+        assert!(std::ptr::eq(primary_only.read_pool(), primary_only.write_pool()));
+
+        // Réplique fermée: les lectures échouent, les écritures passent toujours
+        let db = Database::new(&url, Some(&url), &settings).await.unwrap();
+        assert!(db.has_read_replica());
+        db.read_pool().close().await;
+
+        let user = User::new(format!("replica-{}@example.com", Uuid::new_v4()), "MotDePasse123!");
+        db.create_user(&user).await.unwrap();
+        assert!(db.get_user_by_email(&user.email).await.is_err());
+
+        // Primaire fermé: les lectures passent toujours par la réplique
+        let db = Database::new(&url, Some(&url), &settings).await.unwrap();
+        db.write_pool().close().await;
+
+        assert_eq!(db.get_user_by_email(&user.email).await.unwrap().id, user.id);
+        let other = User::new(format!("replica-{}@example.com", Uuid::new_v4()), "MotDePasse123!");
+        assert!(db.create_user(&other).await.is_err());
+    }
+}
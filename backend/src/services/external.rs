@@ -210,6 +210,117 @@ impl SendGridClient {
     }
 }
 
+/// Fournisseur d'emails SMTP (alternative à SendGrid)
+#[cfg(feature = "email")]
+pub struct SmtpEmailProvider {
+    transport: lettre::AsyncSmtpTransport<lettre::Tokio1Executor>,
+    from: lettre::message::Mailbox,
+}
+
+#[cfg(feature = "email")]
+impl SmtpEmailProvider {
+    /// Construire le transport SMTP (STARTTLS si `use_tls`, sinon connexion en clair)
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        use_tls: bool,
+        from_email: &str,
+        from_name: &str,
+    ) -> Result<Self> {
+        use lettre::transport::smtp::authentication::Credentials;
+        use lettre::{AsyncSmtpTransport, Tokio1Executor};
+
+        let mut builder = if use_tls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(|e| AppError::ExternalService(format!("SMTP relay invalide: {}", e)))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+        };
+
+        builder = builder.port(port).timeout(Some(Duration::from_secs(30)));
+
+        if let (Some(username), Some(password)) = (username, password) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = format!("{} <{}>", from_name, from_email)
+            .parse()
+            .map_err(|e| AppError::Validation(format!("EMAIL_FROM invalide: {}", e)))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+
+    /// Vérifier la connexion au serveur SMTP
+    pub async fn health_check(&self) -> Result<()> {
+        use lettre::AsyncTransport;
+
+        match self.transport.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AppError::ExternalService("Serveur SMTP injoignable".to_string())),
+            Err(e) => Err(Self::map_smtp_error(e)),
+        }
+    }
+
+    /// Distinguer les échecs d'authentification (535) des autres erreurs SMTP
+    fn map_smtp_error(error: lettre::transport::smtp::Error) -> AppError {
+        let is_auth_failure = error
+            .status()
+            .map(|code| code.to_string() == "535")
+            .unwrap_or(false);
+
+        if is_auth_failure {
+            AppError::SmtpAuthFailed(error.to_string())
+        } else {
+            AppError::ExternalService(format!("SMTP: {}", error))
+        }
+    }
+}
+
+#[cfg(feature = "email")]
+#[async_trait::async_trait]
+impl crate::core::notification_service::EmailProvider for SmtpEmailProvider {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        use lettre::{AsyncTransport, Message};
+
+        let recipient = to
+            .parse()
+            .map_err(|e| AppError::Validation(format!("Adresse email invalide '{}': {}", to, e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(recipient)
+            .subject(subject)
+            .body(body.to_string())
+            .map_err(|e| AppError::NotificationError(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(Self::map_smtp_error)?;
+
+        Ok(())
+    }
+}
+
+/// Délai laissé à un script interrompu (SIGINT) avant de le tuer
+const INTERRUPT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Intervalle de mesure de la mémoire d'un script
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sortie d'un script exécuté sous surveillance mémoire
+#[derive(Debug, Clone)]
+pub struct ScriptOutput {
+    pub stdout: String,
+    /// Pic de mémoire résidente observé (None si non mesurable sur cette plateforme)
+    pub peak_memory_bytes: Option<u64>,
+}
+
 /// Client Python pour exécuter des scripts
 pub struct PythonClient {
     scripts_dir: std::path::PathBuf,
@@ -228,6 +339,23 @@ impl PythonClient {
 
     /// Exécuter un script Python
     pub async fn call_script(&self, script_name: &str, args: &[&str]) -> Result<String> {
+        self.call_script_with_memory_limit(script_name, args, None).await
+            .map(|output| output.stdout)
+    }
+
+    /// Exécuter un script Python en surveillant sa mémoire résidente
+    ///
+    /// Au-delà de `timeout_seconds`, le script est interrompu (voir `interrupt`)
+    /// et `Timeout` n'est rendu qu'une fois le processus terminé. Au-delà de
+    /// `memory_limit_bytes`, il est tué aussitôt (`OutOfMemory`) avant que le
+    /// noyau ne s'en prenne au worker. Un appel abandonné (job annulé) tue
+    /// aussi le processus.
+    pub async fn call_script_with_memory_limit(
+        &self,
+        script_name: &str,
+        args: &[&str],
+        memory_limit_bytes: Option<u64>,
+    ) -> Result<ScriptOutput> {
         let script_path = self.scripts_dir.join(script_name);
         
         if !script_path.exists() {
@@ -241,16 +369,66 @@ impl PythonClient {
             command.arg(arg);
         }
 
-        let output = command
-            .output()
-            .await
+        let mut child = command
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
             .map_err(|e| AppError::ExternalService(e.to_string()))?;
 
-        if output.status.success() {
-            String::from_utf8(output.stdout)
-                .map_err(|e| AppError::ParseError(e.to_string()))
+        // Lire les sorties en parallèle pour ne pas bloquer le script sur un pipe plein
+        let stdout = tokio::spawn(read_pipe(child.stdout.take()));
+        let stderr = tokio::spawn(read_pipe(child.stderr.take()));
+
+        let pid = child.id();
+        let mut peak_memory_bytes: Option<u64> = None;
+        let deadline = tokio::time::sleep(Duration::from_secs(self.timeout_seconds));
+        tokio::pin!(deadline);
+        let mut sampler = tokio::time::interval(MEMORY_SAMPLE_INTERVAL);
+
+        let status = loop {
+            tokio::select! {
+                status = child.wait() => {
+                    break status.map_err(|e| AppError::ExternalService(e.to_string()))?;
+                }
+                _ = &mut deadline => {
+                    self.interrupt(&mut child, script_name).await;
+                    return Err(AppError::Timeout(format!(
+                        "{} interrompu après {} s",
+                        script_name, self.timeout_seconds
+                    )));
+                }
+                _ = sampler.tick() => {
+                    let Some(rss) = pid.and_then(resident_memory_bytes) else {
+                        continue;
+                    };
+                    peak_memory_bytes = Some(peak_memory_bytes.map_or(rss, |peak| peak.max(rss)));
+
+                    if let Some(limit) = memory_limit_bytes.filter(|limit| rss > *limit) {
+                        if let Err(e) = child.kill().await {
+                            log::error!("Impossible d'arrêter le script {}: {}", script_name, e);
+                        }
+                        log::warn!("Script {} tué (mémoire)", script_name);
+                        return Err(AppError::OutOfMemory(format!(
+                            "{} a dépassé {} de mémoire ({} utilisés)",
+                            script_name,
+                            crate::utils::helpers::format_file_size(limit),
+                            crate::utils::helpers::format_file_size(rss),
+                        )));
+                    }
+                }
+            }
+        };
+
+        let stdout = stdout.await.unwrap_or_default();
+        let stderr = stderr.await.unwrap_or_default();
+
+        if status.success() {
+            let stdout = String::from_utf8(stdout)
+                .map_err(|e| AppError::ParseError(e.to_string()))?;
+            Ok(ScriptOutput { stdout, peak_memory_bytes })
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = String::from_utf8_lossy(&stderr);
             Err(AppError::ExternalService(format!(
                 "Python script failed: {}",
                 stderr
@@ -258,6 +436,53 @@ impl PythonClient {
         }
     }
 
+    /// Interrompre un script: SIGINT d'abord (`KeyboardInterrupt` côté Python,
+    /// qui libère proprement le GPU), puis SIGKILL passé le délai de grâce.
+    /// Ne rend la main qu'une fois le processus terminé.
+    async fn interrupt(&self, child: &mut tokio::process::Child, script_name: &str) {
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+            let signalled = tokio::process::Command::new("kill")
+                .args(["-INT", &pid.to_string()])
+                .status()
+                .await
+                .map(|status| status.success())
+                .unwrap_or(false);
+
+            if signalled {
+                if let Ok(Ok(_)) = tokio::time::timeout(INTERRUPT_GRACE_PERIOD, child.wait()).await {
+                    log::warn!("Script {} interrompu (timeout)", script_name);
+                    return;
+                }
+            }
+        }
+
+        match child.kill().await {
+            Ok(()) => log::warn!("Script {} tué (timeout)", script_name),
+            Err(e) => log::error!("Impossible d'arrêter le script {}: {}", script_name, e),
+        }
+    }
+
+    /// Le module Python est-il importable par l'interpréteur configuré ?
+    pub async fn is_module_available(&self, module: &str) -> bool {
+        let output = tokio::process::Command::new(&self.python_path)
+            .args(["-c", &format!("import {}", module)])
+            .output()
+            .await;
+
+        matches!(output, Ok(output) if output.status.success())
+    }
+
+    /// Backend GPTQ (auto_gptq) installé ?
+    pub async fn test_gptq_connection(&self) -> bool {
+        self.is_module_available("auto_gptq").await
+    }
+
+    /// Backend AWQ (autoawq, module `awq`) installé ?
+    pub async fn test_awq_connection(&self) -> bool {
+        self.is_module_available("awq").await
+    }
+
     /// Vérifier les dépendances Python
     pub async fn check_dependencies(&self) -> Result<Vec<DependencyStatus>> {
         let scripts = ["quantize_int8.py", "quantize_gptq.py", "convert_gguf.py"];
@@ -287,6 +512,220 @@ impl PythonClient {
     }
 }
 
+/// Mémoire résidente d'un processus (`VmRSS` de `/proc`, Linux uniquement)
+fn resident_memory_bytes(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+/// Lire entièrement la sortie d'un processus
+async fn read_pipe<R: tokio::io::AsyncRead + Unpin>(pipe: Option<R>) -> Vec<u8> {
+    use tokio::io::AsyncReadExt;
+
+    let mut buffer = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut buffer).await;
+    }
+    buffer
+}
+
+/// Client pour le hub Hugging Face
+pub struct HuggingFaceClient {
+    http_client: Arc<HttpClient>,
+    token: Option<String>,
+    max_download_bytes: u64,
+    hub_url: String,
+}
+
+/// Longueur maximale d'un segment (propriétaire ou nom) d'identifiant de dépôt
+const MAX_REPO_SEGMENT_LEN: usize = 96;
+
+/// L'identifiant est-il de la forme `propriétaire/nom` ou `nom` ?
+///
+/// Les dépôts historiques du Hub n'ont pas de propriétaire (`gpt2`,
+/// `bert-base-uncased`). Segments alphanumériques avec `-`, `_` et `.`,
+/// ni vides ni `.`/`..`: l'identifiant est interpolé tel quel dans les URLs du Hub.
+pub fn is_valid_repo_id(repo_id: &str) -> bool {
+    let segments: Vec<&str> = repo_id.split('/').collect();
+    if segments.len() > 2 {
+        return false;
+    }
+
+    segments.iter().all(|segment| {
+        !segment.is_empty()
+            && segment.len() <= MAX_REPO_SEGMENT_LEN
+            && *segment != "."
+            && *segment != ".."
+            && segment.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    })
+}
+
+/// Chemin relatif sûr d'un fichier de dépôt (`rfilename`)
+///
+/// Refuse tout composant `..`, racine ou préfixe de lecteur: le fichier ne
+/// peut être écrit qu'à l'intérieur du répertoire de destination.
+pub fn safe_repo_file_path(rfilename: &str) -> Result<std::path::PathBuf> {
+    use std::path::Component;
+
+    let path = std::path::Path::new(rfilename);
+    let mut relative = std::path::PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(AppError::InvalidPath);
+            }
+        }
+    }
+
+    if relative.as_os_str().is_empty() {
+        return Err(AppError::InvalidPath);
+    }
+    Ok(relative)
+}
+
+impl HuggingFaceClient {
+    const HUB_URL: &'static str = "https://huggingface.co";
+
+    pub fn new(token: Option<String>, max_download_bytes: u64) -> Self {
+        let http_client = Arc::new(
+            HttpClient::builder()
+                .timeout(Duration::from_secs(3600))
+                .build()
+                .expect("Failed to create HTTP client")
+        );
+
+        Self {
+            http_client,
+            token,
+            max_download_bytes,
+            hub_url: Self::HUB_URL.to_string(),
+        }
+    }
+
+    /// Interroger un autre hub (miroir, serveur simulé en test)
+    pub fn with_hub_url(mut self, hub_url: &str) -> Self {
+        self.hub_url = hub_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Récupérer les informations d'un dépôt (fichiers et tailles)
+    pub async fn model_info(&self, repo_id: &str, revision: &str) -> Result<HubModelInfo> {
+        if !is_valid_repo_id(repo_id) {
+            return Err(AppError::Validation(format!(
+                "Identifiant de dépôt invalide (attendu: propriétaire/nom ou nom): {}", repo_id
+            )));
+        }
+
+        let url = format!(
+            "{}/api/models/{}/revision/{}",
+            self.hub_url,
+            repo_id,
+            urlencoding::encode(revision)
+        );
+
+        let mut request = self.http_client.get(&url).query(&[("blobs", "true")]);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(e.to_string()))?;
+
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::NOT_FOUND | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(AppError::NotFound(format!("Dépôt Hugging Face {}@{}", repo_id, revision)));
+            }
+            status => {
+                return Err(AppError::ExternalService(format!("Hugging Face error: {}", status)));
+            }
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::ParseError(e.to_string()))
+    }
+
+    /// Télécharger les fichiers d'un dépôt dans `dest_dir`
+    pub async fn download_model(&self, info: &HubModelInfo, revision: &str, dest_dir: &std::path::Path) -> Result<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        if info.total_size() > self.max_download_bytes {
+            return Err(AppError::FileTooLarge);
+        }
+
+        if !is_valid_repo_id(&info.id) {
+            return Err(AppError::InvalidPath);
+        }
+
+        tokio::fs::create_dir_all(dest_dir).await?;
+        let mut downloaded: u64 = 0;
+
+        for file in &info.siblings {
+            let target = dest_dir.join(safe_repo_file_path(&file.rfilename)?);
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+
+            let url = format!(
+                "{}/{}/resolve/{}/{}",
+                self.hub_url,
+                info.id,
+                urlencoding::encode(revision),
+                file.rfilename
+            );
+
+            let mut request = self.http_client.get(&url);
+            if let Some(token) = &self.token {
+                request = request.bearer_auth(token);
+            }
+
+            let mut response = request
+                .send()
+                .await
+                .map_err(|e| AppError::ExternalService(e.to_string()))?;
+
+            if !response.status().is_success() {
+                return Err(AppError::ExternalService(format!(
+                    "Hugging Face download failed for {}: {}",
+                    file.rfilename,
+                    response.status()
+                )));
+            }
+
+            let mut output = tokio::fs::File::create(&target).await?;
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| AppError::ExternalService(e.to_string()))?
+            {
+                downloaded += chunk.len() as u64;
+                if downloaded > self.max_download_bytes {
+                    return Err(AppError::FileTooLarge);
+                }
+                output.write_all(&chunk).await?;
+            }
+            output.flush().await?;
+        }
+
+        Ok(downloaded)
+    }
+}
+
 // Structures pour Google OAuth
 #[derive(Debug, Deserialize)]
 struct GoogleTokenInfo {
@@ -351,4 +790,250 @@ pub struct DependencyStatus {
     pub name: String,
     pub status: String,
     pub version: String,
-}
\ No newline at end of file
+}
+
+// Structures pour Hugging Face
+#[derive(Debug, Deserialize)]
+pub struct HubModelInfo {
+    pub id: String,
+    pub sha: Option<String>,
+    #[serde(default)]
+    pub siblings: Vec<HubFile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HubFile {
+    pub rfilename: String,
+    pub size: Option<u64>,
+}
+
+impl HubModelInfo {
+    /// Taille totale des fichiers du dépôt
+    pub fn total_size(&self) -> u64 {
+        self.siblings.iter().filter_map(|f| f.size).sum()
+    }
+
+    /// Format des poids du dépôt
+    pub fn weights_format(&self) -> crate::models::ModelFormat {
+        if self.siblings.iter().any(|f| f.rfilename.ends_with(".safetensors")) {
+            crate::models::ModelFormat::Safetensors
+        } else if self.siblings.iter().any(|f| f.rfilename.ends_with(".gguf")) {
+            crate::models::ModelFormat::Gguf
+        } else if self.siblings.iter().any(|f| f.rfilename.ends_with(".onnx")) {
+            crate::models::ModelFormat::Onnx
+        } else {
+            crate::models::ModelFormat::PyTorch
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn nonexistent_repo_is_rejected() {
+        let hub = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/models/acme/missing/revision/main"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&hub)
+            .await;
+
+        let client = HuggingFaceClient::new(None, 1024).with_hub_url(&hub.uri());
+        let err = client.model_info("acme/missing", "main").await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn existing_repo_lists_its_files() {
+        let hub = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/models/acme/tiny/revision/main"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "acme/tiny",
+                "sha": "abc123",
+                "siblings": [
+                    { "rfilename": "config.json", "size": 512 },
+                    { "rfilename": "model.safetensors", "size": 4096 }
+                ]
+            })))
+            .mount(&hub)
+            .await;
+
+        let client = HuggingFaceClient::new(None, 1024).with_hub_url(&hub.uri());
+        let info = client.model_info("acme/tiny", "main").await.unwrap();
+        assert_eq!(info.total_size(), 4608);
+        assert!(matches!(info.weights_format(), crate::models::ModelFormat::Safetensors));
+    }
+
+    #[tokio::test]
+    async fn malformed_repo_id_is_rejected_without_calling_the_hub() {
+        let hub = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&hub)
+            .await;
+
+        let client = HuggingFaceClient::new(None, 1024).with_hub_url(&hub.uri());
+        let err = client.model_info("../../admin", "main").await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn repo_ids_are_owner_slash_name_or_single_segment() {
+        assert!(is_valid_repo_id("meta-llama/Llama-2-7b-hf"));
+        assert!(is_valid_repo_id("acme/model_v1.5"));
+        assert!(is_valid_repo_id("gpt2"));
+        assert!(is_valid_repo_id("bert-base-uncased"));
+        assert!(is_valid_repo_id("t5-small"));
+
+        assert!(!is_valid_repo_id(""));
+        assert!(!is_valid_repo_id(".."));
+        assert!(!is_valid_repo_id("acme/"));
+        assert!(!is_valid_repo_id("/model"));
+        assert!(!is_valid_repo_id("../model"));
+        assert!(!is_valid_repo_id("acme/.."));
+        assert!(!is_valid_repo_id("acme/model/extra"));
+        assert!(!is_valid_repo_id("acme/mo del"));
+        assert!(!is_valid_repo_id(&format!("acme/{}", "a".repeat(MAX_REPO_SEGMENT_LEN + 1))));
+    }
+
+    #[test]
+    fn repo_file_paths_stay_inside_the_destination() {
+        assert_eq!(
+            safe_repo_file_path("onnx/model.onnx").unwrap(),
+            std::path::PathBuf::from("onnx/model.onnx")
+        );
+        assert_eq!(
+            safe_repo_file_path("./config.json").unwrap(),
+            std::path::PathBuf::from("config.json")
+        );
+
+        for rejected in ["../etc/passwd", "weights/../../x", "/etc/passwd", "", "."] {
+            assert!(safe_repo_file_path(rejected).is_err(), "{}", rejected);
+        }
+    }
+
+    /// Serveur SMTP minimal: accepte un message et retourne l'enveloppe et le contenu reçus
+    #[cfg(feature = "email")]
+    async fn mock_smtp_server() -> (u16, tokio::task::JoinHandle<(String, String)>) {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = socket.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let (mut mail_from, mut data) = (String::new(), String::new());
+
+            writer.write_all(b"220 mock ESMTP\r\n").await.unwrap();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let command = line.to_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                    b"250 mock\r\n"
+                } else if command.starts_with("MAIL FROM") {
+                    mail_from = line.clone();
+                    b"250 OK\r\n"
+                } else if command.starts_with("RCPT TO") {
+                    b"250 OK\r\n"
+                } else if command == "DATA" {
+                    writer.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n").await.unwrap();
+                    while let Some(line) = lines.next_line().await.unwrap() {
+                        if line == "." {
+                            break;
+                        }
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    b"250 OK: queued\r\n"
+                } else if command == "QUIT" {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+
+            (mail_from, data)
+        });
+
+        (port, server)
+    }
+
+    #[cfg(feature = "email")]
+    #[tokio::test]
+    async fn smtp_provider_sends_from_the_configured_address() {
+        use crate::core::notification_service::EmailProvider;
+
+        let (port, server) = mock_smtp_server().await;
+        let provider = SmtpEmailProvider::new(
+            "127.0.0.1",
+            port,
+            None,
+            None,
+            false,
+            "noreply@quantization.test",
+            "Plateforme de quantification",
+        )
+        .unwrap();
+
+        provider.send("user@example.com", "Votre job est terminé", "Bonjour").await.unwrap();
+        drop(provider);
+
+        let (mail_from, data) = tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .expect("message reçu")
+            .unwrap();
+        assert!(mail_from.contains("<noreply@quantization.test>"), "{}", mail_from);
+        assert!(data.contains("From: \"Plateforme de quantification\" <noreply@quantization.test>")
+            || data.contains("From: Plateforme de quantification <noreply@quantization.test>"), "{}", data);
+        assert!(data.contains("To: user@example.com"), "{}", data);
+    }
+
+    #[tokio::test]
+    async fn script_past_its_timeout_is_terminated() {
+        // Le script écrirait ce fichier s'il allait au bout
+        let scripts = crate::utils::testing::scratch_dir("scripts");
+        let marker = scripts.join("termine");
+        std::fs::write(
+            scripts.join("long.py"),
+            format!("import time\ntime.sleep(3)\nopen({:?}, 'w').write('fini')\n", marker.to_string_lossy()),
+        )
+        .unwrap();
+        let client = PythonClient::new(&scripts.to_string_lossy(), Some("python3"), 1);
+
+        let started = std::time::Instant::now();
+        let err = client.call_script("long.py", &[]).await.unwrap_err();
+        assert!(matches!(err, AppError::Timeout(_)), "{:?}", err);
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        tokio::time::sleep(Duration::from_secs(4)).await;
+        assert!(!marker.exists(), "le script a continué après le timeout");
+    }
+
+    #[tokio::test]
+    async fn script_above_its_memory_cap_is_killed() {
+        let scripts = crate::utils::testing::scratch_dir("scripts");
+        // ~200 Mo réellement écrits, puis une attente pour laisser passer l'échantillonnage
+        std::fs::write(scripts.join("gourmand.py"), "import time\ndata = b'x' * (200 * 1024 * 1024)\ntime.sleep(5)\n").unwrap();
+        std::fs::write(scripts.join("sobre.py"), "import time\ntime.sleep(1.5)\nprint('ok')\n").unwrap();
+        let client = PythonClient::new(&scripts.to_string_lossy(), Some("python3"), 30);
+        let limit = 64 * 1024 * 1024;
+
+        let err = client.call_script_with_memory_limit("gourmand.py", &[], Some(limit)).await.unwrap_err();
+        assert!(matches!(err, AppError::OutOfMemory(_)), "{:?}", err);
+
+        let output = client.call_script_with_memory_limit("sobre.py", &[], Some(limit)).await.unwrap();
+        assert_eq!(output.stdout.trim(), "ok");
+        let peak = output.peak_memory_bytes.expect("pic mesuré via /proc");
+        assert!(peak > 0 && peak < limit);
+    }
+}
@@ -4,10 +4,16 @@ pub mod queue;
 pub mod storage;
 pub mod external;
 pub mod cache;
+pub mod audit;
+pub mod webhook;
 
 // Ré-exports pour faciliter l'import
-pub use database::Database;
-pub use queue::{JobQueue, ProgressEvent, JobResult};
-pub use storage::FileStorage;
-pub use external::{GoogleAuthClient, SendGridClient, PythonClient};
-pub use cache::{Cache, CacheStats};
\ No newline at end of file
+pub use database::{Database, PoolSettings};
+pub use queue::{JobQueue, ProgressEvent, ProgressStage, JobResult};
+pub use storage::{FileStorage, StoredObject};
+pub use external::{GoogleAuthClient, SendGridClient, PythonClient, HuggingFaceClient};
+#[cfg(feature = "email")]
+pub use external::SmtpEmailProvider;
+pub use cache::{Cache, CacheStats, UploadSlot};
+pub use audit::{AuditRepository, AuditFilter};
+pub use webhook::WebhookNotifier;
\ No newline at end of file
@@ -10,11 +10,15 @@ use tokio::sync::Mutex;
 pub struct JobQueue {
     client: Arc<Client>,
     prefix: String,
+    scheduler: Arc<Mutex<FairScheduler>>,
 }
 
 impl JobQueue {
     /// Créer une nouvelle queue Redis
-    pub async fn new(redis_url: &str, prefix: Option<&str>) -> Result<Self> {
+    ///
+    /// `fair_ratio` fixe le rapport de service entre deux niveaux de priorité
+    /// adjacents (ex: 3 => 3 jobs "high" pour 1 job "normal").
+    pub async fn new(redis_url: &str, prefix: Option<&str>, fair_ratio: u32) -> Result<Self> {
         let client = Client::open(redis_url)
             .map_err(|e| AppError::RedisError(e.to_string()))?;
         
@@ -30,9 +34,20 @@ impl JobQueue {
         Ok(Self {
             client: Arc::new(client),
             prefix: prefix.unwrap_or("quant:").to_string(),
+            scheduler: Arc::new(Mutex::new(FairScheduler::new(fair_ratio))),
         })
     }
 
+    /// Queue branchée sur un Redis injoignable: chaque opération échoue
+    #[cfg(test)]
+    pub(crate) fn unreachable() -> Self {
+        Self {
+            client: Arc::new(Client::open("redis://127.0.0.1:1").expect("URL Redis valide")),
+            prefix: "test:".to_string(),
+            scheduler: Arc::new(Mutex::new(FairScheduler::new(3))),
+        }
+    }
+
     /// Ajouter un job à la queue
     pub async fn enqueue(&self, job_id: Uuid, priority: i32) -> Result<()> {
         let mut conn = self.client.get_async_connection().await
@@ -48,11 +63,7 @@ impl JobQueue {
             .map_err(|e| AppError::SerializeError(e.to_string()))?;
 
         // Choisir la queue selon la priorité
-        let queue_name = match priority {
-            3 => self.key("queue:high"),
-            2 => self.key("queue:normal"),
-            _ => self.key("queue:low"),
-        };
+        let queue_name = self.tier_key(priority);
 
         conn.lpush(&queue_name, data).await
             .map_err(|e| AppError::RedisError(e.to_string()))?;
@@ -65,12 +76,13 @@ impl JobQueue {
         let mut conn = self.client.get_async_connection().await
             .map_err(|e| AppError::RedisError(e.to_string()))?;
 
-        // Essayer dans l'ordre: high -> normal -> low
-        let queues = [
-            self.key("queue:high"),
-            self.key("queue:normal"), 
-            self.key("queue:low"),
-        ];
+        // L'ordre est choisi par le scheduler pondéré pour éviter la famine
+        // des queues basses sous une charge prioritaire constante
+        let order = self.scheduler.lock().await.next_order();
+        let queues: Vec<String> = order
+            .iter()
+            .map(|tier| self.key(&format!("queue:{}", tier)))
+            .collect();
 
         for queue in &queues {
             let data: Option<String> = conn.rpop(queue, None).await
@@ -87,6 +99,38 @@ impl JobQueue {
         Ok(None)
     }
 
+    /// Poser le marqueur `processing:{id}` d'un job pris en charge
+    ///
+    /// Le TTL borne la durée de traitement attendue: un job encore
+    /// "processing" sans marqueur signale un worker perdu.
+    pub async fn mark_processing(&self, job_id: Uuid, worker_id: &str, ttl_seconds: u64) -> Result<()> {
+        let mut conn = self.client.get_async_connection().await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        let marker = ProcessingMarker {
+            worker_id: worker_id.to_string(),
+            started_at: chrono::Utc::now(),
+        };
+        let value = serde_json::to_string(&marker)
+            .map_err(|e| AppError::SerializeError(e.to_string()))?;
+
+        conn.set_ex(self.key(&format!("{}{}", PROCESSING_PREFIX, job_id)), value, ttl_seconds).await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Retirer le marqueur de traitement d'un job terminé
+    pub async fn clear_processing(&self, job_id: Uuid) -> Result<()> {
+        let mut conn = self.client.get_async_connection().await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        conn.del(self.key(&format!("{}{}", PROCESSING_PREFIX, job_id))).await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Obtenir la taille de la queue
     pub async fn queue_size(&self, priority: Option<i32>) -> Result<u64> {
         let mut conn = self.client.get_async_connection().await
@@ -106,20 +150,82 @@ impl JobQueue {
         .map_err(|e| AppError::RedisError(e.to_string()))
     }
 
-    /// Publier un événement de progression
-    pub async fn publish_progress(&self, job_id: Uuid, progress: i32, status: &str) -> Result<()> {
+    /// Lire sans les retirer les `limit` prochains jobs de chaque queue (inspection admin)
+    ///
+    /// Les entrées sont rangées dans l'ordre de sortie (position 0 = prochain
+    /// job servi dans ce niveau);
+    /// une charge utile illisible est signalée plutôt que d'interrompre la
+    /// lecture. Les marqueurs de traitement sont listés avec leur TTL restant.
+    pub async fn inspect(&self, limit: usize) -> Result<QueueSnapshot> {
         let mut conn = self.client.get_async_connection().await
             .map_err(|e| AppError::RedisError(e.to_string()))?;
 
-        let event = ProgressEvent {
-            job_id,
-            progress,
-            status: status.to_string(),
-            timestamp: chrono::Utc::now(),
+        let mut tiers = Vec::with_capacity(QUEUE_TIERS.len());
+        for tier in QUEUE_TIERS {
+            let queue = self.key(&format!("queue:{}", tier));
+            let length: u64 = conn.llen(&queue).await
+                .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+            // `dequeue` retire par la droite: la fin de la liste sort en premier
+            let mut payloads: Vec<String> = if limit == 0 {
+                Vec::new()
+            } else {
+                conn.lrange(&queue, -(limit as isize), -1).await
+                    .map_err(|e| AppError::RedisError(e.to_string()))?
+            };
+            payloads.reverse();
+
+            let entries = payloads
+                .into_iter()
+                .enumerate()
+                .map(|(position, payload)| QueueEntry::parse(position, payload))
+                .collect();
+
+            tiers.push(QueueTierSnapshot {
+                tier: tier.to_string(),
+                length,
+                entries,
+            });
+        }
+
+        // SCAN plutôt que KEYS: ne bloque pas Redis pendant l'inspection
+        let pattern = self.key(&format!("{}*", PROCESSING_PREFIX));
+        let marker_keys: Vec<String> = {
+            let mut iter = conn.scan_match::<_, String>(&pattern).await
+                .map_err(|e| AppError::RedisError(e.to_string()))?;
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
         };
 
-        let channel = self.key(&format!("progress:{}", job_id));
-        let message = serde_json::to_string(&event)
+        let marker_prefix = self.key(PROCESSING_PREFIX);
+        let mut processing = Vec::with_capacity(marker_keys.len());
+        for key in marker_keys {
+            let ttl_seconds: i64 = conn.ttl(&key).await
+                .map_err(|e| AppError::RedisError(e.to_string()))?;
+            let value: Option<String> = conn.get(&key).await
+                .map_err(|e| AppError::RedisError(e.to_string()))?;
+            // Marqueur expiré entre SCAN et GET
+            let Some(value) = value else {
+                continue;
+            };
+            let job_id = key.strip_prefix(&marker_prefix).and_then(|id| id.parse().ok());
+            processing.push(ProcessingMarkerSnapshot::parse(key, job_id, value, ttl_seconds));
+        }
+        processing.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+
+        Ok(QueueSnapshot { tiers, processing })
+    }
+
+    /// Publier un événement de progression sur `jobs:progress:{id}`
+    pub async fn publish_progress(&self, event: &ProgressEvent) -> Result<()> {
+        let mut conn = self.client.get_async_connection().await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        let channel = self.key(&format!("jobs:progress:{}", event.job_id));
+        let message = serde_json::to_string(event)
             .map_err(|e| AppError::SerializeError(e.to_string()))?;
 
         conn.publish(&channel, message).await
@@ -134,7 +240,7 @@ impl JobQueue {
             .map_err(|e| AppError::RedisError(e.to_string()))?
             .into_pubsub();
 
-        let channel = self.key(&format!("progress:{}", job_id));
+        let channel = self.key(&format!("jobs:progress:{}", job_id));
         pubsub.subscribe(&channel).await
             .map_err(|e| AppError::RedisError(e.to_string()))?;
 
@@ -230,6 +336,15 @@ impl JobQueue {
     fn key(&self, name: &str) -> String {
         format!("{}{}", self.prefix, name)
     }
+
+    /// Clé du niveau correspondant à une priorité
+    fn tier_key(&self, priority: i32) -> String {
+        match priority {
+            3 => self.key("queue:high"),
+            2 => self.key("queue:normal"),
+            _ => self.key("queue:low"),
+        }
+    }
 }
 
 impl Clone for JobQueue {
@@ -237,10 +352,68 @@ impl Clone for JobQueue {
         Self {
             client: self.client.clone(),
             prefix: self.prefix.clone(),
+            scheduler: self.scheduler.clone(),
         }
     }
 }
 
+/// Niveaux de queue, du plus prioritaire au moins prioritaire
+const QUEUE_TIERS: [&str; 3] = ["high", "normal", "low"];
+
+/// Préfixe des marqueurs de traitement (`processing:{job_id}`)
+const PROCESSING_PREFIX: &str = "processing:";
+
+/// Scheduler "smooth weighted round-robin" entre les niveaux de queue
+///
+/// Les poids sont ratio², ratio et 1 : deux niveaux adjacents sont servis
+/// dans un rapport `ratio:1`, et aucun niveau n'attend plus de ratio² + ratio
+/// tours avant d'être prioritaire.
+#[derive(Debug)]
+pub struct FairScheduler {
+    weights: [i64; 3],
+    current: [i64; 3],
+}
+
+impl FairScheduler {
+    /// Créer un scheduler avec le ratio donné (minimum 1)
+    pub fn new(ratio: u32) -> Self {
+        let ratio = ratio.max(1) as i64;
+
+        Self {
+            weights: [ratio * ratio, ratio, 1],
+            current: [0; 3],
+        }
+    }
+
+    /// Ordre dans lequel interroger les queues pour le prochain dequeue
+    ///
+    /// Le niveau élu passe en premier, les autres suivent par priorité
+    /// décroissante pour ne jamais laisser un worker inactif.
+    pub fn next_order(&mut self) -> [&'static str; 3] {
+        let total: i64 = self.weights.iter().sum();
+
+        for (current, weight) in self.current.iter_mut().zip(self.weights.iter()) {
+            *current += weight;
+        }
+
+        let elected = (0..QUEUE_TIERS.len())
+            .max_by_key(|&i| (self.current[i], -(i as i64)))
+            .unwrap_or(0);
+        self.current[elected] -= total;
+
+        let mut order = [QUEUE_TIERS[elected]; 3];
+        let mut slot = 1;
+        for (i, tier) in QUEUE_TIERS.iter().enumerate() {
+            if i != elected {
+                order[slot] = tier;
+                slot += 1;
+            }
+        }
+
+        order
+    }
+}
+
 /// Données d'un job dans la queue
 #[derive(Debug, Serialize, Deserialize)]
 struct JobData {
@@ -249,15 +422,166 @@ struct JobData {
     priority: i32,
 }
 
+/// Entrée d'une queue telle que stockée dans Redis
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueEntry {
+    /// Rang de sortie dans le niveau (0 = prochain job servi)
+    pub position: usize,
+    /// Charge utile brute
+    pub payload: String,
+    pub job_id: Option<Uuid>,
+    pub priority: Option<i32>,
+    pub enqueued_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Charge utile illisible (job empoisonné en tête de queue)
+    pub parse_error: Option<String>,
+}
+
+impl QueueEntry {
+    fn parse(position: usize, payload: String) -> Self {
+        match serde_json::from_str::<JobData>(&payload) {
+            Ok(data) => Self {
+                position,
+                payload,
+                job_id: Some(data.id),
+                priority: Some(data.priority),
+                enqueued_at: Some(data.enqueued_at),
+                parse_error: None,
+            },
+            Err(e) => Self {
+                position,
+                payload,
+                job_id: None,
+                priority: None,
+                enqueued_at: None,
+                parse_error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Contenu d'un niveau de queue (inspection admin)
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueTierSnapshot {
+    pub tier: String,
+    /// Nombre total de jobs en attente dans ce niveau
+    pub length: u64,
+    /// Prochains jobs, dans l'ordre de sortie
+    pub entries: Vec<QueueEntry>,
+}
+
+/// Valeur d'un marqueur de traitement
+#[derive(Debug, Serialize, Deserialize)]
+struct ProcessingMarker {
+    worker_id: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Marqueur de traitement tel que lu dans Redis (inspection admin)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessingMarkerSnapshot {
+    pub key: String,
+    pub job_id: Option<Uuid>,
+    pub worker_id: Option<String>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// TTL restant (-1: aucun TTL, le marqueur ne disparaîtra pas seul)
+    pub ttl_seconds: i64,
+    /// Valeur illisible
+    pub parse_error: Option<String>,
+}
+
+impl ProcessingMarkerSnapshot {
+    fn parse(key: String, job_id: Option<Uuid>, value: String, ttl_seconds: i64) -> Self {
+        match serde_json::from_str::<ProcessingMarker>(&value) {
+            Ok(marker) => Self {
+                key,
+                job_id,
+                worker_id: Some(marker.worker_id),
+                started_at: Some(marker.started_at),
+                ttl_seconds,
+                parse_error: None,
+            },
+            Err(e) => Self {
+                key,
+                job_id,
+                worker_id: None,
+                started_at: None,
+                ttl_seconds,
+                parse_error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Contenu complet des queues (inspection admin)
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueSnapshot {
+    /// Niveaux de priorité, du plus prioritaire au moins prioritaire
+    pub tiers: Vec<QueueTierSnapshot>,
+    /// Jobs pris en charge par un worker
+    pub processing: Vec<ProcessingMarkerSnapshot>,
+}
+
+/// Étape du pipeline de quantification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressStage {
+    Download,  // Récupération du modèle source
+    Analyze,   // Analyse de l'architecture
+    Quantize,  // Quantification
+    Validate,  // Mesure de la qualité
+    Export,    // Conversion de format (ex: ONNX)
+    Upload,    // Envoi du résultat vers le stockage
+}
+
+impl ProgressStage {
+    /// Identifiant textuel (identique à la sérialisation)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgressStage::Download => "download",
+            ProgressStage::Analyze => "analyze",
+            ProgressStage::Quantize => "quantize",
+            ProgressStage::Validate => "validate",
+            ProgressStage::Export => "export",
+            ProgressStage::Upload => "upload",
+        }
+    }
+    
+    /// Progression (en %) atteinte au début de l'étape
+    pub fn percent(&self) -> i32 {
+        match self {
+            ProgressStage::Download => 15,
+            ProgressStage::Analyze => 25,
+            ProgressStage::Export => 35,
+            ProgressStage::Quantize => 45,
+            ProgressStage::Validate => 80,
+            ProgressStage::Upload => 90,
+        }
+    }
+}
+
 /// Événement de progression
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgressEvent {
     pub job_id: Uuid,
-    pub progress: i32,
-    pub status: String,
+    pub stage: ProgressStage,
+    pub percent: i32,
+    pub message: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+impl ProgressEvent {
+    /// Événement de début d'étape
+    pub fn new(job_id: Uuid, stage: ProgressStage, message: impl Into<String>) -> Self {
+        Self {
+            job_id,
+            stage,
+            percent: stage.percent(),
+            message: message.into(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
 /// Résultat d'un job
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobResult {
@@ -266,4 +590,139 @@ pub struct JobResult {
     pub output_file_id: Option<Uuid>,
     pub error_message: Option<String>,
     pub completed_at: chrono::DateTime<chrono::Utc>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tours nécessaires pour servir un job seul dans `tier`, les niveaux
+    /// `saturated` recevant un job à chaque tour
+    fn ticks_until_served(scheduler: &mut FairScheduler, tier: &str, saturated: &[&str]) -> usize {
+        for tick in 1..=1000 {
+            let served = scheduler.next_order()
+                .into_iter()
+                .find(|candidate| *candidate == tier || saturated.contains(candidate))
+                .unwrap();
+            if served == tier {
+                return tick;
+            }
+        }
+        panic!("niveau {} jamais servi", tier);
+    }
+
+    #[test]
+    fn default_job_is_served_under_constant_priority_load() {
+        for ratio in 1..=5u32 {
+            let bound = (ratio * ratio + ratio + 1) as usize;
+
+            // Le job arrive à n'importe quel moment du cycle du scheduler
+            for warmup in 0..20 {
+                let mut scheduler = FairScheduler::new(ratio);
+                for _ in 0..warmup {
+                    scheduler.next_order();
+                }
+
+                let ticks = ticks_until_served(&mut scheduler, "normal", &["high"]);
+                assert!(ticks <= bound, "ratio {}: servi après {} tours (borne {})", ratio, ticks, bound);
+            }
+        }
+    }
+
+    #[test]
+    fn low_job_is_served_under_constant_higher_load() {
+        let ratio = 3;
+        let mut scheduler = FairScheduler::new(ratio);
+        let ticks = ticks_until_served(&mut scheduler, "low", &["high", "normal"]);
+        assert!(ticks <= (ratio * ratio + ratio + 1) as usize);
+    }
+
+    #[test]
+    fn tiers_are_served_in_the_configured_ratio() {
+        let mut scheduler = FairScheduler::new(3);
+        let mut served = std::collections::HashMap::new();
+        for _ in 0..13 * 10 {
+            *served.entry(scheduler.next_order()[0]).or_insert(0) += 1;
+        }
+        assert_eq!(served["high"], 90);
+        assert_eq!(served["normal"], 30);
+        assert_eq!(served["low"], 10);
+    }
+
+    #[test]
+    fn every_tier_is_always_polled() {
+        let mut scheduler = FairScheduler::new(0);
+        for _ in 0..10 {
+            let mut order = scheduler.next_order().to_vec();
+            order.sort();
+            assert_eq!(order, ["high", "low", "normal"]);
+        }
+    }
+
+    /// Queue isolée par un préfixe unique sur le Redis de test
+    async fn test_queue() -> JobQueue {
+        let redis_url = std::env::var("TEST_REDIS_URL")
+            .unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let prefix = format!("test:{}:", Uuid::new_v4());
+        JobQueue::new(&redis_url, Some(&prefix), 3).await.expect("Redis de test")
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite Redis (TEST_REDIS_URL)"]
+    async fn inspect_lists_enqueued_jobs_with_their_priority() {
+        let queue = test_queue().await;
+        let (high, normal, low) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let first_low = Uuid::new_v4();
+
+        queue.enqueue(first_low, 1).await.unwrap();
+        queue.enqueue(low, 1).await.unwrap();
+        queue.enqueue(normal, 2).await.unwrap();
+        queue.enqueue(high, 3).await.unwrap();
+        queue.mark_processing(Uuid::new_v4(), "worker-test", 600).await.unwrap();
+
+        let snapshot = queue.inspect(10).await.unwrap();
+
+        let tier = |name: &str| snapshot.tiers.iter().find(|t| t.tier == name).unwrap();
+        assert_eq!(tier("high").entries[0].job_id, Some(high));
+        assert_eq!(tier("high").entries[0].priority, Some(3));
+        assert_eq!(tier("normal").entries[0].job_id, Some(normal));
+        assert_eq!(tier("normal").entries[0].priority, Some(2));
+
+        // Ordre de sortie: le premier enqueue est servi en premier
+        let low_tier = tier("low");
+        assert_eq!(low_tier.length, 2);
+        assert_eq!(low_tier.entries[0].job_id, Some(first_low));
+        assert_eq!(low_tier.entries[1].job_id, Some(low));
+        assert!(low_tier.entries.iter().all(|e| e.priority == Some(1)));
+
+        assert_eq!(snapshot.processing.len(), 1);
+        assert_eq!(snapshot.processing[0].worker_id.as_deref(), Some("worker-test"));
+        assert!(snapshot.processing[0].ttl_seconds > 0 && snapshot.processing[0].ttl_seconds <= 600);
+
+        // Lecture seule
+        assert_eq!(queue.queue_size(None).await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    #[ignore = "nécessite Redis (TEST_REDIS_URL)"]
+    async fn queues_with_different_namespaces_are_isolated() {
+        let redis_url = crate::utils::testing::redis_url();
+        let namespace = |env: &str| {
+            let mut config = crate::utils::testing::config();
+            config.redis_queue_prefix = format!("{}-{}", env, Uuid::new_v4());
+            config.redis_namespace()
+        };
+        let staging = JobQueue::new(&redis_url, Some(&namespace("staging")), 3).await.unwrap();
+        let production = JobQueue::new(&redis_url, Some(&namespace("production")), 3).await.unwrap();
+
+        let job_id = Uuid::new_v4();
+        staging.enqueue(job_id, 2).await.unwrap();
+        staging.mark_processing(Uuid::new_v4(), "worker-staging", 600).await.unwrap();
+
+        assert_eq!(production.queue_size(None).await.unwrap(), 0);
+        assert!(production.inspect(10).await.unwrap().processing.is_empty());
+        assert_eq!(production.dequeue().await.unwrap(), None);
+
+        assert_eq!(staging.dequeue().await.unwrap(), Some(job_id));
+    }
+}
@@ -1,6 +1,10 @@
 // services/storage.rs
 use crate::models::{ModelFile, FileMetadata, ModelFormat};
 use crate::utils::error::{AppError, Result};
+use crate::utils::retry::RetryPolicy;
+use crate::utils::ids::{IdProvider, default_id_provider};
+use crate::utils::helpers::{sanitize_filename, content_disposition_attachment};
+use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
 use aws_sdk_s3::{
     Client as S3Client,
     config::{Credentials, Region},
@@ -13,12 +17,168 @@ use std::sync::Arc;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
+/// Objet présent dans le stockage
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    /// Clé telle qu'enregistrée dans `model_files.storage_path`
+    pub key: String,
+    pub size: i64,
+    pub last_modified: chrono::DateTime<chrono::Utc>,
+}
+
+/// Préfixe des clés de dépôt des uploads directs (`incoming/<uuid>`)
+const DIRECT_UPLOAD_PREFIX: &str = "incoming/";
+
+/// Octets lus en tête d'un upload direct pour reconnaître son format
+const DIRECT_UPLOAD_SNIFF_BYTES: usize = 16;
+
+/// Clé de dépôt d'un upload direct, distincte de sa clé définitive
+fn direct_upload_staging_key(file_id: Uuid) -> String {
+    format!("{}{}", DIRECT_UPLOAD_PREFIX, file_id)
+}
+
+/// Nom d'objet attribué par ce service (`<uuid>_<nom>` ou clé de dépôt)
+///
+/// Les autres objets du bucket ne sont jamais considérés comme orphelins;
+/// une clé de dépôt abandonnée l'est une fois le délai de grâce écoulé.
+fn is_managed_object_name(name: &str) -> bool {
+    if let Some(id) = name.strip_prefix(DIRECT_UPLOAD_PREFIX) {
+        return Uuid::parse_str(id).is_ok();
+    }
+    name.len() > 37
+        && name.as_bytes()[36] == b'_'
+        && Uuid::parse_str(&name[..36]).is_ok()
+}
+
+/// Empreinte SHA-256 hexadécimale en base64 (format des en-têtes de checksum S3)
+fn sha256_hex_to_base64(hex: &str) -> Result<String> {
+    use base64::Engine;
+
+    let invalid = || AppError::Validation("Empreinte SHA-256 invalide (64 caractères hexadécimaux attendus)".to_string());
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid()))
+        .collect::<Result<Vec<u8>>>()?;
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// URL PUT signée d'un upload direct
+#[derive(Debug, Clone)]
+pub struct PresignedUpload {
+    /// ID du futur fichier
+    pub file_id: Uuid,
+    pub staging_key: String,
+    pub url: String,
+    /// En-têtes signés que le client doit envoyer (taille, empreinte)
+    pub headers: std::collections::HashMap<String, String>,
+}
+
+/// Upload direct vérifié, en attente de sa clé définitive
+#[derive(Debug, Clone)]
+pub struct DirectUploadObject {
+    /// Version vérifiée de l'objet (copie conditionnée)
+    pub etag: String,
+    /// Début de l'objet, pour reconnaître son format
+    pub header: Vec<u8>,
+}
+
+/// Objets à supprimer: sans référence en base et modifiés avant `older_than`
+pub fn select_orphaned_objects(
+    objects: Vec<StoredObject>,
+    referenced: &std::collections::HashSet<String>,
+    older_than: chrono::DateTime<chrono::Utc>,
+) -> Vec<StoredObject> {
+    objects
+        .into_iter()
+        .filter(|object| object.last_modified < older_than && !referenced.contains(&object.key))
+        .collect()
+}
+
 pub struct FileStorage {
     s3_client: Option<S3Client>,
     local_dir: PathBuf,
     bucket: String,
     encryption_key: Option<Vec<u8>>,
     max_file_size: u64,
+    download_url_expiry_hours: u32,
+    retry_policy: RetryPolicy,
+    ids: Arc<dyn IdProvider>,
+    /// Niveau zstd appliqué avant chiffrement (None = objets stockés tels quels)
+    compression_level: Option<i32>,
+}
+
+/// Clé des métadonnées S3 indiquant l'algorithme de compression d'un objet
+const COMPRESSION_METADATA_KEY: &str = "compression";
+
+/// Valeur de `COMPRESSION_METADATA_KEY` pour un objet compressé en zstd
+const ZSTD_ALGORITHM: &str = "zstd";
+
+/// Suffixe des fichiers compressés en stockage local (pas de métadonnées sur disque)
+const LOCAL_ZSTD_SUFFIX: &str = ".zst";
+
+/// Taille des morceaux d'une réponse en flux (lecture disque ou objet restauré)
+const STREAM_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Flux des octets d'un objet, à transmettre tel quel dans une réponse HTTP
+pub type ObjectStream = futures_util::stream::BoxStream<'static, Result<bytes::Bytes>>;
+
+/// Codes S3 indiquant une erreur transitoire côté serveur
+const RETRYABLE_S3_CODES: &[&str] = &[
+    "InternalError",
+    "ServiceUnavailable",
+    "SlowDown",
+    "RequestTimeout",
+    "Throttling",
+    "ThrottlingException",
+];
+
+/// Une erreur S3 mérite-t-elle un nouvel essai ? (réseau, timeout, 5xx, throttling)
+///
+/// Les erreurs 4xx (clé absente, accès refusé, signature) ne sont jamais réessayées.
+fn is_retryable_s3_error<E: ProvideErrorMetadata, R>(error: &SdkError<E, R>) -> bool {
+    match error {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) | SdkError::ResponseError(_) => true,
+        SdkError::ServiceError(service_error) => service_error
+            .err()
+            .code()
+            .map(|code| RETRYABLE_S3_CODES.contains(&code))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Taille maximale lue pour inspecter l'en-tête d'un modèle
+const MAX_MODEL_HEADER_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Reconnaître le format d'un modèle à son en-tête (None si indéterminé)
+///
+/// Les signatures les plus discriminantes sont testées d'abord: celle
+/// d'ONNX (un seul octet) n'est retenue qu'en dernier recours.
+pub fn detect_format_from_header(header: &[u8]) -> Option<ModelFormat> {
+    [ModelFormat::Gguf, ModelFormat::Safetensors, ModelFormat::PyTorch, ModelFormat::Onnx]
+        .into_iter()
+        .find(|format| header_matches_format(format, header))
+}
+
+/// L'en-tête d'un fichier correspond-il à son format déclaré ?
+fn header_matches_format(format: &ModelFormat, header: &[u8]) -> bool {
+    match format {
+        // Archive zip (torch.save) ou pickle brut (protocole >= 2)
+        ModelFormat::PyTorch => header.starts_with(b"PK\x03\x04") || header.first() == Some(&0x80),
+        // Longueur de l'en-tête JSON (u64 little-endian) puis '{'
+        ModelFormat::Safetensors => {
+            header.len() >= 9
+                && u64::from_le_bytes(header[..8].try_into().unwrap_or([0; 8])) > 0
+                && header[8] == b'{'
+        }
+        ModelFormat::Gguf => header.starts_with(b"GGUF"),
+        // Protobuf: premier champ (ir_version) encodé en varint
+        ModelFormat::Onnx => header.first() == Some(&0x08),
+    }
 }
 
 impl FileStorage {
@@ -31,6 +191,8 @@ impl FileStorage {
         local_dir: Option<&Path>,
         encryption_key: Option<&str>,
         max_file_size_mb: u64,
+        download_url_expiry_hours: u32,
+        retry_policy: RetryPolicy,
     ) -> Self {
         let s3_client = if let (Some(endpoint), Some(access_key), Some(secret_key)) = 
             (endpoint, access_key, secret_key) 
@@ -53,9 +215,28 @@ impl FileStorage {
             bucket: bucket.to_string(),
             encryption_key,
             max_file_size: max_file_size_mb * 1024 * 1024,
+            download_url_expiry_hours,
+            retry_policy,
+            ids: default_id_provider(),
+            compression_level: None,
         }
     }
 
+    /// Compresser les nouveaux objets en zstd au niveau donné
+    ///
+    /// Les objets existants non compressés restent lisibles: l'algorithme est
+    /// lu dans les métadonnées de chaque objet, pas dans la configuration.
+    pub fn with_compression(mut self, level: Option<i32>) -> Self {
+        self.compression_level = level;
+        self
+    }
+
+    /// Remplacer la source d'identifiants (séquence déterministe en test)
+    pub fn with_id_provider(mut self, ids: Arc<dyn IdProvider>) -> Self {
+        self.ids = ids;
+        self
+    }
+
     /// Créer le client S3
     fn create_s3_client(endpoint: &str, access_key: &str, secret_key: &str) -> S3Client {
         let creds = Credentials::new(access_key, secret_key, None, None, "minio");
@@ -78,29 +259,17 @@ impl FileStorage {
         data: &[u8],
         checksum: &str,
         format: ModelFormat,
-    ) -> Result<FileMetadata> {
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ModelFile> {
         // Vérifier la taille
         if data.len() as u64 > self.max_file_size {
             return Err(AppError::FileTooLarge);
         }
 
-        // Générer un nom de fichier unique
-        let file_id = Uuid::new_v4();
-        let storage_filename = format!("{}_{}", file_id, filename);
-        
-        // Chiffrer les données si nécessaire
-        let data_to_store = if let Some(key) = &self.encryption_key {
-            self.encrypt_data(data, key)?
-        } else {
-            data.to_vec()
-        };
-
-        // Stocker le fichier
-        let storage_path = if let Some(client) = &self.s3_client {
-            self.upload_to_s3(&storage_filename, &data_to_store).await?
-        } else {
-            self.save_locally(&storage_filename, &data_to_store).await?
-        };
+        // Générer un nom de fichier unique (jamais de chemin fourni par le client)
+        let file_id = self.ids.next_id();
+        let storage_filename = format!("{}_{}", file_id, sanitize_filename(filename));
+        let storage_path = self.store_object(&storage_filename, data).await?;
 
         // Créer les métadonnées
         let file = ModelFile::new(
@@ -111,26 +280,207 @@ impl FileStorage {
             format,
             self.bucket.clone(),
             storage_path,
+        )
+        .with_id(file_id)
+        .with_expiry(expires_at);
+
+        Ok(file)
+    }
+
+    /// Vérifier un fichier de données externes ONNX avant tout stockage
+    pub fn check_external_data(&self, filename: &str, size: usize) -> Result<()> {
+        if size as u64 > self.max_file_size {
+            return Err(AppError::FileTooLarge);
+        }
+        if filename.is_empty() || sanitize_filename(filename) != filename {
+            return Err(AppError::Validation(format!(
+                "Nom de fichier de données externes invalide: {}", filename
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Supprimer les objets d'un groupe stocké mais jamais enregistré
+    ///
+    /// Au mieux: un objet qui ne peut pas être supprimé est seulement journalisé.
+    pub async fn discard_objects(&self, files: &[ModelFile]) {
+        for file in files {
+            if let Err(e) = self.delete_file(file).await {
+                log::warn!("Objet {} non supprimé après un upload abandonné: {}", file.storage_path, e);
+            }
+        }
+    }
+
+    /// Uploader un fichier de données externes ONNX rattaché à un modèle
+    ///
+    /// Le nom est conservé tel quel: le `.onnx` référence ses poids par nom
+    /// relatif, qui doit donc être déjà sûr. Le fichier est stocké sous le
+    /// préfixe du modèle pour que le groupe reste regroupé dans le bucket.
+    pub async fn upload_external_data(
+        &self,
+        parent: &ModelFile,
+        user_id: Uuid,
+        filename: &str,
+        data: &[u8],
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ModelFile> {
+        self.check_external_data(filename, data.len())?;
+
+        let file_id = self.ids.next_id();
+        let storage_filename = format!("{}_data_{}", parent.id, filename);
+        let storage_path = self.store_object(&storage_filename, data).await?;
+
+        use sha2::{Sha256, Digest};
+        let checksum = format!("{:x}", Sha256::digest(data));
+
+        let file = ModelFile::new(
+            user_id,
+            filename.to_string(),
+            data.len() as i64,
+            checksum,
+            ModelFormat::Onnx,
+            self.bucket.clone(),
+            storage_path,
+        )
+        .with_id(file_id)
+        .with_parent(parent.id)
+        .with_expiry(expires_at);
+
+        Ok(file)
+    }
+
+    /// Uploader un artefact produit par un job, rattaché à son résultat principal
+    pub async fn upload_job_artifact(
+        &self,
+        parent_file_id: Uuid,
+        user_id: Uuid,
+        filename: &str,
+        path: &str,
+        format: ModelFormat,
+    ) -> Result<ModelFile> {
+        let data = fs::read(path).await
+            .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+        let file_id = self.ids.next_id();
+        let storage_filename = format!("{}_{}", file_id, sanitize_filename(filename));
+        let storage_path = self.store_object(&storage_filename, &data).await?;
+
+        use sha2::{Sha256, Digest};
+        let checksum = format!("{:x}", Sha256::digest(&data));
+
+        let file = ModelFile::new(
+            user_id,
+            filename.to_string(),
+            data.len() as i64,
+            checksum,
+            format,
+            self.bucket.clone(),
+            storage_path,
+        )
+        .with_id(file_id)
+        .with_parent(parent_file_id);
+
+        Ok(file)
+    }
+
+    /// Compresser et chiffrer si nécessaire puis écrire un objet (S3 ou disque local)
+    async fn store_object(&self, storage_filename: &str, data: &[u8]) -> Result<String> {
+        let compressed = self.compress_data(storage_filename, data)?;
+        let plain = compressed.as_deref().unwrap_or(data);
+
+        let data_to_store = if let Some(key) = &self.encryption_key {
+            self.encrypt_data(plain, key)?
+        } else {
+            plain.to_vec()
+        };
+
+        if self.s3_client.is_some() {
+            self.upload_to_s3(storage_filename, &data_to_store, compressed.is_some()).await
+        } else if compressed.is_some() {
+            self.save_locally(&format!("{}{}", storage_filename, LOCAL_ZSTD_SUFFIX), &data_to_store).await
+        } else {
+            self.save_locally(storage_filename, &data_to_store).await
+        }
+    }
+
+    /// Compresser un objet en zstd si la compression est activée
+    ///
+    /// None si elle est désactivée ou n'apporte rien: l'objet est alors stocké tel quel.
+    fn compress_data(&self, name: &str, data: &[u8]) -> Result<Option<Vec<u8>>> {
+        let Some(level) = self.compression_level else {
+            return Ok(None);
+        };
+
+        let compressed = zstd::bulk::compress(data, level)
+            .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+        let ratio = if data.is_empty() { 1.0 } else { compressed.len() as f64 / data.len() as f64 };
+        if compressed.len() >= data.len() {
+            log::debug!("{} non compressé: aucun gain ({:.1}%)", name, ratio * 100.0);
+            return Ok(None);
+        }
+
+        log::info!(
+            "🗜️ {} compressé: {} → {} octets ({:.1}%)",
+            name, data.len(), compressed.len(), ratio * 100.0
         );
+        Ok(Some(compressed))
+    }
 
-        Ok(file.to_metadata())
+    /// Décompresser un objet zstd (après déchiffrement)
+    fn decompress_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+        zstd::stream::decode_all(data)
+            .map_err(|e| AppError::StorageError(e.to_string()))
     }
 
-    /// Uploader vers S3/MinIO
-    async fn upload_to_s3(&self, filename: &str, data: &[u8]) -> Result<String> {
+    /// L'objet a-t-il été stocké compressé ?
+    ///
+    /// Lu dans les métadonnées S3 (ou le suffixe en local), jamais déduit de la configuration.
+    async fn is_compressed_object(&self, key: &str) -> Result<bool> {
+        let Some(client) = &self.s3_client else {
+            return Ok(key.ends_with(LOCAL_ZSTD_SUFFIX));
+        };
+
+        let head = self.retry_policy
+            .run(
+                "head_object",
+                || client.head_object().bucket(&self.bucket).key(key).send(),
+                is_retryable_s3_error,
+            )
+            .await
+            .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+        Ok(head
+            .metadata()
+            .and_then(|metadata| metadata.get(COMPRESSION_METADATA_KEY))
+            .map(|algorithm| algorithm == ZSTD_ALGORITHM)
+            .unwrap_or(false))
+    }
+
+    /// Uploader vers S3/MinIO (`compressed` est enregistré dans les métadonnées)
+    async fn upload_to_s3(&self, filename: &str, data: &[u8], compressed: bool) -> Result<String> {
         let client = self.s3_client.as_ref().unwrap();
         
         // Vérifier que le bucket existe
         self.ensure_bucket_exists().await?;
 
-        let stream = ByteStream::from(data.to_vec());
-        
-        client
-            .put_object()
-            .bucket(&self.bucket)
-            .key(filename)
-            .body(stream)
-            .send()
+        self.retry_policy
+            .run(
+                "put_object",
+                || {
+                    let mut request = client
+                        .put_object()
+                        .bucket(&self.bucket)
+                        .key(filename)
+                        .body(ByteStream::from(data.to_vec()));
+                    if compressed {
+                        request = request.metadata(COMPRESSION_METADATA_KEY, ZSTD_ALGORITHM);
+                    }
+                    request.send()
+                },
+                is_retryable_s3_error,
+            )
             .await
             .map_err(|e| AppError::StorageError(e.to_string()))?;
 
@@ -156,32 +506,284 @@ impl FileStorage {
 
     /// Télécharger un fichier
     pub async fn download_file(&self, file: &ModelFile) -> Result<Vec<u8>> {
-        let data = if let Some(client) = &self.s3_client {
-            self.download_from_s3(&file.storage_path).await?
+        self.download_by_key(&file.storage_path).await
+    }
+
+    /// Télécharger un objet par sa clé interne (usage worker, jamais d'URL publique)
+    pub async fn download_by_key(&self, key: &str) -> Result<Vec<u8>> {
+        let (data, compressed) = if self.s3_client.is_some() {
+            self.download_from_s3(key).await?
         } else {
-            self.read_locally(&file.storage_path).await?
+            (self.read_locally(key).await?, key.ends_with(LOCAL_ZSTD_SUFFIX))
         };
 
         // Déchiffrer si nécessaire
-        if let Some(key) = &self.encryption_key {
-            self.decrypt_data(&data, key)
+        let data = if let Some(key) = &self.encryption_key {
+            self.decrypt_data(&data, key)?
+        } else {
+            data
+        };
+
+        // Décompresser après déchiffrement (ordre inverse de l'écriture)
+        if compressed {
+            self.decompress_data(&data)
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Télécharger un objet vers un fichier local (ex: répertoire de travail d'un job)
+    pub async fn download_to(&self, key: &str, destination: &Path) -> Result<u64> {
+        let data = self.download_by_key(key).await?;
+        fs::write(destination, &data).await?;
+        Ok(data.len() as u64)
+    }
+
+    /// Les objets sont-ils chiffrés au repos ?
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_key.is_some()
+    }
+
+    /// Flux de la plage `start..=end` d'un fichier, déchiffrée et décompressée
+    ///
+    /// Objet en clair: la plage est lue au fil de l'eau (corps S3 ou fichier
+    /// local par morceaux), jamais chargée en entier. Un objet chiffré
+    /// (AES-GCM d'un seul tenant, authentifié en entier avant tout octet
+    /// rendu) ou compressé est restauré une fois puis découpé sans copie.
+    pub async fn stream_range(&self, file: &ModelFile, start: u64, end: u64) -> Result<ObjectStream> {
+        use futures_util::StreamExt;
+
+        if self.encryption_key.is_some() || self.is_compressed_object(&file.storage_path).await? {
+            let data = bytes::Bytes::from(self.download_file(file).await?);
+            let end = (end as usize).min(data.len().saturating_sub(1));
+            if start as usize > end {
+                return Err(AppError::InvalidPath);
+            }
+            let range = data.slice(start as usize..=end);
+            let chunks = (0..range.len())
+                .step_by(STREAM_CHUNK_BYTES)
+                .map(move |offset| Ok(range.slice(offset..(offset + STREAM_CHUNK_BYTES).min(range.len()))))
+                .collect::<Vec<_>>();
+            return Ok(futures_util::stream::iter(chunks).boxed());
+        }
+
+        if let Some(client) = &self.s3_client {
+            let response = self.retry_policy
+                .run(
+                    "get_object_range",
+                    || {
+                        client
+                            .get_object()
+                            .bucket(&self.bucket)
+                            .key(&file.storage_path)
+                            .range(format!("bytes={}-{}", start, end))
+                            .send()
+                    },
+                    is_retryable_s3_error,
+                )
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+            let body = futures_util::stream::unfold(response.body, |mut body| async move {
+                let chunk = body.next().await?;
+                Some((chunk.map_err(|e| AppError::StorageError(e.to_string())), body))
+            });
+            Ok(body.boxed())
         } else {
+            use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+            let mut local = fs::File::open(&file.storage_path).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+            local.seek(std::io::SeekFrom::Start(start)).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+            let remaining = end.saturating_sub(start) + 1;
+            let body = futures_util::stream::unfold((local, remaining), |(mut local, remaining)| async move {
+                if remaining == 0 {
+                    return None;
+                }
+                let mut chunk = vec![0; (remaining as usize).min(STREAM_CHUNK_BYTES)];
+                match local.read(&mut chunk).await {
+                    Ok(0) => None,
+                    Ok(read) => {
+                        chunk.truncate(read);
+                        Some((Ok(bytes::Bytes::from(chunk)), (local, remaining - read as u64)))
+                    }
+                    Err(e) => Some((Err(AppError::StorageError(e.to_string())), (local, 0))),
+                }
+            });
+            Ok(body.boxed())
+        }
+    }
+
+    /// Lire la plage `start..=end` d'un fichier, déchiffrée et décompressée
+    ///
+    /// Sans chiffrement ni compression, seule la plage est lue depuis le
+    /// stockage; sinon l'objet doit être restauré en entier avant d'être découpé.
+    pub async fn read_range(&self, file: &ModelFile, start: u64, end: u64) -> Result<Vec<u8>> {
+        if self.encryption_key.is_some() || self.is_compressed_object(&file.storage_path).await? {
+            let data = self.download_file(file).await?;
+            let end = (end as usize).min(data.len().saturating_sub(1));
+            return data
+                .get(start as usize..=end)
+                .map(|slice| slice.to_vec())
+                .ok_or(AppError::InvalidPath);
+        }
+
+        let len = end.saturating_sub(start) + 1;
+
+        if let Some(client) = &self.s3_client {
+            let response = self.retry_policy
+                .run(
+                    "get_object_range",
+                    || {
+                        client
+                            .get_object()
+                            .bucket(&self.bucket)
+                            .key(&file.storage_path)
+                            .range(format!("bytes={}-{}", start, end))
+                            .send()
+                    },
+                    is_retryable_s3_error,
+                )
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+            let bytes = response
+                .body
+                .collect()
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?
+                .to_vec();
+
+            Ok(bytes)
+        } else {
+            use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+            let mut local = fs::File::open(&file.storage_path).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+            local.seek(std::io::SeekFrom::Start(start)).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+            let mut data = Vec::with_capacity(len as usize);
+            local.take(len).read_to_end(&mut data).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
             Ok(data)
         }
     }
 
-    /// Télécharger depuis S3
-    async fn download_from_s3(&self, key: &str) -> Result<Vec<u8>> {
+    /// Vérification légère d'un fichier source avant de facturer un job
+    ///
+    /// L'objet doit exister, être non vide et commencer par l'en-tête attendu
+    /// pour son format (en-tête non vérifiable si le stockage est chiffré).
+    pub async fn check_input_integrity(&self, file: &ModelFile) -> Result<()> {
+        if file.file_size <= 0 {
+            return Err(AppError::CorruptInput("fichier vide".to_string()));
+        }
+
+        let header = self.read_header(&file.storage_path, 16).await.map_err(|e| {
+            log::warn!("Fichier source {} illisible: {}", file.id, e);
+            AppError::CorruptInput("fichier introuvable dans le stockage".to_string())
+        })?;
+
+        if header.is_empty() {
+            return Err(AppError::CorruptInput("fichier vide".to_string()));
+        }
+
+        let compressed = self.is_compressed_object(&file.storage_path).await?;
+        if self.encryption_key.is_none() && !compressed && !header_matches_format(&file.format, &header) {
+            return Err(AppError::CorruptInput(format!(
+                "en-tête incompatible avec le format {:?}",
+                file.format
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// En-tête décrivant les tenseurs d'un modèle (vide si le format n'en a pas d'exploitable)
+    ///
+    /// Safetensors: longueur u64 puis en-tête JSON complet; ONNX: début du
+    /// graphe, où figurent les nœuds avant les poids.
+    pub async fn read_model_header(&self, file: &ModelFile) -> Result<Vec<u8>> {
+        let size = file.file_size.max(0) as u64;
+
+        match file.format {
+            ModelFormat::Safetensors if size > 8 => {
+                let prefix = self.read_range(file, 0, 7).await?;
+                let len = u64::from_le_bytes(prefix.get(..8).and_then(|b| b.try_into().ok()).unwrap_or([0; 8]));
+                if len == 0 || len > MAX_MODEL_HEADER_BYTES || 8 + len > size {
+                    return Ok(prefix);
+                }
+                self.read_range(file, 0, 8 + len - 1).await
+            }
+            ModelFormat::Onnx if size > 0 => {
+                self.read_range(file, 0, size.min(MAX_MODEL_HEADER_BYTES) - 1).await
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Lire les premiers octets d'un objet sans le télécharger entièrement
+    async fn read_header(&self, key: &str, len: usize) -> Result<Vec<u8>> {
+        if let Some(client) = &self.s3_client {
+            let response = self.retry_policy
+                .run(
+                    "get_object_range",
+                    || {
+                        client
+                            .get_object()
+                            .bucket(&self.bucket)
+                            .key(key)
+                            .range(format!("bytes=0-{}", len.saturating_sub(1)))
+                            .send()
+                    },
+                    is_retryable_s3_error,
+                )
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+            let bytes = response
+                .body
+                .collect()
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?
+                .to_vec();
+
+            Ok(bytes)
+        } else {
+            use tokio::io::AsyncReadExt;
+            
+            let file = fs::File::open(key).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+            let mut header = Vec::with_capacity(len);
+            file.take(len as u64).read_to_end(&mut header).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+            Ok(header)
+        }
+    }
+
+    /// Télécharger depuis S3 (et indiquer si l'objet est compressé)
+    async fn download_from_s3(&self, key: &str) -> Result<(Vec<u8>, bool)> {
         let client = self.s3_client.as_ref().unwrap();
         
-        let response = client
-            .get_object()
-            .bucket(&self.bucket)
-            .key(key)
-            .send()
+        let response = self.retry_policy
+            .run(
+                "get_object",
+                || client.get_object().bucket(&self.bucket).key(key).send(),
+                is_retryable_s3_error,
+            )
             .await
             .map_err(|e| AppError::StorageError(e.to_string()))?;
 
+        let compressed = response
+            .metadata()
+            .and_then(|metadata| metadata.get(COMPRESSION_METADATA_KEY))
+            .map(|algorithm| algorithm == ZSTD_ALGORITHM)
+            .unwrap_or(false);
+
         let bytes = response
             .body
             .collect()
@@ -189,7 +791,7 @@ impl FileStorage {
             .map_err(|e| AppError::StorageError(e.to_string()))?
             .to_vec();
 
-        Ok(bytes)
+        Ok((bytes, compressed))
     }
 
     /// Lire localement
@@ -200,29 +802,140 @@ impl FileStorage {
 
     /// Supprimer un fichier
     pub async fn delete_file(&self, file: &ModelFile) -> Result<()> {
+        self.delete_by_key(&file.storage_path).await
+    }
+
+    /// Supprimer un objet par sa clé interne
+    pub async fn delete_by_key(&self, key: &str) -> Result<()> {
         if let Some(client) = &self.s3_client {
             client
                 .delete_object()
                 .bucket(&self.bucket)
-                .key(&file.storage_path)
+                .key(key)
+                .send()
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+        } else {
+            fs::remove_file(key).await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Lister les objets nommés par ce service (bucket S3 ou répertoire local)
+    pub async fn list_managed_objects(&self) -> Result<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+
+        if let Some(client) = &self.s3_client {
+            let mut continuation_token = None;
+            loop {
+                let page = client
+                    .list_objects_v2()
+                    .bucket(&self.bucket)
+                    .set_continuation_token(continuation_token.take())
+                    .send()
+                    .await
+                    .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+                for object in page.contents().unwrap_or_default() {
+                    let (Some(key), Some(modified)) = (object.key(), object.last_modified()) else {
+                        continue;
+                    };
+                    if !is_managed_object_name(key) {
+                        continue;
+                    }
+                    let Some(last_modified) = chrono::DateTime::<chrono::Utc>::from_timestamp(modified.secs(), 0) else {
+                        continue;
+                    };
+                    objects.push(StoredObject {
+                        key: key.to_string(),
+                        size: object.size(),
+                        last_modified,
+                    });
+                }
+
+                match page.next_continuation_token() {
+                    Some(token) => continuation_token = Some(token.to_string()),
+                    None => break,
+                }
+            }
+        } else {
+            let mut entries = match fs::read_dir(&self.local_dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(objects),
+                Err(e) => return Err(AppError::StorageError(e.to_string())),
+            };
+
+            while let Some(entry) = entries.next_entry().await
+                .map_err(|e| AppError::StorageError(e.to_string()))?
+            {
+                if !is_managed_object_name(&entry.file_name().to_string_lossy()) {
+                    continue;
+                }
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                let Ok(modified) = metadata.modified() else {
+                    continue;
+                };
+                if !metadata.is_file() {
+                    continue;
+                }
+                objects.push(StoredObject {
+                    // Même forme que le chemin enregistré par `save_locally`
+                    key: self.local_dir.join(entry.file_name()).to_string_lossy().to_string(),
+                    size: metadata.len() as i64,
+                    last_modified: modified.into(),
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Vérifier l'accès au stockage (bucket S3, ou répertoire local inscriptible)
+    pub async fn health_check(&self) -> Result<()> {
+        if let Some(client) = &self.s3_client {
+            client
+                .head_bucket()
+                .bucket(&self.bucket)
                 .send()
                 .await
                 .map_err(|e| AppError::StorageError(e.to_string()))?;
         } else {
-            fs::remove_file(&file.storage_path).await
+            fs::create_dir_all(&self.local_dir).await
                 .map_err(|e| AppError::StorageError(e.to_string()))?;
+            let probe = self.local_dir.join(".health_check");
+            fs::write(&probe, b"ok").await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+            let _ = fs::remove_file(&probe).await;
         }
 
         Ok(())
     }
 
+    /// L'objet doit-il passer par le proxy de l'API plutôt que par une URL présignée ?
+    ///
+    /// Une URL présignée sert les octets stockés tels quels: un objet chiffré
+    /// ou compressé n'y serait pas lisible sous son nom d'origine.
+    pub async fn requires_proxy_download(&self, file: &ModelFile) -> Result<bool> {
+        if self.encryption_key.is_some() {
+            return Ok(true);
+        }
+        self.is_compressed_object(&file.storage_path).await
+    }
+
     /// Générer une URL de téléchargement signée
+    ///
+    /// Réservée aux objets stockés en clair (voir `requires_proxy_download`).
     pub async fn generate_download_url(&self, file: &ModelFile, expires_in_hours: u32) -> Result<String> {
         if let Some(client) = &self.s3_client {
             let presigned_request = client
                 .get_object()
                 .bucket(&self.bucket)
                 .key(&file.storage_path)
+                .response_content_disposition(content_disposition_attachment(&file.original_filename))
                 .presigned(
                     aws_sdk_s3::presigning::PresigningConfig::expires_in(
                         std::time::Duration::from_secs(expires_in_hours as u64 * 3600)
@@ -239,6 +952,194 @@ impl FileStorage {
         }
     }
 
+    /// Allouer une clé de dépôt et signer une URL PUT pour un upload direct
+    ///
+    /// La signature fige la taille et l'empreinte annoncées: S3 refuse tout
+    /// autre contenu. Le client ne choisit jamais la clé; seul le stockage S3
+    /// sans chiffrement applicatif permet l'upload direct (l'objet n'est
+    /// jamais relu par l'API pour être chiffré).
+    pub async fn create_upload_url(
+        &self,
+        filename: &str,
+        file_size: i64,
+        checksum_sha256: &str,
+        expires_in_seconds: u64,
+    ) -> Result<PresignedUpload> {
+        let client = self.s3_client.as_ref().ok_or_else(|| {
+            AppError::Validation("Upload direct indisponible sans stockage S3".to_string())
+        })?;
+        if self.encryption_key.is_some() {
+            return Err(AppError::Validation(
+                "Upload direct indisponible avec le chiffrement des fichiers".to_string()
+            ));
+        }
+
+        self.ensure_bucket_exists().await?;
+
+        let file_id = self.ids.next_id();
+        let staging_key = direct_upload_staging_key(file_id);
+
+        let presigned_request = client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&staging_key)
+            .content_length(file_size)
+            .checksum_sha256(sha256_hex_to_base64(checksum_sha256)?)
+            .presigned(
+                aws_sdk_s3::presigning::PresigningConfig::expires_in(
+                    std::time::Duration::from_secs(expires_in_seconds)
+                )
+                .map_err(|e| AppError::StorageError(e.to_string()))?,
+            )
+            .await
+            .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+        let headers = presigned_request
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+
+        log::debug!("Upload direct {} alloué ({})", file_id, filename);
+        Ok(PresignedUpload {
+            file_id,
+            staging_key,
+            url: presigned_request.uri().to_string(),
+            headers,
+        })
+    }
+
+    /// Vérifier un upload direct sans le relire en entier
+    ///
+    /// Taille et empreinte viennent de S3 (empreinte calculée au dépôt); seul
+    /// le début de l'objet est lu pour reconnaître son format. FileNotFound si
+    /// le client n'a pas (encore) envoyé l'objet; un objet trop volumineux ou
+    /// différent de l'annonce est supprimé.
+    pub async fn inspect_direct_upload(
+        &self,
+        staging_key: &str,
+        file_size: i64,
+        checksum_sha256: &str,
+    ) -> Result<DirectUploadObject> {
+        let client = self.s3_client.as_ref().ok_or_else(|| {
+            AppError::Validation("Upload direct indisponible sans stockage S3".to_string())
+        })?;
+
+        let head = match client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(staging_key)
+            .checksum_mode(aws_sdk_s3::types::ChecksumMode::Enabled)
+            .send()
+            .await
+        {
+            Ok(head) => head,
+            Err(e) if e.as_service_error().map(|e| e.is_not_found()).unwrap_or(false) => {
+                return Err(AppError::FileNotFound);
+            }
+            Err(e) => return Err(AppError::StorageError(e.to_string())),
+        };
+
+        let size = head.content_length().max(0);
+        if size as u64 > self.max_file_size {
+            self.discard_direct_upload(staging_key).await?;
+            return Err(AppError::FileTooLarge);
+        }
+        if size != file_size {
+            self.discard_direct_upload(staging_key).await?;
+            return Err(AppError::Validation(format!(
+                "Taille différente de celle annoncée ({} octets reçus, {} annoncés)",
+                size, file_size
+            )));
+        }
+        if head.checksum_sha256() != Some(sha256_hex_to_base64(checksum_sha256)?.as_str()) {
+            self.discard_direct_upload(staging_key).await?;
+            return Err(AppError::Validation("Empreinte SHA-256 différente de celle annoncée".to_string()));
+        }
+
+        let etag = head.e_tag().unwrap_or_default().to_string();
+        let response = client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(staging_key)
+            .if_match(&etag)
+            .range(format!("bytes=0-{}", DIRECT_UPLOAD_SNIFF_BYTES - 1))
+            .send()
+            .await
+            .map_err(|e| AppError::StorageError(e.to_string()))?;
+        let header = response
+            .body
+            .collect()
+            .await
+            .map_err(|e| AppError::StorageError(e.to_string()))?
+            .to_vec();
+
+        Ok(DirectUploadObject { etag, header })
+    }
+
+    /// Déplacer un upload direct vérifié vers sa clé définitive
+    ///
+    /// Copie côté S3 conditionnée à l'ETag vérifié, puis suppression de la
+    /// clé de dépôt: l'URL signée, encore valide, ne peut plus modifier
+    /// l'objet référencé. Retourne la clé définitive.
+    pub async fn promote_direct_upload(
+        &self,
+        staging_key: &str,
+        etag: &str,
+        file_id: Uuid,
+        filename: &str,
+    ) -> Result<String> {
+        let client = self.s3_client.as_ref().ok_or_else(|| {
+            AppError::Validation("Upload direct indisponible sans stockage S3".to_string())
+        })?;
+
+        let storage_key = format!("{}_{}", file_id, sanitize_filename(filename));
+        client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(format!("{}/{}", self.bucket, staging_key))
+            .copy_source_if_match(etag)
+            .key(&storage_key)
+            .send()
+            .await
+            .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+        if let Err(e) = self.discard_direct_upload(staging_key).await {
+            log::warn!("Clé de dépôt {} non supprimée: {}", staging_key, e);
+        }
+
+        Ok(storage_key)
+    }
+
+    /// Supprimer un upload direct refusé
+    pub async fn discard_direct_upload(&self, key: &str) -> Result<()> {
+        if let Some(client) = &self.s3_client {
+            client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .send()
+                .await
+                .map_err(|e| AppError::StorageError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Bucket de stockage des fichiers
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Durée de validité configurée des URLs de téléchargement
+    pub fn download_url_expiry_hours(&self) -> u32 {
+        self.download_url_expiry_hours
+    }
+
+    /// Date d'expiration d'une URL générée maintenant
+    pub fn download_url_expires_at(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now() + chrono::Duration::hours(self.download_url_expiry_hours as i64)
+    }
+
     /// Obtenir les métadonnées d'un fichier
     pub async fn get_file_metadata(&self, file_id: Uuid) -> Result<FileMetadata> {
         // Dans une vraie implémentation, on récupérerait depuis la base
@@ -340,4 +1241,195 @@ impl FileStorage {
 
         Ok(deleted)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+
+    /// Stockage local dans un répertoire temporaire
+    fn local_storage(encryption_key: Option<&str>) -> FileStorage {
+        FileStorage::new(
+            None,
+            None,
+            None,
+            "test",
+            Some(&testing::scratch_dir("storage")),
+            encryption_key,
+            100,
+            24,
+            RetryPolicy::default(),
+        )
+    }
+
+    /// Écrire `data` comme artefact de job et retourner sa clé de stockage
+    async fn store_artifact(storage: &FileStorage, data: &[u8]) -> String {
+        let source = testing::scratch_dir("artifact").join("model.onnx");
+        std::fs::write(&source, data).unwrap();
+
+        storage
+            .upload_job_artifact(Uuid::new_v4(), Uuid::new_v4(), "model.onnx", source.to_str().unwrap(), ModelFormat::Onnx)
+            .await
+            .unwrap()
+            .storage_path
+    }
+
+    /// Stockage S3 hors ligne: la signature d'URL ne contacte pas le serveur
+    fn s3_storage(download_url_expiry_hours: u32) -> FileStorage {
+        FileStorage::new(
+            Some("http://localhost:9000"),
+            Some("access"),
+            Some("secret"),
+            "test",
+            None,
+            None,
+            100,
+            download_url_expiry_hours,
+            RetryPolicy::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn regenerated_download_url_is_fresh_and_uses_the_configured_expiry() {
+        let storage = s3_storage(6);
+        let file = ModelFile::new(
+            Uuid::new_v4(),
+            "model.gguf".to_string(),
+            1024,
+            "0".repeat(64),
+            ModelFormat::Gguf,
+            "test".to_string(),
+            "results/model.gguf".to_string(),
+        );
+
+        let first = storage.generate_download_url(&file, storage.download_url_expiry_hours()).await.unwrap();
+        // La signature est datée à la seconde
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let second = storage.generate_download_url(&file, storage.download_url_expiry_hours()).await.unwrap();
+
+        assert_ne!(first, second);
+        assert!(second.contains("X-Amz-Expires=21600"), "{}", second);
+
+        let expires_in = storage.download_url_expires_at() - chrono::Utc::now();
+        assert!((expires_in - chrono::Duration::hours(6)).num_seconds().abs() <= 1);
+    }
+
+    #[tokio::test]
+    async fn download_by_key_round_trips_a_stored_object() {
+        let storage = local_storage(None);
+        let data = b"poids du modele".repeat(64);
+
+        let key = store_artifact(&storage, &data).await;
+        assert_eq!(storage.download_by_key(&key).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn download_by_key_decrypts_encrypted_objects() {
+        let storage = local_storage(Some("cle-de-chiffrement-de-32-octets!"));
+        let data = b"poids du modele".repeat(64);
+
+        let key = store_artifact(&storage, &data).await;
+        assert_eq!(storage.download_by_key(&key).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn compressed_objects_round_trip_and_uncompressed_ones_still_read() {
+        let encryption_key = Some("cle-de-chiffrement-de-32-octets!");
+        let data = b"poids du modele".repeat(64);
+
+        // Objet écrit avant l'activation de la compression
+        let legacy_key = store_artifact(&local_storage(encryption_key), &data).await;
+        assert!(!legacy_key.ends_with(LOCAL_ZSTD_SUFFIX), "{}", legacy_key);
+
+        let storage = local_storage(encryption_key).with_compression(Some(3));
+        let key = store_artifact(&storage, &data).await;
+        assert!(key.ends_with(LOCAL_ZSTD_SUFFIX), "{}", key);
+        assert!(std::fs::metadata(&key).unwrap().len() < data.len() as u64);
+
+        assert_eq!(storage.download_by_key(&key).await.unwrap(), data);
+        assert_eq!(storage.download_by_key(&legacy_key).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn path_traversal_name_is_neutralized_in_the_key_and_header() {
+        let storage = local_storage(None);
+        let hostile = "../../etc/passwd\r\nX-Injected: 1";
+        let source = testing::scratch_dir("artifact").join("model.bin");
+        std::fs::write(&source, b"poids").unwrap();
+
+        let file = storage
+            .upload_job_artifact(Uuid::new_v4(), Uuid::new_v4(), hostile, source.to_str().unwrap(), ModelFormat::PyTorch)
+            .await
+            .unwrap();
+
+        // L'objet reste dans le répertoire du stockage, sans segment remontant
+        let key = std::path::Path::new(&file.storage_path);
+        assert_eq!(key.parent(), Some(storage.local_dir.as_path()));
+        let object_name = key.file_name().unwrap().to_str().unwrap();
+        assert!(!object_name.contains(".."), "{}", object_name);
+        assert!(!object_name.contains(|c: char| c.is_control()), "{:?}", object_name);
+
+        let header = content_disposition_attachment(hostile);
+        assert!(!header.contains(['\r', '\n', '/']), "{:?}", header);
+        assert_eq!(header.matches('"').count(), 2, "{}", header);
+    }
+
+    #[tokio::test]
+    async fn injected_id_provider_names_stored_files() {
+        use crate::utils::ids::SequentialIdProvider;
+
+        let storage = local_storage(None).with_id_provider(Arc::new(SequentialIdProvider::new(7)));
+        let source = testing::scratch_dir("artifact").join("model.onnx");
+        std::fs::write(&source, b"onnx").unwrap();
+
+        for counter in 1..=2 {
+            let file = storage
+                .upload_job_artifact(Uuid::new_v4(), Uuid::new_v4(), "model.onnx", source.to_str().unwrap(), ModelFormat::Onnx)
+                .await
+                .unwrap();
+            let expected = Uuid::from_u64_pair(7, counter);
+            assert_eq!(file.id, expected);
+            assert!(file.storage_path.ends_with(&format!("{}_model.onnx", expected)), "{}", file.storage_path);
+        }
+    }
+
+    #[test]
+    fn orphan_selection_keeps_referenced_and_recent_objects() {
+        let now = chrono::Utc::now();
+        let object = |key: &str, age_hours: i64| StoredObject {
+            key: key.to_string(),
+            size: 10,
+            last_modified: now - chrono::Duration::hours(age_hours),
+        };
+        let referenced: std::collections::HashSet<String> = ["reference".to_string()].into_iter().collect();
+
+        let selected = select_orphaned_objects(
+            vec![object("orphelin", 48), object("reference", 48), object("recent", 1)],
+            &referenced,
+            now - chrono::Duration::hours(24),
+        );
+
+        let keys: Vec<_> = selected.iter().map(|object| object.key.as_str()).collect();
+        assert_eq!(keys, vec!["orphelin"]);
+    }
+
+    #[test]
+    fn only_service_named_objects_are_managed() {
+        assert!(is_managed_object_name(&format!("{}_model.onnx", Uuid::new_v4())));
+        assert!(!is_managed_object_name("notes.txt"));
+        assert!(!is_managed_object_name(&Uuid::new_v4().to_string()));
+        // Clé de dépôt d'un upload direct: supprimée si abandonnée
+        assert!(is_managed_object_name(&direct_upload_staging_key(Uuid::new_v4())));
+        assert!(!is_managed_object_name("incoming/notes.txt"));
+    }
+
+    #[test]
+    fn sha256_checksum_is_signed_in_base64() {
+        // SHA-256 de la chaîne vide
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(sha256_hex_to_base64(empty).unwrap(), "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+        assert!(sha256_hex_to_base64("abc").is_err());
+        assert!(sha256_hex_to_base64(&"zz".repeat(32)).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+// services/webhook.rs
+use crate::services::database::Database;
+use crate::utils::error::{AppError, Result};
+use chrono::Utc;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Adresse joignable depuis Internet ? (ni boucle locale, ni réseau privé,
+/// ni lien local, ni adresse réservée)
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                // Espace partagé des opérateurs (100.64.0.0/10)
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Adresses uniques locales (fc00::/7) et lien local (fe80::/10)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Vérifier qu'une URL de webhook vise une destination publique en HTTPS
+///
+/// Le nom est résolu: toutes ses adresses doivent être publiques. Retourne
+/// l'hôte et l'adresse retenue, à laquelle l'envoi est ensuite épinglé.
+pub async fn check_webhook_destination(url: &str) -> Result<(String, SocketAddr)> {
+    let invalid = |reason: &str| AppError::Validation(format!("URL de webhook refusée: {}", reason));
+
+    let url = reqwest::Url::parse(url).map_err(|_| invalid("URL invalide"))?;
+    if url.scheme() != "https" {
+        return Err(invalid("HTTPS requis"));
+    }
+    let host = url.host_str().ok_or_else(|| invalid("hôte manquant"))?.to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(|_| invalid("hôte introuvable"))?
+        .collect();
+    if addresses.is_empty() {
+        return Err(invalid("hôte introuvable"));
+    }
+    if addresses.iter().any(|address| !is_public_ip(address.ip())) {
+        return Err(invalid("adresse privée, locale ou réservée"));
+    }
+
+    Ok((host, addresses[0]))
+}
+
+/// Envoi des événements aux webhooks enregistrés par les utilisateurs
+///
+/// Chaque envoi est signé avec le secret du webhook: l'en-tête
+/// `X-Webhook-Signature` vaut `t=<timestamp>,v1=<HMAC-SHA256 de "<timestamp>.<corps>">`.
+///
+/// La destination est revérifiée à chaque envoi (le DNS a pu changer depuis
+/// l'enregistrement) et la connexion épinglée à l'adresse vérifiée; les
+/// redirections ne sont jamais suivies.
+pub struct WebhookNotifier {
+    db: Arc<Database>,
+    timeout: Duration,
+    /// Destinations locales acceptées (serveurs de test uniquement)
+    allow_private_destinations: bool,
+}
+
+impl WebhookNotifier {
+    pub fn new(db: Arc<Database>, timeout_seconds: u64) -> Self {
+        Self {
+            db,
+            timeout: Duration::from_secs(timeout_seconds),
+            allow_private_destinations: false,
+        }
+    }
+
+    /// Accepter les destinations locales en HTTP (serveur simulé des tests)
+    #[cfg(test)]
+    pub(crate) fn allowing_private_destinations(mut self) -> Self {
+        self.allow_private_destinations = true;
+        self
+    }
+
+    /// Client HTTP d'un envoi, épinglé à une destination publique vérifiée
+    async fn client_for(&self, url: &str) -> Result<reqwest::Client> {
+        let builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .redirect(reqwest::redirect::Policy::none());
+
+        let builder = if self.allow_private_destinations {
+            builder
+        } else {
+            let (host, address) = check_webhook_destination(url).await?;
+            builder.resolve(&host, address)
+        };
+
+        builder.build().map_err(|e| AppError::ExternalService(e.to_string()))
+    }
+
+    /// Émettre un événement en tâche de fond (best effort, sans nouvelle tentative)
+    pub fn emit(self: &Arc<Self>, user_id: Uuid, event_type: &'static str, data: serde_json::Value) {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.deliver(user_id, event_type, data).await {
+                log::warn!("Webhooks '{}' non envoyés pour l'utilisateur {}: {}", event_type, user_id, e);
+            }
+        });
+    }
+
+    /// Envoyer un événement à tous les webhooks abonnés de l'utilisateur
+    pub async fn deliver(&self, user_id: Uuid, event_type: &str, data: serde_json::Value) -> Result<usize> {
+        let webhooks = self.db.list_webhooks_for_event(user_id, event_type).await?;
+        if webhooks.is_empty() {
+            return Ok(0);
+        }
+
+        let timestamp = Utc::now().timestamp();
+        let body = serde_json::json!({
+            "id": Uuid::new_v4(),
+            "type": event_type,
+            "created_at": Utc::now(),
+            "data": data,
+        })
+        .to_string();
+
+        let mut delivered = 0;
+        for webhook in &webhooks {
+            let signature = crate::utils::security::sign_webhook_payload(&webhook.secret, timestamp, &body);
+
+            let client = match self.client_for(&webhook.url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    log::warn!("Webhook {} ({}) non envoyé: {}", webhook.id, event_type, e);
+                    continue;
+                }
+            };
+
+            let result = client
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-Webhook-Event", event_type)
+                .header("X-Webhook-Signature", format!("t={},v1={}", timestamp, signature))
+                .body(body.clone())
+                .send()
+                .await
+                .map_err(|e| AppError::ExternalService(e.to_string()))
+                .and_then(|response| {
+                    if response.status().is_success() {
+                        Ok(())
+                    } else {
+                        Err(AppError::ExternalService(format!("HTTP {}", response.status())))
+                    }
+                });
+
+            match result {
+                Ok(()) => delivered += 1,
+                Err(e) => log::warn!("Webhook {} ({}) en échec: {}", webhook.id, event_type, e),
+            }
+        }
+
+        Ok(delivered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_webhook_destination, is_public_ip};
+    use crate::utils::error::AppError;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1", "10.0.0.5", "172.16.3.4", "192.168.1.1", "169.254.169.254",
+            "0.0.0.0", "100.64.0.1", "::1", "fe80::1", "fd00::1", "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} ne doit pas être public", ip);
+        }
+        for ip in ["93.184.216.34", "8.8.8.8", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} doit être public", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn webhook_destination_requires_https_and_a_public_address() {
+        for url in [
+            "http://example.com/hook",
+            "https://127.0.0.1/hook",
+            "https://localhost/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::1]:8443/hook",
+        ] {
+            assert!(
+                matches!(check_webhook_destination(url).await, Err(AppError::Validation(_))),
+                "{} doit être refusée",
+                url
+            );
+        }
+    }
+}
@@ -15,14 +15,23 @@ pub struct Config {
     
     // Base de données
     pub database_url: String,
+    /// Réplique en lecture seule (optionnelle)
+    pub database_read_url: Option<String>,
     pub database_max_connections: u32,
     pub database_min_connections: u32,
     pub database_connection_timeout: u32,
+    pub database_acquire_timeout: u64,
+    pub database_idle_timeout: u64,
+    /// Durée maximale d'une requête SQL (ms, 0 = illimitée)
+    pub database_statement_timeout_ms: u64,
+    /// Requêtes journalisées comme lentes au-delà de ce seuil (ms)
+    pub database_slow_query_ms: u64,
     
     // Sécurité
     pub jwt_secret: String,
     pub jwt_access_token_expiry_hours: i64,
     pub jwt_refresh_token_expiry_days: i64,
+    pub impersonation_token_expiry_minutes: i64,
     pub admin_email: String,
     pub admin_password: String,
     pub password_reset_token_expiry_hours: i64,
@@ -39,6 +48,12 @@ pub struct Config {
     pub redis_connection_timeout: u64,
     pub redis_queue_prefix: String,
     pub redis_cache_ttl_seconds: u64,
+    pub analysis_cache_ttl_seconds: u64,
+    pub queue_consumers: usize,
+    pub queue_fair_ratio: u32,
+    pub scaling_target_drain_seconds: u64,
+    pub scaling_min_workers: u32,
+    pub scaling_max_workers: u32,
     
     // MinIO/S3
     pub storage_type: String,
@@ -50,19 +65,36 @@ pub struct Config {
     pub minio_secure: bool,
     pub minio_connection_timeout: u64,
     pub max_file_size_mb: u64,
+    pub download_url_expiry_hours: u32,
+    pub direct_upload_url_expiry_minutes: u64,
+    pub share_link_max_expiry_hours: i64,
+    pub storage_retry_max_attempts: u32,
+    pub storage_retry_base_delay_ms: u64,
+    pub storage_retry_max_delay_ms: u64,
+    /// Compression zstd des objets stockés (None = désactivée)
+    pub storage_compression_level: Option<i32>,
     
     // Quantification
     pub quantization_python_path: String,
     pub quantization_max_concurrent_jobs: usize,
+    pub gguf_export_max_concurrent: usize,
+    /// Accepter les précisions expérimentales 2/3 bits (GPTQ/AWQ)
+    pub enable_experimental_quantization: bool,
+    pub quality_validation_max_concurrent: usize,
     pub quantization_timeout_seconds: u64,
     pub quantization_max_retries: u32,
     pub quantization_gpu_enabled: bool,
+    pub work_dir: String,
+    pub sync_analysis_max_size_mb: u64,
     
     // Google OAuth
     pub google_oauth_client_id: Option<String>,
     pub google_oauth_client_secret: Option<String>,
     pub google_oauth_redirect_uri: Option<String>,
     
+    // Hugging Face
+    pub huggingface_token: Option<String>,
+    
     // Stripe
     pub stripe_secret_key: Option<String>,
     pub stripe_publishable_key: Option<String>,
@@ -72,6 +104,9 @@ pub struct Config {
     pub stripe_price_starter: Option<String>,
     pub stripe_price_pro: Option<String>,
     
+    // Webhooks utilisateur
+    pub webhook_timeout_seconds: u64,
+    
     // Email
     pub email_provider: String,
     pub email_from: String,
@@ -83,30 +118,63 @@ pub struct Config {
     pub smtp_password: Option<String>,
     pub smtp_tls: bool,
     
+    // Annonces admin
+    pub broadcast_batch_size: usize,
+    pub broadcast_batch_delay_ms: u64,
+    
     // Limites et quotas
     pub free_user_credits_per_month: i32,
     pub free_user_max_file_size_mb: u64,
     pub free_user_file_retention_days: i32,
+    pub free_user_storage_quota_mb: u64,
+    pub free_user_max_active_jobs: i64,
+    /// Mémoire maximale d'un job (Mo, 0 = illimitée)
+    pub free_user_max_job_memory_mb: u64,
+    /// Formats de sortie autorisés (vide = tous)
+    pub free_user_output_formats: Vec<crate::models::ModelFormat>,
     pub free_user_queue_priority: String,
     
     pub starter_user_credits_per_month: i32,
     pub starter_user_max_file_size_mb: u64,
     pub starter_user_file_retention_days: i32,
+    pub starter_user_storage_quota_mb: u64,
+    pub starter_user_max_active_jobs: i64,
+    pub starter_user_max_job_memory_mb: u64,
+    pub starter_user_output_formats: Vec<crate::models::ModelFormat>,
     pub starter_user_queue_priority: String,
     
     pub pro_user_max_file_size_mb: u64,
     pub pro_user_file_retention_days: i32,
+    pub pro_user_storage_quota_mb: u64,
+    pub pro_user_max_active_jobs: i64,
+    pub pro_user_max_job_memory_mb: u64,
+    pub pro_user_output_formats: Vec<crate::models::ModelFormat>,
     pub pro_user_queue_priority: String,
     
+    // Coût de base des jobs en crédits, par méthode
+    pub credit_cost_int8: i32,
+    pub credit_cost_gptq: i32,
+    pub credit_cost_awq: i32,
+    pub credit_cost_gguf: i32,
+    /// Nouvelle mesure de qualité d'un job terminé (0 = gratuite)
+    pub credit_cost_revalidation: i32,
+    
     pub rate_limit_requests_per_minute: i32,
     pub rate_limit_requests_per_hour: i32,
+    pub download_proxy_requests_per_minute: i64,
+    pub admin_search_requests_per_minute: i64,
     pub max_upload_size_mb: u64,
+    /// Taille maximale d'un corps JSON (ou brut) hors route d'upload
+    pub max_json_payload_kb: usize,
     pub max_concurrent_uploads_per_user: usize,
+    /// Extensions acceptées à l'upload (minuscules, sans point)
+    pub allowed_upload_extensions: Vec<String>,
     
     // Monitoring
     pub prometheus_enabled: bool,
     pub prometheus_port: u16,
     pub otel_exporter_otlp_endpoint: Option<String>,
+    /// "json" (défaut) ou "text"
     pub logging_format: String,
     
     // Maintenance
@@ -114,6 +182,23 @@ pub struct Config {
     pub delete_expired_files_days: i64,
     pub delete_failed_jobs_days: i64,
     pub delete_inactive_users_days: i64,
+    pub keep_temp_workspaces: bool,
+    /// Refuser le trafic non admin (503) dès le démarrage
+    pub maintenance_mode: bool,
+    pub maintenance_retry_after_seconds: u64,
+    pub file_expiry_warning_days: i64,
+    pub job_log_max_lines: i64,
+    /// Taille de page maximale des listes (`per_page` ramené à cette valeur)
+    pub pagination_max_per_page: i64,
+    pub job_log_retention_days: i64,
+    /// Jobs annulés (par le système) après ce délai en attente (0 = jamais)
+    pub job_pending_timeout_hours: u64,
+    /// Suppression des objets du stockage sans fichier en base
+    pub orphan_cleanup_enabled: bool,
+    /// Âge minimal d'un objet orphelin avant suppression
+    pub orphan_cleanup_grace_hours: i64,
+    /// Journaliser les suppressions sans les effectuer
+    pub orphan_cleanup_dry_run: bool,
     
     // URLs
     pub frontend_url: String,
@@ -170,6 +255,7 @@ impl Config {
             
             // Base de données
             database_url: env::var("DATABASE_URL")?,
+            database_read_url: env::var("DATABASE_READ_URL").ok().filter(|url| !url.is_empty()),
             database_max_connections: env::var("DATABASE_MAX_CONNECTIONS")
                 .unwrap_or_else(|_| "20".to_string())
                 .parse()
@@ -182,6 +268,22 @@ impl Config {
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("DATABASE_CONNECTION_TIMEOUT must be a number".to_string()))?,
+            database_acquire_timeout: env::var("DATABASE_ACQUIRE_TIMEOUT")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DATABASE_ACQUIRE_TIMEOUT must be a number".to_string()))?,
+            database_idle_timeout: env::var("DATABASE_IDLE_TIMEOUT")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DATABASE_IDLE_TIMEOUT must be a number".to_string()))?,
+            database_statement_timeout_ms: env::var("DATABASE_STATEMENT_TIMEOUT_MS")
+                .unwrap_or_else(|_| "30000".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DATABASE_STATEMENT_TIMEOUT_MS must be a number".to_string()))?,
+            database_slow_query_ms: env::var("DATABASE_SLOW_QUERY_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DATABASE_SLOW_QUERY_MS must be a number".to_string()))?,
             
             // Sécurité
             jwt_secret: env::var("JWT_SECRET")?,
@@ -193,6 +295,10 @@ impl Config {
                 .unwrap_or_else(|_| "7".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("JWT_REFRESH_TOKEN_EXPIRY_DAYS must be a number".to_string()))?,
+            impersonation_token_expiry_minutes: env::var("IMPERSONATION_TOKEN_EXPIRY_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("IMPERSONATION_TOKEN_EXPIRY_MINUTES must be a number".to_string()))?,
             admin_email: env::var("ADMIN_EMAIL").unwrap_or_else(|_| "admin@example.com".to_string()),
             admin_password: env::var("ADMIN_PASSWORD").unwrap_or_else(|_| "admin123".to_string()),
             password_reset_token_expiry_hours: env::var("PASSWORD_RESET_TOKEN_EXPIRY_HOURS")
@@ -227,6 +333,30 @@ impl Config {
                 .unwrap_or_else(|_| "3600".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("REDIS_CACHE_TTL_SECONDS must be a number".to_string()))?,
+            analysis_cache_ttl_seconds: env::var("ANALYSIS_CACHE_TTL_SECONDS")
+                .unwrap_or_else(|_| "604800".to_string()) // 7 jours
+                .parse()
+                .map_err(|_| AppError::Validation("ANALYSIS_CACHE_TTL_SECONDS must be a number".to_string()))?,
+            queue_consumers: env::var("QUEUE_CONSUMERS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("QUEUE_CONSUMERS must be a number".to_string()))?,
+            queue_fair_ratio: env::var("QUEUE_FAIR_RATIO")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("QUEUE_FAIR_RATIO must be a number".to_string()))?,
+            scaling_target_drain_seconds: env::var("SCALING_TARGET_DRAIN_SECONDS")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("SCALING_TARGET_DRAIN_SECONDS must be a number".to_string()))?,
+            scaling_min_workers: env::var("SCALING_MIN_WORKERS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("SCALING_MIN_WORKERS must be a number".to_string()))?,
+            scaling_max_workers: env::var("SCALING_MAX_WORKERS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("SCALING_MAX_WORKERS must be a number".to_string()))?,
             
             // MinIO/S3
             storage_type: env::var("STORAGE_TYPE").unwrap_or_else(|_| "minio".to_string()),
@@ -247,6 +377,42 @@ impl Config {
                 .unwrap_or_else(|_| "10240".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("MAX_FILE_SIZE_MB must be a number".to_string()))?,
+            download_url_expiry_hours: env::var("DOWNLOAD_URL_EXPIRY_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DOWNLOAD_URL_EXPIRY_HOURS must be a number".to_string()))?,
+            direct_upload_url_expiry_minutes: env::var("DIRECT_UPLOAD_URL_EXPIRY_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DIRECT_UPLOAD_URL_EXPIRY_MINUTES must be a number".to_string()))?,
+            share_link_max_expiry_hours: env::var("SHARE_LINK_MAX_EXPIRY_HOURS")
+                .unwrap_or_else(|_| "168".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("SHARE_LINK_MAX_EXPIRY_HOURS must be a number".to_string()))?,
+            storage_retry_max_attempts: env::var("STORAGE_RETRY_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("STORAGE_RETRY_MAX_ATTEMPTS must be a number".to_string()))?,
+            storage_retry_base_delay_ms: env::var("STORAGE_RETRY_BASE_DELAY_MS")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("STORAGE_RETRY_BASE_DELAY_MS must be a number".to_string()))?,
+            storage_retry_max_delay_ms: env::var("STORAGE_RETRY_MAX_DELAY_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("STORAGE_RETRY_MAX_DELAY_MS must be a number".to_string()))?,
+            storage_compression_level: if env::var("STORAGE_COMPRESSION")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .map_err(|_| AppError::Validation("STORAGE_COMPRESSION must be a boolean".to_string()))?
+            {
+                Some(env::var("STORAGE_COMPRESSION_LEVEL")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .map_err(|_| AppError::Validation("STORAGE_COMPRESSION_LEVEL must be a number".to_string()))?)
+            } else {
+                None
+            },
             
             // Quantification
             quantization_python_path: env::var("QUANTIZATION_PYTHON_PATH").unwrap_or_else(|_| "./python".to_string()),
@@ -254,6 +420,18 @@ impl Config {
                 .unwrap_or_else(|_| "2".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("QUANTIZATION_MAX_CONCURRENT_JOBS must be a number".to_string()))?,
+            gguf_export_max_concurrent: env::var("GGUF_EXPORT_MAX_CONCURRENT")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("GGUF_EXPORT_MAX_CONCURRENT must be a number".to_string()))?,
+            enable_experimental_quantization: env::var("ENABLE_EXPERIMENTAL_QUANTIZATION")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("ENABLE_EXPERIMENTAL_QUANTIZATION must be a boolean".to_string()))?,
+            quality_validation_max_concurrent: env::var("QUALITY_VALIDATION_MAX_CONCURRENT")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("QUALITY_VALIDATION_MAX_CONCURRENT must be a number".to_string()))?,
             quantization_timeout_seconds: env::var("QUANTIZATION_TIMEOUT_SECONDS")
                 .unwrap_or_else(|_| "3600".to_string())
                 .parse()
@@ -266,12 +444,20 @@ impl Config {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("QUANTIZATION_GPU_ENABLED must be a boolean".to_string()))?,
+            work_dir: env::var("WORK_DIR").unwrap_or_else(|_| "./work".to_string()),
+            sync_analysis_max_size_mb: env::var("SYNC_ANALYSIS_MAX_SIZE_MB")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("SYNC_ANALYSIS_MAX_SIZE_MB must be a number".to_string()))?,
             
             // Google OAuth
             google_oauth_client_id: env::var("GOOGLE_OAUTH_CLIENT_ID").ok(),
             google_oauth_client_secret: env::var("GOOGLE_OAUTH_CLIENT_SECRET").ok(),
             google_oauth_redirect_uri: env::var("GOOGLE_OAUTH_REDIRECT_URI").ok(),
             
+            // Hugging Face
+            huggingface_token: env::var("HUGGINGFACE_TOKEN").ok(),
+            
             // Stripe
             stripe_secret_key: env::var("STRIPE_SECRET_KEY").ok(),
             stripe_publishable_key: env::var("STRIPE_PUBLISHABLE_KEY").ok(),
@@ -284,6 +470,11 @@ impl Config {
             stripe_price_starter: env::var("STRIPE_PRICE_STARTER").ok(),
             stripe_price_pro: env::var("STRIPE_PRICE_PRO").ok(),
             
+            webhook_timeout_seconds: env::var("WEBHOOK_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("WEBHOOK_TIMEOUT_SECONDS must be a number".to_string()))?,
+            
             // Email
             email_provider: env::var("EMAIL_PROVIDER").unwrap_or_else(|_| "log".to_string()),
             email_from: env::var("EMAIL_FROM").unwrap_or_else(|_| "noreply@quantization.io".to_string()),
@@ -300,6 +491,16 @@ impl Config {
                 .parse()
                 .map_err(|_| AppError::Validation("SMTP_TLS must be a boolean".to_string()))?,
             
+            // Annonces admin
+            broadcast_batch_size: env::var("BROADCAST_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("BROADCAST_BATCH_SIZE must be a number".to_string()))?,
+            broadcast_batch_delay_ms: env::var("BROADCAST_BATCH_DELAY_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("BROADCAST_BATCH_DELAY_MS must be a number".to_string()))?,
+            
             // Limites et quotas
             free_user_credits_per_month: env::var("FREE_USER_CREDITS_PER_MONTH")
                 .unwrap_or_else(|_| "1".to_string())
@@ -313,6 +514,19 @@ impl Config {
                 .unwrap_or_else(|_| "7".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("FREE_USER_FILE_RETENTION_DAYS must be a number".to_string()))?,
+            free_user_storage_quota_mb: env::var("FREE_USER_STORAGE_QUOTA_MB")
+                .unwrap_or_else(|_| "10240".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("FREE_USER_STORAGE_QUOTA_MB must be a number".to_string()))?,
+            free_user_max_active_jobs: env::var("FREE_USER_MAX_ACTIVE_JOBS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("FREE_USER_MAX_ACTIVE_JOBS must be a number".to_string()))?,
+            free_user_max_job_memory_mb: env::var("FREE_USER_MAX_JOB_MEMORY_MB")
+                .unwrap_or_else(|_| "8192".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("FREE_USER_MAX_JOB_MEMORY_MB must be a number".to_string()))?,
+            free_user_output_formats: parse_output_formats("FREE_USER_OUTPUT_FORMATS", "onnx")?,
             free_user_queue_priority: env::var("FREE_USER_QUEUE_PRIORITY").unwrap_or_else(|_| "low".to_string()),
             
             starter_user_credits_per_month: env::var("STARTER_USER_CREDITS_PER_MONTH")
@@ -327,6 +541,19 @@ impl Config {
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("STARTER_USER_FILE_RETENTION_DAYS must be a number".to_string()))?,
+            starter_user_storage_quota_mb: env::var("STARTER_USER_STORAGE_QUOTA_MB")
+                .unwrap_or_else(|_| "102400".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("STARTER_USER_STORAGE_QUOTA_MB must be a number".to_string()))?,
+            starter_user_max_active_jobs: env::var("STARTER_USER_MAX_ACTIVE_JOBS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("STARTER_USER_MAX_ACTIVE_JOBS must be a number".to_string()))?,
+            starter_user_max_job_memory_mb: env::var("STARTER_USER_MAX_JOB_MEMORY_MB")
+                .unwrap_or_else(|_| "32768".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("STARTER_USER_MAX_JOB_MEMORY_MB must be a number".to_string()))?,
+            starter_user_output_formats: parse_output_formats("STARTER_USER_OUTPUT_FORMATS", "onnx,safetensors,pytorch,gguf")?,
             starter_user_queue_priority: env::var("STARTER_USER_QUEUE_PRIORITY").unwrap_or_else(|_| "medium".to_string()),
             
             pro_user_max_file_size_mb: env::var("PRO_USER_MAX_FILE_SIZE_MB")
@@ -337,8 +564,42 @@ impl Config {
                 .unwrap_or_else(|_| "90".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("PRO_USER_FILE_RETENTION_DAYS must be a number".to_string()))?,
+            pro_user_storage_quota_mb: env::var("PRO_USER_STORAGE_QUOTA_MB")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("PRO_USER_STORAGE_QUOTA_MB must be a number".to_string()))?,
+            pro_user_max_active_jobs: env::var("PRO_USER_MAX_ACTIVE_JOBS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("PRO_USER_MAX_ACTIVE_JOBS must be a number".to_string()))?,
+            pro_user_max_job_memory_mb: env::var("PRO_USER_MAX_JOB_MEMORY_MB")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("PRO_USER_MAX_JOB_MEMORY_MB must be a number".to_string()))?,
+            pro_user_output_formats: parse_output_formats("PRO_USER_OUTPUT_FORMATS", "onnx,safetensors,pytorch,gguf")?,
             pro_user_queue_priority: env::var("PRO_USER_QUEUE_PRIORITY").unwrap_or_else(|_| "high".to_string()),
             
+            credit_cost_int8: env::var("CREDIT_COST_INT8")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("CREDIT_COST_INT8 must be a number".to_string()))?,
+            credit_cost_gptq: env::var("CREDIT_COST_GPTQ")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("CREDIT_COST_GPTQ must be a number".to_string()))?,
+            credit_cost_awq: env::var("CREDIT_COST_AWQ")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("CREDIT_COST_AWQ must be a number".to_string()))?,
+            credit_cost_gguf: env::var("CREDIT_COST_GGUF")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("CREDIT_COST_GGUF must be a number".to_string()))?,
+            credit_cost_revalidation: env::var("CREDIT_COST_REVALIDATION")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("CREDIT_COST_REVALIDATION must be a number".to_string()))?,
+            
             rate_limit_requests_per_minute: env::var("RATE_LIMIT_REQUESTS_PER_MINUTE")
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()
@@ -347,14 +608,32 @@ impl Config {
                 .unwrap_or_else(|_| "1000".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("RATE_LIMIT_REQUESTS_PER_HOUR must be a number".to_string()))?,
+            download_proxy_requests_per_minute: env::var("DOWNLOAD_PROXY_REQUESTS_PER_MINUTE")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("DOWNLOAD_PROXY_REQUESTS_PER_MINUTE must be a number".to_string()))?,
+            admin_search_requests_per_minute: env::var("ADMIN_SEARCH_REQUESTS_PER_MINUTE")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("ADMIN_SEARCH_REQUESTS_PER_MINUTE must be a number".to_string()))?,
             max_upload_size_mb: env::var("MAX_UPLOAD_SIZE_MB")
                 .unwrap_or_else(|_| "10240".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("MAX_UPLOAD_SIZE_MB must be a number".to_string()))?,
+            max_json_payload_kb: env::var("MAX_JSON_PAYLOAD_KB")
+                .unwrap_or_else(|_| "256".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("MAX_JSON_PAYLOAD_KB must be a number".to_string()))?,
             max_concurrent_uploads_per_user: env::var("MAX_CONCURRENT_UPLOADS_PER_USER")
                 .unwrap_or_else(|_| "3".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("MAX_CONCURRENT_UPLOADS_PER_USER must be a number".to_string()))?,
+            allowed_upload_extensions: env::var("ALLOWED_UPLOAD_EXTENSIONS")
+                .unwrap_or_else(|_| "onnx,safetensors,gguf,bin,pt".to_string())
+                .split(',')
+                .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+                .filter(|extension| !extension.is_empty())
+                .collect(),
             
             // Monitoring
             prometheus_enabled: env::var("PROMETHEUS_ENABLED")
@@ -385,6 +664,50 @@ impl Config {
                 .unwrap_or_else(|_| "180".to_string())
                 .parse()
                 .map_err(|_| AppError::Validation("DELETE_INACTIVE_USERS_DAYS must be a number".to_string()))?,
+            keep_temp_workspaces: env::var("KEEP_TEMP_WORKSPACES")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("KEEP_TEMP_WORKSPACES must be a boolean".to_string()))?,
+            maintenance_mode: env::var("MAINTENANCE_MODE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("MAINTENANCE_MODE must be a boolean".to_string()))?,
+            maintenance_retry_after_seconds: env::var("MAINTENANCE_RETRY_AFTER_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("MAINTENANCE_RETRY_AFTER_SECONDS must be a number".to_string()))?,
+            file_expiry_warning_days: env::var("FILE_EXPIRY_WARNING_DAYS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("FILE_EXPIRY_WARNING_DAYS must be a number".to_string()))?,
+            job_log_max_lines: env::var("JOB_LOG_MAX_LINES")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("JOB_LOG_MAX_LINES must be a number".to_string()))?,
+            pagination_max_per_page: env::var("PAGINATION_MAX_PER_PAGE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("PAGINATION_MAX_PER_PAGE must be a number".to_string()))?,
+            job_log_retention_days: env::var("JOB_LOG_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("JOB_LOG_RETENTION_DAYS must be a number".to_string()))?,
+            job_pending_timeout_hours: env::var("JOB_PENDING_TIMEOUT_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("JOB_PENDING_TIMEOUT_HOURS must be a number".to_string()))?,
+            orphan_cleanup_enabled: env::var("ORPHAN_CLEANUP_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("ORPHAN_CLEANUP_ENABLED must be a boolean".to_string()))?,
+            orphan_cleanup_grace_hours: env::var("ORPHAN_CLEANUP_GRACE_HOURS")
+                .unwrap_or_else(|_| "48".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("ORPHAN_CLEANUP_GRACE_HOURS must be a number".to_string()))?,
+            orphan_cleanup_dry_run: env::var("ORPHAN_CLEANUP_DRY_RUN")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| AppError::Validation("ORPHAN_CLEANUP_DRY_RUN must be a boolean".to_string()))?,
             
             // URLs
             frontend_url: env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string()),
@@ -427,6 +750,97 @@ impl Config {
         Ok(config)
     }
     
+    /// Espace de noms Redis de l'environnement (toujours terminé par `:`)
+    ///
+    /// Plusieurs environnements peuvent partager une instance Redis tant
+    /// que leurs `REDIS_QUEUE_PREFIX` diffèrent.
+    pub fn redis_namespace(&self) -> String {
+        let prefix = self.redis_queue_prefix.trim_end_matches(':');
+        if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}:", prefix)
+        }
+    }
+    
+    /// Préfixe des clés du cache, séparé de celui de la queue
+    pub fn redis_cache_prefix(&self) -> String {
+        format!("{}cache:", self.redis_namespace())
+    }
+    
+    /// Politique de rétention des fichiers par plan
+    pub fn retention_policy(&self) -> crate::models::RetentionPolicy {
+        crate::models::RetentionPolicy {
+            free_days: self.free_user_file_retention_days,
+            starter_days: self.starter_user_file_retention_days,
+            pro_days: self.pro_user_file_retention_days,
+        }
+    }
+    
+    /// Politique de réessai des opérations de stockage
+    pub fn storage_retry_policy(&self) -> crate::utils::retry::RetryPolicy {
+        crate::utils::retry::RetryPolicy {
+            max_attempts: self.storage_retry_max_attempts,
+            base_delay_ms: self.storage_retry_base_delay_ms,
+            max_delay_ms: self.storage_retry_max_delay_ms,
+        }
+    }
+    
+    /// Quota d'espace de stockage par plan (0 = illimité)
+    pub fn storage_quota_policy(&self) -> crate::models::StorageQuotaPolicy {
+        crate::models::StorageQuotaPolicy {
+            free_mb: self.free_user_storage_quota_mb,
+            starter_mb: self.starter_user_storage_quota_mb,
+            pro_mb: self.pro_user_storage_quota_mb,
+        }
+    }
+    
+    /// Coût de base des jobs en crédits, par méthode
+    pub fn credit_cost_policy(&self) -> crate::models::CreditCostPolicy {
+        crate::models::CreditCostPolicy {
+            int8: self.credit_cost_int8,
+            gptq: self.credit_cost_gptq,
+            awq: self.credit_cost_awq,
+            gguf: self.credit_cost_gguf,
+        }
+    }
+    
+    /// Nombre maximal de jobs en cours par utilisateur selon le plan (0 = illimité)
+    pub fn active_job_policy(&self) -> crate::models::ActiveJobPolicy {
+        crate::models::ActiveJobPolicy {
+            free: self.free_user_max_active_jobs,
+            starter: self.starter_user_max_active_jobs,
+            pro: self.pro_user_max_active_jobs,
+        }
+    }
+    
+    /// Mémoire maximale d'un job selon le plan (0 = illimitée)
+    pub fn job_memory_policy(&self) -> crate::models::JobMemoryPolicy {
+        crate::models::JobMemoryPolicy {
+            free_mb: self.free_user_max_job_memory_mb,
+            starter_mb: self.starter_user_max_job_memory_mb,
+            pro_mb: self.pro_user_max_job_memory_mb,
+        }
+    }
+    
+    /// Formats de sortie autorisés selon le plan
+    pub fn output_format_policy(&self) -> crate::models::OutputFormatPolicy {
+        crate::models::OutputFormatPolicy {
+            free: self.free_user_output_formats.clone(),
+            starter: self.starter_user_output_formats.clone(),
+            pro: self.pro_user_output_formats.clone(),
+        }
+    }
+    
+    /// Taille maximale d'un modèle source selon le plan (en Mo)
+    pub fn max_file_size_mb_for(&self, plan: &crate::models::SubscriptionPlan) -> u64 {
+        match plan {
+            crate::models::SubscriptionPlan::Free => self.free_user_max_file_size_mb,
+            crate::models::SubscriptionPlan::Starter => self.starter_user_max_file_size_mb,
+            crate::models::SubscriptionPlan::Pro => self.pro_user_max_file_size_mb,
+        }
+    }
+    
     /// Vérifier si on est en production
     pub fn is_production(&self) -> bool {
         self.run_mode == "production"
@@ -441,4 +855,17 @@ impl Config {
     pub fn is_staging(&self) -> bool {
         self.run_mode == "staging"
     }
-}
\ No newline at end of file
+}
+
+/// Lire une liste de formats séparés par des virgules (ex: "onnx,gguf")
+fn parse_output_formats(var: &str, default: &str) -> Result<Vec<crate::models::ModelFormat>> {
+    env::var(var)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|format| !format.is_empty())
+        .map(|format| {
+            format.parse().map_err(|e| AppError::Validation(format!("{}: {}", var, e)))
+        })
+        .collect()
+}
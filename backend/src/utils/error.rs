@@ -2,6 +2,7 @@
 use actix_web::{HttpResponse, ResponseError};
 use serde_json::json;
 use thiserror::Error;
+use std::collections::BTreeMap;
 use std::fmt;
 
 #[derive(Error, Debug)]
@@ -30,6 +31,9 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
     
+    #[error("Validation failed")]
+    InvalidFields(BTreeMap<String, Vec<String>>),
+    
     #[error("Parse error: {0}")]
     ParseError(String),
     
@@ -43,6 +47,9 @@ pub enum AppError {
     #[error("Resource already exists")]
     AlreadyExists,
     
+    #[error("Concurrent modification, please retry")]
+    ConcurrentModification,
+    
     #[error("Insufficient credits")]
     InsufficientCredits,
     
@@ -55,9 +62,21 @@ pub enum AppError {
     #[error("File too large")]
     FileTooLarge,
     
+    #[error("Storage quota exceeded")]
+    StorageQuotaExceeded,
+    
+    #[error("File is used by an active job")]
+    FileInUse,
+    
+    #[error("File has expired and was purged")]
+    FileExpired,
+    
     #[error("Invalid file format")]
     InvalidFileFormat,
     
+    #[error("Input file is unreadable: {0}")]
+    CorruptInput(String),
+    
     // Erreurs de traitement
     #[error("Job cannot be cancelled")]
     JobCannotBeCancelled,
@@ -65,12 +84,39 @@ pub enum AppError {
     #[error("Job cannot be retried")]
     JobCannotBeRetried,
     
+    #[error("Too many active jobs (limit: {0})")]
+    TooManyActiveJobs(i64),
+    
+    #[error("An active job already has this name (available: {0})")]
+    DuplicateJobName(String),
+    
     #[error("Invalid combination of parameters")]
     InvalidCombination,
     
     #[error("GPU required for this operation")]
     GpuRequired,
     
+    #[error("Quantization method unavailable on this server: {0}")]
+    MethodUnavailable(String),
+    
+    #[error("{0}")]
+    IncompatibleArchitecture(String),
+    
+    #[error("Model conversion failed: {0}")]
+    ConversionFailed(String),
+    
+    #[error("Quality threshold exceeded: {0}")]
+    QualityThresholdExceeded(String),
+    
+    #[error("Memory limit exceeded: {0}")]
+    OutOfMemory(String),
+    
+    #[error("Model already quantized ({0} detected)")]
+    AlreadyQuantized(String),
+    
+    #[error("Quantized output failed to load: {0}")]
+    CorruptOutput(String),
+    
     // Erreurs de paiement
     #[error("Invalid plan")]
     InvalidPlan,
@@ -81,13 +127,25 @@ pub enum AppError {
     #[error("Payment failed")]
     PaymentFailed,
     
+    #[error("Output format {0} is not included in the current plan")]
+    OutputFormatNotInPlan(String),
+    
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+    
     // Erreurs externes
     #[error("External service error: {0}")]
     ExternalService(String),
     
+    #[error("Operation timed out: {0}")]
+    Timeout(String),
+    
     #[error("Stripe error: {0}")]
     StripeError(String),
     
+    #[error("SMTP authentication failed: {0}")]
+    SmtpAuthFailed(String),
+    
     // Erreurs de base de données
     #[error("Database error: {0}")]
     Database(String),
@@ -111,6 +169,9 @@ pub enum AppError {
     #[error("Invalid path")]
     InvalidPath,
     
+    #[error("Insufficient disk space: {0}")]
+    InsufficientDiskSpace(String),
+    
     #[error("Notification error: {0}")]
     NotificationError(String),
     
@@ -125,6 +186,8 @@ impl ResponseError for AppError {
             AppError::Validation(_)
             | AppError::InvalidCombination
             | AppError::InvalidPlan
+            | AppError::InvalidSignature
+            | AppError::CorruptInput(_)
             | AppError::InvalidPath => {
                 HttpResponse::BadRequest().json(json!({
                     "error": self.to_string(),
@@ -132,6 +195,15 @@ impl ResponseError for AppError {
                 }))
             }
             
+            // 400 - Bad Request (détail par champ)
+            AppError::InvalidFields(fields) => {
+                HttpResponse::BadRequest().json(json!({
+                    "error": self.to_string(),
+                    "code": "VALIDATION_ERROR",
+                    "details": fields
+                }))
+            }
+            
             // 401 - Unauthorized
             AppError::Unauthorized
             | AppError::InvalidToken
@@ -163,9 +235,20 @@ impl ResponseError for AppError {
                 }))
             }
             
+            // 410 - Gone
+            AppError::FileExpired => {
+                HttpResponse::Gone().json(json!({
+                    "error": self.to_string(),
+                    "code": "GONE"
+                }))
+            }
+            
             // 409 - Conflict
             AppError::UserAlreadyExists
-            | AppError::AlreadyExists => {
+            | AppError::AlreadyExists
+            | AppError::ConcurrentModification
+            | AppError::DuplicateJobName(_)
+            | AppError::FileInUse => {
                 HttpResponse::Conflict().json(json!({
                     "error": self.to_string(),
                     "code": "CONFLICT"
@@ -182,7 +265,8 @@ impl ResponseError for AppError {
             }
             
             // 413 - Payload Too Large
-            AppError::FileTooLarge => {
+            AppError::FileTooLarge
+            | AppError::StorageQuotaExceeded => {
                 HttpResponse::PayloadTooLarge().json(json!({
                     "error": self.to_string(),
                     "code": "PAYLOAD_TOO_LARGE"
@@ -190,7 +274,13 @@ impl ResponseError for AppError {
             }
             
             // 422 - Unprocessable Entity
-            AppError::InvalidFileFormat => {
+            AppError::InvalidFileFormat
+            | AppError::IncompatibleArchitecture(_)
+            | AppError::ConversionFailed(_)
+            | AppError::QualityThresholdExceeded(_)
+            | AppError::OutOfMemory(_)
+            | AppError::AlreadyQuantized(_)
+            | AppError::CorruptOutput(_) => {
                 HttpResponse::UnprocessableEntity().json(json!({
                     "error": self.to_string(),
                     "code": "UNPROCESSABLE_ENTITY"
@@ -198,15 +288,41 @@ impl ResponseError for AppError {
             }
             
             // 429 - Too Many Requests
-            AppError::ResourceBusy => {
+            AppError::ResourceBusy
+            | AppError::TooManyActiveJobs(_) => {
                 HttpResponse::TooManyRequests().json(json!({
                     "error": self.to_string(),
                     "code": "TOO_MANY_REQUESTS"
                 }))
             }
             
+            // 503 - Service Unavailable
+            AppError::MethodUnavailable(_) => {
+                HttpResponse::ServiceUnavailable().json(json!({
+                    "error": self.to_string(),
+                    "code": "SERVICE_UNAVAILABLE"
+                }))
+            }
+            
+            // 504 - Gateway Timeout
+            AppError::Timeout(_) => {
+                HttpResponse::GatewayTimeout().json(json!({
+                    "error": self.to_string(),
+                    "code": "TIMEOUT"
+                }))
+            }
+            
+            // 507 - Insufficient Storage
+            AppError::InsufficientDiskSpace(_) => {
+                HttpResponse::InsufficientStorage().json(json!({
+                    "error": self.to_string(),
+                    "code": "INSUFFICIENT_STORAGE"
+                }))
+            }
+            
             // 402 - Payment Required
-            AppError::InsufficientCredits => {
+            AppError::InsufficientCredits
+            | AppError::OutputFormatNotInPlan(_) => {
                 HttpResponse::PaymentRequired().json(json!({
                     "error": self.to_string(),
                     "code": "PAYMENT_REQUIRED"
@@ -225,6 +341,12 @@ impl ResponseError for AppError {
     }
 }
 
+impl From<crate::models::UnknownQuantizationMethod> for AppError {
+    fn from(err: crate::models::UnknownQuantizationMethod) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
         match err {
@@ -242,6 +364,9 @@ impl From<serde_json::Error> for AppError {
 
 impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
+        if crate::utils::helpers::is_out_of_space(&err) {
+            return AppError::InsufficientDiskSpace(err.to_string());
+        }
         AppError::StorageError(err.to_string())
     }
 }
@@ -254,19 +379,24 @@ impl From<uuid::Error> for AppError {
 
 impl From<validator::ValidationErrors> for AppError {
     fn from(err: validator::ValidationErrors) -> Self {
-        let messages: Vec<String> = err
+        let fields: BTreeMap<String, Vec<String>> = err
             .field_errors()
             .iter()
             .map(|(field, errors)| {
                 let error_messages: Vec<String> = errors
                     .iter()
-                    .filter_map(|e| e.message.as_ref().map(|m| m.to_string()))
+                    .map(|e| {
+                        e.message
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| e.code.to_string())
+                    })
                     .collect();
-                format!("{}: {}", field, error_messages.join(", "))
+                (field.to_string(), error_messages)
             })
             .collect();
         
-        AppError::Validation(messages.join("; "))
+        AppError::InvalidFields(fields)
     }
 }
 
@@ -74,14 +74,98 @@ pub fn truncate_string(s: &str, max_length: usize) -> String {
 }
 
 /// Nettoyer une chaîne pour un nom de fichier
+///
+/// À appliquer à tout nom fourni par l'utilisateur avant de construire une clé
+/// de stockage ou un en-tête `Content-Disposition`: seul le dernier segment
+/// est conservé, les caractères de contrôle (CR/LF) sont supprimés et les
+/// séquences `..` neutralisées.
 pub fn sanitize_filename(filename: &str) -> String {
-    filename
+    let base = filename.rsplit(|c| c == '/' || c == '\\').next().unwrap_or("");
+    
+    let cleaned: String = base
         .chars()
+        .filter(|c| !c.is_control())
         .map(|c| match c {
             'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | ' ' => c,
             _ => '_',
         })
-        .collect()
+        .collect();
+    
+    let cleaned = cleaned.replace("..", "_");
+    let cleaned: String = cleaned
+        .trim_matches(|c| c == '.' || c == ' ')
+        .chars()
+        .take(200)
+        .collect();
+    
+    if cleaned.is_empty() {
+        "model".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Valeur d'en-tête `Content-Disposition` pour télécharger un fichier
+pub fn content_disposition_attachment(filename: &str) -> String {
+    format!("attachment; filename=\"{}\"", sanitize_filename(filename))
+}
+
+/// Plage d'octets demandée par un en-tête `Range` (bornes incluses)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// Pas de plage exploitable: servir le contenu complet
+    Full,
+    /// Plage `start..=end` valide pour la taille du contenu
+    Partial(u64, u64),
+    /// Plage hors du contenu (réponse 416)
+    Unsatisfiable,
+}
+
+/// Interpréter un en-tête `Range` pour un contenu de `size` octets
+///
+/// Seule une plage unique en octets est gérée; un en-tête invalide, multiple
+/// ou d'une autre unité est ignoré (contenu complet), comme le permet la RFC 9110.
+pub fn parse_byte_range(header: Option<&str>, size: u64) -> ByteRange {
+    let spec = match header.and_then(|h| h.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Full,
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return ByteRange::Full,
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        // Suffixe: les N derniers octets
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(n) => (size.saturating_sub(n), size.saturating_sub(1)),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) => (start, size.saturating_sub(1)),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(size.saturating_sub(1))),
+            (Ok(_), Ok(_)) => return ByteRange::Unsatisfiable,
+            _ => return ByteRange::Full,
+        },
+    };
+
+    if size == 0 || range.0 >= size {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Partial(range.0, range.1)
+    }
+}
+
+/// La réponse à cet en-tête `Range` sert-elle le contenu depuis le premier octet ?
+///
+/// Seule une reprise (`Partial` au-delà du premier octet) ou une plage hors
+/// du contenu n'en sert pas le début; un en-tête invalide donne le contenu complet.
+pub fn serves_from_start(header: Option<&str>, size: u64) -> bool {
+    matches!(parse_byte_range(header, size), ByteRange::Full | ByteRange::Partial(0, _))
 }
 
 /// Créer un répertoire s'il n'existe pas
@@ -102,6 +186,18 @@ pub fn remove_directory(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Obtenir l'espace disque disponible (en octets) sur le volume contenant `path`
+pub fn available_disk_space(path: &Path) -> Result<u64> {
+    fs2::available_space(path)
+        .map_err(|e| AppError::StorageError(e.to_string()))
+}
+
+/// Vérifier si une erreur IO correspond à un disque plein (ENOSPC)
+pub fn is_out_of_space(err: &io::Error) -> bool {
+    const ENOSPC: i32 = 28;
+    err.raw_os_error() == Some(ENOSPC)
+}
+
 /// Lire un fichier en bytes
 pub fn read_file_bytes(path: &Path) -> Result<Vec<u8>> {
     fs::read(path)
@@ -202,4 +298,36 @@ where
     )
     .await
     .map_err(|_| AppError::ResourceBusy)?
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_ranges_follow_rfc_9110() {
+        assert_eq!(parse_byte_range(Some("bytes=0-99"), 1000), ByteRange::Partial(0, 99));
+        assert_eq!(parse_byte_range(Some("bytes=900-"), 1000), ByteRange::Partial(900, 999));
+        assert_eq!(parse_byte_range(Some("bytes=-100"), 1000), ByteRange::Partial(900, 999));
+        // Fin au-delà du contenu: ramenée au dernier octet
+        assert_eq!(parse_byte_range(Some("bytes=500-5000"), 1000), ByteRange::Partial(500, 999));
+
+        assert_eq!(parse_byte_range(Some("bytes=1000-"), 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_byte_range(Some("bytes=50-10"), 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_byte_range(Some("bytes=-0"), 1000), ByteRange::Unsatisfiable);
+
+        // Invalide, multiple ou autre unité: contenu complet
+        assert_eq!(parse_byte_range(None, 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("bytes=0-10,20-30"), 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("items=0-10"), 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("bytes=abc"), 1000), ByteRange::Full);
+    }
+
+    #[test]
+    fn only_resumed_ranges_skip_the_start() {
+        assert!(serves_from_start(None, 1000));
+        assert!(serves_from_start(Some("bytes=0-"), 1000));
+        assert!(!serves_from_start(Some("bytes=100-"), 1000));
+        assert!(!serves_from_start(Some("bytes=2000-"), 1000));
+    }
+}
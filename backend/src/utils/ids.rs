@@ -0,0 +1,65 @@
+// utils/ids.rs
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Source des identifiants attribués aux entités créées (jobs, fichiers, transactions)
+///
+/// UUID v4 aléatoires en production; une séquence déterministe peut être
+/// injectée pour les tests ou pour rejouer un scénario.
+pub trait IdProvider: Send + Sync {
+    fn next_id(&self) -> Uuid;
+}
+
+/// UUID v4 aléatoires (défaut)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomIdProvider;
+
+impl IdProvider for RandomIdProvider {
+    fn next_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// Séquence déterministe: `seed` dans les 64 bits de poids fort, compteur dans les autres
+#[derive(Debug)]
+pub struct SequentialIdProvider {
+    seed: u64,
+    next: AtomicU64,
+}
+
+impl SequentialIdProvider {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl IdProvider for SequentialIdProvider {
+    fn next_id(&self) -> Uuid {
+        Uuid::from_u64_pair(self.seed, self.next.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+/// Fournisseur par défaut
+pub fn default_id_provider() -> Arc<dyn IdProvider> {
+    Arc::new(RandomIdProvider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_provider_is_predictable() {
+        let ids = SequentialIdProvider::new(42);
+        assert_eq!(ids.next_id(), Uuid::from_u64_pair(42, 1));
+        assert_eq!(ids.next_id(), Uuid::from_u64_pair(42, 2));
+
+        // Même graine, même séquence
+        let replay = SequentialIdProvider::new(42);
+        assert_eq!(replay.next_id(), Uuid::from_u64_pair(42, 1));
+    }
+}
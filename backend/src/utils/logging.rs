@@ -0,0 +1,163 @@
+// utils/logging.rs
+use crate::utils::config::Config;
+use crate::utils::error::Result;
+use tracing_subscriber::layer::{Identity, Layered};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter, Layer, Registry};
+
+/// Subscriber de base (registre + sortie formatée) sur lequel s'empilent les couches optionnelles
+type BaseSubscriber = Layered<Box<dyn Layer<Registry> + Send + Sync>, Registry>;
+
+/// Format de sortie des logs (`LOGGING_FORMAT`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Json,  // Une ligne JSON par événement (production)
+    Text,  // Texte lisible (développement)
+}
+
+impl LogFormat {
+    /// Interpréter la valeur configurée ("json" ou "text")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "json" => Some(LogFormat::Json),
+            "text" | "pretty" | "plain" => Some(LogFormat::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Initialiser le logging selon la configuration (format, niveau, export OTLP)
+///
+/// Sans effet si un subscriber est déjà installé (tests, double appel).
+pub fn init(config: &Config) -> Result<()> {
+    let format = LogFormat::parse(&config.logging_format);
+
+    // Export des traces si un collecteur OTLP est configuré (sinon logs seuls)
+    let mut otel_error = None;
+    let otel_layer = match &config.otel_exporter_otlp_endpoint {
+        Some(endpoint) => match init_otlp_tracer(endpoint) {
+            Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+            Err(e) => {
+                otel_error = Some(e.to_string());
+                None
+            }
+        },
+        None => None,
+    };
+    let otel_enabled = otel_layer.is_some();
+
+    let installed = install(
+        &config.log_level,
+        format.unwrap_or(LogFormat::Text),
+        false,
+        otel_layer,
+    );
+    if !installed {
+        log::debug!("Logging déjà initialisé, configuration conservée");
+        return Ok(());
+    }
+
+    log::info!("Logging initialisé avec niveau: {}", config.log_level);
+    if format.is_none() {
+        log::warn!("LOGGING_FORMAT inconnu ({}), format texte utilisé", config.logging_format);
+    }
+    if otel_enabled {
+        log::info!("Export OTLP activé vers {}", config.otel_exporter_otlp_endpoint.as_deref().unwrap_or_default());
+    } else if let Some(e) = otel_error {
+        log::warn!("Export OTLP désactivé, collecteur injoignable: {}", e);
+    }
+    Ok(())
+}
+
+/// Initialiser le logging des tests (sortie capturée par le harnais, idempotent)
+pub fn init_for_tests() {
+    let level = std::env::var("LOG_LEVEL").unwrap_or_else(|_| "debug".to_string());
+    let format = std::env::var("LOGGING_FORMAT")
+        .ok()
+        .and_then(|value| LogFormat::parse(&value))
+        .unwrap_or(LogFormat::Text);
+
+    install::<Identity>(&level, format, true, None);
+}
+
+/// Installer le subscriber global (retourne false s'il en existe déjà un)
+fn install<L>(level: &str, format: LogFormat, test_writer: bool, extra: Option<L>) -> bool
+where
+    L: Layer<BaseSubscriber> + Send + Sync,
+{
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(level));
+
+    let fmt_layer = match (format, test_writer) {
+        (LogFormat::Json, false) => fmt::layer().json().boxed(),
+        (LogFormat::Json, true) => fmt::layer().json().with_test_writer().boxed(),
+        (LogFormat::Text, false) => fmt::layer().boxed(),
+        (LogFormat::Text, true) => fmt::layer().with_test_writer().boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(extra)
+        .with(filter)
+        .try_init()
+        .is_ok()
+}
+
+/// Installer le tracer OTLP (gRPC) et son export par lots
+fn init_otlp_tracer(
+    endpoint: &str,
+) -> std::result::Result<opentelemetry_sdk::trace::Tracer, opentelemetry::trace::TraceError> {
+    use opentelemetry_otlp::WithExportConfig;
+    
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(endpoint)
+        .with_timeout(std::time::Duration::from_secs(5));
+    
+    let resource = opentelemetry_sdk::Resource::new(vec![
+        opentelemetry::KeyValue::new("service.name", env!("CARGO_PKG_NAME")),
+        opentelemetry::KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+    ]);
+    
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(resource))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{Span, Tracer};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn otlp_tracer_provider_is_installed_when_an_endpoint_is_configured() {
+        // Aucun collecteur n'écoute: la connexion gRPC est paresseuse, l'installation réussit
+        let tracer = init_otlp_tracer("http://127.0.0.1:4317").expect("tracer OTLP");
+
+        let span = tracer.start("smoke");
+        assert!(span.span_context().is_valid());
+
+        // Le fournisseur global n'est plus le fournisseur no-op
+        let span = opentelemetry::global::tracer("smoke").start("global");
+        assert!(span.span_context().is_valid());
+    }
+
+    #[test]
+    fn double_initialization_does_not_panic() {
+        let mut config = crate::utils::testing::config();
+        config.otel_exporter_otlp_endpoint = None;
+
+        init_for_tests();
+        init(&config).unwrap();
+        init(&config).unwrap();
+        init_for_tests();
+    }
+
+    #[test]
+    fn log_format_accepts_json_and_text_aliases() {
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse(" pretty "), Some(LogFormat::Text));
+        assert_eq!(LogFormat::parse("xml"), None);
+    }
+}
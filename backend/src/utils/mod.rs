@@ -4,10 +4,20 @@ pub mod config;
 pub mod security;
 pub mod validation;
 pub mod helpers;
+pub mod workspace;
+pub mod retry;
+pub mod logging;
+pub mod ids;
+pub mod zip;
+#[cfg(test)]
+pub mod testing;
 
 // Ré-exports pour faciliter l'import
 pub use error::{AppError, Result};
 pub use config::Config;
+pub use workspace::TempWorkspace;
+pub use retry::RetryPolicy;
+pub use ids::{IdProvider, RandomIdProvider, SequentialIdProvider};
 pub use security::{
     generate_access_token, generate_refresh_token,
     verify_access_token, verify_refresh_token,
@@ -28,7 +38,7 @@ pub use validation::{
 pub use helpers::{
     generate_uuid, format_date, format_relative_date,
     format_file_size, calculate_percentage,
-    truncate_string, sanitize_filename,
+    truncate_string, sanitize_filename, content_disposition_attachment,
     ensure_directory_exists, remove_directory,
     read_file_bytes, write_file_bytes, get_file_size,
     is_file, is_directory, get_file_extension,
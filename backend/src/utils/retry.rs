@@ -0,0 +1,135 @@
+// utils/retry.rs
+use rand::Rng;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+/// Politique de réessai pour les erreurs transitoires (réseau, 5xx, throttling)
+///
+/// Le délai double à chaque tentative (`base_delay_ms * 2^n`, plafonné à
+/// `max_delay_ms`) avec une gigue aléatoire de ±50% pour éviter les rafales.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 200,
+            max_delay_ms: 5_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Délai avant la tentative suivante (`attempt` commence à 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(16))
+            .min(self.max_delay_ms);
+        let jitter = rand::thread_rng().gen_range(0.5..=1.5);
+
+        Duration::from_millis((exponential as f64 * jitter) as u64)
+    }
+
+    /// Exécuter `operation`, en réessayant tant que `is_retryable` l'autorise
+    pub async fn run<T, E, F, Fut>(
+        &self,
+        name: &str,
+        mut operation: F,
+        is_retryable: impl Fn(&E) -> bool,
+    ) -> std::result::Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: Display,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < max_attempts && is_retryable(&e) => {
+                    let delay = self.backoff(attempt);
+                    log::warn!(
+                        "{}: erreur transitoire (tentative {}/{}), nouvel essai dans {:?}: {}",
+                        name, attempt, max_attempts, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Opération simulée: échoue avec `error` pour les `failures` premiers appels
+    fn flaky(calls: &AtomicU32, failures: u32, error: &'static str) -> impl Future<Output = Result<u32, String>> {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        async move {
+            if call <= failures {
+                Err(error.to_string())
+            } else {
+                Ok(call)
+            }
+        }
+    }
+
+    fn is_transient(error: &String) -> bool {
+        error.starts_with("503")
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn succeeds_after_two_transient_failures() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::default();
+
+        let result = policy.run("put_object", || flaky(&calls, 2, "503 Slow Down"), is_transient).await;
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_errors_are_not_retried() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::default();
+
+        let result = policy.run("get_object", || flaky(&calls, 5, "403 Forbidden"), is_transient).await;
+        assert_eq!(result, Err("403 Forbidden".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy { max_attempts: 3, ..RetryPolicy::default() };
+
+        let result = policy.run("get_object", || flaky(&calls, 5, "503 Slow Down"), is_transient).await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn backoff_doubles_with_jitter_and_is_capped() {
+        let policy = RetryPolicy { max_attempts: 10, base_delay_ms: 100, max_delay_ms: 1_000 };
+
+        let first = policy.backoff(1).as_millis();
+        assert!((50..=150).contains(&first), "{}", first);
+        let third = policy.backoff(3).as_millis();
+        assert!((200..=600).contains(&third), "{}", third);
+        let capped = policy.backoff(10).as_millis();
+        assert!((500..=1_500).contains(&capped), "{}", capped);
+    }
+}
@@ -13,6 +13,9 @@ pub struct AccessTokenClaims {
     pub exp: usize,       // Expiration timestamp
     pub iat: usize,       // Issued at timestamp
     pub jti: String,      // Token ID (pour invalidation)
+    /// Admin agissant pour le compte de `sub` (token d'impersonation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Uuid>,
 }
 
 /// Claims JWT pour les refresh tokens
@@ -35,6 +38,7 @@ pub fn generate_access_token(user_id: Uuid, email: &str, secret: &str) -> String
         exp: expires_at.timestamp() as usize,
         iat: now.timestamp() as usize,
         jti: Uuid::new_v4().to_string(),
+        act: None,
     };
     
     encode(
@@ -45,6 +49,34 @@ pub fn generate_access_token(user_id: Uuid, email: &str, secret: &str) -> String
     .expect("Failed to generate access token")
 }
 
+/// Générer un token d'impersonation (courte durée, claim `act` = admin)
+pub fn generate_impersonation_token(
+    target_user_id: Uuid,
+    target_email: &str,
+    admin_id: Uuid,
+    secret: &str,
+    ttl_minutes: i64,
+) -> String {
+    let now = chrono::Utc::now();
+    let expires_at = now + chrono::Duration::minutes(ttl_minutes);
+    
+    let claims = AccessTokenClaims {
+        sub: target_user_id,
+        email: target_email.to_string(),
+        exp: expires_at.timestamp() as usize,
+        iat: now.timestamp() as usize,
+        jti: Uuid::new_v4().to_string(),
+        act: Some(admin_id),
+    };
+    
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .expect("Failed to generate impersonation token")
+}
+
 /// Générer un refresh token JWT
 pub fn generate_refresh_token(user_id: Uuid, secret: &str) -> String {
     let now = chrono::Utc::now();
@@ -126,6 +158,11 @@ pub fn generate_api_key() -> String {
     format!("qnt_{}", generate_random_string(32))
 }
 
+/// Empreinte stockée d'une clé API (SHA-256: la clé est déjà aléatoire)
+pub fn hash_api_key(api_key: &str) -> String {
+    sha256_hash(api_key.as_bytes())
+}
+
 /// Générer un token de réinitialisation de mot de passe
 pub fn generate_reset_token() -> String {
     generate_random_string(32)
@@ -192,6 +229,19 @@ pub fn sha256_hash(data: &[u8]) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Signature HMAC-SHA256 (hexadécimal) d'un envoi de webhook: `<timestamp>.<corps>`
+pub fn sign_webhook_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepte des clés de toute taille");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
 /// Valider la force d'un mot de passe
 pub fn validate_password_strength(password: &str) -> Result<()> {
     if password.len() < 8 {
@@ -67,15 +67,10 @@ pub fn validate_model_format(format: &str) -> Result<()> {
 
 /// Valider une méthode de quantification
 pub fn validate_quantization_method(method: &str) -> Result<()> {
-    let valid_methods = ["int8", "gptq", "awq", "gguf_q4_0", "gguf_q5_0"];
-    
-    if !valid_methods.contains(&method.to_lowercase().as_str()) {
-        return Err(AppError::Validation(
-            format!("Invalid quantization method. Must be one of: {}", valid_methods.join(", "))
-        ));
-    }
-    
-    Ok(())
+    method
+        .parse::<crate::models::QuantizationMethod>()
+        .map(|_| ())
+        .map_err(AppError::from)
 }
 
 /// Valider un plan d'abonnement
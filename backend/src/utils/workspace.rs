@@ -0,0 +1,175 @@
+// utils/workspace.rs
+use crate::utils::error::{AppError, Result};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Sous-répertoires dérivés de `WORK_DIR`
+#[derive(Debug, Clone)]
+pub struct WorkDirs {
+    /// Répertoires de travail des jobs en cours
+    pub worker: PathBuf,
+    /// Artefacts produits en attente d'upload ou de nettoyage
+    pub results: PathBuf,
+}
+
+impl WorkDirs {
+    /// Créer l'arborescence sous `root` et vérifier qu'elle est inscriptible
+    ///
+    /// À appeler au démarrage: une erreur ici doit empêcher le lancement.
+    pub fn prepare(root: &Path) -> Result<Self> {
+        let dirs = Self {
+            worker: root.join("worker"),
+            results: root.join("results"),
+        };
+
+        for dir in [&dirs.worker, &dirs.results] {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                AppError::Validation(format!(
+                    "WORK_DIR: impossible de créer {}: {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+            ensure_writable(dir)?;
+        }
+
+        Ok(dirs)
+    }
+}
+
+/// Écrire puis supprimer un fichier témoin
+fn ensure_writable(dir: &Path) -> Result<()> {
+    let probe = dir.join(format!(".write-test-{}", Uuid::new_v4().simple()));
+
+    std::fs::write(&probe, b"ok").map_err(|e| {
+        AppError::Validation(format!(
+            "WORK_DIR: {} n'est pas inscriptible: {}",
+            dir.display(),
+            e
+        ))
+    })?;
+    let _ = std::fs::remove_file(&probe);
+
+    Ok(())
+}
+
+/// Répertoire de travail temporaire propre à un job
+///
+/// Le répertoire est unique (même pour deux exécutions du même job) et
+/// supprimé au `Drop`, y compris en cas de panique ou d'annulation de la
+/// tâche qui le détient.
+#[derive(Debug)]
+pub struct TempWorkspace {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempWorkspace {
+    /// Créer un répertoire `<root>/<job_id>-<aléatoire>`
+    pub fn create(root: &Path, job_id: Uuid) -> Result<Self> {
+        let path = root.join(format!("{}-{}", job_id, Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&path)?;
+
+        Ok(Self { path, keep: false })
+    }
+
+    /// Conserver le répertoire après usage (débogage)
+    pub fn keep(mut self, keep: bool) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Chemin du répertoire
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Chemin d'un fichier dans le répertoire
+    pub fn join(&self, name: &str) -> Result<PathBuf> {
+        let name = Path::new(name)
+            .file_name()
+            .ok_or(AppError::InvalidPath)?;
+        Ok(self.path.join(name))
+    }
+}
+
+impl Drop for TempWorkspace {
+    fn drop(&mut self) {
+        if self.keep {
+            log::debug!("Répertoire de travail conservé: {}", self.path.display());
+            return;
+        }
+
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Impossible de supprimer {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing;
+
+    #[test]
+    fn dropping_the_guard_removes_the_directory() {
+        let root = testing::scratch_dir("workspace");
+        let workspace = TempWorkspace::create(&root, Uuid::new_v4()).unwrap();
+        let path = workspace.path().to_path_buf();
+        std::fs::write(workspace.join("model.bin").unwrap(), b"poids").unwrap();
+
+        drop(workspace);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn panics_still_clean_up() {
+        let root = testing::scratch_dir("workspace");
+        let job_id = Uuid::new_v4();
+
+        let result = std::panic::catch_unwind(|| {
+            let workspace = TempWorkspace::create(&root, job_id).unwrap();
+            std::fs::write(workspace.join("model.bin").unwrap(), b"poids").unwrap();
+            panic!("échec du job");
+        });
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn each_run_of_a_job_gets_its_own_directory() {
+        let root = testing::scratch_dir("workspace");
+        let job_id = Uuid::new_v4();
+
+        let first = TempWorkspace::create(&root, job_id).unwrap();
+        let second = TempWorkspace::create(&root, job_id).unwrap();
+        assert_ne!(first.path(), second.path());
+    }
+
+    #[test]
+    fn kept_workspace_survives_the_drop() {
+        let root = testing::scratch_dir("workspace");
+        let workspace = TempWorkspace::create(&root, Uuid::new_v4()).unwrap().keep(true);
+        let path = workspace.path().to_path_buf();
+
+        drop(workspace);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn unwritable_work_dir_fails_validation() {
+        // Un fichier à la place du répertoire: aucun sous-répertoire ne peut y être créé
+        // (les permissions seules ne suffisent pas quand les tests tournent en root)
+        let root = testing::scratch_dir("workspace").join("occupe");
+        std::fs::write(&root, b"pas un dossier").unwrap();
+
+        match WorkDirs::prepare(&root) {
+            Err(AppError::Validation(message)) => assert!(message.starts_with("WORK_DIR"), "{}", message),
+            Err(other) => panic!("erreur inattendue: {:?}", other),
+            Ok(_) => panic!("répertoire de travail invalide accepté"),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+// utils/zip.rs
+use chrono::{Datelike, Timelike, Utc};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const ZIP64_END_SIGNATURE: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+const END_SIGNATURE: u32 = 0x0605_4b50;
+
+/// Version 4.5: ZIP64
+const VERSION: u16 = 45;
+/// Bit 3: CRC et tailles dans le descripteur; bit 11: nom en UTF-8
+const FLAGS: u16 = 0x0808;
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+const U32_MAX: u64 = 0xFFFF_FFFF;
+
+/// CRC-32 (polynôme IEEE, celui du format zip)
+#[derive(Debug, Clone)]
+pub struct Crc32 {
+    state: u32,
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Self { state: 0xFFFF_FFFF }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state = CRC_TABLE[((self.state ^ *byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    pub fn finalize(&self) -> u32 {
+        !self.state
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entrée déjà écrite, reprise dans le répertoire central
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u64,
+    offset: u64,
+}
+
+/// Archive zip écrite au fil de l'eau (entrées stockées, sans compression)
+///
+/// Le CRC et la taille suivent les données (descripteur): un fichier lu par
+/// morceaux n'est jamais gardé en mémoire. Les champs ZIP64 sont toujours
+/// présents dans les en-têtes locaux pour les modèles de plus de 4 Go.
+///
+/// Usage: `start_entry`, puis les données (signalées par `data`), puis
+/// `finish_entry`; enfin `finish` pour le répertoire central. Chaque appel
+/// rend les octets à écrire, dans l'ordre.
+pub struct ZipStreamWriter {
+    entries: Vec<CentralEntry>,
+    offset: u64,
+    current: Option<(String, u64, Crc32, u64)>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl ZipStreamWriter {
+    pub fn new() -> Self {
+        let now = Utc::now();
+        let dos_time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+        let dos_date = (((now.year().max(1980) - 1980) as u32) << 9 | now.month() << 5 | now.day()) as u16;
+
+        Self {
+            entries: Vec::new(),
+            offset: 0,
+            current: None,
+            dos_time,
+            dos_date,
+        }
+    }
+
+    /// En-tête local d'une nouvelle entrée
+    pub fn start_entry(&mut self, name: &str) -> Vec<u8> {
+        let mut header = Vec::with_capacity(30 + name.len() + 20);
+        put_u32(&mut header, LOCAL_HEADER_SIGNATURE);
+        put_u16(&mut header, VERSION);
+        put_u16(&mut header, FLAGS);
+        put_u16(&mut header, 0); // stocké
+        put_u16(&mut header, self.dos_time);
+        put_u16(&mut header, self.dos_date);
+        put_u32(&mut header, 0); // CRC (descripteur)
+        put_u32(&mut header, U32_MAX as u32); // tailles dans l'extra ZIP64
+        put_u32(&mut header, U32_MAX as u32);
+        put_u16(&mut header, name.len() as u16);
+        put_u16(&mut header, 20);
+        header.extend_from_slice(name.as_bytes());
+        put_u16(&mut header, ZIP64_EXTRA_ID);
+        put_u16(&mut header, 16);
+        put_u64(&mut header, 0);
+        put_u64(&mut header, 0);
+
+        self.current = Some((name.to_string(), self.offset, Crc32::new(), 0));
+        self.offset += header.len() as u64;
+        header
+    }
+
+    /// Signaler des données de l'entrée en cours (écrites telles quelles)
+    pub fn data(&mut self, chunk: &[u8]) {
+        if let Some((_, _, crc, size)) = &mut self.current {
+            crc.update(chunk);
+            *size += chunk.len() as u64;
+        }
+        self.offset += chunk.len() as u64;
+    }
+
+    /// Descripteur (CRC et tailles) de l'entrée en cours
+    pub fn finish_entry(&mut self) -> Vec<u8> {
+        let Some((name, offset, crc, size)) = self.current.take() else {
+            return Vec::new();
+        };
+        let crc = crc.finalize();
+
+        let mut descriptor = Vec::with_capacity(24);
+        put_u32(&mut descriptor, DATA_DESCRIPTOR_SIGNATURE);
+        put_u32(&mut descriptor, crc);
+        put_u64(&mut descriptor, size);
+        put_u64(&mut descriptor, size);
+
+        self.entries.push(CentralEntry { name, crc, size, offset });
+        self.offset += descriptor.len() as u64;
+        descriptor
+    }
+
+    /// Répertoire central et fin d'archive
+    pub fn finish(self) -> Vec<u8> {
+        let central_offset = self.offset;
+        let mut out = Vec::new();
+
+        for entry in &self.entries {
+            let mut extra = Vec::new();
+            if entry.size >= U32_MAX {
+                put_u64(&mut extra, entry.size);
+                put_u64(&mut extra, entry.size);
+            }
+            if entry.offset >= U32_MAX {
+                put_u64(&mut extra, entry.offset);
+            }
+
+            put_u32(&mut out, CENTRAL_HEADER_SIGNATURE);
+            put_u16(&mut out, VERSION);
+            put_u16(&mut out, VERSION);
+            put_u16(&mut out, FLAGS);
+            put_u16(&mut out, 0);
+            put_u16(&mut out, self.dos_time);
+            put_u16(&mut out, self.dos_date);
+            put_u32(&mut out, entry.crc);
+            put_u32(&mut out, entry.size.min(U32_MAX) as u32);
+            put_u32(&mut out, entry.size.min(U32_MAX) as u32);
+            put_u16(&mut out, entry.name.len() as u16);
+            put_u16(&mut out, if extra.is_empty() { 0 } else { extra.len() as u16 + 4 });
+            put_u16(&mut out, 0); // commentaire
+            put_u16(&mut out, 0); // disque
+            put_u16(&mut out, 0); // attributs internes
+            put_u32(&mut out, 0); // attributs externes
+            put_u32(&mut out, entry.offset.min(U32_MAX) as u32);
+            out.extend_from_slice(entry.name.as_bytes());
+            if !extra.is_empty() {
+                put_u16(&mut out, ZIP64_EXTRA_ID);
+                put_u16(&mut out, extra.len() as u16);
+                out.extend_from_slice(&extra);
+            }
+        }
+
+        let central_size = out.len() as u64;
+        let count = self.entries.len() as u64;
+
+        if count >= 0xFFFF || central_size >= U32_MAX || central_offset >= U32_MAX {
+            let zip64_end_offset = central_offset + central_size;
+
+            put_u32(&mut out, ZIP64_END_SIGNATURE);
+            put_u64(&mut out, 44);
+            put_u16(&mut out, VERSION);
+            put_u16(&mut out, VERSION);
+            put_u32(&mut out, 0);
+            put_u32(&mut out, 0);
+            put_u64(&mut out, count);
+            put_u64(&mut out, count);
+            put_u64(&mut out, central_size);
+            put_u64(&mut out, central_offset);
+
+            put_u32(&mut out, ZIP64_LOCATOR_SIGNATURE);
+            put_u32(&mut out, 0);
+            put_u64(&mut out, zip64_end_offset);
+            put_u32(&mut out, 1);
+        }
+
+        put_u32(&mut out, END_SIGNATURE);
+        put_u16(&mut out, 0);
+        put_u16(&mut out, 0);
+        put_u16(&mut out, count.min(0xFFFF) as u16);
+        put_u16(&mut out, count.min(0xFFFF) as u16);
+        put_u32(&mut out, central_size.min(U32_MAX) as u32);
+        put_u32(&mut out, central_offset.min(U32_MAX) as u32);
+        put_u16(&mut out, 0);
+
+        out
+    }
+}
+
+impl Default for ZipStreamWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_matches_the_reference_value() {
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.finalize(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn archive_offsets_and_entry_count_are_consistent() {
+        let mut zip = ZipStreamWriter::new();
+        let mut archive = Vec::new();
+        for (name, data) in [("model.onnx", &b"poids"[..]), ("report.json", &b"{}"[..])] {
+            archive.extend(zip.start_entry(name));
+            zip.data(data);
+            archive.extend_from_slice(data);
+            archive.extend(zip.finish_entry());
+        }
+        let central_offset = archive.len();
+        archive.extend(zip.finish());
+
+        let end = &archive[archive.len() - 22..];
+        assert_eq!(u32::from_le_bytes(end[..4].try_into().unwrap()), END_SIGNATURE);
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        assert_eq!(u32::from_le_bytes(end[16..20].try_into().unwrap()) as usize, central_offset);
+        assert_eq!(
+            u32::from_le_bytes(archive[central_offset..central_offset + 4].try_into().unwrap()),
+            CENTRAL_HEADER_SIGNATURE
+        );
+    }
+}